@@ -1,11 +1,15 @@
 use tauri::State;
 
+use crate::engine::topics;
 use crate::models::{GameMode, WorldState};
 use crate::persistence::state::GameState;
 
 const BASE_COMMANDS: &[&str] = &[
-    "look", "examine", "go", "take", "drop", "use", "equip", "unequip", "talk", "attack", "flee",
-    "inventory", "map", "quests", "codex", "help", "save", "load", "craft", "combine",
+    "look", "examine", "go", "take", "drop", "use", "open", "equip", "unequip", "repair", "talk",
+    "ask", "tell", "attack", "flee", "inventory", "stats", "time", "map", "quests", "codex",
+    "reputation", "help", "save", "load", "craft", "combine", "recruit", "dismiss", "give", "rest",
+    "sleep", "sneak", "hide", "search", "disarm", "close", "pick", "bash", "pull", "push", "set",
+    "answer",
 ];
 
 const DIRECTIONS: &[&str] = &["north", "south", "east", "west", "up", "down"];
@@ -36,21 +40,40 @@ pub fn get_completions(prefix: String, game_state: State<GameState>) -> Result<V
         };
 
         match effective_verb {
-            "go" | "move" | "walk" | "head" => {
+            "go" | "move" | "walk" | "head" | "sneak" | "creep" => {
+                candidates.extend(direction_completions(effective_arg, &state));
+            }
+            "pull" | "push" | "turn" | "press" | "set" => {
+                candidates.extend(fixture_completions(effective_arg, &state));
+            }
+            "close" | "shut" | "bash" | "kick" => {
                 candidates.extend(direction_completions(effective_arg, &state));
             }
             "take" | "get" | "grab" | "pick up" | "pick" => {
                 candidates.extend(room_item_completions(effective_arg, &state));
             }
-            "drop" | "use" | "equip" => {
+            "drop" | "use" | "equip" | "repair" => {
+                candidates.extend(inventory_completions(effective_arg, &state));
+            }
+            "open" | "loot" => {
+                candidates.extend(room_item_completions(effective_arg, &state));
                 candidates.extend(inventory_completions(effective_arg, &state));
             }
             "unequip" | "remove" => {
                 candidates.extend(equipped_completions(effective_arg, &state));
             }
-            "talk" | "speak" | "ask" | "chat" => {
+            "ask" | "tell" if effective_arg.contains(" about ") => {
+                candidates.extend(topic_completions(effective_arg, &state));
+            }
+            "talk" | "speak" | "ask" | "tell" | "chat" => {
+                candidates.extend(npc_completions(effective_arg, &state));
+            }
+            "recruit" | "dismiss" => {
                 candidates.extend(npc_completions(effective_arg, &state));
             }
+            "give" | "hand" if !effective_arg.contains(" to ") => {
+                candidates.extend(inventory_completions(effective_arg, &state));
+            }
             "attack" | "fight" | "hit" | "kill" | "strike" => {
                 candidates.extend(npc_completions(effective_arg, &state));
             }
@@ -58,6 +81,7 @@ pub fn get_completions(prefix: String, game_state: State<GameState>) -> Result<V
                 candidates.extend(room_item_completions(effective_arg, &state));
                 candidates.extend(inventory_completions(effective_arg, &state));
                 candidates.extend(npc_completions(effective_arg, &state));
+                candidates.extend(fixture_completions(effective_arg, &state));
             }
             "craft" | "combine" | "mix" => {
                 candidates.extend(inventory_completions(effective_arg, &state));
@@ -129,6 +153,19 @@ fn room_item_completions(arg: &str, state: &WorldState) -> Vec<String> {
         .collect()
 }
 
+fn fixture_completions(arg: &str, state: &WorldState) -> Vec<String> {
+    let loc = match state.locations.get(&state.player.location) {
+        Some(l) => l,
+        None => return Vec::new(),
+    };
+    loc.fixtures
+        .iter()
+        .filter_map(|id| state.fixtures.get(id))
+        .map(|f| f.name.to_lowercase())
+        .filter(|name| name.starts_with(arg) || name.contains(arg))
+        .collect()
+}
+
 fn inventory_completions(arg: &str, state: &WorldState) -> Vec<String> {
     state
         .player
@@ -141,24 +178,14 @@ fn inventory_completions(arg: &str, state: &WorldState) -> Vec<String> {
 }
 
 fn equipped_completions(arg: &str, state: &WorldState) -> Vec<String> {
-    let mut results = Vec::new();
-    if let Some(ref wid) = state.player.equipped_weapon {
-        if let Some(item) = state.items.get(wid) {
-            let name = item.name.to_lowercase();
-            if name.starts_with(arg) || name.contains(arg) {
-                results.push(name);
-            }
-        }
-    }
-    if let Some(ref aid) = state.player.equipped_armor {
-        if let Some(item) = state.items.get(aid) {
-            let name = item.name.to_lowercase();
-            if name.starts_with(arg) || name.contains(arg) {
-                results.push(name);
-            }
-        }
-    }
-    results
+    state
+        .player
+        .equipped_items()
+        .into_iter()
+        .filter_map(|(_, id)| state.items.get(id))
+        .map(|i| i.name.to_lowercase())
+        .filter(|name| name.starts_with(arg) || name.contains(arg))
+        .collect()
 }
 
 fn npc_completions(arg: &str, state: &WorldState) -> Vec<String> {
@@ -175,6 +202,30 @@ fn npc_completions(arg: &str, state: &WorldState) -> Vec<String> {
         .collect()
 }
 
+/// Completes "<npc> about <topic>" from the topics an NPC in the room knows.
+fn topic_completions(arg: &str, state: &WorldState) -> Vec<String> {
+    let Some((npc_part, topic_part)) = arg.split_once(" about ") else {
+        return Vec::new();
+    };
+    let loc = match state.locations.get(&state.player.location) {
+        Some(l) => l,
+        None => return Vec::new(),
+    };
+    let Some(npc) = loc
+        .npcs
+        .iter()
+        .filter_map(|id| state.npcs.get(id))
+        .find(|n| n.name.to_lowercase().contains(npc_part) || n.id.contains(npc_part))
+    else {
+        return Vec::new();
+    };
+    topics::known_topics(&npc.id, state)
+        .into_iter()
+        .filter(|topic| topic.starts_with(topic_part))
+        .map(|topic| format!("{} about {}", npc_part, topic))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -236,6 +287,32 @@ mod tests {
         assert!(!candidates.is_empty());
     }
 
+    #[test]
+    fn completes_known_topics() {
+        let mut state = build_state();
+        state.topics.insert(
+            "merchant_ghost".into(),
+            crate::models::TopicMap {
+                topics: vec![crate::models::Topic {
+                    name: "journal".into(),
+                    aliases: vec![],
+                    ask: Some("Have you seen it?".into()),
+                    tell: None,
+                    condition: None,
+                    journal: None,
+                    sets_flag: None,
+                }],
+                fallback: None,
+            },
+        );
+        assert_eq!(
+            topic_completions("merchant about jo", &state),
+            vec!["merchant about journal".to_string()]
+        );
+        assert!(topic_completions("merchant about x", &state).is_empty());
+        assert!(topic_completions("merchant", &state).is_empty());
+    }
+
     #[test]
     fn inventory_completions_empty_when_no_items() {
         let state = build_state();
@@ -203,6 +203,21 @@ mod tests {
             .any(|e| e.contains("doesn't exist") && e.contains("phantom_npc")));
     }
 
+    #[test]
+    fn validate_unsolvable_puzzle() {
+        let mut state = world_builder::build_thornhold();
+        if let Some(puzzle) = state.puzzles.get_mut("bust_riddle") {
+            puzzle.solution.insert("stone_bust".into(), "shrugged".into());
+        }
+        let json = serde_json::to_string(&state).unwrap();
+        let result = validate_module_json(json).unwrap();
+        assert!(!result.valid);
+        assert!(result
+            .errors
+            .iter()
+            .any(|e| e.contains("can't be solved") && e.contains("stone_bust")));
+    }
+
     #[test]
     fn validate_empty_locations() {
         let state = WorldState::default();
@@ -2,9 +2,7 @@ use tauri::{Emitter, Manager, State};
 use tokio::sync::mpsc;
 
 use crate::engine::{
-    achievement_checker,
-    dungeon_generator::{self, DungeonConfig},
-    executor, hints, parser, world_builder,
+    achievement_checker, dungeon_generator, executor, hints, parser, world_builder,
 };
 use crate::models::*;
 use crate::narrative::narrator::{self, NarrativeEvent};
@@ -18,21 +16,9 @@ pub fn initialize_game(game_state: State<GameState>) -> Result<CommandResponse,
     if !state.initialized {
         *state = world_builder::build_thornhold();
 
-        // Generate procedural dungeon wing
-        let difficulty_base = match state.difficulty {
-            Difficulty::Easy => 3,
-            Difficulty::Normal => 5,
-            Difficulty::Hard => 8,
-        };
-        dungeon_generator::generate_dungeon(
-            &DungeonConfig {
-                entry_location: "armory".to_string(),
-                entry_direction: Direction::Down,
-                depth: 5,
-                difficulty_base,
-            },
-            &mut state,
-        );
+        // Generate procedural regions (the dungeon wing)
+        state.rng_seed = rand::random();
+        dungeon_generator::generate_regions(&mut state);
     }
 
     let loc = state.locations.get(&state.player.location).cloned();
@@ -51,8 +37,15 @@ pub fn initialize_game(game_state: State<GameState>) -> Result<CommandResponse,
                 line_type: LineType::System,
             },
         ];
-        let look_lines =
-            crate::engine::templates::describe_location(&location, &state.items, &state.npcs, true);
+        let look_lines = crate::engine::templates::describe_location(
+            &location,
+            &state.items,
+            &state.npcs,
+            &state.doors,
+            &state.fixtures,
+            true,
+            state.clock.phase(),
+        );
         msgs.extend(look_lines.into_iter().map(|text| OutputLine {
             text,
             line_type: LineType::Narration,
@@ -86,21 +79,9 @@ pub fn new_game(
         state.difficulty = settings.difficulty;
     }
 
-    // Generate procedural dungeon wing
-    let difficulty_base = match state.difficulty {
-        Difficulty::Easy => 3,
-        Difficulty::Normal => 5,
-        Difficulty::Hard => 8,
-    };
-    dungeon_generator::generate_dungeon(
-        &DungeonConfig {
-            entry_location: "armory".to_string(),
-            entry_direction: Direction::Down,
-            depth: 5,
-            difficulty_base,
-        },
-        &mut state,
-    );
+    // Generate procedural regions (the dungeon wing)
+    state.rng_seed = rand::random();
+    dungeon_generator::generate_regions(&mut state);
 
     // Track games_started stat
     if let Some(db) = app.try_state::<DbState>() {
@@ -121,8 +102,15 @@ pub fn new_game(
                 line_type: LineType::System,
             },
         ];
-        let look_lines =
-            crate::engine::templates::describe_location(&location, &state.items, &state.npcs, true);
+        let look_lines = crate::engine::templates::describe_location(
+            &location,
+            &state.items,
+            &state.npcs,
+            &state.doors,
+            &state.fixtures,
+            true,
+            state.clock.phase(),
+        );
         msgs.extend(look_lines.into_iter().map(|text| OutputLine {
             text,
             line_type: LineType::Narration,
@@ -205,7 +193,10 @@ pub async fn process_command(
                         &location,
                         &state.items,
                         &state.npcs,
+                        &state.doors,
+                        &state.fixtures,
                         false,
+                        state.clock.phase(),
                     );
                     msgs.extend(look_lines.into_iter().map(|text| OutputLine {
                         text,
@@ -346,7 +337,7 @@ pub async fn process_command(
                 // Only route through LLM for free-form text, not quest mechanic results
                 let is_quest_mechanic = matches!(
                     dialogue_text.as_str(),
-                    "hostile" | "dead" | "declined"
+                    "hostile" | "dead" | "declined" | "authored"
                 );
                 if !is_quest_mechanic {
                     // Record user input in dialogue history
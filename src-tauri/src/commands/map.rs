@@ -1,7 +1,8 @@
 use serde::Serialize;
 use tauri::State;
 
-use crate::models::WorldState;
+use crate::engine::dungeon_generator;
+use crate::models::{DoorState, WorldState};
 use crate::persistence::state::GameState;
 
 #[derive(Debug, Clone, Serialize)]
@@ -21,6 +22,8 @@ pub struct MapEdge {
     pub from: String,
     pub to: String,
     pub locked: bool,
+    /// State of a door on this exit, if there is one.
+    pub door_state: Option<DoorState>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -30,11 +33,12 @@ pub struct MapData {
     pub edges: Vec<MapEdge>,
 }
 
+const DEFAULT_POSITION: (f32, f32) = (140.0, 200.0);
+
 /// Hand-tuned x/y positions for Thornhold locations.
 /// Coordinate space: 0-280 x, 0-400+ y (fits sidebar width).
-/// Dungeon rooms are positioned dynamically below the armory.
-fn get_position(id: &str) -> (f32, f32) {
-    match id {
+fn fixed_position(id: &str) -> Option<(f32, f32)> {
+    let position = match id {
         "courtyard"       => (40.0, 40.0),
         "great_hall"      => (140.0, 40.0),
         "tower_apex"      => (140.0, 0.0),      // above great hall (up)
@@ -49,21 +53,35 @@ fn get_position(id: &str) -> (f32, f32) {
         "deep_chamber"    => (200.0, 320.0),
         "final_sanctum"   => (200.0, 380.0),
         "hidden_vault"    => (80.0, 40.0),
-        _ => {
-            // Handle dungeon rooms: dungeon_d{depth}_r{n}
-            if let Some(rest) = id.strip_prefix("dungeon_d") {
-                if let Some(d_str) = rest.split('_').next() {
-                    if let Ok(depth) = d_str.parse::<u32>() {
-                        // Zigzag below armory (40, 200)
-                        let x = if depth % 2 == 0 { 40.0 } else { 100.0 };
-                        let y = 260.0 + (depth as f32 * 50.0);
-                        return (x, y);
-                    }
-                }
-            }
-            (140.0, 200.0)
-        }
-    }
+        _ => return None,
+    };
+    Some(position)
+}
+
+/// Procedural region rooms ({region}_d{depth}_r{n}) zigzag down from their
+/// own region's entry room.
+fn region_position(id: &str, state: &WorldState) -> Option<(f32, f32)> {
+    let region = state
+        .regions
+        .iter()
+        .filter(|r| dungeon_generator::in_region(&r.id, id))
+        .max_by_key(|r| r.id.len())?;
+    let depth = region_room_depth(id)?;
+    let (x, y) = fixed_position(&region.entry_location).unwrap_or(DEFAULT_POSITION);
+    let dx = if depth % 2 == 0 { 0.0 } else { 60.0 };
+    Some((x + dx, y + 60.0 + depth as f32 * 50.0))
+}
+
+fn get_position(id: &str, state: &WorldState) -> (f32, f32) {
+    fixed_position(id)
+        .or_else(|| region_position(id, state))
+        .unwrap_or(DEFAULT_POSITION)
+}
+
+fn region_room_depth(id: &str) -> Option<u32> {
+    let (rest, _) = id.rsplit_once("_r")?;
+    let (_, depth) = rest.rsplit_once("_d")?;
+    depth.parse().ok()
 }
 
 #[tauri::command]
@@ -78,7 +96,7 @@ fn build_map_data(state: &WorldState) -> MapData {
     let mut seen_edges = std::collections::HashSet::new();
 
     for (id, loc) in &state.locations {
-        let (x, y) = get_position(id);
+        let (x, y) = get_position(id, state);
         nodes.push(MapNode {
             id: id.clone(),
             name: loc.name.clone(),
@@ -111,11 +129,30 @@ fn build_map_data(state: &WorldState) -> MapData {
                             .any(|(dir, _)| other.exits.get(dir) == Some(id))
                     })
                     .unwrap_or(false);
+                // Either side may hold the door; both share its state
+                let door_state = loc
+                    .exits
+                    .iter()
+                    .filter(|(_, dest)| *dest == dest_id)
+                    .find_map(|(dir, _)| loc.doors.get(dir))
+                    .or_else(|| {
+                        let other = state.locations.get(dest_id)?;
+                        other
+                            .exits
+                            .iter()
+                            .filter(|(_, dest)| *dest == id)
+                            .find_map(|(dir, _)| other.doors.get(dir))
+                    })
+                    .and_then(|door_id| state.doors.get(door_id))
+                    .map(|door| door.state);
 
                 edges.push(MapEdge {
                     from: id.clone(),
                     to: dest_id.clone(),
-                    locked: locked_from_here || locked_from_other,
+                    locked: locked_from_here
+                        || locked_from_other
+                        || door_state == Some(DoorState::Locked),
+                    door_state,
                 });
             }
         }
@@ -144,6 +181,29 @@ mod tests {
         assert!(!data.edges.is_empty());
     }
 
+    #[test]
+    fn door_edges_carry_the_door_state() {
+        let mut state = world_builder::build_thornhold();
+        let library_edge = |data: &MapData| {
+            data.edges
+                .iter()
+                .find(|e| {
+                    [e.from.as_str(), e.to.as_str()] == ["great_hall", "library"]
+                        || [e.from.as_str(), e.to.as_str()] == ["library", "great_hall"]
+                })
+                .cloned()
+                .unwrap()
+        };
+        let edge = library_edge(&build_map_data(&state));
+        assert!(edge.locked);
+        assert_eq!(edge.door_state, Some(DoorState::Locked));
+
+        state.doors.get_mut("library_door").unwrap().state = DoorState::Open;
+        let edge = library_edge(&build_map_data(&state));
+        assert!(!edge.locked);
+        assert_eq!(edge.door_state, Some(DoorState::Open));
+    }
+
     #[test]
     fn current_location_marked() {
         let state = world_builder::build_thornhold();
@@ -151,4 +211,21 @@ mod tests {
         let current = data.nodes.iter().find(|n| n.current).unwrap();
         assert_eq!(current.id, "courtyard");
     }
+
+    #[test]
+    fn region_rooms_positioned_below_their_entry() {
+        let mut state = world_builder::build_thornhold();
+        let mut ice_caves = state.regions[0].clone();
+        ice_caves.id = "ice_caves".into();
+        ice_caves.entry_location = "library".into();
+        state.regions.push(ice_caves);
+
+        // The dungeon hangs below the armory (40, 200), the caves below the
+        // library (240, 40)
+        assert_eq!(get_position("dungeon_d0_r0", &state), (40.0, 260.0));
+        assert_eq!(get_position("dungeon_d3_r3", &state), (100.0, 410.0));
+        assert_eq!(get_position("ice_caves_d3_r3", &state), (300.0, 250.0));
+        assert_eq!(get_position("great_hall", &state), (140.0, 40.0));
+        assert_eq!(get_position("nowhere_d1_r1", &state), DEFAULT_POSITION);
+    }
 }
@@ -1,6 +1,6 @@
 use tauri::{Manager, State};
 
-use crate::engine::module_loader;
+use crate::engine::{dungeon_generator, module_loader};
 use crate::models::module::ModuleInfo;
 use crate::models::{CommandResponse, LineType, OutputLine};
 use crate::persistence::state::GameState;
@@ -98,7 +98,12 @@ pub fn load_module(
         return Err("Module path must be within the modules directory.".into());
     }
 
-    let loaded = module_loader::load_module(&requested)?;
+    let mut loaded = module_loader::load_module(&requested)?;
+    // Modules may pin a seed for a fixed layout; otherwise roll a fresh one
+    if loaded.rng_seed == 0 {
+        loaded.rng_seed = rand::random();
+    }
+    dungeon_generator::generate_regions(&mut loaded);
 
     let loc = loaded.locations.get(&loaded.player.location).cloned();
     let mut state = game_state.0.lock().map_err(|e| e.to_string())?;
@@ -115,8 +120,15 @@ pub fn load_module(
                 line_type: LineType::System,
             },
         ];
-        let look_lines =
-            crate::engine::templates::describe_location(&location, &state.items, &state.npcs, true);
+        let look_lines = crate::engine::templates::describe_location(
+            &location,
+            &state.items,
+            &state.npcs,
+            &state.doors,
+            &state.fixtures,
+            true,
+            state.clock.phase(),
+        );
         msgs.extend(look_lines.into_iter().map(|text| OutputLine {
             text,
             line_type: LineType::Narration,
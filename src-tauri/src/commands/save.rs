@@ -38,7 +38,10 @@ pub fn load_game(
             &location,
             &state.items,
             &state.npcs,
+            &state.doors,
+            &state.fixtures,
             false,
+            state.clock.phase(),
         );
         messages.extend(
             look_lines
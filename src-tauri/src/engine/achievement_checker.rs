@@ -1,3 +1,4 @@
+use crate::engine::equipment;
 use crate::models::{ActionType, WorldState};
 
 /// Check which achievements should be unlocked based on current state and last action.
@@ -69,14 +70,9 @@ pub fn check_achievements(state: &WorldState, action_type: &ActionType) -> Vec<S
     let legendary_items = ["ethereal_blade", "mithril_mail", "phoenix_feather"];
     if state
         .player
-        .equipped_weapon
-        .as_ref()
-        .is_some_and(|w| legendary_items.contains(&w.as_str()))
-        || state
-            .player
-            .equipped_armor
-            .as_ref()
-            .is_some_and(|a| legendary_items.contains(&a.as_str()))
+        .equipped_items()
+        .iter()
+        .any(|(_, id)| legendary_items.contains(&equipment::base_id(id, state)))
     {
         earned.push("legendary_collector".to_string());
     }
@@ -154,4 +150,15 @@ mod tests {
         let earned = check_achievements(&state, &ActionType::DisplayOnly);
         assert!(earned.contains(&"diplomat".to_string()));
     }
+
+    #[test]
+    fn legendary_collector_counts_worn_copies() {
+        let mut state = world_builder::build_thornhold();
+        state
+            .item_instances
+            .insert("mithril_mail_copy1".into(), "mithril_mail".into());
+        state.player.equipped_armor = Some("mithril_mail_copy1".into());
+        let earned = check_achievements(&state, &ActionType::DisplayOnly);
+        assert!(earned.contains(&"legendary_collector".to_string()));
+    }
 }
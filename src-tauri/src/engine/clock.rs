@@ -0,0 +1,56 @@
+use crate::engine::templates;
+use crate::models::*;
+
+/// Moves the clock forward, announcing each phase of the day it enters.
+pub fn advance(minutes: u32, state: &mut WorldState) -> Vec<OutputLine> {
+    let mut messages = Vec::new();
+    let mut phase = state.clock.phase();
+    // Step by the hour so a long rest still passes through every phase
+    let mut left = minutes;
+    while left > 0 {
+        let step = left.min(60 - state.clock.minute());
+        state.clock.minutes += step;
+        left -= step;
+        let now = state.clock.phase();
+        if now != phase {
+            phase = now;
+            messages.push(OutputLine {
+                text: templates::describe_phase_change(now).to_string(),
+                line_type: LineType::Narration,
+            });
+        }
+    }
+    messages
+}
+
+/// Scales a percent spawn chance by the time of day.
+pub fn spawn_chance(chance: u32, state: &WorldState) -> u32 {
+    (chance * state.clock.phase().spawn_rate() / 100).min(100)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn advancing_announces_each_new_phase() {
+        let mut state = WorldState::default();
+        state.clock.minutes = 17 * 60 + 50;
+        assert!(advance(5, &mut state).is_empty());
+
+        let msgs = advance(12 * 60, &mut state);
+        let texts: Vec<&str> = msgs.iter().map(|m| m.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            vec![
+                templates::describe_phase_change(DayPhase::Dusk),
+                templates::describe_phase_change(DayPhase::Night),
+                templates::describe_phase_change(DayPhase::Dawn),
+            ]
+        );
+        assert_eq!((state.clock.day(), state.clock.hour()), (2, 5));
+        assert_eq!(spawn_chance(80, &state), 80);
+        state.clock.minutes += 20 * 60;
+        assert_eq!(spawn_chance(80, &state), 100);
+    }
+}
@@ -1,5 +1,6 @@
 use rand::Rng;
 
+use crate::engine::{companions, doors, equipment, factions, loot, stealth};
 use crate::models::*;
 use crate::models::settings::Difficulty;
 
@@ -45,31 +46,184 @@ fn calculate_damage(attacker_attack: i32, defender_defense: i32, multiplier: f64
     (damage, critical)
 }
 
-fn get_player_attack(player: &Player, items: &std::collections::HashMap<String, Item>) -> i32 {
-    let weapon_bonus = player
-        .equipped_weapon
-        .as_ref()
-        .and_then(|id| items.get(id))
-        .and_then(|i| i.modifier.as_ref())
-        .map(|m| m.attack)
-        .unwrap_or(0);
+/// Base attack plus every equipped slot, active set bonuses and statuses.
+pub fn get_player_attack(state: &WorldState) -> i32 {
+    let player = &state.player;
+    let gear = equipment::equipment_bonus(player, &state.items, &state.item_sets);
     let status_bonus: i32 = player.status_effects.iter().map(|e| e.attack_modifier).sum();
-    (player.attack + weapon_bonus + status_bonus).max(0)
+    (player.attack + gear.attack + status_bonus).max(0)
 }
 
-fn get_player_defense(player: &Player, items: &std::collections::HashMap<String, Item>) -> i32 {
-    let armor_bonus = player
-        .equipped_armor
-        .as_ref()
-        .and_then(|id| items.get(id))
-        .and_then(|i| i.modifier.as_ref())
-        .map(|m| m.defense)
-        .unwrap_or(0);
+pub fn get_player_defense(state: &WorldState) -> i32 {
+    let player = &state.player;
+    let gear = equipment::equipment_bonus(player, &state.items, &state.item_sets);
     let status_bonus: i32 = player.status_effects.iter().map(|e| e.defense_modifier).sum();
-    (player.defense + armor_bonus + status_bonus).max(0)
+    (player.defense + gear.defense + status_bonus).max(0)
+}
+
+/// Ticks the enemy's lingering effects, then applies the equipped weapon's
+/// on-hit specials. Returns the enemy's HP afterwards.
+fn apply_weapon_effects(
+    state: &mut WorldState,
+    enemy_id: &str,
+    enemy_name: &str,
+    damage: i32,
+    messages: &mut Vec<OutputLine>,
+) -> i32 {
+    let weapon_effects: Vec<SpecialEffect> = state
+        .player
+        .equipped_weapon
+        .as_ref()
+        .and_then(|id| state.items.get(id))
+        .filter(|i| !i.is_broken())
+        .map(|i| i.special_effects.clone())
+        .unwrap_or_default();
+
+    let Some(npc) = state.npcs.get_mut(enemy_id) else {
+        return 0;
+    };
+
+    for effect in &mut npc.status_effects {
+        if effect.damage_per_turn > 0 {
+            npc.health = npc.health.saturating_sub(effect.damage_per_turn).max(0);
+            messages.push(OutputLine {
+                text: format!(
+                    "{} takes {} {} damage. (HP: {})",
+                    enemy_name,
+                    effect.damage_per_turn,
+                    effect.name.to_lowercase(),
+                    npc.health
+                ),
+                line_type: LineType::Combat,
+            });
+        }
+        effect.turns_remaining -= 1;
+    }
+    npc.status_effects.retain(|e| e.turns_remaining > 0);
+
+    for effect in weapon_effects {
+        match effect {
+            SpecialEffect::Poison {
+                damage_per_turn,
+                turns,
+            } => {
+                npc.status_effects.retain(|e| e.effect_type != StatusEffectType::Poison);
+                npc.status_effects.push(StatusEffect {
+                    effect_type: StatusEffectType::Poison,
+                    name: "Poison".into(),
+                    turns_remaining: turns,
+                    damage_per_turn,
+                    attack_modifier: 0,
+                    defense_modifier: 0,
+                });
+                messages.push(OutputLine {
+                    text: format!("{} is poisoned!", enemy_name),
+                    line_type: LineType::Combat,
+                });
+            }
+            SpecialEffect::Lifesteal { percent } => {
+                let healed = (damage.saturating_mul(percent) / 100).max(1);
+                state.player.health =
+                    state.player.health.saturating_add(healed).min(state.player.max_health);
+                messages.push(OutputLine {
+                    text: format!(
+                        "You drain {} HP from {}. (HP: {})",
+                        healed, enemy_name, state.player.health
+                    ),
+                    line_type: LineType::Combat,
+                });
+            }
+        }
+    }
+
+    npc.health
+}
+
+/// Marks an enemy dead, drops its items and loot in the player's room, and
+/// ends combat.
+pub fn defeat_enemy(state: &mut WorldState, enemy_id: &str, messages: &mut Vec<OutputLine>) {
+    let enemy_name = state
+        .npcs
+        .get(enemy_id)
+        .map(|n| n.name.clone())
+        .unwrap_or_default();
+    if let Some(npc) = state.npcs.get_mut(enemy_id) {
+        npc.dialogue_state = DialogueState::Dead;
+        npc.hostile = false;
+    }
+
+    // Drop items, plus whatever the enemy's loot table rolls
+    let dropped_items: Vec<String> = state
+        .npcs
+        .get(enemy_id)
+        .map(|n| n.items.clone())
+        .unwrap_or_default();
+    let loot = match state.npcs.get(enemy_id).and_then(|n| n.loot_table.clone()) {
+        Some(table_id) => loot::roll_loot(&table_id, state),
+        None => Vec::new(),
+    };
+
+    if let Some(loc) = state.locations.get_mut(&state.player.location) {
+        for item_id in &dropped_items {
+            if !loc.items.contains(item_id) {
+                loc.items.push(item_id.clone());
+            }
+        }
+        loc.items.extend(loot::drop_item_ids(&loot));
+        loc.npcs.retain(|id| id != enemy_id);
+    }
+
+    let mut names: Vec<String> = dropped_items
+        .iter()
+        .filter_map(|id| state.items.get(id).map(|i| i.name.clone()))
+        .collect();
+    names.extend(loot::describe_drops(&loot, &state.items));
+    if !names.is_empty() {
+        messages.push(OutputLine {
+            text: format!("Dropped: {}", names.join(", ")),
+            line_type: LineType::System,
+        });
+    }
+
+    messages.push(OutputLine {
+        text: crate::engine::templates::describe_combat_victory(&enemy_name),
+        line_type: LineType::Combat,
+    });
+
+    state.game_mode = GameMode::Exploring;
+    state.combat_state = None;
+    messages.extend(factions::record_deed(enemy_id, factions::Deed::Killed, state));
 }
 
 pub fn execute_player_attack(state: &mut WorldState) -> CombatResult {
+    player_attack(state, 1.0)
+}
+
+/// Opens a fight with a blow against an enemy that hasn't noticed the
+/// player, for extra damage.
+pub fn execute_sneak_attack(state: &mut WorldState) -> CombatResult {
+    player_attack(state, stealth::SNEAK_ATTACK_MULTIPLIER)
+}
+
+/// An enemy that caught the player sneaking gets the first blow.
+pub fn execute_enemy_first_strike(state: &mut WorldState) -> CombatResult {
+    let enemy = match &state.game_mode {
+        GameMode::InCombat(id) => state.npcs.get(id).cloned(),
+        _ => None,
+    };
+    let Some(enemy) = enemy else {
+        return CombatResult {
+            messages: Vec::new(),
+            action_type: ActionType::DisplayOnly,
+            enemy_defeated: false,
+            player_died: false,
+            fled: false,
+        };
+    };
+    enemy_turn(state, &enemy, ActionType::DisplayOnly, Vec::new())
+}
+
+fn player_attack(state: &mut WorldState, bonus: f64) -> CombatResult {
     let mut messages = Vec::new();
     let enemy_id = match &state.game_mode {
         GameMode::InCombat(id) => id.clone(),
@@ -89,7 +243,7 @@ pub fn execute_player_attack(state: &mut WorldState) -> CombatResult {
         }
     };
 
-    let player_atk = get_player_attack(&state.player, &state.items);
+    let player_atk = get_player_attack(state);
     let enemy = match state.npcs.get(&enemy_id) {
         Some(e) => e.clone(),
         None => {
@@ -107,7 +261,8 @@ pub fn execute_player_attack(state: &mut WorldState) -> CombatResult {
             };
         }
     };
-    let (damage, critical) = calculate_damage(player_atk, enemy.defense, difficulty_player_multiplier(&state.difficulty));
+    let multiplier = difficulty_player_multiplier(&state.difficulty) * bonus;
+    let (damage, critical) = calculate_damage(player_atk, enemy.defense, multiplier);
 
     // Apply damage to enemy
     let new_hp = (enemy.health - damage).max(0);
@@ -115,13 +270,6 @@ pub fn execute_player_attack(state: &mut WorldState) -> CombatResult {
         npc.health = new_hp;
     }
 
-    let action_type = ActionType::CombatAttack {
-        damage,
-        target_name: enemy.name.clone(),
-        target_hp: new_hp,
-        target_max_hp: enemy.max_health,
-    };
-
     // Log player attack
     state.combat_log.push(CombatLogEntry {
         turn: state.player.turns_elapsed,
@@ -141,50 +289,30 @@ pub fn execute_player_attack(state: &mut WorldState) -> CombatResult {
         ),
         line_type: LineType::Combat,
     });
+    messages.extend(equipment::wear_equipment(state, &[EquipSlot::Weapon]));
 
-    // Check if enemy is dead
-    if new_hp <= 0 {
-        if let Some(npc) = state.npcs.get_mut(&enemy_id) {
-            npc.dialogue_state = DialogueState::Dead;
-            npc.hostile = false;
-        }
-
-        // Drop items
-        let dropped_items: Vec<String> = state
-            .npcs
-            .get(&enemy_id)
-            .map(|n| n.items.clone())
-            .unwrap_or_default();
-
-        if let Some(loc) = state.locations.get_mut(&state.player.location) {
-            for item_id in &dropped_items {
-                if !loc.items.contains(item_id) {
-                    loc.items.push(item_id.clone());
-                }
-            }
-            loc.npcs.retain(|id| id != &enemy_id);
-        }
+    let new_hp = if new_hp > 0 {
+        apply_weapon_effects(state, &enemy_id, &enemy.name, damage, &mut messages)
+    } else {
+        new_hp
+    };
 
-        if !dropped_items.is_empty() {
-            let names: Vec<String> = dropped_items
-                .iter()
-                .filter_map(|id| state.items.get(id).map(|i| i.name.clone()))
-                .collect();
-            if !names.is_empty() {
-                messages.push(OutputLine {
-                    text: format!("Dropped: {}", names.join(", ")),
-                    line_type: LineType::System,
-                });
-            }
-        }
+    let new_hp = if new_hp > 0 {
+        companions_strike(state, &enemy_id, new_hp, &mut messages)
+    } else {
+        new_hp
+    };
 
-        messages.push(OutputLine {
-            text: crate::engine::templates::describe_combat_victory(&enemy.name),
-            line_type: LineType::Combat,
-        });
+    let action_type = ActionType::CombatAttack {
+        damage,
+        target_name: enemy.name.clone(),
+        target_hp: new_hp,
+        target_max_hp: enemy.max_health,
+    };
 
-        state.game_mode = GameMode::Exploring;
-        state.combat_state = None;
+    // Check if enemy is dead
+    if new_hp <= 0 {
+        defeat_enemy(state, &enemy_id, &mut messages);
 
         return CombatResult {
             messages,
@@ -197,8 +325,31 @@ pub fn execute_player_attack(state: &mut WorldState) -> CombatResult {
         };
     }
 
-    // Enemy's turn
-    let player_def = get_player_defense(&state.player, &state.items);
+    enemy_turn(state, &enemy, action_type, messages)
+}
+
+/// The enemy's half of a round. It may go for a companion instead of the
+/// player.
+fn enemy_turn(
+    state: &mut WorldState,
+    enemy: &Npc,
+    action_type: ActionType,
+    mut messages: Vec<OutputLine>,
+) -> CombatResult {
+    if let Some(companion_id) = enemy_target(state) {
+        enemy_strikes_companion(enemy, &companion_id, state, &mut messages);
+        if let Some(cs) = &mut state.combat_state {
+            cs.turn_count += 1;
+        }
+        return CombatResult {
+            messages,
+            action_type,
+            enemy_defeated: false,
+            player_died: false,
+            fled: false,
+        };
+    }
+    let player_def = get_player_defense(state);
     let (enemy_damage, enemy_crit) = calculate_damage(enemy.attack, player_def, difficulty_enemy_multiplier(&state.difficulty));
     state.player.health = (state.player.health - enemy_damage).max(0);
 
@@ -225,6 +376,7 @@ pub fn execute_player_attack(state: &mut WorldState) -> CombatResult {
         ),
         line_type: LineType::Combat,
     });
+    messages.extend(equipment::wear_equipment(state, &equipment::ARMOR_SLOTS));
 
     if state.player.health <= 0 {
         state.game_mode = GameMode::GameOver(EndingType::Death);
@@ -255,6 +407,85 @@ pub fn execute_player_attack(state: &mut WorldState) -> CombatResult {
     }
 }
 
+/// Companions fighting beside the player each strike the enemy. Returns
+/// the enemy's health afterwards.
+fn companions_strike(
+    state: &mut WorldState,
+    enemy_id: &str,
+    mut enemy_hp: i32,
+    messages: &mut Vec<OutputLine>,
+) -> i32 {
+    for companion_id in companions::following(state) {
+        let (Some(companion), Some(enemy)) =
+            (state.npcs.get(&companion_id), state.npcs.get(enemy_id))
+        else {
+            continue;
+        };
+        if enemy_hp <= 0 {
+            break;
+        }
+        let attack = companion.attack + companions::gear_bonus(companion, state).attack;
+        let (damage, critical) = calculate_damage(attack, enemy.defense, 1.0);
+        enemy_hp = (enemy_hp - damage).max(0);
+        messages.push(OutputLine {
+            text: crate::engine::templates::describe_combat_attack(
+                &companion.name,
+                &enemy.name,
+                damage,
+                critical,
+                enemy_hp,
+            ),
+            line_type: LineType::Combat,
+        });
+        if let Some(npc) = state.npcs.get_mut(enemy_id) {
+            npc.health = enemy_hp;
+        }
+    }
+    enemy_hp
+}
+
+/// Picks who the enemy swings at: the player or, as often, any one of the
+/// companions beside them. `None` means the player.
+fn enemy_target(state: &WorldState) -> Option<String> {
+    let followers = companions::following(state);
+    if followers.is_empty() {
+        return None;
+    }
+    let pick = rand::thread_rng().gen_range(0..=followers.len());
+    followers.get(pick).cloned()
+}
+
+fn enemy_strikes_companion(
+    enemy: &Npc,
+    companion_id: &str,
+    state: &mut WorldState,
+    messages: &mut Vec<OutputLine>,
+) {
+    let Some(companion) = state.npcs.get(companion_id).cloned() else {
+        return;
+    };
+    let defense = companion.defense + companions::gear_bonus(&companion, state).defense;
+    let multiplier = difficulty_enemy_multiplier(&state.difficulty);
+    let (damage, critical) = calculate_damage(enemy.attack, defense, multiplier);
+    let hp = (companion.health - damage).max(0);
+    if let Some(npc) = state.npcs.get_mut(companion_id) {
+        npc.health = hp;
+    }
+    messages.push(OutputLine {
+        text: crate::engine::templates::describe_combat_attack(
+            &enemy.name,
+            &companion.name,
+            damage,
+            critical,
+            hp,
+        ),
+        line_type: LineType::Combat,
+    });
+    if hp <= 0 {
+        messages.extend(companions::companion_dies(companion_id, state));
+    }
+}
+
 pub fn execute_flee(state: &mut WorldState) -> CombatResult {
     let enemy_id = match &state.game_mode {
         GameMode::InCombat(id) => id.clone(),
@@ -285,9 +516,9 @@ pub fn execute_flee(state: &mut WorldState) -> CombatResult {
         });
 
         // Enemy gets a free attack
-
+        let mut struck = false;
         if let Some(enemy) = state.npcs.get(&enemy_id) {
-            let player_def = get_player_defense(&state.player, &state.items);
+            let player_def = get_player_defense(state);
             let (damage, critical) = calculate_damage(enemy.attack, player_def, difficulty_enemy_multiplier(&state.difficulty));
             state.player.health = (state.player.health - damage).max(0);
             messages.push(OutputLine {
@@ -296,6 +527,10 @@ pub fn execute_flee(state: &mut WorldState) -> CombatResult {
                 ),
                 line_type: LineType::Combat,
             });
+            struck = true;
+        }
+        if struck {
+            messages.extend(equipment::wear_equipment(state, &equipment::ARMOR_SLOTS));
         }
 
         if state.player.health <= 0 {
@@ -334,10 +569,16 @@ pub fn execute_flee(state: &mut WorldState) -> CombatResult {
 
     let current_loc = state.player.location.clone();
     if let Some(loc) = state.locations.get(&current_loc) {
-        let exits: Vec<String> = loc.exits.values().cloned().collect();
+        let exits: Vec<String> = loc
+            .exits
+            .iter()
+            .filter(|(dir, _)| !doors::is_barred(loc, dir, state))
+            .map(|(_, dest)| dest.clone())
+            .collect();
         if !exits.is_empty() {
             let idx = rng.gen_range(0..exits.len());
             let new_loc = &exits[idx];
+            let followers = companions::following(state);
             state.player.location = new_loc.clone();
             state.player.visited_locations.insert(new_loc.clone());
             if let Some(new_location) = state.locations.get_mut(new_loc) {
@@ -349,6 +590,7 @@ pub fn execute_flee(state: &mut WorldState) -> CombatResult {
                     line_type: LineType::System,
                 });
             }
+            messages.extend(companions::follow_player(&current_loc, new_loc, &followers, state));
         } else {
             messages.push(OutputLine {
                 text: "You break free from combat but there's nowhere to run!".into(),
@@ -388,6 +630,7 @@ mod tests {
                 ambient_mood: Mood::Dangerous,
                 examine_details: None,
                 revisit_description: None,
+                ..Default::default()
             },
         );
         state.locations.insert(
@@ -405,6 +648,7 @@ mod tests {
                 ambient_mood: Mood::Peaceful,
                 examine_details: None,
                 revisit_description: None,
+                ..Default::default()
             },
         );
         state.npcs.insert(
@@ -425,6 +669,7 @@ mod tests {
                 examine_text: None,
                 relationship: 0,
                 memory: vec![],
+                ..Default::default()
             },
         );
         state.items.insert(
@@ -439,6 +684,7 @@ mod tests {
                 consumable: false,
                 key_id: None,
                 lore: None,
+                ..Default::default()
             },
         );
         state.player.location = "arena".into();
@@ -471,4 +717,114 @@ mod tests {
         assert_eq!(state.game_mode, GameMode::Exploring);
         assert!(state.combat_state.is_none());
     }
+
+    #[test]
+    fn weapon_specials_poison_and_drain() {
+        let mut state = make_combat_state();
+        state.items.insert(
+            "fang".into(),
+            Item {
+                id: "fang".into(),
+                name: "Vampiric Fang".into(),
+                description: "It thirsts.".into(),
+                item_type: ItemType::Weapon,
+                modifier: None,
+                usable: false,
+                consumable: false,
+                key_id: None,
+                lore: None,
+                rarity: Rarity::Rare,
+                special_effects: vec![
+                    SpecialEffect::Poison {
+                        damage_per_turn: 2,
+                        turns: 3,
+                    },
+                    SpecialEffect::Lifesteal { percent: 50 },
+                ],
+                ..Default::default()
+            },
+        );
+        state.player.equipped_weapon = Some("fang".into());
+        state.npcs.get_mut("goblin").unwrap().health = 500;
+        state.npcs.get_mut("goblin").unwrap().max_health = 500;
+        state.player.health = 50;
+
+        let result = execute_player_attack(&mut state);
+        let goblin = state.npcs.get("goblin").unwrap();
+        assert_eq!(goblin.status_effects.len(), 1);
+        assert_eq!(goblin.status_effects[0].effect_type, StatusEffectType::Poison);
+        assert!(result.messages.iter().any(|m| m.text.contains("poisoned")));
+        assert!(result.messages.iter().any(|m| m.text.contains("You drain")));
+
+        // The next swing ticks the poison before refreshing it
+        let result = execute_player_attack(&mut state);
+        assert!(result.messages.iter().any(|m| m.text.contains("2 poison damage")));
+    }
+
+    #[test]
+    fn gear_wears_down_and_breaks() {
+        let mut state = make_combat_state();
+        state.durability_enabled = true;
+        for (id, item_type, durability) in [
+            ("club", ItemType::Weapon, 3),
+            ("vest", ItemType::Armor, 8),
+        ] {
+            let mut item = state.items["gold_coin"].clone();
+            item.id = id.into();
+            item.name = id.into();
+            item.item_type = item_type;
+            item.modifier = Some(StatModifier {
+                attack: if item_type == ItemType::Weapon { 50 } else { 0 },
+                defense: 50,
+                health: 0,
+            });
+            item.durability = Some(Durability {
+                current: durability,
+                max: 8,
+            });
+            state.items.insert(id.into(), item);
+        }
+        state.player.equipped_weapon = Some("club".into());
+        state.player.equipped_armor = Some("vest".into());
+        state.npcs.get_mut("goblin").unwrap().health = 10_000;
+        state.npcs.get_mut("goblin").unwrap().max_health = 10_000;
+        let base_attack = state.player.attack;
+        let worn = |state: &WorldState, slot| {
+            let id = state.player.equipped(slot).unwrap();
+            state.items[id].durability.unwrap().current
+        };
+
+        let first = execute_player_attack(&mut state);
+        assert!(first.messages.iter().any(|m| m.text.contains("close to breaking")));
+        execute_player_attack(&mut state);
+        let third = execute_player_attack(&mut state);
+        assert!(third.messages.iter().any(|m| m.text.contains("club breaks")));
+        assert_eq!(get_player_attack(&state), base_attack);
+        assert_eq!(worn(&state, EquipSlot::Armor), 5);
+
+        // Broken gear stays broken rather than wrapping around
+        execute_player_attack(&mut state);
+        assert_eq!(worn(&state, EquipSlot::Weapon), 0);
+
+        // Only the equipped copies wore; the items they came from are as new
+        assert_eq!(state.items["club"].durability.unwrap().current, 3);
+        assert_eq!(state.items["vest"].durability.unwrap().current, 8);
+        let club = state.player.equipped_weapon.clone().unwrap();
+        assert_eq!(equipment::base_id(&club, &state), "club");
+    }
+
+    #[test]
+    fn gear_does_not_wear_when_durability_disabled() {
+        let mut state = make_combat_state();
+        let mut club = state.items["gold_coin"].clone();
+        club.id = "club".into();
+        club.item_type = ItemType::Weapon;
+        club.durability = Some(Durability { current: 1, max: 1 });
+        state.items.insert("club".into(), club);
+        state.player.equipped_weapon = Some("club".into());
+        state.npcs.get_mut("goblin").unwrap().health = 10_000;
+
+        execute_player_attack(&mut state);
+        assert_eq!(state.items["club"].durability.unwrap().current, 1);
+    }
 }
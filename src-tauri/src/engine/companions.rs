@@ -0,0 +1,278 @@
+use crate::engine::{conditions, equipment, factions};
+use crate::models::*;
+
+/// How many companions can travel with the player at once.
+pub const MAX_COMPANIONS: usize = 3;
+
+pub fn is_companion(npc_id: &str, state: &WorldState) -> bool {
+    state.companions.iter().any(|c| c.npc_id == npc_id)
+}
+
+/// Companions standing with the player and not told to wait.
+pub fn following(state: &WorldState) -> Vec<String> {
+    let here = state.locations.get(&state.player.location);
+    state
+        .companions
+        .iter()
+        .filter(|c| !c.waiting && here.is_some_and(|l| l.npcs.contains(&c.npc_id)))
+        .filter(|c| {
+            state
+                .npcs
+                .get(&c.npc_id)
+                .is_some_and(|n| n.dialogue_state != DialogueState::Dead)
+        })
+        .map(|c| c.npc_id.clone())
+        .collect()
+}
+
+/// Names of the companions with the player, for the narrator.
+pub fn companion_names(state: &WorldState) -> Vec<String> {
+    following(state)
+        .iter()
+        .filter_map(|id| state.npcs.get(id))
+        .map(|n| n.name.clone())
+        .collect()
+}
+
+/// Adds an NPC to the party. Errors explain why the NPC won't come.
+pub fn recruit(npc_id: &str, state: &mut WorldState) -> Result<String, String> {
+    let npc = state
+        .npcs
+        .get(npc_id)
+        .ok_or("There's no one like that here.")?;
+    if is_companion(npc_id, state) {
+        return Err(format!("{} is already with you.", npc.name));
+    }
+    if npc.hostile || npc.dialogue_state == DialogueState::Dead || !npc.recruitable {
+        return Err(format!("{} won't join you.", npc.name));
+    }
+    if !conditions::holds(npc.recruit_condition.as_ref(), state)
+        || !factions::will_deal(npc_id, state)
+    {
+        return Err(format!("{} isn't ready to follow you yet.", npc.name));
+    }
+    if state.companions.len() >= MAX_COMPANIONS {
+        return Err("Your party is full.".into());
+    }
+    let name = npc.name.clone();
+    state.companions.push(Companion {
+        npc_id: npc_id.to_string(),
+        waiting: false,
+    });
+    Ok(format!("{} joins you.", name))
+}
+
+/// Sends a companion on their way; they stay where they are.
+pub fn dismiss(npc_id: &str, state: &mut WorldState) -> Result<String, String> {
+    let name = npc_name(npc_id, state);
+    if !is_companion(npc_id, state) {
+        return Err(format!("{} isn't travelling with you.", name));
+    }
+    state.companions.retain(|c| c.npc_id != npc_id);
+    Ok(format!("{} parts ways with you.", name))
+}
+
+/// Tells a companion to wait here, or to follow again.
+pub fn set_waiting(npc_id: &str, waiting: bool, state: &mut WorldState) -> Result<String, String> {
+    let name = npc_name(npc_id, state);
+    let Some(companion) = state.companions.iter_mut().find(|c| c.npc_id == npc_id) else {
+        return Err(format!("{} isn't travelling with you.", name));
+    };
+    companion.waiting = waiting;
+    Ok(if waiting {
+        format!("{} settles in to wait for you.", name)
+    } else {
+        format!("{} falls in behind you.", name)
+    })
+}
+
+/// Hands an item from the player's inventory to a companion.
+pub fn give(item_id: &str, npc_id: &str, state: &mut WorldState) -> Result<String, String> {
+    let name = npc_name(npc_id, state);
+    if !is_companion(npc_id, state) {
+        return Err(format!("{} doesn't want it.", name));
+    }
+    let Some(pos) = equipment::carried_position(item_id, state) else {
+        return Err("You don't have that.".into());
+    };
+    let item_id = state.player.inventory.remove(pos);
+    if !state.player.inventory.contains(&item_id) {
+        state.player.unequip_item(&item_id);
+    }
+    if let Some(npc) = state.npcs.get_mut(npc_id) {
+        npc.items.push(item_id.clone());
+    }
+    let item_name = state
+        .items
+        .get(&item_id)
+        .map_or(item_id.as_str(), |i| i.name.as_str());
+    Ok(format!("You give the {} to {}.", item_name, name))
+}
+
+/// Weapon and armor bonuses from the items a companion carries.
+pub fn gear_bonus(npc: &Npc, state: &WorldState) -> StatModifier {
+    let mut bonus = StatModifier {
+        attack: 0,
+        defense: 0,
+        health: 0,
+    };
+    for item in npc.items.iter().filter_map(|id| state.items.get(id)) {
+        if let (ItemType::Weapon | ItemType::Armor, Some(modifier)) =
+            (&item.item_type, &item.modifier)
+        {
+            bonus.attack += modifier.attack;
+            bonus.defense += modifier.defense;
+        }
+    }
+    bonus
+}
+
+/// Moves the companions following the player from `from` to `to`.
+pub fn follow_player(
+    from: &str,
+    to: &str,
+    followers: &[String],
+    state: &mut WorldState,
+) -> Vec<OutputLine> {
+    let mut messages = Vec::new();
+    for npc_id in followers {
+        if let Some(loc) = state.locations.get_mut(from) {
+            loc.npcs.retain(|id| id != npc_id);
+        }
+        if let Some(dest) = state.locations.get_mut(to) {
+            if !dest.npcs.contains(npc_id) {
+                dest.npcs.push(npc_id.clone());
+            }
+        }
+        if let Some(npc) = state.npcs.get(npc_id) {
+            messages.push(OutputLine {
+                text: format!("{} follows you.", npc.name),
+                line_type: LineType::System,
+            });
+        }
+    }
+    messages
+}
+
+/// A companion fell in battle: they drop what they carried and leave the
+/// party.
+pub fn companion_dies(npc_id: &str, state: &mut WorldState) -> Vec<OutputLine> {
+    let name = npc_name(npc_id, state);
+    let dropped = match state.npcs.get_mut(npc_id) {
+        Some(npc) => {
+            npc.dialogue_state = DialogueState::Dead;
+            std::mem::take(&mut npc.items)
+        }
+        None => Vec::new(),
+    };
+    if let Some(loc) = state.locations.get_mut(&state.player.location) {
+        loc.npcs.retain(|id| id != npc_id);
+        loc.items.extend(dropped);
+    }
+    state.companions.retain(|c| c.npc_id != npc_id);
+    vec![OutputLine {
+        text: format!("{} falls!", name),
+        line_type: LineType::Combat,
+    }]
+}
+
+/// Lines for the stats view.
+pub fn describe_party(state: &WorldState) -> Vec<String> {
+    state
+        .companions
+        .iter()
+        .filter_map(|c| state.npcs.get(&c.npc_id).map(|n| (c, n)))
+        .map(|(companion, npc)| {
+            let mut line = format!("  {} (HP: {}/{})", npc.name, npc.health, npc.max_health);
+            if companion.waiting {
+                let at = state
+                    .locations
+                    .values()
+                    .find(|l| l.npcs.contains(&npc.id))
+                    .map_or("somewhere", |l| l.name.as_str());
+                line.push_str(&format!(" — waiting in {}", at));
+            }
+            line
+        })
+        .collect()
+}
+
+fn npc_name(npc_id: &str, state: &WorldState) -> String {
+    state
+        .npcs
+        .get(npc_id)
+        .map_or_else(|| npc_id.to_string(), |n| n.name.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::world_builder;
+
+    #[test]
+    fn recruiting_needs_the_npc_to_be_willing() {
+        let mut state = world_builder::build_thornhold();
+        state.player.location = "kitchen".into();
+        assert_eq!(
+            recruit("merchant_ghost", &mut state).unwrap_err(),
+            "The Dead Merchant won't join you."
+        );
+        // Gristle only comes along once the rat's request is done
+        assert_eq!(
+            recruit("gristle_rat", &mut state).unwrap_err(),
+            "Gristle isn't ready to follow you yet."
+        );
+        state.quests.get_mut("rats_request").unwrap().completed = true;
+        assert_eq!(
+            recruit("gristle_rat", &mut state).unwrap(),
+            "Gristle joins you."
+        );
+        assert!(recruit("gristle_rat", &mut state).is_err());
+
+        assert_eq!(companion_names(&state), vec!["Gristle"]);
+        assert!(dismiss("gristle_rat", &mut state).is_ok());
+        assert!(state.companions.is_empty());
+    }
+
+    #[test]
+    fn waiting_companions_do_not_follow() {
+        let mut state = world_builder::build_thornhold();
+        state.player.location = "kitchen".into();
+        state.companions.push(Companion {
+            npc_id: "gristle_rat".into(),
+            waiting: false,
+        });
+        assert_eq!(following(&state), vec!["gristle_rat"]);
+
+        set_waiting("gristle_rat", true, &mut state).unwrap();
+        assert!(following(&state).is_empty());
+        assert_eq!(
+            describe_party(&state),
+            vec!["  Gristle (HP: 5/5) — waiting in The Kitchen"]
+        );
+    }
+
+    #[test]
+    fn gifts_arm_companions_and_drop_when_they_fall() {
+        let mut state = world_builder::build_thornhold();
+        state.player.location = "kitchen".into();
+        state.companions.push(Companion {
+            npc_id: "gristle_rat".into(),
+            waiting: false,
+        });
+        state.player.inventory.push("short_sword".into());
+        state.player.equipped_weapon = Some("short_sword".into());
+
+        give("short_sword", "gristle_rat", &mut state).unwrap();
+        assert!(state.player.equipped_weapon.is_none());
+        let bonus = gear_bonus(&state.npcs["gristle_rat"], &state);
+        assert!(bonus.attack > 0);
+
+        let msgs = companion_dies("gristle_rat", &mut state);
+        assert_eq!(msgs[0].text, "Gristle falls!");
+        assert!(state.companions.is_empty());
+        assert!(state.locations["kitchen"]
+            .items
+            .contains(&"short_sword".to_string()));
+    }
+}
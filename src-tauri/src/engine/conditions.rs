@@ -0,0 +1,107 @@
+use crate::engine::equipment;
+use crate::models::*;
+
+/// Evaluates a condition against the current world state. Unknown ids
+/// evaluate as false; module validation rejects them up front.
+pub fn evaluate(condition: &Condition, state: &WorldState) -> bool {
+    let player = &state.player;
+    match condition {
+        Condition::Flag(flag) => player.quest_flags.get(flag).copied().unwrap_or(false),
+        Condition::HasItem(item_id) => equipment::carried_position(item_id, state).is_some(),
+        Condition::HealthAtLeast(value) => player.health >= *value,
+        Condition::HealthBelow(value) => player.health < *value,
+        Condition::TurnBetween(from, to) => (*from..=*to).contains(&player.turns_elapsed),
+        Condition::TimeOfDay(phase) => state.clock.phase() == *phase,
+        Condition::NpcState(npc_id, dialogue_state) => state
+            .npcs
+            .get(npc_id)
+            .is_some_and(|npc| npc.dialogue_state == *dialogue_state),
+        Condition::RelationshipAtLeast(npc_id, value) => state
+            .npcs
+            .get(npc_id)
+            .is_some_and(|npc| npc.relationship >= *value),
+        Condition::ReputationAtLeast(faction_id, value) => state
+            .factions
+            .get(faction_id)
+            .is_some_and(|faction| faction.reputation >= *value),
+        Condition::Visited(location_id) => player.visited_locations.contains(location_id),
+        Condition::QuestCompleted(quest_id) => {
+            state.quests.get(quest_id).is_some_and(|quest| quest.completed)
+        }
+        Condition::AtLocation(location_id) => player.location == *location_id,
+        Condition::All(terms) => terms.iter().all(|term| evaluate(term, state)),
+        Condition::Any(terms) => terms.iter().any(|term| evaluate(term, state)),
+        Condition::Not(inner) => !evaluate(inner, state),
+    }
+}
+
+/// Whether an optional condition is absent or holds.
+pub fn holds(condition: Option<&Condition>, state: &WorldState) -> bool {
+    condition.is_none_or(|c| evaluate(c, state))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::world_builder;
+
+    #[test]
+    fn leaf_conditions() {
+        let mut state = world_builder::build_thornhold();
+        state.player.health = 40;
+        state.player.turns_elapsed = 12;
+        state.player.quest_flags.insert("seal_broken".into(), true);
+        state.player.inventory.push("torch".into());
+
+        assert!(evaluate(&Condition::Flag("seal_broken".into()), &state));
+        assert!(!evaluate(&Condition::Flag("other".into()), &state));
+        assert!(evaluate(&Condition::HasItem("torch".into()), &state));
+        assert!(evaluate(&Condition::HealthAtLeast(40), &state));
+        assert!(!evaluate(&Condition::HealthBelow(40), &state));
+        assert!(evaluate(&Condition::TurnBetween(10, 12), &state));
+        assert!(!evaluate(&Condition::TurnBetween(13, 20), &state));
+        assert!(evaluate(&Condition::TimeOfDay(DayPhase::Day), &state));
+        assert!(!evaluate(&Condition::TimeOfDay(DayPhase::Night), &state));
+        assert!(evaluate(
+            &Condition::NpcState("the_warden".into(), DialogueState::Hostile),
+            &state
+        ));
+        assert!(evaluate(
+            &Condition::RelationshipAtLeast("the_warden".into(), 0),
+            &state
+        ));
+        assert!(evaluate(
+            &Condition::ReputationAtLeast("faithful_dead".into(), 0),
+            &state
+        ));
+        assert!(!evaluate(
+            &Condition::ReputationAtLeast("wardens_host".into(), -50),
+            &state
+        ));
+        assert!(!evaluate(&Condition::Visited("chapel".into()), &state));
+        assert!(evaluate(&Condition::AtLocation("courtyard".into()), &state));
+    }
+
+    #[test]
+    fn compound_conditions() {
+        let mut state = world_builder::build_thornhold();
+        state.player.health = 10;
+        let low_and_unflagged = Condition::All(vec![
+            Condition::HealthBelow(20),
+            Condition::Not(Box::new(Condition::Flag("blessed".into()))),
+        ]);
+        assert!(evaluate(&low_and_unflagged, &state));
+
+        state.player.quest_flags.insert("blessed".into(), true);
+        assert!(!evaluate(&low_and_unflagged, &state));
+
+        let either = Condition::Any(vec![
+            Condition::HealthAtLeast(50),
+            Condition::Flag("blessed".into()),
+        ]);
+        assert!(evaluate(&either, &state));
+        assert!(evaluate(&Condition::All(vec![]), &state));
+        assert!(!evaluate(&Condition::Any(vec![]), &state));
+        assert!(holds(None, &state));
+    }
+}
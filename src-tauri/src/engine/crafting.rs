@@ -1,3 +1,4 @@
+use crate::engine::{equipment, quest, templates};
 use crate::models::*;
 
 pub fn execute_craft(
@@ -26,12 +27,17 @@ pub fn execute_craft(
         return craft_error("You can't combine an item with itself.");
     }
 
-    // Check recipes
+    // Check recipes; worn copies count as the item they were split off from
+    let first_base = equipment::base_id(&first_id, state).to_string();
+    let second_base = equipment::base_id(&second_id, state).to_string();
     let recipe = state.recipes.iter().position(|r| {
-        r.inputs.contains(&first_id) && r.inputs.contains(&second_id) && r.inputs.len() == 2
+        r.inputs.contains(&first_base) && r.inputs.contains(&second_base) && r.inputs.len() == 2
     });
 
     match recipe {
+        Some(idx) if state.recipes[idx].repairs => {
+            repair_with_recipe(idx, &first_id, &second_id, state)
+        }
         Some(idx) => {
             let output_id = state.recipes[idx].output.clone();
             let recipe_id = state.recipes[idx].id.clone();
@@ -47,6 +53,7 @@ pub fn execute_craft(
 
             // Add output to inventory
             state.player.inventory.push(output_id.clone());
+            quest::record_activity(quest::QuestActivity::Crafted(&recipe_id), state);
 
             let output_name = state
                 .items
@@ -107,6 +114,56 @@ pub fn execute_craft(
     }
 }
 
+/// Restores the recipe's output item to full durability, consuming the other input.
+fn repair_with_recipe(
+    idx: usize,
+    first_id: &str,
+    second_id: &str,
+    state: &mut WorldState,
+) -> ActionResult {
+    let output_id = &state.recipes[idx].output;
+    let (target_id, material_id) = if equipment::base_id(first_id, state) == output_id {
+        (first_id.to_string(), second_id)
+    } else {
+        (second_id.to_string(), first_id)
+    };
+    let Some(target) = state.items.get(&target_id).cloned() else {
+        return craft_error("Those items can't be combined into anything useful.");
+    };
+    if target.durability.is_none_or(|d| d.current >= d.max) {
+        return craft_error(&format!("The {} doesn't need repairing.", target.name));
+    }
+
+    state.recipes[idx].discovered = true;
+    if let Some(pos) = state.player.inventory.iter().position(|x| x == material_id) {
+        state.player.inventory.remove(pos);
+    }
+    equipment::repair_item(state, &target_id, None);
+
+    let mut messages = Vec::new();
+    if let Some(material) = state.items.get(material_id) {
+        messages.push(OutputLine {
+            text: format!("You work the {} with the {}.", target.name, material.name),
+            line_type: LineType::System,
+        });
+    }
+    if let Some(durability) = state.items.get(&target_id).and_then(|i| i.durability) {
+        messages.push(OutputLine {
+            text: templates::describe_repair(&target.name, &durability),
+            line_type: LineType::System,
+        });
+    }
+    ActionResult {
+        messages,
+        action_type: ActionType::ItemUsed {
+            item_name: target.name,
+            effect: "repaired".into(),
+        },
+        narrative_context: None,
+        sound_cues: vec![SoundCue::ItemUse],
+    }
+}
+
 fn find_inventory_item(name: &str, state: &WorldState) -> Option<String> {
     let name_lower = name.to_lowercase();
     state.player.inventory.iter().find(|id| {
@@ -301,4 +358,35 @@ mod tests {
         let result = execute_craft("sword", None, &mut state);
         assert!(result.messages[0].text.contains("with what?"));
     }
+
+    #[test]
+    fn repair_recipe_restores_item_and_consumes_material() {
+        let mut state = world_builder::build_thornhold();
+        state.player.inventory = vec!["short_sword".into(), "whetstone".into()];
+        let result = execute_craft("short sword", Some("whetstone"), &mut state);
+        assert!(result.messages[0].text.contains("doesn't need repairing"));
+
+        state.items.get_mut("short_sword").unwrap().durability =
+            Some(Durability { current: 3, max: 40 });
+        execute_craft("whetstone", Some("short sword"), &mut state);
+        assert_eq!(state.items["short_sword"].durability.unwrap().current, 40);
+        assert_eq!(state.player.inventory, vec!["short_sword".to_string()]);
+    }
+
+    #[test]
+    fn repair_recipe_mends_worn_copies() {
+        let mut state = world_builder::build_thornhold();
+        let mut copy = state.items["short_sword"].clone();
+        copy.id = "short_sword_copy1".into();
+        copy.durability = Some(Durability { current: 3, max: 40 });
+        state.items.insert(copy.id.clone(), copy);
+        state
+            .item_instances
+            .insert("short_sword_copy1".into(), "short_sword".into());
+        state.player.inventory = vec!["short_sword_copy1".into(), "whetstone".into()];
+
+        execute_craft("whetstone", Some("short sword"), &mut state);
+        assert_eq!(state.items["short_sword_copy1"].durability.unwrap().current, 40);
+        assert_eq!(state.player.inventory, vec!["short_sword_copy1".to_string()]);
+    }
 }
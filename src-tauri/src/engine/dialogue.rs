@@ -1,3 +1,6 @@
+use rand::Rng;
+
+use crate::engine::{combat, events, random};
 use crate::models::*;
 
 pub struct DialogueResult {
@@ -65,6 +68,15 @@ pub fn enter_dialogue(npc_id: &str, state: &mut WorldState) -> DialogueResult {
         }
     }
 
+    crate::engine::quest::record_activity(
+        crate::engine::quest::QuestActivity::TalkedTo(npc_id),
+        state,
+    );
+
+    if state.dialogues.contains_key(npc_id) {
+        return enter_tree(npc_id, state);
+    }
+
     // Re-read npc after mutation for greeting text
     let npc = state.npcs.get(npc_id).cloned().unwrap();
 
@@ -76,10 +88,15 @@ pub fn enter_dialogue(npc_id: &str, state: &mut WorldState) -> DialogueResult {
         line_type: LineType::Dialogue,
     }];
 
-    // If quest giver in Greeting state, auto-transition to QuestOffered (only if quest exists)
+    // If quest giver in Greeting state, auto-transition to QuestOffered (only if quest
+    // exists and its prerequisites are met)
     if let Some(quest_id) = &npc.quest_giver {
         if npc.dialogue_state == DialogueState::Greeting {
-            if let Some(quest) = state.quests.get(quest_id) {
+            if let Some(quest) = state
+                .quests
+                .get(quest_id)
+                .filter(|q| crate::engine::quest::prerequisites_met(q, state))
+            {
                 let quest_desc = quest.description.clone();
                 if let Some(npc_mut) = state.npcs.get_mut(npc_id) {
                     npc_mut.dialogue_state = DialogueState::QuestOffered;
@@ -135,6 +152,7 @@ pub fn process_dialogue_input(
     // Check for exit commands
     if matches!(input_lower.as_str(), "leave" | "goodbye" | "bye" | "exit" | "quit") {
         state.dialogue_history.clear();
+        state.dialogue_node = None;
         state.game_mode = GameMode::Exploring;
         let npc_name = state
             .npcs
@@ -163,6 +181,14 @@ pub fn process_dialogue_input(
         }
     };
 
+    // Numbered replies pick a choice in an authored tree; anything else is
+    // free text for the narrator
+    if state.dialogue_node.is_some() && state.dialogues.contains_key(npc_id) {
+        if let Ok(number) = input_lower.parse::<usize>() {
+            return choose(npc_id, number, state);
+        }
+    }
+
     match npc.dialogue_state {
         DialogueState::QuestOffered => {
             if matches!(input_lower.as_str(), "yes" | "y" | "accept" | "sure" | "ok") {
@@ -220,7 +246,7 @@ pub fn process_dialogue_input(
             // Give rewards
             if let Some(quest_id) = &npc.quest_giver {
                 if let Some(quest) = state.quests.get(quest_id) {
-                    let rewards = quest.reward.clone();
+                    let rewards = quest.rewards();
                     let mut dropped_rewards = Vec::new();
                     for item_id in &rewards {
                         if state.player.inventory.len() < state.player.max_inventory {
@@ -254,6 +280,11 @@ pub fn process_dialogue_input(
                         text: completion_text,
                         line_type: LineType::System,
                     }];
+                    messages.extend(crate::engine::factions::record_deed(
+                        npc_id,
+                        crate::engine::factions::Deed::Helped,
+                        state,
+                    ));
                     if !reward_names.is_empty() {
                         messages.push(OutputLine {
                             text: format!("You received: {}", reward_names.join(", ")),
@@ -275,6 +306,7 @@ pub fn process_dialogue_input(
                             });
                         }
                     }
+                    messages.extend(crate::engine::quest::start_follow_up(quest_id, state));
                     return DialogueResult {
                         messages,
                         action_type: ActionType::QuestCompleted { quest_name },
@@ -300,6 +332,206 @@ pub fn process_dialogue_input(
     }
 }
 
+fn authored_result(
+    npc_name: &str,
+    messages: Vec<OutputLine>,
+    exit_dialogue: bool,
+) -> DialogueResult {
+    DialogueResult {
+        messages,
+        action_type: ActionType::NpcDialogue {
+            npc_name: npc_name.to_string(),
+            dialogue_text: "authored".into(),
+        },
+        exit_dialogue,
+    }
+}
+
+fn end_conversation(npc_name: &str, state: &mut WorldState, messages: &mut Vec<OutputLine>) {
+    state.dialogue_history.clear();
+    state.dialogue_node = None;
+    if matches!(state.game_mode, GameMode::InDialogue(_)) {
+        state.game_mode = GameMode::Exploring;
+    }
+    messages.push(OutputLine {
+        text: format!("You end your conversation with {}.", npc_name),
+        line_type: LineType::System,
+    });
+}
+
+/// Choices of a node whose conditions hold, in authored order.
+fn visible_choices(node: &DialogueNode, state: &WorldState) -> Vec<DialogueChoice> {
+    node.choices
+        .iter()
+        .filter(|c| crate::engine::conditions::holds(c.condition.as_ref(), state))
+        .cloned()
+        .collect()
+}
+
+/// The player's bonus for a skill check.
+fn skill_bonus(skill: Skill, npc_id: &str, state: &WorldState) -> i32 {
+    match skill {
+        Skill::Might => combat::get_player_attack(state),
+        Skill::Resolve => combat::get_player_defense(state),
+        Skill::Charm => {
+            let faction = crate::engine::factions::npc_faction(npc_id, state)
+                .map_or(0, |f| f.reputation);
+            state.npcs.get(npc_id).map_or(0, |n| n.relationship + faction) / 10
+        }
+    }
+}
+
+/// Whether the conversation can go on after effects ran: the player is
+/// still talking and the NPC hasn't turned on them.
+fn conversation_continues(npc_id: &str, state: &WorldState) -> bool {
+    state.game_mode == GameMode::InDialogue(npc_id.to_string())
+        && state.npcs.get(npc_id).is_some_and(|n| !n.hostile)
+}
+
+/// Says a node's line, applies its effects and lists the replies. A node
+/// without replies ends the conversation; returns whether it ended.
+fn show_node(
+    npc_id: &str,
+    node_id: &str,
+    state: &mut WorldState,
+    messages: &mut Vec<OutputLine>,
+) -> bool {
+    let npc_name = state
+        .npcs
+        .get(npc_id)
+        .map(|n| n.name.clone())
+        .unwrap_or_default();
+    let Some(node) = state
+        .dialogues
+        .get(npc_id)
+        .and_then(|t| t.nodes.get(node_id))
+        .cloned()
+    else {
+        end_conversation(&npc_name, state, messages);
+        return true;
+    };
+    state.dialogue_node = Some(node_id.to_string());
+    messages.push(OutputLine {
+        text: format!("{}: \"{}\"", npc_name, node.text),
+        line_type: LineType::Dialogue,
+    });
+    let location_id = state.player.location.clone();
+    for action in node.effects {
+        events::apply_action(action, &location_id, state, messages);
+    }
+    if !conversation_continues(npc_id, state) {
+        state.dialogue_node = None;
+        return true;
+    }
+
+    let node = &state.dialogues[npc_id].nodes[node_id];
+    let choices = visible_choices(node, state);
+    if choices.is_empty() {
+        end_conversation(&npc_name, state, messages);
+        return true;
+    }
+    for (idx, choice) in choices.iter().enumerate() {
+        let check = choice
+            .skill_check
+            .as_ref()
+            .map(|c| format!("[{} {}] ", c.skill.display_name(), c.difficulty))
+            .unwrap_or_default();
+        messages.push(OutputLine {
+            text: format!("  {}. {}{}", idx + 1, check, choice.text),
+            line_type: LineType::System,
+        });
+    }
+    false
+}
+
+/// Opens an NPC's authored tree at its start node.
+fn enter_tree(npc_id: &str, state: &mut WorldState) -> DialogueResult {
+    let npc_name = state
+        .npcs
+        .get(npc_id)
+        .map(|n| n.name.clone())
+        .unwrap_or_default();
+    let start = state.dialogues[npc_id].start.clone();
+    state.game_mode = GameMode::InDialogue(npc_id.to_string());
+    let mut messages = Vec::new();
+    let ended = show_node(npc_id, &start, state, &mut messages);
+    authored_result(&npc_name, messages, ended)
+}
+
+/// Picks the numbered reply at the current node.
+fn choose(npc_id: &str, number: usize, state: &mut WorldState) -> DialogueResult {
+    let npc_name = state
+        .npcs
+        .get(npc_id)
+        .map(|n| n.name.clone())
+        .unwrap_or_default();
+    let node = state
+        .dialogue_node
+        .as_ref()
+        .and_then(|node_id| state.dialogues.get(npc_id)?.nodes.get(node_id));
+    let choices = node.map(|n| visible_choices(n, state)).unwrap_or_default();
+    let Some(choice) = number
+        .checked_sub(1)
+        .and_then(|idx| choices.get(idx))
+        .cloned()
+    else {
+        let text = format!("Choose a reply between 1 and {}.", choices.len());
+        return DialogueResult {
+            messages: vec![OutputLine {
+                text: text.clone(),
+                line_type: LineType::Error,
+            }],
+            action_type: ActionType::Error { message: text },
+            exit_dialogue: false,
+        };
+    };
+
+    let mut messages = vec![OutputLine {
+        text: format!("You: \"{}\"", choice.text),
+        line_type: LineType::PlayerInput,
+    }];
+    let mut passed = true;
+    if let Some(check) = &choice.skill_check {
+        let roll = random::world_rng(state).gen_range(1..=20);
+        let bonus = skill_bonus(check.skill, npc_id, state);
+        passed = roll + bonus >= check.difficulty;
+        messages.push(OutputLine {
+            text: format!(
+                "[{} check: {} + {} vs {} — {}]",
+                check.skill.display_name(),
+                roll,
+                bonus,
+                check.difficulty,
+                if passed { "success" } else { "failure" }
+            ),
+            line_type: LineType::System,
+        });
+    }
+
+    let next = if passed {
+        let location_id = state.player.location.clone();
+        for action in choice.effects {
+            events::apply_action(action, &location_id, state, &mut messages);
+        }
+        if !conversation_continues(npc_id, state) {
+            state.dialogue_node = None;
+            return authored_result(&npc_name, messages, true);
+        }
+        choice.next
+    } else {
+        choice.fail_next
+    };
+
+    let ended = match next {
+        Some(node_id) => show_node(npc_id, &node_id, state, &mut messages),
+        None => {
+            end_conversation(&npc_name, state, &mut messages);
+            true
+        }
+    };
+    authored_result(&npc_name, messages, ended)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -322,6 +554,7 @@ mod tests {
                 ambient_mood: Mood::Peaceful,
                 examine_details: None,
                 revisit_description: None,
+                ..Default::default()
             },
         );
         state.npcs.insert(
@@ -342,6 +575,7 @@ mod tests {
                 examine_text: None,
                 relationship: 0,
                 memory: vec![],
+                ..Default::default()
             },
         );
         state.quests.insert(
@@ -351,11 +585,12 @@ mod tests {
                 name: "Test Quest".into(),
                 description: "Find the thing.".into(),
                 giver: "merchant".into(),
-                objective: QuestObjective::FetchItem("thing".into()),
+                objective: Some(QuestObjective::FetchItem("thing".into())),
                 reward: vec![],
                 completed: false,
                 active: false,
                 completed_turn: None,
+                ..Default::default()
             },
         );
         state
@@ -378,6 +613,18 @@ mod tests {
         assert!(matches!(result.action_type, ActionType::QuestStarted { .. }));
     }
 
+    #[test]
+    fn quest_withheld_until_prerequisites_met() {
+        let mut state = make_dialogue_state();
+        state.quests.get_mut("test_quest").unwrap().required_flags = vec!["met_the_king".into()];
+        enter_dialogue("merchant", &mut state);
+        assert_eq!(state.npcs["merchant"].dialogue_state, DialogueState::Greeting);
+
+        state.player.quest_flags.insert("met_the_king".into(), true);
+        enter_dialogue("merchant", &mut state);
+        assert_eq!(state.npcs["merchant"].dialogue_state, DialogueState::QuestOffered);
+    }
+
     #[test]
     fn leave_dialogue() {
         let mut state = make_dialogue_state();
@@ -449,4 +696,114 @@ mod tests {
         assert!(state.dialogue_history.is_empty());
         assert_eq!(state.game_mode, GameMode::Exploring);
     }
+
+    fn add_merchant_tree(state: &mut WorldState) {
+        let choice = |text: &str, next: Option<&str>| DialogueChoice {
+            text: text.into(),
+            condition: None,
+            skill_check: None,
+            effects: vec![],
+            next: next.map(Into::into),
+            fail_next: None,
+        };
+        let mut friend = choice("A friend.", Some("friend"));
+        friend.effects = vec![EventAction::SetQuestFlag("friendly".into())];
+        let mut secrets = choice("Tell me your secrets.", Some("friend"));
+        secrets.skill_check = Some(SkillCheck {
+            skill: Skill::Charm,
+            difficulty: 40,
+        });
+        secrets.fail_next = Some("refused".into());
+        let mut thing = choice("I have the thing.", None);
+        thing.condition = Some(Condition::HasItem("thing".into()));
+
+        let node = |text: &str, choices: Vec<DialogueChoice>| DialogueNode {
+            text: text.into(),
+            effects: vec![],
+            choices,
+        };
+        state.dialogues.insert(
+            "merchant".into(),
+            DialogueTree {
+                start: "hello".into(),
+                nodes: HashMap::from([
+                    (
+                        "hello".to_string(),
+                        node("Who goes there?", vec![friend, secrets, thing]),
+                    ),
+                    ("friend".to_string(), node("Then be welcome.", vec![])),
+                    (
+                        "refused".to_string(),
+                        node("Never.", vec![choice("Goodbye.", None)]),
+                    ),
+                ]),
+            },
+        );
+    }
+
+    #[test]
+    fn dialogue_tree_lists_numbered_choices() {
+        let mut state = make_dialogue_state();
+        add_merchant_tree(&mut state);
+        let result = enter_dialogue("merchant", &mut state);
+        let texts: Vec<&str> = result.messages.iter().map(|m| m.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            vec![
+                "Merchant: \"Who goes there?\"",
+                "  1. A friend.",
+                "  2. [Charm 40] Tell me your secrets.",
+            ]
+        );
+        // The tree replaces the built-in quest offer
+        assert_eq!(state.npcs["merchant"].dialogue_state, DialogueState::Greeting);
+        assert_eq!(state.dialogue_node.as_deref(), Some("hello"));
+
+        state.player.inventory.push("thing".into());
+        let result = enter_dialogue("merchant", &mut state);
+        assert!(result.messages.iter().any(|m| m.text == "  3. I have the thing."));
+    }
+
+    #[test]
+    fn dialogue_choice_applies_effects_and_ends_at_leaf() {
+        let mut state = make_dialogue_state();
+        add_merchant_tree(&mut state);
+        enter_dialogue("merchant", &mut state);
+        let result = process_dialogue_input("1", "merchant", &mut state);
+        assert!(result.exit_dialogue);
+        assert!(result.messages.iter().any(|m| m.text == "Merchant: \"Then be welcome.\""));
+        assert_eq!(state.player.quest_flags.get("friendly"), Some(&true));
+        assert_eq!(state.game_mode, GameMode::Exploring);
+        assert!(state.dialogue_node.is_none());
+    }
+
+    #[test]
+    fn failed_skill_check_follows_fail_branch() {
+        let mut state = make_dialogue_state();
+        add_merchant_tree(&mut state);
+        enter_dialogue("merchant", &mut state);
+        let result = process_dialogue_input("2", "merchant", &mut state);
+        assert!(!result.exit_dialogue);
+        assert!(result
+            .messages
+            .iter()
+            .any(|m| m.text.starts_with("[Charm check:") && m.text.ends_with("failure]")));
+        assert_eq!(state.dialogue_node.as_deref(), Some("refused"));
+    }
+
+    #[test]
+    fn dialogue_tree_rejects_bad_numbers_and_passes_free_text() {
+        let mut state = make_dialogue_state();
+        add_merchant_tree(&mut state);
+        enter_dialogue("merchant", &mut state);
+        let result = process_dialogue_input("5", "merchant", &mut state);
+        assert_eq!(result.messages[0].text, "Choose a reply between 1 and 2.");
+        assert_eq!(state.dialogue_node.as_deref(), Some("hello"));
+
+        let result = process_dialogue_input("tell me of the castle", "merchant", &mut state);
+        assert!(matches!(
+            result.action_type,
+            ActionType::NpcDialogue { ref dialogue_text, .. } if dialogue_text == "tell me of the castle"
+        ));
+    }
 }
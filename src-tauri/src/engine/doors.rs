@@ -0,0 +1,261 @@
+use crate::engine::{combat, equipment, random, templates};
+use crate::models::*;
+
+/// Whether an exit is locked, by a door or by a seal in `locked_exits`.
+/// Wandering NPCs and fleeing players can't pass; closed doors they simply
+/// open.
+pub fn is_barred(loc: &Location, direction: &Direction, state: &WorldState) -> bool {
+    loc.locked_exits.contains_key(direction)
+        || loc
+            .doors
+            .get(direction)
+            .and_then(|id| state.doors.get(id))
+            .is_some_and(|door| door.state == DoorState::Locked)
+}
+
+/// Finds the door in the player's room that a command names, either by
+/// direction ("north door") or by name.
+pub fn find_door(target: &str, state: &WorldState) -> Option<String> {
+    let loc = state.locations.get(&state.player.location)?;
+    let mut doors: Vec<(&Direction, &String)> = loc
+        .doors
+        .iter()
+        .filter(|(dir, _)| loc.exits.contains_key(dir))
+        .collect();
+    doors.sort_by_key(|(dir, _)| dir.display_name());
+    let target = target.to_lowercase();
+    if let Some(dir) = target.split_whitespace().find_map(Direction::parse) {
+        return doors
+            .into_iter()
+            .find(|(d, _)| **d == dir)
+            .map(|(_, id)| id.clone());
+    }
+    doors
+        .into_iter()
+        .find(|(_, id)| {
+            state
+                .doors
+                .get(*id)
+                .is_some_and(|door| door.name.to_lowercase().contains(&target))
+        })
+        .map(|(_, id)| id.clone())
+}
+
+fn door(door_id: &str, state: &WorldState) -> Result<Door, String> {
+    state
+        .doors
+        .get(door_id)
+        .cloned()
+        .ok_or_else(|| "There is no such door.".to_string())
+}
+
+fn set_state(door_id: &str, new_state: DoorState, state: &mut WorldState) {
+    if let Some(door) = state.doors.get_mut(door_id) {
+        door.state = new_state;
+    }
+}
+
+/// Whether the door can be opened from the room the player stands in. This
+/// only bars opening: an open one-sided door lets the player through both
+/// ways.
+fn works_from_here(door: &Door, state: &WorldState) -> bool {
+    door.opens_from
+        .as_ref()
+        .is_none_or(|room| *room == state.player.location)
+}
+
+fn line(text: String) -> OutputLine {
+    OutputLine {
+        text,
+        line_type: LineType::System,
+    }
+}
+
+/// Opens a door, unlocking it first with its key if the player carries it.
+/// The key is used up.
+pub fn open(door_id: &str, state: &mut WorldState) -> Result<Vec<OutputLine>, String> {
+    let door = door(door_id, state)?;
+    if door.state == DoorState::Open {
+        return Err(format!("The {} is already open.", door.name));
+    }
+    if !works_from_here(&door, state) {
+        return Err(format!("The {} won't open from this side.", door.name));
+    }
+    let mut messages = Vec::new();
+    if door.state == DoorState::Locked {
+        let (key_id, pos) = door
+            .key_id
+            .as_ref()
+            .and_then(|id| Some((id, equipment::carried_position(id, state)?)))
+            .ok_or_else(|| templates::describe_door_locked(&door.name))?;
+        let key_name = state
+            .items
+            .get(key_id)
+            .map_or(key_id.as_str(), |item| item.name.as_str());
+        messages.push(line(format!("You use the {} to unlock the {}.", key_name, door.name)));
+        state.player.inventory.remove(pos);
+        state.pending_sound_cues.push(SoundCue::DoorUnlock);
+    }
+    set_state(door_id, DoorState::Open, state);
+    messages.push(line(format!("You open the {}.", door.name)));
+    Ok(messages)
+}
+
+/// Lets the player through the door in an exit of their room, opening it on
+/// the way if need be.
+pub fn pass(direction: Direction, state: &mut WorldState) -> Result<Vec<OutputLine>, String> {
+    let door_id = state
+        .locations
+        .get(&state.player.location)
+        .and_then(|loc| loc.doors.get(&direction))
+        .cloned();
+    match door_id {
+        Some(id) if state.doors.get(&id).is_some_and(|d| d.state != DoorState::Open) => {
+            open(&id, state)
+        }
+        _ => Ok(Vec::new()),
+    }
+}
+
+pub fn close(door_id: &str, state: &mut WorldState) -> Result<Vec<OutputLine>, String> {
+    let door = door(door_id, state)?;
+    if door.broken {
+        return Err(format!("The {} hangs off its hinges and won't close.", door.name));
+    }
+    if door.state != DoorState::Open {
+        return Err(format!("The {} is already closed.", door.name));
+    }
+    set_state(door_id, DoorState::Closed, state);
+    Ok(vec![line(format!("You close the {}.", door.name))])
+}
+
+/// Percent chance of picking the door's lock with the best lockpick carried,
+/// or `None` without one.
+pub fn pick_chance(door: &Door, state: &WorldState) -> Option<u32> {
+    let bonus = state
+        .player
+        .inventory
+        .iter()
+        .filter_map(|id| state.items.get(id)?.lockpick)
+        .max()?;
+    Some(50u32.saturating_add(bonus).saturating_sub(door.lock_difficulty).clamp(5, 95))
+}
+
+/// Percent chance of bashing the door in, going by the player's attack.
+pub fn bash_chance(door: &Door, state: &WorldState) -> u32 {
+    let strength = combat::get_player_attack(state).max(0) as u32 * 4 + 30;
+    strength.saturating_sub(door.lock_difficulty).clamp(5, 95)
+}
+
+/// Tries to pick a locked door, leaving it closed but unlocked. A failed
+/// attempt only costs time.
+pub fn pick(door_id: &str, state: &mut WorldState) -> Result<Vec<OutputLine>, String> {
+    let door = door(door_id, state)?;
+    if door.state != DoorState::Locked {
+        return Err(format!("The {} isn't locked.", door.name));
+    }
+    if door.sealed {
+        return Err(format!("No pick will work the {}.", door.name));
+    }
+    if !works_from_here(&door, state) {
+        return Err(format!("The lock of the {} is on the other side.", door.name));
+    }
+    let chance = pick_chance(&door, state)
+        .ok_or_else(|| "You have nothing to pick the lock with.".to_string())?;
+    let mut rng = random::world_rng(state);
+    if !random::roll_percent(&mut rng, chance) {
+        return Ok(vec![line(format!("The lock of the {} resists you.", door.name))]);
+    }
+    set_state(door_id, DoorState::Closed, state);
+    state.pending_sound_cues.push(SoundCue::DoorUnlock);
+    Ok(vec![line(format!("The lock of the {} clicks open.", door.name))])
+}
+
+/// Tries to bash a closed or locked door in, leaving it broken open for
+/// good. The noise gives a hidden player away either way.
+pub fn bash(door_id: &str, state: &mut WorldState) -> Result<Vec<OutputLine>, String> {
+    let door = door(door_id, state)?;
+    if door.state == DoorState::Open {
+        return Err(format!("The {} is already open.", door.name));
+    }
+    if door.sealed {
+        return Err(format!("The {} doesn't so much as shudder.", door.name));
+    }
+    state.player.hidden = false;
+    let chance = bash_chance(&door, state);
+    let mut rng = random::world_rng(state);
+    if !random::roll_percent(&mut rng, chance) {
+        return Ok(vec![line(format!(
+            "You throw your weight against the {}, but it holds.",
+            door.name
+        ))]);
+    }
+    if let Some(d) = state.doors.get_mut(door_id) {
+        d.state = DoorState::Open;
+        d.broken = true;
+    }
+    Ok(vec![line(format!("The {} bursts open with a crash!", door.name))])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::world_builder;
+
+    #[test]
+    fn doors_open_with_their_key_and_close_again() {
+        let mut state = world_builder::build_thornhold();
+        state.player.location = "great_hall".into();
+        let door_id = find_door("east door", &state).unwrap();
+        assert_eq!(find_door("iron", &state).as_deref(), Some(door_id.as_str()));
+        assert!(find_door("west", &state).is_none());
+
+        assert!(open(&door_id, &mut state).is_err());
+        state.player.inventory.push("library_key".into());
+        let msgs = open(&door_id, &mut state).unwrap();
+        assert_eq!(msgs.len(), 2);
+        assert!(!state.player.inventory.contains(&"library_key".to_string()));
+        assert_eq!(state.doors[&door_id].state, DoorState::Open);
+
+        // The library side shares the door
+        state.player.location = "library".into();
+        assert!(close(&door_id, &mut state).is_ok());
+        assert!(close(&door_id, &mut state).is_err());
+        assert!(pass(Direction::West, &mut state).is_ok());
+        assert_eq!(state.doors[&door_id].state, DoorState::Open);
+    }
+
+    #[test]
+    fn picking_needs_a_tool_and_bashing_breaks_doors() {
+        let mut state = world_builder::build_thornhold();
+        state.player.location = "great_hall".into();
+        let door_id = find_door("east", &state).unwrap();
+        assert!(pick(&door_id, &mut state).is_err());
+
+        state.player.inventory.push("thieves_picks".into());
+        state.doors.get_mut(&door_id).unwrap().lock_difficulty = 0;
+        let door = state.doors[&door_id].clone();
+        assert_eq!(pick_chance(&door, &state), Some(70));
+        let picked = (0..50).any(|_| {
+            pick(&door_id, &mut state).unwrap();
+            state.doors[&door_id].state == DoorState::Closed
+        });
+        assert!(picked);
+
+        set_state(&door_id, DoorState::Locked, &mut state);
+        state.player.attack = 100;
+        let bashed = (0..20).any(|_| {
+            bash(&door_id, &mut state).unwrap();
+            state.doors[&door_id].broken
+        });
+        assert!(bashed);
+        assert_eq!(state.doors[&door_id].state, DoorState::Open);
+        assert!(close(&door_id, &mut state).is_err());
+
+        // A one-sided door won't open from the far side
+        let door = state.doors.get_mut(&door_id).unwrap();
+        door.state = DoorState::Closed;
+        door.opens_from = Some("library".into());
+        assert!(pass(Direction::East, &mut state).is_err());
+    }
+}
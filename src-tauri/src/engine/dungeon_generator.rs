@@ -1,274 +1,221 @@
 use std::collections::HashMap;
 
+use rand::Rng;
+
+use crate::engine::{clock, loot};
+use crate::engine::random::{pick_weighted, roll_percent, world_rng};
 use crate::models::*;
 
-pub struct DungeonConfig {
-    pub entry_location: String,
-    pub entry_direction: Direction,
-    pub depth: usize,
-    pub difficulty_base: i32,
+/// Difficulty level fed into region scaling.
+pub fn difficulty_level(difficulty: Difficulty) -> i32 {
+    match difficulty {
+        Difficulty::Easy => 3,
+        Difficulty::Normal => 5,
+        Difficulty::Hard => 8,
+    }
+}
+
+pub fn room_id(region_id: &str, depth: usize) -> String {
+    format!("{region_id}_d{depth}_r{depth}")
 }
 
-struct RoomTemplate {
-    name: &'static str,
-    description: &'static str,
-    mood: Mood,
+pub fn enemy_npc_id(region_id: &str, enemy_id: &str, depth: usize) -> String {
+    format!("{region_id}_{enemy_id}_{depth}")
 }
 
-const ROOM_TEMPLATES: &[RoomTemplate] = &[
-    RoomTemplate {
-        name: "Narrow Tunnel",
-        description: "A cramped tunnel hewn from rough stone. Water seeps through cracks in the walls.",
-        mood: Mood::Dark,
-    },
-    RoomTemplate {
-        name: "Musty Chamber",
-        description: "A large chamber with a low ceiling. Mushrooms grow in clusters along the walls.",
-        mood: Mood::Mysterious,
-    },
-    RoomTemplate {
-        name: "Collapsed Hall",
-        description: "Once a grand hall, now half-buried in rubble. Dust motes dance in shafts of dim light.",
-        mood: Mood::Tense,
-    },
-    RoomTemplate {
-        name: "Flooded Passage",
-        description: "Ankle-deep water fills this passage. Something ripples beneath the surface.",
-        mood: Mood::Dark,
-    },
-    RoomTemplate {
-        name: "Bone Gallery",
-        description: "Walls lined with ancient bones arranged in deliberate patterns. A dark shrine stands at one end.",
-        mood: Mood::Dangerous,
-    },
-    RoomTemplate {
-        name: "Crystal Cavern",
-        description: "Crystalline formations jut from every surface, casting prismatic reflections.",
-        mood: Mood::Mysterious,
-    },
-    RoomTemplate {
-        name: "Ancient Forge",
-        description: "A dwarven forge, cold and silent. Tools still lie where their owners left them.",
-        mood: Mood::Tense,
-    },
-];
-
-pub fn generate_dungeon(config: &DungeonConfig, state: &mut WorldState) {
-    // Skip if dungeon already exists (e.g. loaded from save)
-    if state.locations.contains_key("dungeon_d0_r0") {
+pub fn boss_npc_id(region_id: &str, boss_id: &str) -> String {
+    format!("{region_id}_{boss_id}")
+}
+
+/// Most rooms a region can generate; it always gets at least two.
+pub fn max_rooms(region: &ProceduralRegion) -> usize {
+    region.max_depth.max(region.min_depth.max(2))
+}
+
+/// Every room id and NPC id the region may generate, so the loader can
+/// keep them clear of authored ones.
+pub fn generated_ids(region: &ProceduralRegion) -> (Vec<String>, Vec<String>) {
+    let depths = 0..max_rooms(region);
+    let rooms = depths.clone().map(|d| room_id(&region.id, d)).collect();
+    let mut npcs: Vec<String> = depths
+        .flat_map(|d| {
+            region
+                .enemy_table
+                .iter()
+                .map(move |enemy| enemy_npc_id(&region.id, &enemy.id, d))
+        })
+        .collect();
+    npcs.extend(region.boss.iter().map(|boss| boss_npc_id(&region.id, &boss.id)));
+    (rooms, npcs)
+}
+
+/// Whether a location is one of the region's generated rooms.
+pub fn in_region(region_id: &str, location_id: &str) -> bool {
+    location_id
+        .strip_prefix(region_id)
+        .is_some_and(|rest| rest.starts_with("_d"))
+}
+
+/// Generates every procedural region declared by the world.
+pub fn generate_regions(state: &mut WorldState) {
+    let regions = state.regions.clone();
+    for region in &regions {
+        generate_region(region, state);
+    }
+}
+
+pub fn generate_region(region: &ProceduralRegion, state: &mut WorldState) {
+    // Skip if region already exists (e.g. loaded from save)
+    if state.locations.contains_key(&room_id(&region.id, 0)) {
+        return;
+    }
+    // Never take over an exit the entry room already has
+    let entry_free = state
+        .locations
+        .get(&region.entry_location)
+        .is_some_and(|loc| !loc.exits.contains_key(&region.entry_direction));
+    if region.room_templates.is_empty() || !entry_free {
         return;
     }
 
-    let depth = config.depth.max(2); // At least 2 rooms
+    let mut rng = world_rng(state);
+    let level = difficulty_level(state.difficulty);
+    let min_depth = region.min_depth.max(2); // At least 2 rooms
+    let max_depth = max_rooms(region);
+    let depth = rng.gen_range(min_depth..=max_depth);
 
-    let mut prev_room_id = config.entry_location.clone();
-    let mut prev_direction = config.entry_direction;
+    let mut prev_room_id = region.entry_location.clone();
+    let mut prev_direction = region.entry_direction;
 
     for d in 0..depth {
-        let room_id = format!("dungeon_d{d}_r{d}");
+        let id = room_id(&region.id, d);
         let is_final = d == depth - 1;
-        let template = &ROOM_TEMPLATES[d % ROOM_TEMPLATES.len()];
+        let back_direction = prev_direction.opposite();
 
-        let name = if is_final {
-            "The Dungeon Heart".to_string()
-        } else {
-            format!("{} (Depth {})", template.name, d + 1)
+        let template = match (&region.final_room, is_final) {
+            (Some(final_room), true) => final_room,
+            _ => pick_weighted(&mut rng, &region.room_templates, |t| t.weight)
+                .unwrap_or(&region.room_templates[0]),
         };
-
-        let description = if is_final {
-            "The deepest point of the dungeon. An oppressive aura permeates the air. \
-             A massive creature guards a chest overflowing with treasures."
-                .to_string()
+        let name = if is_final && region.final_room.is_some() {
+            template.name.clone()
         } else {
-            template.description.to_string()
+            format!("{} (Depth {})", template.name, d + 1)
         };
 
-        let mood = if is_final {
-            Mood::Dangerous
-        } else {
-            template.mood
-        };
+        let mut exits = HashMap::new();
+        exits.insert(back_direction, prev_room_id.clone());
 
         // Alternate exit direction: Down, South, Down, South...
-        let next_direction = if d % 2 == 0 {
+        let mut next_direction = if d % 2 == 0 {
             Direction::Down
         } else {
             Direction::South
         };
-        let back_direction = prev_direction.opposite();
-
-        let mut exits = HashMap::new();
-        exits.insert(back_direction, prev_room_id.clone());
-
+        if next_direction == back_direction {
+            next_direction = if d % 2 == 0 {
+                Direction::South
+            } else {
+                Direction::Down
+            };
+        }
         if !is_final {
-            exits.insert(
-                next_direction,
-                format!("dungeon_d{}_r{}", d + 1, d + 1),
-            );
+            exits.insert(next_direction, room_id(&region.id, d + 1));
         }
 
-        // Items: health potion in room at index 1, treasure in final room
-        let items = if is_final {
-            vec!["dungeon_treasure".into()]
-        } else if d == 1 {
-            vec!["dungeon_health_potion".into()]
-        } else {
-            vec![]
-        };
-
-        // NPCs: guard at midpoint, boss at final room
-        let npcs = if is_final {
-            vec!["dungeon_boss".into()]
-        } else if d == depth / 2 {
-            vec![format!("dungeon_guard_{d}")]
+        let mut items = Vec::new();
+        let mut npcs = Vec::new();
+        if is_final {
+            items.extend(region.final_items.iter().cloned());
+            if let Some(boss) = &region.boss {
+                let npc_id = boss_npc_id(&region.id, &boss.id);
+                spawn_enemy(state, &npc_id, boss, &region.scaling, d, level);
+                npcs.push(npc_id);
+            }
         } else {
-            vec![]
-        };
-
-        let location = Location {
-            id: room_id.clone(),
-            name,
-            description,
-            items,
-            npcs: npcs.clone(),
-            exits,
-            locked_exits: HashMap::new(),
-            visited: false,
-            discovered_secrets: vec![],
-            ambient_mood: mood,
-            examine_details: None,
-            revisit_description: None,
-        };
-
-        state.locations.insert(room_id.clone(), location);
-
-        // Create mid-dungeon guard NPC
-        if !is_final && d == depth / 2 {
-            let guard_id = format!("dungeon_guard_{d}");
-            let guard_attack = config.difficulty_base + (d as i32 * 2);
-            let guard_health = 15 + (d as i32 * 5);
-            state.npcs.insert(
-                guard_id.clone(),
-                Npc {
-                    id: guard_id,
-                    name: "Dungeon Lurker".into(),
-                    description: "A twisted creature adapted to the darkness. Its pale eyes gleam with hunger.".into(),
-                    personality_seed: "Hostile. Attacks on sight. Protects its territory.".into(),
-                    dialogue_state: DialogueState::Hostile,
-                    hostile: true,
-                    health: guard_health,
-                    max_health: guard_health,
-                    attack: guard_attack,
-                    defense: config.difficulty_base / 2,
-                    items: vec![],
-                    quest_giver: None,
-                    examine_text: None,
-                    relationship: 0,
-                    memory: vec![],
-                },
-            );
+            if let Some(table_id) = &region.loot_table {
+                if roll_percent(&mut rng, region.loot_chance) {
+                    let drops = loot::roll_loot(table_id, state);
+                    items.extend(loot::drop_item_ids(&drops));
+                }
+            }
+            if roll_percent(&mut rng, clock::spawn_chance(region.enemy_chance, state)) {
+                if let Some(enemy) = pick_weighted(&mut rng, &region.enemy_table, |e| e.weight) {
+                    let npc_id = enemy_npc_id(&region.id, &enemy.id, d);
+                    spawn_enemy(state, &npc_id, enemy, &region.scaling, d, level);
+                    npcs.push(npc_id);
+                }
+            }
         }
 
+        state.locations.insert(
+            id.clone(),
+            Location {
+                id: id.clone(),
+                name,
+                description: template.description.clone(),
+                items,
+                npcs,
+                exits,
+                locked_exits: HashMap::new(),
+                visited: false,
+                discovered_secrets: vec![],
+                ambient_mood: template.mood,
+                examine_details: None,
+                revisit_description: None,
+                ..Default::default()
+            },
+        );
+
         // Link previous room to this one
         if let Some(prev_loc) = state.locations.get_mut(&prev_room_id) {
-            prev_loc.exits.insert(prev_direction, room_id.clone());
+            prev_loc.exits.insert(prev_direction, id.clone());
         }
 
-        prev_room_id = room_id;
+        prev_room_id = id;
         prev_direction = next_direction;
     }
+}
+
+/// Adds a hostile NPC built from the template, scaled by depth and
+/// difficulty level.
+pub fn spawn_enemy(
+    state: &mut WorldState,
+    npc_id: &str,
+    template: &EnemyTemplate,
+    scaling: &DifficultyScaling,
+    depth: usize,
+    level: i32,
+) {
+    let depth = depth as i32;
+    let scaled = |base: i32, per_depth: i32, per_level: i32| {
+        base.saturating_add(per_depth.saturating_mul(depth))
+            .saturating_add(per_level.saturating_mul(level))
+    };
+    let health = scaled(template.health, scaling.health_per_depth, scaling.health_per_level).max(1);
+    let attack = scaled(template.attack, scaling.attack_per_depth, scaling.attack_per_level);
+    let defense = scaled(template.defense, scaling.defense_per_depth, scaling.defense_per_level);
 
-    // Create boss NPC
-    let boss_health = 30 + (config.difficulty_base * 2);
-    let boss_attack = config.difficulty_base + 8;
     state.npcs.insert(
-        "dungeon_boss".into(),
+        npc_id.to_string(),
         Npc {
-            id: "dungeon_boss".into(),
-            name: "The Dungeon Keeper".into(),
-            description: "A massive, armored beast with eyes like molten gold. \
-                          It guards its hoard with primal fury."
-                .into(),
-            personality_seed: "Territorial and primal. Roars before attacking. Ancient and powerful."
-                .into(),
+            id: npc_id.to_string(),
+            name: template.name.clone(),
+            description: template.description.clone(),
+            personality_seed: template.personality_seed.clone(),
             dialogue_state: DialogueState::Hostile,
             hostile: true,
-            health: boss_health,
-            max_health: boss_health,
-            attack: boss_attack,
-            defense: config.difficulty_base,
-            items: vec!["dungeon_key_shard".into()],
+            health,
+            max_health: health,
+            attack: attack.max(0),
+            defense: defense.max(0),
+            items: template.items.clone(),
             quest_giver: None,
-            examine_text: Some(
-                "Scars criss-cross its thick hide. A crown of twisted metal sits upon its head \
-                 — perhaps it was once something more."
-                    .into(),
-            ),
+            examine_text: template.examine_text.clone(),
             relationship: 0,
             memory: vec![],
-        },
-    );
-
-    // Create dungeon items
-    state.items.insert(
-        "dungeon_treasure".into(),
-        Item {
-            id: "dungeon_treasure".into(),
-            name: "Dungeon Treasure".into(),
-            description: "A chest of ancient gold coins, gemstones, and a mysterious crystal shard."
-                .into(),
-            item_type: ItemType::Quest,
-            modifier: Some(StatModifier {
-                attack: 2,
-                defense: 2,
-                health: 0,
-            }),
-            usable: false,
-            consumable: false,
-            key_id: None,
-            lore: Some(
-                "The accumulated wealth of centuries, guarded by a creature that long forgot why it hoards."
-                    .into(),
-            ),
-        },
-    );
-
-    state.items.insert(
-        "dungeon_health_potion".into(),
-        Item {
-            id: "dungeon_health_potion".into(),
-            name: "Glowing Elixir".into(),
-            description: "A vial of luminescent liquid found deep underground. It radiates warmth."
-                .into(),
-            item_type: ItemType::Consumable,
-            modifier: Some(StatModifier {
-                attack: 0,
-                defense: 0,
-                health: 25,
-            }),
-            usable: true,
-            consumable: true,
-            key_id: None,
-            lore: None,
-        },
-    );
-
-    state.items.insert(
-        "dungeon_key_shard".into(),
-        Item {
-            id: "dungeon_key_shard".into(),
-            name: "Key Shard".into(),
-            description: "A fragment of an ancient key. It hums with residual magic.".into(),
-            item_type: ItemType::Miscellaneous,
-            modifier: None,
-            usable: false,
-            consumable: false,
-            key_id: None,
-            lore: Some(
-                "Part of the original key to Thornhold's deepest vault. \
-                 Whoever carried it was consumed by what they guarded."
-                    .into(),
-            ),
+            loot_table: template.loot_table.clone(),
+            ..Default::default()
         },
     );
 }
@@ -278,34 +225,34 @@ mod tests {
     use super::*;
     use crate::engine::world_builder;
 
-    fn build_state_with_dungeon(difficulty_base: i32, depth: usize) -> WorldState {
+    fn build_state_with_dungeon(difficulty: Difficulty, seed: u64) -> WorldState {
         let mut state = world_builder::build_thornhold();
-        generate_dungeon(
-            &DungeonConfig {
-                entry_location: "armory".to_string(),
-                entry_direction: Direction::Down,
-                depth,
-                difficulty_base,
-            },
-            &mut state,
-        );
+        state.difficulty = difficulty;
+        state.rng_seed = seed;
+        generate_regions(&mut state);
         state
     }
 
-    #[test]
-    fn generates_correct_number_of_rooms() {
-        let state = build_state_with_dungeon(5, 5);
-        let dungeon_rooms: Vec<_> = state
+    fn dungeon_depth(state: &WorldState) -> usize {
+        state
             .locations
             .keys()
-            .filter(|k| k.starts_with("dungeon_"))
-            .collect();
-        assert_eq!(dungeon_rooms.len(), 5);
+            .filter(|k| k.starts_with("dungeon_d"))
+            .count()
+    }
+
+    #[test]
+    fn depth_stays_within_region_range() {
+        for seed in 0..20 {
+            let state = build_state_with_dungeon(Difficulty::Normal, seed);
+            let depth = dungeon_depth(&state);
+            assert!((4..=6).contains(&depth), "seed {seed} gave depth {depth}");
+        }
     }
 
     #[test]
     fn armory_has_down_exit_to_dungeon() {
-        let state = build_state_with_dungeon(5, 5);
+        let state = build_state_with_dungeon(Difficulty::Normal, 1);
         let armory = state.locations.get("armory").unwrap();
         assert_eq!(
             armory.exits.get(&Direction::Down),
@@ -315,160 +262,235 @@ mod tests {
 
     #[test]
     fn first_room_links_back_to_armory() {
-        let state = build_state_with_dungeon(5, 5);
+        let state = build_state_with_dungeon(Difficulty::Normal, 1);
         let first = state.locations.get("dungeon_d0_r0").unwrap();
-        assert_eq!(
-            first.exits.get(&Direction::Up),
-            Some(&"armory".to_string())
-        );
+        assert_eq!(first.exits.get(&Direction::Up), Some(&"armory".to_string()));
     }
 
     #[test]
     fn rooms_are_connected_sequentially() {
-        let state = build_state_with_dungeon(5, 5);
-        // Walk from armory through each dungeon room.
+        let state = build_state_with_dungeon(Difficulty::Normal, 3);
+        let depth = dungeon_depth(&state);
         // Forward directions follow the generator pattern:
-        //   armory->d0: Down, d0->d1: Down, d1->d2: South, d2->d3: Down, d3->d4: South
+        //   armory->d0: Down, d0->d1: Down, d1->d2: South, d2->d3: Down, ...
         let mut current = "armory".to_string();
-        let forward_dirs = [
-            Direction::Down,  // armory -> d0
-            Direction::Down,  // d0 -> d1
-            Direction::South, // d1 -> d2
-            Direction::Down,  // d2 -> d3
-            Direction::South, // d3 -> d4
-        ];
-
-        for (d, direction) in forward_dirs.iter().enumerate() {
+        for d in 0..depth {
+            let direction = if d == 0 || d % 2 == 1 {
+                Direction::Down
+            } else {
+                Direction::South
+            };
             let loc = state.locations.get(&current).unwrap();
             let next_id = loc
                 .exits
-                .get(direction)
+                .get(&direction)
                 .unwrap_or_else(|| panic!("No {direction:?} exit from {current}"));
-            let expected = format!("dungeon_d{d}_r{d}");
-            assert_eq!(*next_id, expected, "Room {d} not connected from {current}");
+            assert_eq!(
+                *next_id,
+                room_id("dungeon", d),
+                "Room {d} not connected from {current}"
+            );
             current = next_id.clone();
         }
     }
 
     #[test]
     fn final_room_is_dungeon_heart() {
-        let state = build_state_with_dungeon(5, 5);
-        let final_room = state.locations.get("dungeon_d4_r4").unwrap();
+        let state = build_state_with_dungeon(Difficulty::Normal, 5);
+        let final_room = state
+            .locations
+            .get(&room_id("dungeon", dungeon_depth(&state) - 1))
+            .unwrap();
         assert_eq!(final_room.name, "The Dungeon Heart");
         assert_eq!(final_room.ambient_mood, Mood::Dangerous);
-    }
-
-    #[test]
-    fn final_room_has_no_forward_exit() {
-        let state = build_state_with_dungeon(5, 5);
-        let final_room = state.locations.get("dungeon_d4_r4").unwrap();
         // Should only have the back exit, no forward
         assert_eq!(final_room.exits.len(), 1);
+        assert!(final_room.items.contains(&"dungeon_treasure".to_string()));
     }
 
     #[test]
     fn boss_npc_exists_at_final_room() {
-        let state = build_state_with_dungeon(5, 5);
-        let final_room = state.locations.get("dungeon_d4_r4").unwrap();
+        let state = build_state_with_dungeon(Difficulty::Normal, 5);
+        let final_room = state
+            .locations
+            .get(&room_id("dungeon", dungeon_depth(&state) - 1))
+            .unwrap();
         assert!(final_room.npcs.contains(&"dungeon_boss".to_string()));
 
         let boss = state.npcs.get("dungeon_boss").unwrap();
         assert_eq!(boss.name, "The Dungeon Keeper");
         assert!(boss.hostile);
+        assert_eq!(boss.items, vec!["dungeon_key_shard".to_string()]);
     }
 
     #[test]
-    fn mid_dungeon_guard_exists() {
-        let state = build_state_with_dungeon(5, 5);
-        // depth / 2 = 2, so guard at dungeon_d2_r2
-        let mid_room = state.locations.get("dungeon_d2_r2").unwrap();
-        assert!(mid_room.npcs.contains(&"dungeon_guard_2".to_string()));
-
-        let guard = state.npcs.get("dungeon_guard_2").unwrap();
-        assert_eq!(guard.name, "Dungeon Lurker");
-        assert!(guard.hostile);
+    fn rooms_only_hold_enemies_and_loot_from_tables() {
+        for seed in 0..20 {
+            let state = build_state_with_dungeon(Difficulty::Normal, seed);
+            let depth = dungeon_depth(&state);
+            for d in 0..depth - 1 {
+                let room = state.locations.get(&room_id("dungeon", d)).unwrap();
+                for npc_id in &room.npcs {
+                    assert_eq!(*npc_id, format!("dungeon_guard_{d}"));
+                    let npc = state.npcs.get(npc_id).unwrap();
+                    assert_eq!(npc.name, "Dungeon Lurker");
+                    assert!(npc.hostile);
+                }
+                let table = &state.loot_tables["dungeon_rooms"];
+                for item_id in &room.items {
+                    // Randomized entries come back as generated variants
+                    assert!(table.entries.iter().any(|e| {
+                        e.item_id == *item_id || item_id.starts_with(&format!("{}_gen", e.item_id))
+                    }));
+                    assert!(state.items.contains_key(item_id));
+                }
+            }
+        }
     }
 
     #[test]
-    fn difficulty_scaling_easy() {
-        let state = build_state_with_dungeon(3, 5);
-        let boss = state.npcs.get("dungeon_boss").unwrap();
-        assert_eq!(boss.health, 30 + 6); // 30 + 3*2
-        assert_eq!(boss.attack, 3 + 8);
-        assert_eq!(boss.defense, 3);
+    fn same_seed_produces_same_layout() {
+        let a = build_state_with_dungeon(Difficulty::Normal, 99);
+        let b = build_state_with_dungeon(Difficulty::Normal, 99);
+        assert_eq!(dungeon_depth(&a), dungeon_depth(&b));
+        for d in 0..dungeon_depth(&a) {
+            let id = room_id("dungeon", d);
+            let (ra, rb) = (&a.locations[&id], &b.locations[&id]);
+            assert_eq!(ra.name, rb.name);
+            assert_eq!(ra.npcs, rb.npcs);
+            assert_eq!(ra.items, rb.items);
+        }
     }
 
     #[test]
-    fn difficulty_scaling_hard() {
-        let state = build_state_with_dungeon(8, 5);
-        let boss = state.npcs.get("dungeon_boss").unwrap();
-        assert_eq!(boss.health, 30 + 16); // 30 + 8*2
-        assert_eq!(boss.attack, 8 + 8);
-        assert_eq!(boss.defense, 8);
+    fn different_seeds_vary_layout() {
+        let layouts: std::collections::HashSet<Vec<String>> = (0..10)
+            .map(|seed| {
+                let state = build_state_with_dungeon(Difficulty::Normal, seed);
+                (0..dungeon_depth(&state))
+                    .map(|d| state.locations[&room_id("dungeon", d)].name.clone())
+                    .collect()
+            })
+            .collect();
+        assert!(layouts.len() > 1);
     }
 
     #[test]
-    fn dungeon_items_created() {
-        let state = build_state_with_dungeon(5, 5);
-        assert!(state.items.contains_key("dungeon_treasure"));
-        assert!(state.items.contains_key("dungeon_health_potion"));
-        assert!(state.items.contains_key("dungeon_key_shard"));
-
-        let potion = state.items.get("dungeon_health_potion").unwrap();
-        assert!(potion.consumable);
-        assert!(potion.usable);
-        assert_eq!(potion.item_type, ItemType::Consumable);
-    }
+    fn difficulty_scaling() {
+        let easy = build_state_with_dungeon(Difficulty::Easy, 11);
+        let hard = build_state_with_dungeon(Difficulty::Hard, 11);
+        let boss_depth = dungeon_depth(&easy) as i32 - 1;
+        assert_eq!(dungeon_depth(&easy), dungeon_depth(&hard));
+
+        let boss = easy.npcs.get("dungeon_boss").unwrap();
+        assert_eq!(boss.health, 10 + 5 * boss_depth + 2 * 3);
+        assert_eq!(boss.attack, 4 + boss_depth + 3);
+        assert_eq!(boss.defense, 3);
 
-    #[test]
-    fn health_potion_in_second_room() {
-        let state = build_state_with_dungeon(5, 5);
-        let room = state.locations.get("dungeon_d1_r1").unwrap();
-        assert!(room.items.contains(&"dungeon_health_potion".to_string()));
+        let boss = hard.npcs.get("dungeon_boss").unwrap();
+        assert_eq!(boss.health, 10 + 5 * boss_depth + 2 * 8);
+        assert_eq!(boss.attack, 4 + boss_depth + 8);
+        assert_eq!(boss.defense, 8);
     }
 
     #[test]
-    fn treasure_in_final_room() {
-        let state = build_state_with_dungeon(5, 5);
-        let final_room = state.locations.get("dungeon_d4_r4").unwrap();
-        assert!(final_room.items.contains(&"dungeon_treasure".to_string()));
+    fn extreme_scaling_saturates() {
+        let mut state = world_builder::build_thornhold();
+        let template = EnemyTemplate {
+            health: i32::MAX,
+            attack: i32::MAX,
+            ..state.regions[0].enemy_table[0].clone()
+        };
+        let scaling = DifficultyScaling {
+            health_per_depth: i32::MAX,
+            attack_per_level: i32::MIN,
+            ..DifficultyScaling::default()
+        };
+        spawn_enemy(&mut state, "brute", &template, &scaling, 16, 8);
+        let brute = &state.npcs["brute"];
+        assert_eq!(brute.health, i32::MAX);
+        assert_eq!(brute.attack, 0);
     }
 
     #[test]
     fn skips_if_already_generated() {
-        let mut state = world_builder::build_thornhold();
-        let config = DungeonConfig {
-            entry_location: "armory".to_string(),
-            entry_direction: Direction::Down,
-            depth: 5,
-            difficulty_base: 5,
-        };
-        generate_dungeon(&config, &mut state);
+        let mut state = build_state_with_dungeon(Difficulty::Normal, 4);
         let count_before = state.locations.len();
 
         // Call again — should be a no-op
-        generate_dungeon(&config, &mut state);
+        generate_regions(&mut state);
         assert_eq!(state.locations.len(), count_before);
     }
 
     #[test]
     fn minimum_depth_clamped_to_two() {
-        let state = build_state_with_dungeon(5, 1);
-        let dungeon_rooms: Vec<_> = state
-            .locations
-            .keys()
-            .filter(|k| k.starts_with("dungeon_"))
-            .collect();
-        assert_eq!(dungeon_rooms.len(), 2);
+        let mut state = world_builder::build_thornhold();
+        state.regions[0].min_depth = 1;
+        state.regions[0].max_depth = 1;
+        generate_regions(&mut state);
+        assert_eq!(dungeon_depth(&state), 2);
     }
 
     #[test]
     fn existing_locations_preserved() {
-        let state = build_state_with_dungeon(5, 5);
-        // Original 14 + 5 dungeon rooms = 19
-        assert_eq!(state.locations.len(), 19);
+        let state = build_state_with_dungeon(Difficulty::Normal, 8);
+        assert_eq!(state.locations.len(), 14 + dungeon_depth(&state));
         assert!(state.locations.contains_key("courtyard"));
         assert!(state.locations.contains_key("great_hall"));
         assert!(state.locations.contains_key("armory"));
     }
+
+    #[test]
+    fn module_region_without_boss_or_final_room() {
+        let mut state = WorldState::default();
+        state.locations.insert(
+            "camp".into(),
+            Location {
+                id: "camp".into(),
+                name: "Camp".into(),
+                description: "A campfire.".into(),
+                items: vec![],
+                npcs: vec![],
+                exits: HashMap::new(),
+                locked_exits: HashMap::new(),
+                visited: true,
+                discovered_secrets: vec![],
+                ambient_mood: Mood::Peaceful,
+                examine_details: None,
+                revisit_description: None,
+                ..Default::default()
+            },
+        );
+        state.regions.push(ProceduralRegion {
+            id: "caves".into(),
+            entry_location: "camp".into(),
+            entry_direction: Direction::Up,
+            min_depth: 3,
+            max_depth: 3,
+            room_templates: vec![RoomTemplate {
+                name: "Cave".into(),
+                description: "Damp rock.".into(),
+                mood: Mood::Dark,
+                weight: 1,
+            }],
+            enemy_table: vec![],
+            enemy_chance: 100,
+            loot_table: None,
+            loot_chance: 100,
+            scaling: DifficultyScaling::default(),
+            final_room: None,
+            boss: None,
+            final_items: vec![],
+        });
+        generate_regions(&mut state);
+
+        assert_eq!(state.locations.len(), 4);
+        assert_eq!(state.locations["camp"].exits[&Direction::Up], "caves_d0_r0");
+        // Entry goes up, so the first room can't also use Down as its way on
+        let first = &state.locations["caves_d0_r0"];
+        assert_eq!(first.exits[&Direction::Down], "camp");
+        assert_eq!(first.exits[&Direction::South], "caves_d1_r1");
+        assert_eq!(state.locations["caves_d2_r2"].name, "Cave (Depth 3)");
+        assert!(state.npcs.is_empty());
+    }
 }
@@ -0,0 +1,266 @@
+use crate::engine::dungeon_generator::{difficulty_level, in_region, spawn_enemy};
+use crate::engine::{clock, random};
+use crate::models::*;
+
+fn covers(table: &EncounterTable, location_id: &str) -> bool {
+    table.locations.iter().any(|id| id == location_id)
+        || table
+            .regions
+            .iter()
+            .any(|region| in_region(region, location_id))
+}
+
+fn has_enemy(location_id: &str, state: &WorldState) -> bool {
+    state.locations.get(location_id).is_some_and(|loc| {
+        loc.npcs.iter().any(|id| {
+            state
+                .npcs
+                .get(id)
+                .is_some_and(|n| n.hostile && n.dialogue_state != DialogueState::Dead)
+        })
+    })
+}
+
+/// Rolls the encounter tables covering the player's room. An enemy that
+/// turns up is placed in the room and its id returned.
+pub fn roll_encounter(state: &mut WorldState) -> Option<String> {
+    let here = state.player.location.clone();
+    let turn = state.player.turns_elapsed;
+    for idx in 0..state.encounters.len() {
+        let table = &state.encounters[idx];
+        let cooling = table
+            .last_encounter
+            .is_some_and(|last| turn < last.saturating_add(table.cooldown));
+        if !covers(table, &here) || cooling || table.active.len() >= table.max_active {
+            continue;
+        }
+        let chance = clock::spawn_chance(table.chance, state);
+        let mut rng = random::world_rng(state);
+        if !random::roll_percent(&mut rng, chance) {
+            continue;
+        }
+        let Some(npc_id) = spawn(idx, &here, &mut rng, state) else {
+            continue;
+        };
+        state.encounters[idx].last_encounter = Some(turn);
+        return Some(npc_id);
+    }
+    None
+}
+
+/// Turn upkeep: forgets spawned enemies that died, so they don't pile up
+/// in saves, and repopulates covered rooms that have stood empty long
+/// enough. The player's own room is left alone.
+pub fn tick(state: &mut WorldState) {
+    let turn = state.player.turns_elapsed;
+    for idx in 0..state.encounters.len() {
+        let dead: Vec<String> = state.encounters[idx]
+            .active
+            .iter()
+            .filter(|id| {
+                state
+                    .npcs
+                    .get(*id)
+                    .is_none_or(|n| n.dialogue_state == DialogueState::Dead)
+            })
+            .cloned()
+            .collect();
+        for npc_id in &dead {
+            state.npcs.remove(npc_id);
+            for loc in state.locations.values_mut() {
+                loc.npcs.retain(|id| id != npc_id);
+            }
+        }
+        state.encounters[idx].active.retain(|id| !dead.contains(id));
+
+        let Some(after) = state.encounters[idx].respawn_after else {
+            continue;
+        };
+        let mut rooms: Vec<String> = state
+            .locations
+            .keys()
+            .filter(|id| covers(&state.encounters[idx], id) && **id != state.player.location)
+            .cloned()
+            .collect();
+        rooms.sort();
+        for room in rooms {
+            if has_enemy(&room, state) {
+                state.encounters[idx].empty_since.remove(&room);
+                continue;
+            }
+            let table = &mut state.encounters[idx];
+            let since = *table.empty_since.entry(room.clone()).or_insert(turn);
+            if turn < since.saturating_add(after) || table.active.len() >= table.max_active {
+                continue;
+            }
+            let mut rng = random::world_rng(state);
+            if spawn(idx, &room, &mut rng, state).is_some() {
+                state.encounters[idx].empty_since.remove(&room);
+            }
+        }
+    }
+}
+
+/// Whether an id is one the table gives its spawns:
+/// `{table}_{enemy}_{serial}`.
+pub fn spawns_as(table: &EncounterTable, npc_id: &str) -> bool {
+    let Some(rest) = npc_id
+        .strip_prefix(table.id.as_str())
+        .and_then(|rest| rest.strip_prefix('_'))
+    else {
+        return false;
+    };
+    table.enemies.iter().any(|enemy| {
+        rest.strip_prefix(enemy.id.as_str())
+            .and_then(|serial| serial.strip_prefix('_'))
+            .is_some_and(|serial| !serial.is_empty() && serial.bytes().all(|b| b.is_ascii_digit()))
+    })
+}
+
+/// Spawns an enemy from the table into a room under a fresh id.
+fn spawn(
+    idx: usize,
+    location_id: &str,
+    rng: &mut impl rand::Rng,
+    state: &mut WorldState,
+) -> Option<String> {
+    if !state.locations.contains_key(location_id) {
+        return None;
+    }
+    let table = state.encounters[idx].clone();
+    let template = random::pick_weighted(rng, &table.enemies, |e| e.weight)?;
+    // Serials already taken, by an NPC from a save or another table, are
+    // skipped rather than overwritten
+    let npc_id = loop {
+        state.next_npc_serial += 1;
+        let id = format!("{}_{}_{}", table.id, template.id, state.next_npc_serial);
+        if !state.npcs.contains_key(&id) {
+            break id;
+        }
+    };
+    let level = difficulty_level(state.difficulty);
+    spawn_enemy(state, &npc_id, template, &table.scaling, 0, level);
+    if let Some(loc) = state.locations.get_mut(location_id) {
+        loc.npcs.push(npc_id.clone());
+    }
+    state.encounters[idx].active.push(npc_id.clone());
+    Some(npc_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::world_builder;
+
+    fn table(chance: u32) -> EncounterTable {
+        EncounterTable {
+            id: "crypt_dead".into(),
+            locations: vec!["crypt_passage".into(), "wine_cellar".into()],
+            regions: vec![],
+            chance,
+            cooldown: 5,
+            respawn_after: Some(10),
+            max_active: 2,
+            enemies: vec![EnemyTemplate {
+                id: "bones".into(),
+                name: "Restless Bones".into(),
+                description: "Rattling.".into(),
+                personality_seed: "Mindless.".into(),
+                health: 10,
+                attack: 3,
+                defense: 1,
+                items: vec![],
+                examine_text: None,
+                loot_table: None,
+                weight: 1,
+            }],
+            scaling: DifficultyScaling::default(),
+            last_encounter: None,
+            active: vec![],
+            empty_since: Default::default(),
+        }
+    }
+
+    #[test]
+    fn encounters_spawn_with_unique_ids_and_cool_down() {
+        let mut state = world_builder::build_thornhold();
+        state.encounters = vec![table(100)];
+        state.player.location = "crypt_passage".into();
+
+        let first = roll_encounter(&mut state).unwrap();
+        assert_eq!(first, "crypt_dead_bones_1");
+        assert!(state.npcs[&first].hostile);
+        assert!(state.locations["crypt_passage"].npcs.contains(&first));
+        // Still cooling down
+        state.player.turns_elapsed = 4;
+        assert!(roll_encounter(&mut state).is_none());
+        state.player.turns_elapsed = 5;
+        assert_eq!(roll_encounter(&mut state).unwrap(), "crypt_dead_bones_2");
+        // The table is at its limit
+        state.player.turns_elapsed = 20;
+        assert!(roll_encounter(&mut state).is_none());
+
+        // Outside covered rooms nothing happens
+        state.encounters[0].active.clear();
+        state.player.location = "courtyard".into();
+        assert!(roll_encounter(&mut state).is_none());
+    }
+
+    #[test]
+    fn dead_spawns_are_forgotten_and_empty_rooms_repopulate() {
+        let mut state = world_builder::build_thornhold();
+        state.encounters = vec![table(100)];
+        state.player.location = "crypt_passage".into();
+        let npc_id = roll_encounter(&mut state).unwrap();
+        state.npcs.get_mut(&npc_id).unwrap().dialogue_state = DialogueState::Dead;
+
+        tick(&mut state);
+        assert!(!state.npcs.contains_key(&npc_id));
+        assert!(state.encounters[0].active.is_empty());
+        assert!(!state.locations["crypt_passage"].npcs.contains(&npc_id));
+
+        // The wine cellar has been empty since turn 0; the player's room
+        // stays untouched
+        state.player.turns_elapsed = 10;
+        tick(&mut state);
+        assert!(has_enemy("wine_cellar", &state));
+        assert!(!has_enemy("crypt_passage", &state));
+        assert_eq!(state.encounters[0].active.len(), 1);
+    }
+
+    #[test]
+    fn spawns_skip_taken_ids_and_failed_tables_let_others_roll() {
+        let mut state = world_builder::build_thornhold();
+        let mut empty = table(100);
+        empty.id = "empty".into();
+        empty.enemies[0].weight = 0;
+        state.encounters = vec![empty, table(100)];
+        state.player.location = "crypt_passage".into();
+        let guard = state.npcs["skeletal_guard"].clone();
+        state.npcs.insert("crypt_dead_bones_1".into(), guard);
+
+        let npc_id = roll_encounter(&mut state).unwrap();
+        assert_eq!(npc_id, "crypt_dead_bones_2");
+        assert_eq!(state.npcs["crypt_dead_bones_1"].name, "Skeletal Guard");
+        assert!(spawns_as(&state.encounters[1], &npc_id));
+        assert!(!spawns_as(&state.encounters[1], "crypt_dead_bones_"));
+        assert!(!spawns_as(&state.encounters[1], "crypt_dead_bonesaw_1"));
+    }
+
+    #[test]
+    fn huge_cooldowns_never_run_out() {
+        let mut state = world_builder::build_thornhold();
+        let mut table = table(100);
+        table.cooldown = u32::MAX;
+        table.respawn_after = Some(u32::MAX);
+        state.encounters = vec![table];
+        state.player.location = "crypt_passage".into();
+        state.player.turns_elapsed = 10;
+        assert!(roll_encounter(&mut state).is_some());
+
+        state.player.turns_elapsed = 20;
+        assert!(roll_encounter(&mut state).is_none());
+        tick(&mut state);
+        assert!(!has_enemy("wine_cellar", &state));
+    }
+}
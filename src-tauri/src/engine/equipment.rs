@@ -0,0 +1,352 @@
+use std::collections::HashMap;
+
+use crate::engine::templates;
+use crate::models::*;
+
+/// Slots that take wear when the player is hit.
+pub const ARMOR_SLOTS: [EquipSlot; 6] = [
+    EquipSlot::Armor,
+    EquipSlot::Helmet,
+    EquipSlot::Shield,
+    EquipSlot::Ring,
+    EquipSlot::Amulet,
+    EquipSlot::Boots,
+];
+
+/// Gear only adds attack and defense; the health on a modifier is what a
+/// consumable restores, so it is left out.
+fn add_modifier(total: &mut StatModifier, modifier: &StatModifier) {
+    total.attack += modifier.attack;
+    total.defense += modifier.defense;
+}
+
+/// Counts equipped pieces per item set, sorted by set id.
+pub fn worn_set_pieces(player: &Player, items: &HashMap<String, Item>) -> Vec<(String, usize)> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for (_, item_id) in player.equipped_items() {
+        let item = items.get(item_id).filter(|i| !i.is_broken());
+        if let Some(set_id) = item.and_then(|i| i.set_id.as_deref()) {
+            *counts.entry(set_id).or_insert(0) += 1;
+        }
+    }
+    let mut worn: Vec<(String, usize)> = counts
+        .into_iter()
+        .map(|(id, count)| (id.to_string(), count))
+        .collect();
+    worn.sort();
+    worn
+}
+
+/// Sum of every bonus tier reached with `worn` pieces.
+pub fn set_bonus(set: &ItemSet, worn: usize) -> StatModifier {
+    let mut total = StatModifier::default();
+    for bonus in set.bonuses.iter().filter(|b| worn >= b.pieces) {
+        add_modifier(&mut total, &bonus.modifier);
+    }
+    total
+}
+
+/// Combined modifier from every equipped item plus active set bonuses.
+/// Broken items contribute nothing.
+pub fn equipment_bonus(
+    player: &Player,
+    items: &HashMap<String, Item>,
+    item_sets: &HashMap<String, ItemSet>,
+) -> StatModifier {
+    let mut total = StatModifier::default();
+    for (_, item_id) in player.equipped_items() {
+        let item = items.get(item_id).filter(|i| !i.is_broken());
+        if let Some(modifier) = item.and_then(|i| i.modifier.as_ref()) {
+            add_modifier(&mut total, modifier);
+        }
+    }
+    for (set_id, worn) in worn_set_pieces(player, items) {
+        if let Some(set) = item_sets.get(&set_id) {
+            add_modifier(&mut total, &set_bonus(set, worn));
+        }
+    }
+    total
+}
+
+/// The item a worn copy was split off from, or the id itself.
+pub fn base_id<'a>(item_id: &'a str, state: &'a WorldState) -> &'a str {
+    state.item_instances.get(item_id).map_or(item_id, String::as_str)
+}
+
+/// Where in the pack the player carries `item_id` or a worn copy of it.
+pub fn carried_position(item_id: &str, state: &WorldState) -> Option<usize> {
+    state
+        .player
+        .inventory
+        .iter()
+        .position(|id| id == item_id || base_id(id, state) == item_id)
+}
+
+/// Copies a shared item under an id of its own, recorded against the item
+/// it came from. Items that already are copies are returned as they are.
+pub fn mint_instance(item_id: &str, state: &mut WorldState) -> String {
+    if state.item_instances.contains_key(item_id) {
+        return item_id.to_string();
+    }
+    let Some(mut item) = state.items.get(item_id).cloned() else {
+        return item_id.to_string();
+    };
+    state.next_item_serial += 1;
+    let id = format!("{}_copy{}", item_id, state.next_item_serial);
+    item.id = id.clone();
+    state.items.insert(id.clone(), item);
+    state.item_instances.insert(id.clone(), item_id.to_string());
+    id
+}
+
+/// Forgets copies and variants that nothing holds any more, and folds unworn
+/// copies whose wear matches their item again back into it, so that saves
+/// don't keep every copy ever made.
+pub fn prune_instances(state: &mut WorldState) {
+    let mut instances: Vec<(String, String)> = state
+        .item_instances
+        .iter()
+        .map(|(id, base)| (id.clone(), base.clone()))
+        .collect();
+    instances.sort();
+    for (id, base) in instances {
+        let held_by_player = state.player.inventory.contains(&id);
+        let equipped = state.player.is_equipped(&id);
+        let lying = state.locations.values().any(|l| l.items.contains(&id));
+        let held_by_npc = state.npcs.values().any(|n| n.items.contains(&id));
+        let is_copy = id
+            .strip_prefix(base.as_str())
+            .is_some_and(|rest| rest.starts_with("_copy"));
+        let mended = is_copy
+            && !equipped
+            && !state.opened_containers.contains(&id)
+            && state.items.get(&base).is_some_and(|item| {
+                state.items.get(&id).is_some_and(|copy| {
+                    copy.durability == item.durability && copy.charges == item.charges
+                })
+            });
+        if mended {
+            let lists = std::iter::once(&mut state.player.inventory)
+                .chain(state.locations.values_mut().map(|l| &mut l.items))
+                .chain(state.npcs.values_mut().map(|n| &mut n.items));
+            for list in lists {
+                for held in list.iter_mut().filter(|held| **held == id) {
+                    *held = base.clone();
+                }
+            }
+        } else if held_by_player || equipped || lying || held_by_npc {
+            continue;
+        }
+        state.items.remove(&id);
+        state.item_instances.remove(&id);
+        state.opened_containers.remove(&id);
+    }
+}
+
+/// Gives the equipped copy of a shared item an id of its own, so that its
+/// wear doesn't show on every other copy. Returns the copy's id.
+fn split_instance(item_id: &str, state: &mut WorldState) -> String {
+    let id = mint_instance(item_id, state);
+    if id == item_id {
+        return id;
+    }
+    if let Some(pos) = state.player.inventory.iter().position(|i| i == item_id) {
+        state.player.inventory[pos] = id.clone();
+    }
+    for slot in EquipSlot::ALL {
+        let equipped = state.player.equipped_mut(slot);
+        if equipped.as_deref() == Some(item_id) {
+            *equipped = Some(id.clone());
+        }
+    }
+    id
+}
+
+/// Takes one point of durability from whatever fills `slots`, warning when a
+/// piece becomes badly worn or breaks. No-op unless the module enables durability.
+pub fn wear_equipment(state: &mut WorldState, slots: &[EquipSlot]) -> Vec<OutputLine> {
+    if !state.durability_enabled {
+        return Vec::new();
+    }
+    let item_ids: Vec<String> = slots
+        .iter()
+        .filter_map(|slot| state.player.equipped(*slot).cloned())
+        .collect();
+
+    let mut messages = Vec::new();
+    for item_id in item_ids {
+        let wears = state
+            .items
+            .get(&item_id)
+            .and_then(|item| item.durability)
+            .is_some_and(|d| !d.is_broken());
+        if !wears {
+            continue;
+        }
+        let item_id = split_instance(&item_id, state);
+        let Some(item) = state.items.get_mut(&item_id) else {
+            continue;
+        };
+        let Some(durability) = item.durability.as_mut() else {
+            continue;
+        };
+        let was_worn = durability.is_worn();
+        durability.current -= 1;
+        if durability.is_broken() {
+            messages.push(OutputLine {
+                text: templates::describe_item_broken(&item.name),
+                line_type: LineType::System,
+            });
+        } else if !was_worn && durability.is_worn() {
+            messages.push(OutputLine {
+                text: templates::describe_item_worn(&item.name),
+                line_type: LineType::System,
+            });
+        }
+    }
+    messages
+}
+
+/// Restores up to `amount` durability, or all of it when `amount` is `None`.
+/// Returns the points restored.
+pub fn repair_item(state: &mut WorldState, item_id: &str, amount: Option<u32>) -> u32 {
+    let Some(durability) = state
+        .items
+        .get_mut(item_id)
+        .and_then(|i| i.durability.as_mut())
+    else {
+        return 0;
+    };
+    let missing = durability.max - durability.current;
+    let restored = amount.map_or(missing, |a| a.min(missing));
+    durability.current += restored;
+    restored
+}
+
+/// The equipped item with the lowest remaining durability fraction.
+pub fn most_worn_equipped(state: &WorldState) -> Option<String> {
+    state
+        .player
+        .equipped_items()
+        .into_iter()
+        .filter_map(|(_, id)| {
+            let durability = state.items.get(id)?.durability?;
+            (durability.current < durability.max).then_some((id, durability))
+        })
+        .min_by_key(|(_, d)| (d.current * 100) / d.max.max(1))
+        .map(|(id, _)| id.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gear(id: &str, slot: EquipSlot, defense: i32, set_id: Option<&str>) -> Item {
+        Item {
+            id: id.into(),
+            name: id.into(),
+            description: String::new(),
+            item_type: ItemType::Armor,
+            modifier: Some(StatModifier {
+                attack: 0,
+                defense,
+                health: 0,
+            }),
+            usable: false,
+            consumable: false,
+            key_id: None,
+            lore: None,
+            slot: Some(slot),
+            set_id: set_id.map(String::from),
+            ..Default::default()
+        }
+    }
+
+    fn make_sets() -> HashMap<String, ItemSet> {
+        HashMap::from([(
+            "warden".to_string(),
+            ItemSet {
+                id: "warden".into(),
+                name: "Warden's Watch".into(),
+                bonuses: vec![
+                    SetBonus {
+                        pieces: 2,
+                        modifier: StatModifier {
+                            attack: 0,
+                            defense: 2,
+                            health: 0,
+                        },
+                    },
+                    SetBonus {
+                        pieces: 3,
+                        modifier: StatModifier {
+                            attack: 3,
+                            defense: 0,
+                            health: 0,
+                        },
+                    },
+                ],
+            },
+        )])
+    }
+
+    #[test]
+    fn sums_every_slot_and_stacks_set_tiers() {
+        let items: HashMap<String, Item> = [
+            gear("helm", EquipSlot::Helmet, 1, Some("warden")),
+            gear("boots", EquipSlot::Boots, 1, Some("warden")),
+            gear("ring", EquipSlot::Ring, 1, Some("warden")),
+        ]
+        .into_iter()
+        .map(|i| (i.id.clone(), i))
+        .collect();
+        let sets = make_sets();
+        let mut player = Player {
+            equipped_helmet: Some("helm".into()),
+            ..Player::default()
+        };
+        assert_eq!(equipment_bonus(&player, &items, &sets).defense, 1);
+
+        player.equipped_boots = Some("boots".into());
+        let two = equipment_bonus(&player, &items, &sets);
+        assert_eq!((two.attack, two.defense), (0, 4));
+
+        player.equipped_ring = Some("ring".into());
+        let three = equipment_bonus(&player, &items, &sets);
+        assert_eq!((three.attack, three.defense), (3, 5));
+        assert_eq!(
+            worn_set_pieces(&player, &items),
+            vec![("warden".to_string(), 3)]
+        );
+    }
+
+    #[test]
+    fn spent_and_mended_copies_are_pruned() {
+        let mut state = WorldState::default();
+        let mut helm = gear("helm", EquipSlot::Helmet, 1, None);
+        helm.durability = Some(Durability { current: 10, max: 10 });
+        state.items.insert("helm".into(), helm);
+        for _ in 0..3 {
+            let id = mint_instance("helm", &mut state);
+            state.player.inventory.push(id);
+        }
+        state.player.equipped_helmet = Some("helm_copy1".into());
+        state.items.get_mut("helm_copy2").unwrap().durability =
+            Some(Durability { current: 4, max: 10 });
+        state.player.inventory.retain(|id| id != "helm_copy3");
+        assert_eq!(carried_position("helm_copy2", &state), Some(1));
+
+        prune_instances(&mut state);
+        // Worn copies stay, the lost one is forgotten
+        assert!(state.items.contains_key("helm_copy1"));
+        assert!(state.items.contains_key("helm_copy2"));
+        assert!(!state.items.contains_key("helm_copy3"));
+        assert!(!state.item_instances.contains_key("helm_copy3"));
+
+        // Once mended and taken off, a copy is the plain item again
+        state.player.equipped_helmet = None;
+        prune_instances(&mut state);
+        assert_eq!(state.player.inventory, vec!["helm", "helm_copy2"]);
+        assert!(!state.items.contains_key("helm_copy1"));
+    }
+}
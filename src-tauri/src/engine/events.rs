@@ -1,5 +1,438 @@
+use crate::engine::{conditions, equipment, factions, loot, quest, scripting, templates};
 use crate::models::*;
 
+/// The room an exit leads to and the direction leading back from it, when
+/// the way runs both ways.
+fn far_side(
+    location_id: &str,
+    direction: Direction,
+    state: &WorldState,
+) -> Option<(LocationId, Direction)> {
+    let far_id = state.locations.get(location_id)?.exits.get(&direction)?;
+    let back = direction.opposite();
+    let leads_back = state.locations.get(far_id)?.exits.get(&back)? == location_id;
+    leads_back.then(|| (far_id.clone(), back))
+}
+
+fn give_item(state: &mut WorldState, item_id: &str, messages: &mut Vec<OutputLine>) {
+    if state.player.inventory.len() < state.player.max_inventory {
+        state.player.inventory.push(item_id.to_string());
+        if let Some(item) = state.items.get(item_id) {
+            messages.push(OutputLine {
+                text: format!("You received: {}", item.name),
+                line_type: LineType::System,
+            });
+        }
+    } else if let Some(item) = state.items.get(item_id) {
+        messages.push(OutputLine {
+            text: format!(
+                "Your inventory is full! The {} falls to the ground.",
+                item.name
+            ),
+            line_type: LineType::System,
+        });
+        // Drop item in current location instead
+        if let Some(loc) = state.locations.get_mut(&state.player.location) {
+            loc.items.push(item_id.to_string());
+        }
+    }
+}
+
+/// Applies one event action. `location_id` is the room the triggering event
+/// belongs to; spawns and unlocks happen there.
+pub fn apply_action(
+    action: EventAction,
+    location_id: &str,
+    state: &mut WorldState,
+    messages: &mut Vec<OutputLine>,
+) {
+    match action {
+        EventAction::Damage(amount) => {
+            state.player.health = (state.player.health - amount).max(0);
+            messages.push(OutputLine {
+                text: crate::engine::templates::describe_event_damage(amount),
+                line_type: LineType::Combat,
+            });
+        }
+        EventAction::SpawnNpc(npc_id) => {
+            if let Some(loc) = state.locations.get_mut(location_id) {
+                if !loc.npcs.contains(&npc_id) {
+                    loc.npcs.push(npc_id.clone());
+                }
+            }
+            messages.push(OutputLine {
+                text: "A presence manifests before you...".to_string(),
+                line_type: LineType::Narration,
+            });
+        }
+        EventAction::Unlock(direction) => {
+            if let Some(loc) = state.locations.get_mut(location_id) {
+                loc.locked_exits.remove(&direction);
+                let door = loc.doors.get(&direction).and_then(|id| state.doors.get_mut(id));
+                if let Some(door) = door.filter(|d| d.state == DoorState::Locked) {
+                    door.state = DoorState::Closed;
+                }
+            }
+            // A seal holds both sides of the way
+            if let Some((far_id, back)) = far_side(location_id, direction, state) {
+                if let Some(far) = state.locations.get_mut(&far_id) {
+                    if far.locked_exits.get(&back).is_some_and(|key| key == SEALED) {
+                        far.locked_exits.remove(&back);
+                    }
+                }
+            }
+            messages.push(OutputLine {
+                text: format!("A passage {} has been revealed!", direction.display_name()),
+                line_type: LineType::System,
+            });
+        }
+        EventAction::Message(msg) => {
+            messages.push(OutputLine {
+                text: crate::engine::templates::describe_event_message(&msg),
+                line_type: LineType::Narration,
+            });
+        }
+        EventAction::GiveItem(item_id) => {
+            give_item(state, &item_id, messages);
+        }
+        EventAction::GiveLoot(table_id) => {
+            let drops = loot::roll_loot(&table_id, state);
+            for item_id in loot::drop_item_ids(&drops) {
+                give_item(state, &item_id, messages);
+            }
+        }
+        EventAction::SetQuestFlag(flag) => {
+            state.player.quest_flags.insert(flag.clone(), true);
+        }
+        EventAction::ApplyStatus(effect) => {
+            let name = effect.name.clone();
+            state.player.status_effects.push(effect);
+            messages.push(OutputLine {
+                text: format!("You are now affected by: {}", name),
+                line_type: LineType::System,
+            });
+        }
+        EventAction::RemoveStatus(name) => {
+            state.player.status_effects.retain(|e| e.name != name);
+            messages.push(OutputLine {
+                text: format!("{} has worn off.", name),
+                line_type: LineType::System,
+            });
+        }
+        EventAction::ChangeDescription(loc_id, new_desc) => {
+            if let Some(loc) = state.locations.get_mut(&loc_id) {
+                loc.description = new_desc;
+            }
+        }
+        EventAction::Heal(amount) => {
+            let before = state.player.health;
+            state.player.health =
+                state.player.health.saturating_add(amount).min(state.player.max_health);
+            messages.push(OutputLine {
+                text: format!(
+                    "You recover {} HP. (HP: {})",
+                    state.player.health - before,
+                    state.player.health
+                ),
+                line_type: LineType::System,
+            });
+        }
+        EventAction::Teleport(dest_id) => {
+            let Some(dest) = state.locations.get_mut(&dest_id) else {
+                return;
+            };
+            dest.visited = true;
+            state.player.location = dest_id.clone();
+            state.player.visited_locations.insert(dest_id.clone());
+            messages.push(OutputLine {
+                text: format!("The world lurches, and you find yourself in {}.", dest.name),
+                line_type: LineType::Narration,
+            });
+            if let Some(dest) = state.locations.get(&dest_id) {
+                let lines = templates::describe_location(
+                    dest,
+                    &state.items,
+                    &state.npcs,
+                    &state.doors,
+                    &state.fixtures,
+                    false,
+                    state.clock.phase(),
+                );
+                messages.extend(lines.into_iter().map(|text| OutputLine {
+                    text,
+                    line_type: LineType::Narration,
+                }));
+            }
+        }
+        EventAction::RevealExits => {
+            let Some(loc) = state.locations.get(&state.player.location) else {
+                return;
+            };
+            let mut exits: Vec<(Direction, String)> =
+                loc.exits.iter().map(|(d, id)| (*d, id.clone())).collect();
+            exits.sort_by_key(|(d, _)| d.display_name().to_string());
+            let mut revealed = Vec::new();
+            for (direction, dest_id) in exits {
+                if let Some(dest) = state.locations.get_mut(&dest_id) {
+                    dest.visited = true;
+                    revealed.push(format!("{}: {}", direction.display_name(), dest.name));
+                }
+            }
+            if !revealed.is_empty() {
+                messages.push(OutputLine {
+                    text: format!("The way ahead becomes clear. {}", revealed.join(", ")),
+                    line_type: LineType::System,
+                });
+            }
+        }
+        EventAction::StartQuest(quest_id) => {
+            let Some(quest) = state.quests.get_mut(&quest_id) else {
+                return;
+            };
+            if quest.active || quest.completed || quest.failed {
+                return;
+            }
+            quest.active = true;
+            messages.push(OutputLine {
+                text: templates::describe_quest_started(quest),
+                line_type: LineType::System,
+            });
+            state.pending_sound_cues.push(SoundCue::QuestStart);
+        }
+        EventAction::Lock(direction) => {
+            let Some(loc) = state.locations.get(location_id) else {
+                return;
+            };
+            if !loc.exits.contains_key(&direction) {
+                return;
+            }
+            // A door in the way is locked; otherwise the exit is sealed from
+            // both sides
+            let door_id = loc.doors.get(&direction).cloned();
+            match door_id.and_then(|id| state.doors.get_mut(&id)) {
+                Some(door) => {
+                    door.state = DoorState::Locked;
+                    door.broken = false;
+                }
+                None => {
+                    let far_side = far_side(location_id, direction, state);
+                    let sides = std::iter::once((location_id.to_string(), direction));
+                    for (id, dir) in sides.chain(far_side) {
+                        if let Some(loc) = state.locations.get_mut(&id) {
+                            loc.locked_exits.insert(dir, SEALED.to_string());
+                        }
+                    }
+                }
+            }
+            messages.push(OutputLine {
+                text: format!("The way {} slams shut.", direction.display_name()),
+                line_type: LineType::System,
+            });
+        }
+        EventAction::AddExit(direction, dest_id) => {
+            if let Some(loc) = state.locations.get_mut(location_id) {
+                loc.exits.insert(direction, dest_id);
+                messages.push(OutputLine {
+                    text: format!("A way {} opens up.", direction.display_name()),
+                    line_type: LineType::System,
+                });
+            }
+        }
+        EventAction::RemoveExit(direction) => {
+            if let Some(loc) = state.locations.get_mut(location_id) {
+                if loc.exits.remove(&direction).is_some() {
+                    loc.locked_exits.remove(&direction);
+                    messages.push(OutputLine {
+                        text: format!("The way {} is gone.", direction.display_name()),
+                        line_type: LineType::System,
+                    });
+                }
+            }
+        }
+        EventAction::RemoveItem(item_id) => {
+            if let Some(pos) = equipment::carried_position(&item_id, state) {
+                let held_id = state.player.inventory.remove(pos);
+                if !state.player.inventory.contains(&held_id) {
+                    state.player.unequip_item(&held_id);
+                }
+                if let Some(item) = state.items.get(&item_id) {
+                    messages.push(OutputLine {
+                        text: format!("The {} is gone.", item.name),
+                        line_type: LineType::System,
+                    });
+                }
+            } else if let Some(loc) = state.locations.get_mut(location_id) {
+                if let Some(pos) = loc.items.iter().position(|id| *id == item_id) {
+                    loc.items.remove(pos);
+                }
+            }
+        }
+        EventAction::MoveNpc(npc_id, dest_id) => {
+            if !state.locations.contains_key(&dest_id) {
+                return;
+            }
+            let here = state.player.location.clone();
+            let was_here = state
+                .locations
+                .get(&here)
+                .is_some_and(|loc| loc.npcs.contains(&npc_id));
+            for loc in state.locations.values_mut() {
+                loc.npcs.retain(|id| *id != npc_id);
+            }
+            if let Some(dest) = state.locations.get_mut(&dest_id) {
+                dest.npcs.push(npc_id.clone());
+            }
+            let Some(npc) = state.npcs.get(&npc_id) else {
+                return;
+            };
+            let text = match (was_here, dest_id == here) {
+                (true, false) => format!("{} leaves.", npc.name),
+                (false, true) => format!("{} arrives.", npc.name),
+                _ => return,
+            };
+            messages.push(OutputLine {
+                text,
+                line_type: LineType::Narration,
+            });
+        }
+        EventAction::SetNpcHostile(npc_id, hostile) => {
+            if let Some(npc) = state.npcs.get_mut(&npc_id) {
+                if npc.dialogue_state == DialogueState::Dead {
+                    return;
+                }
+                npc.hostile = hostile;
+                if hostile {
+                    npc.dialogue_state = DialogueState::Hostile;
+                } else if npc.dialogue_state == DialogueState::Hostile {
+                    npc.dialogue_state = DialogueState::Greeting;
+                }
+            }
+        }
+        EventAction::ChangeRelationship(npc_id, amount) => {
+            if let Some(npc) = state.npcs.get_mut(&npc_id) {
+                npc.relationship = npc.relationship.saturating_add(amount);
+            }
+        }
+        EventAction::ChangeReputation(faction_id, amount) => {
+            messages.extend(factions::change_reputation(&faction_id, amount, state));
+        }
+        EventAction::CompleteQuest(quest_id) => {
+            let Some(quest) = state.quests.get_mut(&quest_id) else {
+                return;
+            };
+            if quest.completed {
+                return;
+            }
+            quest.active = true;
+            quest.completed = true;
+            quest.completed_turn = Some(state.player.turns_elapsed);
+            quest::resolve_outcome(&quest_id, state);
+            let quest = state.quests[&quest_id].clone();
+            if let Some(giver) = state.npcs.get_mut(&quest.giver) {
+                if matches!(
+                    giver.dialogue_state,
+                    DialogueState::QuestOffered
+                        | DialogueState::QuestActive
+                        | DialogueState::QuestComplete
+                ) {
+                    giver.dialogue_state = DialogueState::Familiar;
+                }
+            }
+            messages.push(OutputLine {
+                text: templates::describe_quest_completed(&quest),
+                line_type: LineType::System,
+            });
+            for item_id in &quest.rewards() {
+                give_item(state, item_id, messages);
+            }
+            messages.extend(quest::start_follow_up(&quest_id, state));
+            state.pending_sound_cues.push(SoundCue::QuestComplete);
+        }
+        EventAction::FailQuest(quest_id) => {
+            messages.extend(quest::fail_quest(&quest_id, "The chance has passed.", state));
+        }
+        EventAction::PlaySound(cue) => {
+            state.pending_sound_cues.push(cue);
+        }
+        EventAction::EndGame(ending) => {
+            state.game_mode = GameMode::GameOver(ending);
+        }
+        EventAction::Sequence(actions) => {
+            for action in actions {
+                apply_action(action, location_id, state, messages);
+            }
+        }
+        EventAction::Schedule(event_id, delay) => start_timer(state, event_id, delay, None),
+        EventAction::ScheduleRepeating(event_id, every) => {
+            start_timer(state, event_id, every, Some(every))
+        }
+        EventAction::CancelTimer(event_id) => {
+            state.timers.retain(|t| t.event_id != event_id);
+        }
+        EventAction::RunScript(script_id) => {
+            scripting::run_script(&script_id, "", state, messages);
+        }
+    }
+}
+
+/// Starts the timer for a scheduled event, restarting any pending one.
+fn start_timer(state: &mut WorldState, event_id: String, delay: u32, repeat_every: Option<u32>) {
+    state.timers.retain(|t| t.event_id != event_id);
+    state.timers.push(EventTimer {
+        event_id,
+        due_turn: state.player.turns_elapsed + delay.max(1),
+        repeat_every,
+    });
+}
+
+/// Fires the events whose timers have come due. Scheduled events fire
+/// wherever the player is; their condition can restrict that.
+fn process_timers(state: &mut WorldState, messages: &mut Vec<OutputLine>) {
+    let now = state.player.turns_elapsed;
+    let due: Vec<EventTimer> = state
+        .timers
+        .iter()
+        .filter(|t| t.due_turn <= now)
+        .cloned()
+        .collect();
+    state
+        .timers
+        .retain(|t| t.due_turn > now || t.repeat_every.is_some());
+
+    for timer in due {
+        if let Some(every) = timer.repeat_every {
+            if let Some(pending) = state
+                .timers
+                .iter_mut()
+                .find(|t| t.event_id == timer.event_id)
+            {
+                pending.due_turn = now + every.max(1);
+            }
+        }
+        let Some(idx) = state
+            .events
+            .iter()
+            .position(|e| e.id.as_deref() == Some(timer.event_id.as_str()))
+        else {
+            continue;
+        };
+        let event = &state.events[idx];
+        if event.one_shot && event.fired {
+            state.timers.retain(|t| t.event_id != timer.event_id);
+            continue;
+        }
+        if !conditions::holds(event.condition.as_ref(), state) {
+            continue;
+        }
+        let action = event.action.clone();
+        let location_id = event.location_id.clone();
+        if event.one_shot {
+            state.events[idx].fired = true;
+        }
+        apply_action(action, &location_id, state, messages);
+    }
+}
+
 pub fn process_events(
     trigger: &EventTrigger,
     location_id: &str,
@@ -20,6 +453,9 @@ pub fn process_events(
         if &event.trigger != trigger {
             continue;
         }
+        if !conditions::holds(event.condition.as_ref(), state) {
+            continue;
+        }
         actions_to_apply.push(event.action.clone());
         if event.one_shot {
             events_to_mark_fired.push(idx);
@@ -31,89 +467,7 @@ pub fn process_events(
     }
 
     for action in actions_to_apply {
-        match action {
-            EventAction::Damage(amount) => {
-                state.player.health = (state.player.health - amount).max(0);
-                messages.push(OutputLine {
-                    text: crate::engine::templates::describe_event_damage(amount),
-                    line_type: LineType::Combat,
-                });
-            }
-            EventAction::SpawnNpc(npc_id) => {
-                if let Some(loc) = state.locations.get_mut(location_id) {
-                    if !loc.npcs.contains(&npc_id) {
-                        loc.npcs.push(npc_id.clone());
-                    }
-                }
-                messages.push(OutputLine {
-                    text: "A presence manifests before you...".to_string(),
-                    line_type: LineType::Narration,
-                });
-            }
-            EventAction::Unlock(direction) => {
-                if let Some(loc) = state.locations.get_mut(location_id) {
-                    loc.locked_exits.remove(&direction);
-                }
-                messages.push(OutputLine {
-                    text: format!("A passage {} has been revealed!", direction.display_name()),
-                    line_type: LineType::System,
-                });
-            }
-            EventAction::Message(msg) => {
-                messages.push(OutputLine {
-                    text: crate::engine::templates::describe_event_message(&msg),
-                    line_type: LineType::Narration,
-                });
-            }
-            EventAction::GiveItem(item_id) => {
-                if state.player.inventory.len() < state.player.max_inventory {
-                    state.player.inventory.push(item_id.clone());
-                    if let Some(item) = state.items.get(&item_id) {
-                        messages.push(OutputLine {
-                            text: format!("You received: {}", item.name),
-                            line_type: LineType::System,
-                        });
-                    }
-                } else if let Some(item) = state.items.get(&item_id) {
-                    messages.push(OutputLine {
-                        text: format!(
-                            "Your inventory is full! The {} falls to the ground.",
-                            item.name
-                        ),
-                        line_type: LineType::System,
-                    });
-                    // Drop item in current location instead
-                    if let Some(loc) =
-                        state.locations.get_mut(&state.player.location)
-                    {
-                        loc.items.push(item_id.clone());
-                    }
-                }
-            }
-            EventAction::SetQuestFlag(flag) => {
-                state.player.quest_flags.insert(flag.clone(), true);
-            }
-            EventAction::ApplyStatus(effect) => {
-                let name = effect.name.clone();
-                state.player.status_effects.push(effect);
-                messages.push(OutputLine {
-                    text: format!("You are now affected by: {}", name),
-                    line_type: LineType::System,
-                });
-            }
-            EventAction::RemoveStatus(name) => {
-                state.player.status_effects.retain(|e| e.name != name);
-                messages.push(OutputLine {
-                    text: format!("{} has worn off.", name),
-                    line_type: LineType::System,
-                });
-            }
-            EventAction::ChangeDescription(loc_id, new_desc) => {
-                if let Some(loc) = state.locations.get_mut(&loc_id) {
-                    loc.description = new_desc;
-                }
-            }
-        }
+        apply_action(action, location_id, state, &mut messages);
     }
 
     messages.retain(|m| !m.text.is_empty());
@@ -134,7 +488,10 @@ pub fn process_turn_events(state: &mut WorldState) -> Vec<OutputLine> {
             continue;
         }
         if let EventTrigger::OnTurn(turn) = &event.trigger {
-            if *turn == current_turn && event.location_id == location_id {
+            if *turn == current_turn
+                && event.location_id == location_id
+                && conditions::holds(event.condition.as_ref(), state)
+            {
                 actions_to_apply.push(event.action.clone());
                 if event.one_shot {
                     events_to_mark_fired.push(idx);
@@ -148,38 +505,9 @@ pub fn process_turn_events(state: &mut WorldState) -> Vec<OutputLine> {
     }
 
     for action in actions_to_apply {
-        match action {
-            EventAction::Message(msg) => {
-                messages.push(OutputLine {
-                    text: msg,
-                    line_type: LineType::Narration,
-                });
-            }
-            EventAction::ApplyStatus(effect) => {
-                let name = effect.name.clone();
-                state.player.status_effects.push(effect);
-                messages.push(OutputLine {
-                    text: format!("You are now affected by: {}", name),
-                    line_type: LineType::System,
-                });
-            }
-            EventAction::RemoveStatus(name) => {
-                state.player.status_effects.retain(|e| e.name != name);
-                messages.push(OutputLine {
-                    text: format!("{} has worn off.", name),
-                    line_type: LineType::System,
-                });
-            }
-            EventAction::ChangeDescription(loc_id, new_desc) => {
-                if let Some(loc) = state.locations.get_mut(&loc_id) {
-                    loc.description = new_desc;
-                }
-            }
-            _ => {
-                // Other actions handled by process_events
-            }
-        }
+        apply_action(action, &location_id, state, &mut messages);
     }
+    process_timers(state, &mut messages);
 
     // Tick down status effects
     let mut expired = Vec::new();
@@ -244,6 +572,7 @@ mod tests {
                 ambient_mood: Mood::Peaceful,
                 examine_details: None,
                 revisit_description: None,
+                ..Default::default()
             },
         );
         state.player.location = "test_room".into();
@@ -254,11 +583,13 @@ mod tests {
     fn process_damage_event() {
         let mut state = make_test_state();
         state.events.push(GameEvent {
+            id: None,
             trigger: EventTrigger::OnEnter,
             action: EventAction::Damage(10),
             one_shot: false,
             fired: false,
             location_id: "test_room".into(),
+            condition: None,
         });
 
         let msgs = process_events(&EventTrigger::OnEnter, "test_room", &mut state);
@@ -270,11 +601,13 @@ mod tests {
     fn one_shot_event_only_fires_once() {
         let mut state = make_test_state();
         state.events.push(GameEvent {
+            id: None,
             trigger: EventTrigger::OnEnter,
             action: EventAction::Message("Trap!".into()),
             one_shot: true,
             fired: false,
             location_id: "test_room".into(),
+            condition: None,
         });
 
         let msgs1 = process_events(&EventTrigger::OnEnter, "test_room", &mut state);
@@ -283,15 +616,43 @@ mod tests {
         assert_eq!(msgs2.len(), 0);
     }
 
+    #[test]
+    fn conditional_event_waits_for_its_condition() {
+        let mut state = make_test_state();
+        state.events.push(GameEvent {
+            id: None,
+            trigger: EventTrigger::OnEnter,
+            action: EventAction::Message("The door recognises you.".into()),
+            one_shot: true,
+            fired: false,
+            location_id: "test_room".into(),
+            condition: Some(Condition::Any(vec![
+                Condition::Flag("sigil_known".into()),
+                Condition::Not(Box::new(Condition::HealthAtLeast(50))),
+            ])),
+        });
+
+        let msgs = process_events(&EventTrigger::OnEnter, "test_room", &mut state);
+        assert!(msgs.is_empty());
+        assert!(!state.events[0].fired);
+
+        state.player.quest_flags.insert("sigil_known".into(), true);
+        let msgs = process_events(&EventTrigger::OnEnter, "test_room", &mut state);
+        assert_eq!(msgs.len(), 1);
+        assert!(state.events[0].fired);
+    }
+
     #[test]
     fn unlock_event_removes_locked_exit() {
         let mut state = make_test_state();
         state.events.push(GameEvent {
+            id: None,
             trigger: EventTrigger::OnUse("scroll".into()),
             action: EventAction::Unlock(Direction::North),
             one_shot: true,
             fired: false,
             location_id: "test_room".into(),
+            condition: None,
         });
 
         let trigger = EventTrigger::OnUse("scroll".into());
@@ -300,16 +661,368 @@ mod tests {
         assert!(!loc.locked_exits.contains_key(&Direction::North));
     }
 
+    fn apply_in(state: &mut WorldState, location_id: &str, action: EventAction) -> Vec<OutputLine> {
+        let mut messages = Vec::new();
+        apply_action(action, location_id, state, &mut messages);
+        messages
+    }
+
+    #[test]
+    fn fail_quest_action_fails_an_active_quest() {
+        let mut state = crate::engine::world_builder::build_thornhold();
+        state.quests.get_mut("rats_request").unwrap().active = true;
+        let messages = apply_in(
+            &mut state,
+            "courtyard",
+            EventAction::FailQuest("rats_request".into()),
+        );
+        assert!(state.quests["rats_request"].failed);
+        assert!(messages[0].text.starts_with("Quest Failed: The Rat's Request"));
+
+        // A failed quest can't be started again
+        apply_in(&mut state, "courtyard", EventAction::StartQuest("rats_request".into()));
+        assert!(!state.quests["rats_request"].active);
+    }
+
+    #[test]
+    fn lock_and_exit_actions_reshape_a_room() {
+        let mut state = crate::engine::world_builder::build_thornhold();
+        apply_in(&mut state, "courtyard", EventAction::Lock(Direction::East));
+        assert_eq!(state.locations["courtyard"].locked_exits[&Direction::East], SEALED);
+        assert_eq!(state.locations["great_hall"].locked_exits[&Direction::West], SEALED);
+        apply_in(&mut state, "great_hall", EventAction::Unlock(Direction::West));
+        assert!(state.locations["courtyard"].locked_exits.is_empty());
+        assert!(state.locations["great_hall"].locked_exits.is_empty());
+        apply_in(&mut state, "courtyard", EventAction::Lock(Direction::East));
+
+        apply_in(
+            &mut state,
+            "courtyard",
+            EventAction::AddExit(Direction::Down, "hidden_vault".into()),
+        );
+        assert_eq!(
+            state.locations["courtyard"].exits[&Direction::Down],
+            "hidden_vault"
+        );
+
+        apply_in(
+            &mut state,
+            "courtyard",
+            EventAction::RemoveExit(Direction::East),
+        );
+        let courtyard = &state.locations["courtyard"];
+        assert!(!courtyard.exits.contains_key(&Direction::East));
+        assert!(!courtyard.locked_exits.contains_key(&Direction::East));
+    }
+
+    #[test]
+    fn remove_item_prefers_inventory_and_unequips() {
+        let mut state = crate::engine::world_builder::build_thornhold();
+        state.player.inventory.push("short_sword".into());
+        state.player.equipped_weapon = Some("short_sword".into());
+
+        let msgs = apply_in(
+            &mut state,
+            "courtyard",
+            EventAction::RemoveItem("short_sword".into()),
+        );
+        assert!(!state.player.inventory.contains(&"short_sword".to_string()));
+        assert!(state.player.equipped_weapon.is_none());
+        assert!(msgs[0].text.contains("Short Sword"));
+
+        apply_in(
+            &mut state,
+            "courtyard",
+            EventAction::RemoveItem("rusty_lantern".into()),
+        );
+        assert!(!state.locations["courtyard"]
+            .items
+            .contains(&"rusty_lantern".to_string()));
+    }
+
+    #[test]
+    fn npc_actions_move_and_turn_npcs() {
+        let mut state = crate::engine::world_builder::build_thornhold();
+        state.player.location = "courtyard".into();
+
+        let msgs = apply_in(
+            &mut state,
+            "courtyard",
+            EventAction::MoveNpc("merchant_ghost".into(), "chapel".into()),
+        );
+        assert!(msgs[0].text.contains("leaves"));
+        assert!(!state.locations["courtyard"]
+            .npcs
+            .contains(&"merchant_ghost".to_string()));
+        assert!(state.locations["chapel"]
+            .npcs
+            .contains(&"merchant_ghost".to_string()));
+
+        apply_in(
+            &mut state,
+            "courtyard",
+            EventAction::SetNpcHostile("merchant_ghost".into(), true),
+        );
+        let ghost = &state.npcs["merchant_ghost"];
+        assert!(ghost.hostile);
+        assert_eq!(ghost.dialogue_state, DialogueState::Hostile);
+
+        apply_in(
+            &mut state,
+            "courtyard",
+            EventAction::SetNpcHostile("merchant_ghost".into(), false),
+        );
+        apply_in(
+            &mut state,
+            "courtyard",
+            EventAction::ChangeRelationship("merchant_ghost".into(), -15),
+        );
+        let ghost = &state.npcs["merchant_ghost"];
+        assert!(!ghost.hostile);
+        assert_eq!(ghost.dialogue_state, DialogueState::Greeting);
+        assert_eq!(ghost.relationship, -15);
+    }
+
+    #[test]
+    fn complete_quest_grants_rewards_once() {
+        let mut state = crate::engine::world_builder::build_thornhold();
+        let reward = state.quests["rats_request"].reward.clone();
+
+        apply_in(
+            &mut state,
+            "courtyard",
+            EventAction::CompleteQuest("rats_request".into()),
+        );
+        let quest = &state.quests["rats_request"];
+        assert!(quest.completed);
+        assert!(reward.iter().all(|id| state.player.inventory.contains(id)));
+        assert_eq!(state.pending_sound_cues, vec![SoundCue::QuestComplete]);
+
+        let before = state.player.inventory.len();
+        apply_in(
+            &mut state,
+            "courtyard",
+            EventAction::CompleteQuest("rats_request".into()),
+        );
+        assert_eq!(state.player.inventory.len(), before);
+    }
+
+    #[test]
+    fn sequence_runs_actions_in_order() {
+        let mut state = make_test_state();
+        let msgs = apply_in(
+            &mut state,
+            "test_room",
+            EventAction::Sequence(vec![
+                EventAction::Message("The ceiling gives way.".into()),
+                EventAction::PlaySound(SoundCue::PlayerDeath),
+                EventAction::EndGame(EndingType::Death),
+            ]),
+        );
+        assert_eq!(msgs[0].text, "The ceiling gives way.");
+        assert_eq!(state.pending_sound_cues, vec![SoundCue::PlayerDeath]);
+        assert_eq!(state.game_mode, GameMode::GameOver(EndingType::Death));
+    }
+
+    #[test]
+    fn turn_events_run_any_action() {
+        let mut state = make_test_state();
+        state.player.turns_elapsed = 3;
+        state.events.push(GameEvent {
+            id: None,
+            trigger: EventTrigger::OnTurn(3),
+            action: EventAction::Damage(7),
+            one_shot: true,
+            fired: false,
+            location_id: "test_room".into(),
+            condition: None,
+        });
+
+        process_turn_events(&mut state);
+        assert_eq!(state.player.health, 93);
+    }
+
+    fn timed_event(id: &str, action: EventAction, one_shot: bool) -> GameEvent {
+        GameEvent {
+            id: Some(id.into()),
+            trigger: EventTrigger::OnTimer,
+            action,
+            one_shot,
+            fired: false,
+            location_id: "test_room".into(),
+            condition: None,
+        }
+    }
+
+    #[test]
+    fn scheduled_event_fires_after_delay() {
+        let mut state = make_test_state();
+        state.events.push(GameEvent {
+            id: None,
+            trigger: EventTrigger::OnTake("idol".into()),
+            action: EventAction::Schedule("collapse".into(), 5),
+            one_shot: true,
+            fired: false,
+            location_id: "test_room".into(),
+            condition: None,
+        });
+        state
+            .events
+            .push(timed_event("collapse", EventAction::Damage(20), true));
+
+        process_events(
+            &EventTrigger::OnTake("idol".into()),
+            "test_room",
+            &mut state,
+        );
+        assert_eq!(state.timers.len(), 1);
+        assert_eq!(state.timers[0].due_turn, 5);
+
+        state.player.turns_elapsed = 4;
+        process_turn_events(&mut state);
+        assert_eq!(state.player.health, 100);
+
+        state.player.turns_elapsed = 5;
+        process_turn_events(&mut state);
+        assert_eq!(state.player.health, 80);
+        assert!(state.timers.is_empty());
+    }
+
+    #[test]
+    fn repeating_timer_fires_every_interval_until_cancelled() {
+        let mut state = make_test_state();
+        state.events.push(timed_event(
+            "drip",
+            EventAction::Message("Drip.".into()),
+            false,
+        ));
+        apply_in(
+            &mut state,
+            "test_room",
+            EventAction::ScheduleRepeating("drip".into(), 3),
+        );
+
+        let mut fired = 0;
+        for turn in 1..=9 {
+            state.player.turns_elapsed = turn;
+            fired += process_turn_events(&mut state).len();
+        }
+        assert_eq!(fired, 3);
+        assert_eq!(state.timers[0].due_turn, 12);
+
+        apply_in(
+            &mut state,
+            "test_room",
+            EventAction::CancelTimer("drip".into()),
+        );
+        assert!(state.timers.is_empty());
+    }
+
+    #[test]
+    fn scheduling_again_restarts_the_countdown() {
+        let mut state = make_test_state();
+        state.events.push(timed_event(
+            "bell",
+            EventAction::Message("Bong.".into()),
+            true,
+        ));
+        apply_in(
+            &mut state,
+            "test_room",
+            EventAction::Schedule("bell".into(), 2),
+        );
+        state.player.turns_elapsed = 1;
+        apply_in(
+            &mut state,
+            "test_room",
+            EventAction::Schedule("bell".into(), 2),
+        );
+        assert_eq!(state.timers.len(), 1);
+        assert_eq!(state.timers[0].due_turn, 3);
+    }
+
+    #[test]
+    fn timer_condition_can_skip_a_firing() {
+        let mut state = make_test_state();
+        state.events.push(GameEvent {
+            condition: Some(Condition::AtLocation("elsewhere".into())),
+            ..timed_event("quake", EventAction::Damage(10), true)
+        });
+        apply_in(
+            &mut state,
+            "test_room",
+            EventAction::Schedule("quake".into(), 1),
+        );
+
+        state.player.turns_elapsed = 1;
+        process_turn_events(&mut state);
+        assert_eq!(state.player.health, 100);
+        assert!(state.timers.is_empty());
+        assert!(!state.events[0].fired);
+    }
+
+    #[test]
+    fn give_loot_event_grants_guaranteed_drops() {
+        let mut state = make_test_state();
+        state.items.insert(
+            "coin".into(),
+            Item {
+                id: "coin".into(),
+                name: "Old Coin".into(),
+                description: "Tarnished.".into(),
+                item_type: ItemType::Miscellaneous,
+                modifier: None,
+                usable: false,
+                consumable: false,
+                key_id: None,
+                lore: None,
+                ..Default::default()
+            },
+        );
+        state.loot_tables.insert(
+            "purse".into(),
+            LootTable {
+                id: "purse".into(),
+                entries: vec![LootEntry {
+                    item_id: "coin".into(),
+                    weight: 1,
+                    min_quantity: 2,
+                    max_quantity: 2,
+                    rarity: Rarity::Common,
+                    guaranteed: true,
+                    randomize: false,
+                }],
+                rolls: 0,
+                drop_chance: 100,
+            },
+        );
+        state.events.push(GameEvent {
+            id: None,
+            trigger: EventTrigger::OnEnter,
+            action: EventAction::GiveLoot("purse".into()),
+            one_shot: true,
+            fired: false,
+            location_id: "test_room".into(),
+            condition: None,
+        });
+
+        let msgs = process_events(&EventTrigger::OnEnter, "test_room", &mut state);
+        assert_eq!(state.player.inventory, vec!["coin".to_string(), "coin".to_string()]);
+        assert_eq!(msgs.len(), 2);
+    }
+
     #[test]
     fn test_turn_event_fires() {
         let mut state = make_test_state();
         state.player.turns_elapsed = 5;
         state.events.push(GameEvent {
+            id: None,
             trigger: EventTrigger::OnTurn(5),
             action: EventAction::Message("The ground trembles!".into()),
             one_shot: true,
             fired: false,
             location_id: "test_room".into(),
+            condition: None,
         });
 
         let msgs = process_turn_events(&mut state);
@@ -322,6 +1035,24 @@ mod tests {
         assert!(msgs2.is_empty());
     }
 
+    #[test]
+    fn turn_event_condition_is_checked() {
+        let mut state = make_test_state();
+        state.player.turns_elapsed = 5;
+        state.events.push(GameEvent {
+            id: None,
+            trigger: EventTrigger::OnTurn(5),
+            action: EventAction::Message("Footsteps echo above.".into()),
+            one_shot: true,
+            fired: false,
+            location_id: "test_room".into(),
+            condition: Some(Condition::Visited("crypt".into())),
+        });
+
+        assert!(process_turn_events(&mut state).is_empty());
+        assert!(!state.events[0].fired);
+    }
+
     #[test]
     fn test_status_effect_tick_down() {
         let mut state = make_test_state();
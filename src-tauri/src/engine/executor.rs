@@ -1,4 +1,8 @@
-use crate::engine::{combat, crafting, dialogue, events, parser::GameCommand, quest, templates};
+use crate::engine::{
+    clock, combat, companions, crafting, dialogue, doors, encounters, equipment, events, factions,
+    item_effects, loot, npc_movement, parser::GameCommand, puzzles, quest, rest, scripting,
+    search, stealth, templates, topics,
+};
 use crate::models::*;
 
 fn build_narrative_context(
@@ -32,6 +36,7 @@ fn build_narrative_context(
         inventory_names,
         room_item_names,
         room_npc_names,
+        companion_names: companions::companion_names(state),
         action_type: action_type.clone(),
         turns_elapsed: state.player.turns_elapsed,
     })
@@ -43,8 +48,10 @@ fn fuzzy_match_item<'a>(
     items: &'a std::collections::HashMap<String, Item>,
 ) -> Vec<(&'a str, &'a str)> {
     let target_lower = target.to_lowercase();
+    let mut seen = std::collections::HashSet::new();
     available_ids
         .iter()
+        .filter(|id| seen.insert(id.as_str()))
         .filter_map(|id| {
             items.get(id).and_then(|item| {
                 let name_lower = item.name.to_lowercase();
@@ -63,6 +70,13 @@ fn fuzzy_match_item<'a>(
         .collect()
 }
 
+/// Removes a single copy of `id`, leaving any duplicates (stacked loot) in place.
+fn remove_one(list: &mut Vec<String>, id: &str) {
+    if let Some(pos) = list.iter().position(|x| x == id) {
+        list.remove(pos);
+    }
+}
+
 fn fuzzy_match_npc<'a>(
     target: &str,
     available_ids: &'a [String],
@@ -87,21 +101,82 @@ fn fuzzy_match_npc<'a>(
 }
 
 pub fn execute(command: GameCommand, state: &mut WorldState) -> ActionResult {
+    let turn = state.player.turns_elapsed;
+    let paced = matches!(
+        command,
+        GameCommand::Go(_) | GameCommand::Sneak(_) | GameCommand::Rest(_)
+    );
+    let mut result = execute_command(command, state);
+    // Every turn spent passes time; moving and resting keep their own pace
+    let spent = state.player.turns_elapsed.saturating_sub(turn);
+    if !paced && spent > 0 {
+        let minutes = spent * state.clock.minutes_per_action;
+        result.messages.extend(clock::advance(minutes, state));
+    }
+    // Objectives met by anything else the command did, e.g. a flag set by
+    // a script or turns survived
+    if !matches!(state.game_mode, GameMode::GameOver(_)) {
+        result.messages.extend(quest::check_quest_progress(state));
+    }
+    equipment::prune_instances(state);
+    // Sound cues raised by event actions along the way
+    result.sound_cues.append(&mut state.pending_sound_cues);
+    result
+}
+
+fn execute_command(command: GameCommand, state: &mut WorldState) -> ActionResult {
     match command {
         GameCommand::Look(target) => execute_look(target, state),
-        GameCommand::Go(direction) => execute_go(direction, state),
+        GameCommand::Go(direction) => execute_go(direction, false, state),
+        GameCommand::Sneak(direction) => execute_go(direction, true, state),
+        GameCommand::Hide => execute_hide(state),
+        GameCommand::Search => execute_search(state),
+        GameCommand::Disarm(target) => execute_disarm(&target, state),
         GameCommand::Take(target) => execute_take(&target, state),
         GameCommand::Drop(target) => execute_drop(&target, state),
         GameCommand::Use(target) => execute_use(&target, state),
+        GameCommand::Open(target) => execute_open(&target, state),
+        GameCommand::Close(target) => execute_door_action(&target, state, doors::close),
+        GameCommand::PickLock(target) => execute_door_action(&target, state, doors::pick),
+        GameCommand::Bash(target) => execute_door_action(&target, state, doors::bash),
+        GameCommand::Pull(target) => {
+            execute_fixture_action(&target, state, |id, state| puzzles::shift(id, true, state))
+        }
+        GameCommand::Push(target) => {
+            execute_fixture_action(&target, state, |id, state| puzzles::shift(id, false, state))
+        }
+        GameCommand::SetFixture(target, value) => {
+            execute_fixture_action(&target, state, |id, state| puzzles::set(id, &value, state))
+        }
+        GameCommand::Answer(reply) => execute_risky(puzzles::answer(&reply, state), state),
         GameCommand::Equip(target) => execute_equip(&target, state),
+        GameCommand::Repair(target) => execute_repair(&target, state),
         GameCommand::Unequip(target) => execute_unequip(&target, state),
         GameCommand::TalkTo(target) => execute_talk(&target, state),
+        GameCommand::AskAbout(target, topic) => execute_topic(&target, &topic, false, state),
+        GameCommand::TellAbout(target, topic) => execute_topic(&target, &topic, true, state),
         GameCommand::Attack(target) => execute_attack(&target, state),
+        GameCommand::Recruit(target) => {
+            execute_companion_order(&target, false, state, companions::recruit)
+        }
+        GameCommand::Dismiss(target) => {
+            execute_companion_order(&target, true, state, companions::dismiss)
+        }
+        GameCommand::CompanionWait(target, waiting) => {
+            execute_companion_order(&target, true, state, |id, state| {
+                companions::set_waiting(id, waiting, state)
+            })
+        }
+        GameCommand::Give(item, target) => execute_give(&item, &target, state),
         GameCommand::Flee => execute_flee(state),
         GameCommand::Inventory => execute_inventory(state),
+        GameCommand::Stats => execute_stats(state),
         GameCommand::Map => execute_map(state),
         GameCommand::QuestLog => execute_quest_log(state),
         GameCommand::Journal => execute_journal(state),
+        GameCommand::Reputation => execute_reputation(state),
+        GameCommand::Time => execute_time(state),
+        GameCommand::Rest(turns) => execute_rest(turns, state),
         GameCommand::Craft(first, second) => {
             crafting::execute_craft(&first, second.as_deref(), state)
         }
@@ -122,6 +197,9 @@ pub fn execute(command: GameCommand, state: &mut WorldState) -> ActionResult {
                 let npc_id = npc_id.clone();
                 return execute_dialogue_input(&msg, &npc_id, state);
             }
+            if let Some((script_id, arg)) = scripting::match_custom_verb(&msg, state) {
+                return execute_custom_verb(&msg, &script_id, &arg, state);
+            }
             let text = if msg.is_empty() {
                 "What would you like to do?".to_string()
             } else {
@@ -160,7 +238,15 @@ fn execute_look(target: Option<String>, state: &mut WorldState) -> ActionResult
 
     match target {
         None => {
-            let lines = templates::describe_location(&loc, &state.items, &state.npcs, !loc.visited);
+            let lines = templates::describe_location(
+                &loc,
+                &state.items,
+                &state.npcs,
+                &state.doors,
+                &state.fixtures,
+                !loc.visited,
+                state.clock.phase(),
+            );
             let messages: Vec<OutputLine> = lines
                 .into_iter()
                 .map(|text| OutputLine {
@@ -196,7 +282,7 @@ fn execute_look(target: Option<String>, state: &mut WorldState) -> ActionResult
             let matches = fuzzy_match_item(&target, room_items, &state.items);
             if let Some(&(id, _)) = matches.first() {
                 if let Some(item) = state.items.get(id).cloned() {
-                    let lines = templates::describe_examine_item(&item);
+                    let lines = templates::describe_examine_item(&item, state.durability_enabled);
                     if item.lore.is_some() {
                         add_journal_entry(state, &format!("item_{}", id), JournalCategory::Item, &item.name, item.lore.as_deref().unwrap_or(&item.description));
                     }
@@ -212,7 +298,7 @@ fn execute_look(target: Option<String>, state: &mut WorldState) -> ActionResult
             let inv_matches = fuzzy_match_item(&target, &state.player.inventory, &state.items);
             if let Some(&(id, _)) = inv_matches.first() {
                 if let Some(item) = state.items.get(id).cloned() {
-                    let lines = templates::describe_examine_item(&item);
+                    let lines = templates::describe_examine_item(&item, state.durability_enabled);
                     if item.lore.is_some() {
                         add_journal_entry(state, &format!("item_{}", id), JournalCategory::Item, &item.name, item.lore.as_deref().unwrap_or(&item.description));
                     }
@@ -237,6 +323,18 @@ fn execute_look(target: Option<String>, state: &mut WorldState) -> ActionResult
                 }
             }
 
+            if let Some(fixture) = puzzles::find_fixture(&target, state)
+                .and_then(|id| state.fixtures.get(&id))
+            {
+                let lines = templates::describe_examine_fixture(fixture);
+                return ActionResult {
+                    messages: lines.into_iter().map(|text| OutputLine { text, line_type: LineType::Narration }).collect(),
+                    action_type: ActionType::DisplayOnly,
+                    narrative_context: None,
+                    sound_cues: vec![],
+                };
+            }
+
             ActionResult {
                 messages: vec![OutputLine {
                     text: templates::describe_not_found(&target),
@@ -257,8 +355,10 @@ fn finalize_move(dest_id: &str, first_visit: bool, messages: &mut Vec<OutputLine
     // Fire OnEnter events
     let event_msgs = events::process_events(&EventTrigger::OnEnter, dest_id, state);
     messages.extend(event_msgs);
+    // Carried items give hidden things away; traps still hidden spring
+    messages.extend(search::enter_room(state));
 
-    // Check for player death from event damage
+    // Check for player death from event or trap damage
     if state.player.health <= 0 {
         state.game_mode = GameMode::GameOver(EndingType::Death);
         messages.push(OutputLine {
@@ -275,30 +375,29 @@ fn finalize_move(dest_id: &str, first_visit: bool, messages: &mut Vec<OutputLine
 
     // Auto-look
     if let Some(dest_loc) = state.locations.get(dest_id) {
-        let look_lines = templates::describe_location(dest_loc, &state.items, &state.npcs, first_visit);
+        let look_lines = templates::describe_location(
+            dest_loc,
+            &state.items,
+            &state.npcs,
+            &state.doors,
+            &state.fixtures,
+            first_visit,
+            state.clock.phase(),
+        );
         messages.extend(look_lines.into_iter().map(|text| OutputLine {
             text,
             line_type: LineType::Narration,
         }));
     }
 
-    // Check for hostile NPCs → auto enter combat
+    // Check for hostile NPCs → auto enter combat, unless the player slips
+    // past them unseen
     if let Some(hostile_npc) = find_hostile_npc_in_location(dest_id, state) {
-        state.game_mode = GameMode::InCombat(hostile_npc.clone());
-        state.combat_state = Some(CombatState {
-            enemy_id: hostile_npc.clone(),
-            player_turn: true,
-            turn_count: 0,
-        });
-        let npc_name = state
-            .npcs
-            .get(&hostile_npc)
-            .map(|n| n.name.clone())
-            .unwrap_or_default();
-        messages.push(OutputLine {
-            text: format!("{} attacks you!", npc_name),
-            line_type: LineType::Combat,
-        });
+        if !state.player.hidden {
+            start_combat(&hostile_npc, messages, state);
+        } else if let Some(death) = stay_hidden(messages, state) {
+            return Some(death);
+        }
     }
 
     // Check quest progress
@@ -308,7 +407,40 @@ fn finalize_move(dest_id: &str, first_visit: bool, messages: &mut Vec<OutputLine
     None
 }
 
-fn execute_go(direction: Direction, state: &mut WorldState) -> ActionResult {
+/// Rolls for each hostile nearby to notice the hidden player. One that does
+/// starts a fight and strikes first; returns the result if that kills them.
+fn stay_hidden(messages: &mut Vec<OutputLine>, state: &mut WorldState) -> Option<ActionResult> {
+    let Some(spotter) = stealth::detected_by(state) else {
+        let names: Vec<String> = stealth::hostiles_here(state)
+            .iter()
+            .filter_map(|id| state.npcs.get(id).map(|n| n.name.clone()))
+            .collect();
+        messages.push(OutputLine {
+            text: templates::describe_unnoticed(&names),
+            line_type: LineType::System,
+        });
+        return None;
+    };
+    let name = state.npcs.get(&spotter).map(|n| n.name.clone()).unwrap_or_default();
+    messages.push(OutputLine {
+        text: templates::describe_spotted(&name),
+        line_type: LineType::Combat,
+    });
+    start_combat(&spotter, messages, state);
+    let result = combat::execute_enemy_first_strike(state);
+    messages.extend(result.messages);
+    if !result.player_died {
+        return None;
+    }
+    Some(ActionResult {
+        messages: messages.clone(),
+        action_type: ActionType::PlayerDeath,
+        narrative_context: build_narrative_context(&ActionType::PlayerDeath, state),
+        sound_cues: vec![],
+    })
+}
+
+fn execute_go(direction: Direction, sneaking: bool, state: &mut WorldState) -> ActionResult {
     let current_loc = state.player.location.clone();
     let loc = match state.locations.get(&current_loc) {
         Some(l) => l.clone(),
@@ -349,7 +481,8 @@ fn execute_go(direction: Direction, state: &mut WorldState) -> ActionResult {
 
     // Check if locked
     if let Some(key_id) = loc.locked_exits.get(&direction) {
-        if state.player.inventory.contains(key_id) {
+        let carried = equipment::carried_position(key_id, state).filter(|_| key_id != SEALED);
+        if let Some(pos) = carried {
             // Unlock the door
             let key_name = state
                 .items
@@ -358,7 +491,7 @@ fn execute_go(direction: Direction, state: &mut WorldState) -> ActionResult {
                 .unwrap_or_else(|| key_id.clone());
 
             // Remove key from inventory and unlock both sides
-            state.player.inventory.retain(|id| id != key_id);
+            state.player.inventory.remove(pos);
             if let Some(l) = state.locations.get_mut(&current_loc) {
                 l.locked_exits.remove(&direction);
             }
@@ -371,9 +504,14 @@ fn execute_go(direction: Direction, state: &mut WorldState) -> ActionResult {
                 line_type: LineType::System,
             });
         } else {
+            let text = if key_id == SEALED {
+                templates::describe_sealed_exit(&direction)
+            } else {
+                templates::describe_locked_door(&direction)
+            };
             return ActionResult {
                 messages: vec![OutputLine {
-                    text: templates::describe_locked_door(&direction),
+                    text,
                     line_type: LineType::Error,
                 }],
                 action_type: ActionType::Error {
@@ -385,9 +523,38 @@ fn execute_go(direction: Direction, state: &mut WorldState) -> ActionResult {
         }
     }
 
-    // Move player
+    // A door in the way is opened on the way through
+    match doors::pass(direction, state) {
+        Ok(lines) => messages.extend(lines),
+        Err(text) => {
+            return ActionResult {
+                messages: vec![OutputLine {
+                    text,
+                    line_type: LineType::Error,
+                }],
+                action_type: ActionType::Error {
+                    message: format!("Blocked: {}", direction),
+                },
+                narrative_context: None,
+                sound_cues: vec![],
+            }
+        }
+    }
+
+    // Move player, bringing along anyone being escorted and any companions
+    let mut followers = quest::escorted_npcs(state);
+    for npc_id in companions::following(state) {
+        if !followers.contains(&npc_id) {
+            followers.push(npc_id);
+        }
+    }
+    messages.extend(companions::follow_player(&current_loc, &dest_id, &followers, state));
     state.player.location = dest_id.clone();
     state.player.turns_elapsed += 1;
+    state.player.hidden = sneaking;
+    // Sneaking takes twice as long
+    let minutes = state.clock.minutes_per_move * if sneaking { 2 } else { 1 };
+    messages.extend(clock::advance(minutes, state));
     let first_visit = !state.player.visited_locations.contains(&dest_id);
     state.player.visited_locations.insert(dest_id.clone());
 
@@ -404,9 +571,18 @@ fn execute_go(direction: Direction, state: &mut WorldState) -> ActionResult {
         }
     }
 
-    // Process turn-based events and status effect ticks
+    // Process turn-based events and status effect ticks, then let NPCs
+    // walk their routes; a hostile one arriving here starts a fight below
     let turn_msgs = events::process_turn_events(state);
     messages.extend(turn_msgs);
+    messages.extend(npc_movement::move_npcs(state));
+    encounters::tick(state);
+    if let Some(npc_id) = encounters::roll_encounter(state) {
+        messages.push(OutputLine {
+            text: templates::describe_encounter(&state.npcs[&npc_id].name),
+            line_type: LineType::Combat,
+        });
+    }
 
     // Check for player death from status effect damage
     if state.player.health <= 0 {
@@ -438,6 +614,26 @@ fn execute_go(direction: Direction, state: &mut WorldState) -> ActionResult {
     }
 }
 
+/// Puts the player in a fight with `npc_id`, who strikes first.
+fn start_combat(npc_id: &str, messages: &mut Vec<OutputLine>, state: &mut WorldState) {
+    state.player.hidden = false;
+    state.game_mode = GameMode::InCombat(npc_id.to_string());
+    state.combat_state = Some(CombatState {
+        enemy_id: npc_id.to_string(),
+        player_turn: true,
+        turn_count: 0,
+    });
+    let npc_name = state
+        .npcs
+        .get(npc_id)
+        .map(|n| n.name.clone())
+        .unwrap_or_default();
+    messages.push(OutputLine {
+        text: format!("{} attacks you!", npc_name),
+        line_type: LineType::Combat,
+    });
+}
+
 fn find_hostile_npc_in_location(loc_id: &str, state: &WorldState) -> Option<String> {
     state.locations.get(loc_id).and_then(|loc| {
         loc.npcs.iter().find_map(|npc_id| {
@@ -462,6 +658,10 @@ fn execute_take(target: &str, state: &mut WorldState) -> ActionResult {
 
     let matches = fuzzy_match_item(target, &room_items, &state.items);
     if matches.is_empty() {
+        let fixed = puzzles::find_fixture(target, state).and_then(|id| state.fixtures.get(&id));
+        if let Some(fixture) = fixed {
+            return companion_error(format!("The {} is fixed in place.", fixture.name));
+        }
         return ActionResult {
             messages: vec![OutputLine {
                 text: templates::describe_not_found(target),
@@ -506,7 +706,7 @@ fn execute_take(target: &str, state: &mut WorldState) -> ActionResult {
 
     // Move item from room to inventory
     if let Some(loc) = state.locations.get_mut(&loc_id) {
-        loc.items.retain(|id| id != &item_id);
+        remove_one(&mut loc.items, &item_id);
     }
     state.player.inventory.push(item_id.clone());
     state.player.turns_elapsed += 1;
@@ -515,6 +715,14 @@ fn execute_take(target: &str, state: &mut WorldState) -> ActionResult {
         text: templates::describe_take(&item_name),
         line_type: LineType::Narration,
     }];
+    if let Some(witness) = factions::theft_witness(&item_id, state) {
+        let witness_name = state.npcs[&witness].name.clone();
+        messages.push(OutputLine {
+            text: format!("{} sees you take the {}.", witness_name, item_name),
+            line_type: LineType::System,
+        });
+        messages.extend(factions::record_deed(&witness, factions::Deed::Stole, state));
+    }
 
     // Fire OnTake events
     let event_msgs = events::process_events(
@@ -556,15 +764,12 @@ fn execute_drop(target: &str, state: &mut WorldState) -> ActionResult {
 
     let (item_id, item_name) = (matches[0].0.to_string(), matches[0].1.to_string());
 
-    // Unequip if equipped
-    if state.player.equipped_weapon.as_deref() == Some(&item_id) {
-        state.player.equipped_weapon = None;
-    }
-    if state.player.equipped_armor.as_deref() == Some(&item_id) {
-        state.player.equipped_armor = None;
-    }
+    remove_one(&mut state.player.inventory, &item_id);
 
-    state.player.inventory.retain(|id| id != &item_id);
+    // Unequip if that was the last copy
+    if !state.player.inventory.contains(&item_id) {
+        state.player.unequip_item(&item_id);
+    }
     let loc_id = state.player.location.clone();
     if let Some(loc) = state.locations.get_mut(&loc_id) {
         loc.items.push(item_id);
@@ -632,10 +837,56 @@ fn execute_use(target: &str, state: &mut WorldState) -> ActionResult {
         };
     }
 
+    if !item.effects.is_empty() {
+        if let Err(reason) = item_effects::check_ready(&item, state) {
+            return ActionResult {
+                messages: vec![OutputLine {
+                    text: reason.clone(),
+                    line_type: LineType::Error,
+                }],
+                action_type: ActionType::Error { message: reason },
+                narrative_context: None,
+                sound_cues: vec![],
+            };
+        }
+    }
+
     let mut messages = Vec::new();
     let mut effect = String::new();
+    let mut effect_lines = Vec::new();
 
     match item.item_type {
+        // Authored effects replace the built-in behaviour for the item type
+        _ if !item.effects.is_empty() => {
+            effect_lines = item_effects::apply_item_effects(&item, state);
+            if item_effects::is_used_up(&item_id, state) {
+                remove_one(&mut state.player.inventory, &item_id);
+            }
+        }
+        _ if item.repair_amount.is_some() => {
+            let Some(worn_id) = equipment::most_worn_equipped(state) else {
+                return ActionResult {
+                    messages: vec![OutputLine {
+                        text: "None of your equipment needs repair.".into(),
+                        line_type: LineType::Error,
+                    }],
+                    action_type: ActionType::Error {
+                        message: "Nothing to repair".into(),
+                    },
+                    narrative_context: None,
+                    sound_cues: vec![],
+                };
+            };
+            equipment::repair_item(state, &worn_id, item.repair_amount);
+            if let Some(worn) = state.items.get(&worn_id) {
+                if let Some(durability) = &worn.durability {
+                    effect = templates::describe_repair(&worn.name, durability);
+                }
+            }
+            if item.consumable {
+                remove_one(&mut state.player.inventory, &item_id);
+            }
+        }
         ItemType::Consumable => {
             if let Some(modifier) = &item.modifier {
                 if modifier.health > 0 {
@@ -655,13 +906,13 @@ fn execute_use(target: &str, state: &mut WorldState) -> ActionResult {
                 effect = "You consume it.".to_string();
             }
             if item.consumable {
-                state.player.inventory.retain(|id| id != &item_id);
+                remove_one(&mut state.player.inventory, &item_id);
             }
         }
         ItemType::Scroll => {
             effect = "The scroll crumbles to dust as its magic takes effect.".to_string();
             if item.consumable {
-                state.player.inventory.retain(|id| id != &item_id);
+                remove_one(&mut state.player.inventory, &item_id);
             }
         }
         ItemType::Key => {
@@ -681,12 +932,14 @@ fn execute_use(target: &str, state: &mut WorldState) -> ActionResult {
     }
 
     messages.push(OutputLine {
-        text: templates::describe_use(&item.name, &effect),
+        text: templates::describe_use(&item.name, &effect).trim_end().to_string(),
         line_type: LineType::Narration,
     });
+    messages.extend(effect_lines);
 
     // Fire OnUse events
     let loc_id = state.player.location.clone();
+    quest::record_activity(quest::QuestActivity::UsedItem(&item_id, &loc_id), state);
     let event_msgs = events::process_events(
         &EventTrigger::OnUse(item_id.clone()),
         &loc_id,
@@ -713,6 +966,229 @@ fn execute_use(target: &str, state: &mut WorldState) -> ActionResult {
     }
 }
 
+fn repair_error(text: String) -> ActionResult {
+    ActionResult {
+        messages: vec![OutputLine {
+            text: text.clone(),
+            line_type: LineType::Error,
+        }],
+        action_type: ActionType::Error { message: text },
+        narrative_context: None,
+        sound_cues: vec![],
+    }
+}
+
+/// Repairs an inventory item, preferring a smith in the room over using up a
+/// repair kit.
+fn execute_repair(target: &str, state: &mut WorldState) -> ActionResult {
+    let matches = fuzzy_match_item(target, &state.player.inventory, &state.items);
+    let Some(item) = matches.first().and_then(|(id, _)| state.items.get(*id)).cloned() else {
+        return repair_error(format!("You don't have '{}'.", target));
+    };
+    let Some(durability) = item.durability else {
+        return repair_error(format!("The {} can't be repaired.", item.name));
+    };
+    if durability.current >= durability.max {
+        return repair_error(format!("The {} is in perfect condition.", item.name));
+    }
+
+    let smith = state
+        .locations
+        .get(&state.player.location)
+        .into_iter()
+        .flat_map(|l| l.npcs.iter())
+        .filter_map(|id| state.npcs.get(id))
+        .find(|n| n.smith && !n.hostile && n.dialogue_state != DialogueState::Dead)
+        .map(|n| (n.id.clone(), n.name.clone()));
+    let kit = state
+        .player
+        .inventory
+        .iter()
+        .find(|id| state.items.get(*id).is_some_and(|i| i.repair_amount.is_some()))
+        .cloned();
+
+    let mut messages = Vec::new();
+    // Smiths whose faction has turned against the player refuse the work
+    let refusing = smith.as_ref().filter(|(id, _)| !factions::will_deal(id, state));
+    if let Some((_, smith_name)) = refusing {
+        if kit.is_none() {
+            return repair_error(format!("{} refuses to work for you.", smith_name));
+        }
+        messages.push(OutputLine {
+            text: format!("{} refuses to work for you.", smith_name),
+            line_type: LineType::System,
+        });
+    }
+    let smith = smith.filter(|(id, _)| factions::will_deal(id, state));
+    if let Some((_, smith_name)) = smith {
+        equipment::repair_item(state, &item.id, None);
+        messages.push(OutputLine {
+            text: format!("{} takes the {} and sets to work.", smith_name, item.name),
+            line_type: LineType::Narration,
+        });
+    } else if let Some(kit_id) = kit {
+        let kit_item = state.items.get(&kit_id).cloned();
+        let amount = kit_item.as_ref().and_then(|k| k.repair_amount);
+        equipment::repair_item(state, &item.id, amount);
+        if kit_item.is_some_and(|k| k.consumable) {
+            remove_one(&mut state.player.inventory, &kit_id);
+        }
+    } else {
+        return repair_error(format!(
+            "You need a repair kit or a smith to mend the {}.",
+            item.name
+        ));
+    }
+
+    if let Some(durability) = state.items.get(&item.id).and_then(|i| i.durability) {
+        messages.push(OutputLine {
+            text: templates::describe_repair(&item.name, &durability),
+            line_type: LineType::System,
+        });
+    }
+    state.player.turns_elapsed += 1;
+
+    let action_type = ActionType::ItemUsed {
+        item_name: item.name.clone(),
+        effect: "repaired".into(),
+    };
+    let ctx = build_narrative_context(&action_type, state);
+    ActionResult {
+        messages,
+        action_type,
+        narrative_context: ctx,
+        sound_cues: vec![SoundCue::ItemUse],
+    }
+}
+
+fn execute_open(target: &str, state: &mut WorldState) -> ActionResult {
+    if doors::find_door(target, state).is_some() {
+        return execute_door_action(target, state, doors::open);
+    }
+    let loc_id = state.player.location.clone();
+    let room_items = state
+        .locations
+        .get(&loc_id)
+        .map(|l| l.items.clone())
+        .unwrap_or_default();
+
+    // Containers in the room take precedence over ones being carried
+    let found = fuzzy_match_item(target, &room_items, &state.items)
+        .first()
+        .map(|(id, _)| (id.to_string(), true))
+        .or_else(|| {
+            fuzzy_match_item(target, &state.player.inventory, &state.items)
+                .first()
+                .map(|(id, _)| (id.to_string(), false))
+        });
+    let Some((item_id, in_room)) = found else {
+        return ActionResult {
+            messages: vec![OutputLine {
+                text: templates::describe_not_found(target),
+                line_type: LineType::Error,
+            }],
+            action_type: ActionType::Error {
+                message: format!("Not found: {}", target),
+            },
+            narrative_context: None,
+            sound_cues: vec![],
+        };
+    };
+
+    let Some(item) = state.items.get(&item_id).cloned() else {
+        return ActionResult {
+            messages: vec![OutputLine {
+                text: "Item data not found.".into(),
+                line_type: LineType::Error,
+            }],
+            action_type: ActionType::Error {
+                message: "Item not found".into(),
+            },
+            narrative_context: None,
+            sound_cues: vec![],
+        };
+    };
+
+    let Some(table_id) = item.loot_table.clone() else {
+        return ActionResult {
+            messages: vec![OutputLine {
+                text: format!("You can't open the {}.", item.name),
+                line_type: LineType::Error,
+            }],
+            action_type: ActionType::Error {
+                message: format!("Not a container: {}", item.name),
+            },
+            narrative_context: None,
+            sound_cues: vec![],
+        };
+    };
+
+    if state.opened_containers.contains(&item_id) {
+        return ActionResult {
+            messages: vec![OutputLine {
+                text: format!("The {} is empty.", item.name),
+                line_type: LineType::System,
+            }],
+            action_type: ActionType::DisplayOnly,
+            narrative_context: None,
+            sound_cues: vec![],
+        };
+    }
+    // Carried one-use containers are used up; any other gets an id of its
+    // own, so that others sharing its id stay closed
+    if !in_room && item.consumable {
+        remove_one(&mut state.player.inventory, &item_id);
+    } else {
+        let opened_id = equipment::mint_instance(&item_id, state);
+        let holder = if in_room {
+            state.locations.get_mut(&loc_id).map(|l| &mut l.items)
+        } else {
+            Some(&mut state.player.inventory)
+        };
+        if let Some(slot) = holder.and_then(|items| items.iter_mut().find(|id| **id == item_id)) {
+            *slot = opened_id.clone();
+        }
+        state.opened_containers.insert(opened_id);
+    }
+
+    let drops = loot::roll_loot(&table_id, state);
+    let contents = loot::describe_drops(&drops, &state.items);
+    let mut messages = vec![OutputLine {
+        text: templates::describe_container_opened(&item.name, &contents),
+        line_type: LineType::Narration,
+    }];
+
+    // Loot lands where the container is; carried containers fill the pack first
+    for drop_id in loot::drop_item_ids(&drops) {
+        if !in_room && state.player.inventory.len() < state.player.max_inventory {
+            state.player.inventory.push(drop_id);
+        } else if let Some(loc) = state.locations.get_mut(&loc_id) {
+            loc.items.push(drop_id);
+        }
+    }
+    state.player.turns_elapsed += 1;
+
+    let quest_msgs = quest::check_quest_progress(state);
+    messages.extend(quest_msgs);
+
+    let found = if contents.is_empty() {
+        "nothing".to_string()
+    } else {
+        contents.join(", ")
+    };
+    let action_type = ActionType::ItemUsed {
+        item_name: item.name.clone(),
+        effect: format!("Opened it and found {}.", found),
+    };
+    let ctx = build_narrative_context(&action_type, state);
+    ActionResult {
+        messages,
+        action_type,
+        narrative_context: ctx,
+        sound_cues: vec![],
+    }
+}
+
 fn execute_equip(target: &str, state: &mut WorldState) -> ActionResult {
     let matches = fuzzy_match_item(target, &state.player.inventory, &state.items);
     if matches.is_empty() {
@@ -750,43 +1226,30 @@ fn execute_equip(target: &str, state: &mut WorldState) -> ActionResult {
 
     let mut messages = Vec::new();
 
-    match item.item_type {
-        ItemType::Weapon => {
-            if let Some(old_weapon) = &state.player.equipped_weapon {
-                if let Some(old) = state.items.get(old_weapon) {
-                    messages.push(OutputLine {
-                        text: templates::describe_unequip(&old.name),
-                        line_type: LineType::System,
-                    });
-                }
-            }
-            state.player.equipped_weapon = Some(item_id);
-        }
-        ItemType::Armor => {
-            if let Some(old_armor) = &state.player.equipped_armor {
-                if let Some(old) = state.items.get(old_armor) {
-                    messages.push(OutputLine {
-                        text: templates::describe_unequip(&old.name),
-                        line_type: LineType::System,
-                    });
-                }
-            }
-            state.player.equipped_armor = Some(item_id);
-        }
-        _ => {
-            return ActionResult {
-                messages: vec![OutputLine {
-                    text: format!("You can't equip the {}.", item.name),
-                    line_type: LineType::Error,
-                }],
-                action_type: ActionType::Error {
-                    message: format!("Not equippable: {}", item.name),
-                },
-                narrative_context: None,
-                sound_cues: vec![],
-            };
+    let Some(slot) = item.equip_slot() else {
+        return ActionResult {
+            messages: vec![OutputLine {
+                text: format!("You can't equip the {}.", item.name),
+                line_type: LineType::Error,
+            }],
+            action_type: ActionType::Error {
+                message: format!("Not equippable: {}", item.name),
+            },
+            narrative_context: None,
+            sound_cues: vec![],
+        };
+    };
+
+    // Swap out whatever currently fills the slot
+    if let Some(old_id) = state.player.equipped(slot) {
+        if let Some(old) = state.items.get(old_id) {
+            messages.push(OutputLine {
+                text: templates::describe_unequip(&old.name),
+                line_type: LineType::System,
+            });
         }
     }
+    *state.player.equipped_mut(slot) = Some(item_id);
 
     messages.push(OutputLine {
         text: templates::describe_equip(&item.name),
@@ -806,35 +1269,19 @@ fn execute_equip(target: &str, state: &mut WorldState) -> ActionResult {
 fn execute_unequip(target: &str, state: &mut WorldState) -> ActionResult {
     let target_lower = target.to_lowercase();
 
-    // Check weapon
-    if let Some(weapon_id) = &state.player.equipped_weapon.clone() {
-        if let Some(item) = state.items.get(weapon_id) {
-            if item.name.to_lowercase().contains(&target_lower)
-                || weapon_id.to_lowercase().contains(&target_lower)
-            {
-                let name = item.name.clone();
-                state.player.equipped_weapon = None;
-                return ActionResult {
-                    messages: vec![OutputLine {
-                        text: templates::describe_unequip(&name),
-                        line_type: LineType::Narration,
-                    }],
-                    action_type: ActionType::ItemUnequipped { item_name: name },
-                    narrative_context: None,
-                sound_cues: vec![],
-                };
-            }
-        }
-    }
-
-    // Check armor
-    if let Some(armor_id) = &state.player.equipped_armor.clone() {
-        if let Some(item) = state.items.get(armor_id) {
+    let equipped: Vec<(EquipSlot, String)> = state
+        .player
+        .equipped_items()
+        .into_iter()
+        .map(|(slot, id)| (slot, id.clone()))
+        .collect();
+    for (slot, item_id) in equipped {
+        if let Some(item) = state.items.get(&item_id) {
             if item.name.to_lowercase().contains(&target_lower)
-                || armor_id.to_lowercase().contains(&target_lower)
+                || item_id.to_lowercase().contains(&target_lower)
             {
                 let name = item.name.clone();
-                state.player.equipped_armor = None;
+                *state.player.equipped_mut(slot) = None;
                 return ActionResult {
                     messages: vec![OutputLine {
                         text: templates::describe_unequip(&name),
@@ -873,7 +1320,13 @@ fn execute_talk(target: &str, state: &mut WorldState) -> ActionResult {
     match npc_match {
         Some((npc_id, _)) => {
             let npc_id = npc_id.to_string();
-            let result = dialogue::enter_dialogue(&npc_id, state);
+            let mut result = dialogue::enter_dialogue(&npc_id, state);
+            if let Some(text) = puzzles::riddle_prompt(&npc_id, state) {
+                result.messages.push(OutputLine {
+                    text,
+                    line_type: LineType::Dialogue,
+                });
+            }
             state.player.turns_elapsed += 1;
             ActionResult {
                 messages: result.messages,
@@ -896,6 +1349,163 @@ fn execute_talk(target: &str, state: &mut WorldState) -> ActionResult {
     }
 }
 
+/// Recruits, dismisses or gives orders to an NPC. Companions can be named
+/// from anywhere when `anywhere` is set; otherwise the NPC must be here.
+fn execute_companion_order(
+    target: &str,
+    anywhere: bool,
+    state: &mut WorldState,
+    order: impl FnOnce(&str, &mut WorldState) -> Result<String, String>,
+) -> ActionResult {
+    let mut npc_ids = state
+        .locations
+        .get(&state.player.location)
+        .map(|l| l.npcs.clone())
+        .unwrap_or_default();
+    if anywhere {
+        npc_ids.extend(state.companions.iter().map(|c| c.npc_id.clone()));
+    }
+    let Some(npc_id) = fuzzy_match_npc(target, &npc_ids, &state.npcs).map(|(id, _)| id.to_string())
+    else {
+        return companion_error(templates::describe_not_found(target));
+    };
+    match order(&npc_id, state) {
+        Ok(text) => {
+            state.player.turns_elapsed += 1;
+            let action_type = ActionType::EventTriggered {
+                event_description: text.clone(),
+            };
+            ActionResult {
+                messages: vec![OutputLine {
+                    text,
+                    line_type: LineType::Narration,
+                }],
+                narrative_context: build_narrative_context(&action_type, state),
+                action_type,
+                sound_cues: vec![],
+            }
+        }
+        Err(text) => companion_error(text),
+    }
+}
+
+fn execute_give(item: &str, target: &str, state: &mut WorldState) -> ActionResult {
+    let Some(item_id) = fuzzy_match_item(item, &state.player.inventory, &state.items)
+        .first()
+        .map(|(id, _)| id.to_string())
+    else {
+        return companion_error(format!("You don't have '{}'.", item));
+    };
+    execute_companion_order(target, false, state, |npc_id, state| {
+        companions::give(&item_id, npc_id, state)
+    })
+}
+
+/// Opens, closes, picks or bashes the door in the player's room that the
+/// target names.
+fn execute_door_action(
+    target: &str,
+    state: &mut WorldState,
+    action: impl FnOnce(&str, &mut WorldState) -> Result<Vec<OutputLine>, String>,
+) -> ActionResult {
+    let Some(door_id) = doors::find_door(target, state) else {
+        return companion_error(format!("You see no door like '{}' here.", target));
+    };
+    match action(&door_id, state) {
+        Ok(messages) => {
+            state.player.turns_elapsed += 1;
+            ActionResult {
+                messages,
+                action_type: ActionType::DisplayOnly,
+                narrative_context: None,
+                sound_cues: vec![],
+            }
+        }
+        Err(text) => companion_error(text),
+    }
+}
+
+/// Pulls, pushes or sets the fixture in the player's room that the target
+/// names.
+fn execute_fixture_action(
+    target: &str,
+    state: &mut WorldState,
+    action: impl FnOnce(&str, &mut WorldState) -> Result<Vec<OutputLine>, String>,
+) -> ActionResult {
+    let Some(fixture_id) = puzzles::find_fixture(target, state) else {
+        return companion_error(templates::describe_not_found(target));
+    };
+    let outcome = action(&fixture_id, state);
+    execute_risky(outcome, state)
+}
+
+fn companion_error(text: String) -> ActionResult {
+    ActionResult {
+        messages: vec![OutputLine {
+            text: text.clone(),
+            line_type: LineType::Error,
+        }],
+        action_type: ActionType::Error { message: text },
+        narrative_context: None,
+        sound_cues: vec![],
+    }
+}
+
+/// "ask <npc> about <topic>" and "tell <npc> about <topic>". An empty target
+/// means the NPC the player is already talking to.
+fn execute_topic(target: &str, topic: &str, telling: bool, state: &mut WorldState) -> ActionResult {
+    let npc_id = if target.is_empty() {
+        match &state.game_mode {
+            GameMode::InDialogue(npc_id) => Some(npc_id.clone()),
+            _ => None,
+        }
+    } else {
+        let npc_ids = state
+            .locations
+            .get(&state.player.location)
+            .map(|l| l.npcs.clone())
+            .unwrap_or_default();
+        fuzzy_match_npc(target, &npc_ids, &state.npcs).map(|(id, _)| id.to_string())
+    };
+    let Some(npc) = npc_id.and_then(|id| state.npcs.get(&id)).cloned() else {
+        let text = if target.is_empty() {
+            "Whom do you mean?".to_string()
+        } else {
+            templates::describe_not_found(target)
+        };
+        return ActionResult {
+            messages: vec![OutputLine {
+                text,
+                line_type: LineType::Error,
+            }],
+            action_type: ActionType::Error {
+                message: format!("NPC not found: {}", target),
+            },
+            narrative_context: None,
+            sound_cues: vec![],
+        };
+    };
+
+    let messages = if npc.hostile || npc.dialogue_state == DialogueState::Dead {
+        vec![OutputLine {
+            text: templates::describe_npc_dialogue(&npc),
+            line_type: LineType::Dialogue,
+        }]
+    } else {
+        topics::discuss(&npc.id, topic, telling, state)
+    };
+    state.player.turns_elapsed += 1;
+    ActionResult {
+        messages,
+        action_type: ActionType::NpcDialogue {
+            npc_name: npc.name,
+            dialogue_text: "authored".into(),
+        },
+        narrative_context: build_narrative_context(&ActionType::DisplayOnly, state),
+        sound_cues: vec![],
+    }
+}
+
 fn execute_dialogue_input(input: &str, npc_id: &str, state: &mut WorldState) -> ActionResult {
     let result = dialogue::process_dialogue_input(input, npc_id, state);
     ActionResult {
@@ -986,8 +1596,9 @@ fn execute_attack(target: &str, state: &mut WorldState) -> ActionResult {
             });
 
             // Make NPC hostile
+            let was_peaceful = !npc.hostile;
             if let Some(n) = state.npcs.get_mut(&npc_id) {
-                if !n.hostile {
+                if was_peaceful {
                     n.relationship = -50;
                     n.memory.push(crate::models::npc::NpcMemory {
                         turn: state.player.turns_elapsed,
@@ -996,17 +1607,34 @@ fn execute_attack(target: &str, state: &mut WorldState) -> ActionResult {
                 }
                 n.hostile = true;
             }
+            let reputation_msgs = if was_peaceful {
+                factions::record_deed(&npc_id, factions::Deed::Attacked, state)
+            } else {
+                Vec::new()
+            };
 
             // Add bestiary journal entry
             add_journal_entry(state, &format!("npc_{}", npc_id), JournalCategory::Bestiary, &npc.name, &npc.description);
 
+            // Striking from hiding catches the target off guard
+            let sneak_attack = std::mem::take(&mut state.player.hidden);
+            let opening = if sneak_attack {
+                templates::describe_sneak_attack(&npc.name)
+            } else {
+                format!("You engage {} in combat!", npc.name)
+            };
             let mut messages = vec![OutputLine {
-                text: format!("You engage {} in combat!", npc.name),
+                text: opening,
                 line_type: LineType::Combat,
             }];
+            messages.extend(reputation_msgs);
 
             // Execute first attack
-            let result = combat::execute_player_attack(state);
+            let result = if sneak_attack {
+                combat::execute_sneak_attack(state)
+            } else {
+                combat::execute_player_attack(state)
+            };
             messages.extend(result.messages);
             state.player.turns_elapsed += 1;
 
@@ -1113,6 +1741,31 @@ fn execute_inventory(state: &mut WorldState) -> ActionResult {
     }
 }
 
+fn execute_stats(state: &mut WorldState) -> ActionResult {
+    let mut lines =
+        templates::describe_stats(&state.player, &state.items, &state.item_sets, &state.clock);
+    lines.push(templates::describe_stealth(
+        stealth::stealth_rating(state),
+        state.player.hidden,
+    ));
+    if !state.companions.is_empty() {
+        lines.push("Companions:".to_string());
+        lines.extend(companions::describe_party(state));
+    }
+    ActionResult {
+        messages: lines
+            .into_iter()
+            .map(|text| OutputLine {
+                text,
+                line_type: LineType::System,
+            })
+            .collect(),
+        action_type: ActionType::DisplayOnly,
+        narrative_context: None,
+        sound_cues: vec![],
+    }
+}
+
 fn execute_map(state: &mut WorldState) -> ActionResult {
     let lines = templates::describe_map(&state.locations, &state.player);
     ActionResult {
@@ -1135,20 +1788,50 @@ fn execute_quest_log(state: &mut WorldState) -> ActionResult {
 
     let active: Vec<&Quest> = state.quests.values().filter(|q| q.active && !q.completed).collect();
     let completed: Vec<&Quest> = state.quests.values().filter(|q| q.completed).collect();
+    let failed: Vec<&Quest> = state.quests.values().filter(|q| q.failed).collect();
 
-    if active.is_empty() && completed.is_empty() {
+    if active.is_empty() && completed.is_empty() && failed.is_empty() {
         lines.push("No quests yet.".to_string());
     } else {
         if !active.is_empty() {
             lines.push("Active:".to_string());
             for quest in active {
                 lines.push(format!("  - {} — {}", quest.name, quest.description));
+                if quest.stages.is_empty() {
+                    if let Some(progress) = quest::count_progress(quest, state) {
+                        lines.push(format!("    Progress: {}", progress));
+                    }
+                    continue;
+                }
+                for stage in quest::open_stages(quest) {
+                    let number = quest.stages.iter().position(|s| s.id == stage.id).unwrap_or(0) + 1;
+                    let mut heading = format!("    Stage {}/{}: {}", number, quest.stages.len(), stage.name);
+                    if !stage.journal.is_empty() {
+                        heading.push_str(&format!(" — {}", stage.journal));
+                    }
+                    lines.push(heading);
+                    for line in quest::describe_stage_objectives(quest, &stage, state) {
+                        lines.push(format!("      {}", line));
+                    }
+                }
             }
         }
         if !completed.is_empty() {
             lines.push("Completed:".to_string());
             for quest in completed {
-                lines.push(format!("  - {} (done)", quest.name));
+                match quest.chosen_outcome() {
+                    Some(outcome) => {
+                        lines.push(format!("  - {} (done: {})", quest.name, outcome.description))
+                    }
+                    None => lines.push(format!("  - {} (done)", quest.name)),
+                }
+            }
+        }
+        if !failed.is_empty() {
+            lines.push("Failed:".to_string());
+            for quest in failed {
+                let reason = quest.progress.failure.as_deref().unwrap_or("failed");
+                lines.push(format!("  - {} ({})", quest.name, reason));
             }
         }
     }
@@ -1200,6 +1883,10 @@ fn execute_journal(state: &mut WorldState) -> ActionResult {
             }
         }
     }
+    if !state.factions.is_empty() {
+        lines.push("\nReputation:".to_string());
+        lines.extend(factions::describe_standings(state));
+    }
     ActionResult {
         messages: lines.into_iter().map(|text| OutputLine { text, line_type: LineType::System }).collect(),
         action_type: ActionType::DisplayOnly,
@@ -1208,6 +1895,209 @@ fn execute_journal(state: &mut WorldState) -> ActionResult {
     }
 }
 
+fn execute_reputation(state: &WorldState) -> ActionResult {
+    let mut lines = vec!["--- Reputation ---".to_string()];
+    if state.factions.is_empty() {
+        lines.push("No one here cares what you do.".to_string());
+    } else {
+        lines.extend(factions::describe_standings(state));
+    }
+    ActionResult {
+        messages: lines
+            .into_iter()
+            .map(|text| OutputLine { text, line_type: LineType::System })
+            .collect(),
+        action_type: ActionType::DisplayOnly,
+        narrative_context: None,
+        sound_cues: vec![],
+    }
+}
+
+/// Slips out of sight for a turn; hostiles in the room get a chance to
+/// spot the player.
+fn execute_hide(state: &mut WorldState) -> ActionResult {
+    let refusal = match state.game_mode {
+        GameMode::InCombat(_) => Some("You can't hide in the middle of a fight."),
+        GameMode::InDialogue(_) => Some("Not in the middle of a conversation."),
+        _ if state.player.hidden => Some("You are already hidden."),
+        _ => None,
+    };
+    if let Some(text) = refusal {
+        return ActionResult {
+            messages: vec![OutputLine {
+                text: text.into(),
+                line_type: LineType::Error,
+            }],
+            action_type: ActionType::Error {
+                message: text.into(),
+            },
+            narrative_context: None,
+            sound_cues: vec![],
+        };
+    }
+
+    state.player.turns_elapsed += 1;
+    state.player.hidden = true;
+    let mut messages = vec![OutputLine {
+        text: "You slip into the shadows and keep still.".into(),
+        line_type: LineType::System,
+    }];
+    if find_hostile_npc_in_location(&state.player.location, state).is_some() {
+        if let Some(death) = stay_hidden(&mut messages, state) {
+            return death;
+        }
+    }
+    ActionResult {
+        messages,
+        action_type: ActionType::DisplayOnly,
+        narrative_context: None,
+        sound_cues: vec![],
+    }
+}
+
+fn execute_search(state: &mut WorldState) -> ActionResult {
+    state.player.turns_elapsed += 1;
+    ActionResult {
+        messages: search::search(state),
+        action_type: ActionType::DisplayOnly,
+        narrative_context: None,
+        sound_cues: vec![],
+    }
+}
+
+fn execute_disarm(target: &str, state: &mut WorldState) -> ActionResult {
+    execute_risky(search::disarm(target, state), state)
+}
+
+/// Wraps up an action that may set off harm, such as a trap or a solved
+/// puzzle's events: it takes a turn, and the player may not survive it.
+fn execute_risky(outcome: Result<Vec<OutputLine>, String>, state: &mut WorldState) -> ActionResult {
+    let mut messages = match outcome {
+        Ok(messages) => messages,
+        Err(e) => {
+            return ActionResult {
+                messages: vec![OutputLine {
+                    text: e.clone(),
+                    line_type: LineType::Error,
+                }],
+                action_type: ActionType::Error { message: e },
+                narrative_context: None,
+                sound_cues: vec![],
+            }
+        }
+    };
+    state.player.turns_elapsed += 1;
+    if state.player.health <= 0 {
+        state.game_mode = GameMode::GameOver(EndingType::Death);
+        messages.push(OutputLine {
+            text: templates::describe_player_death(),
+            line_type: LineType::Combat,
+        });
+        return ActionResult {
+            messages,
+            action_type: ActionType::PlayerDeath,
+            narrative_context: build_narrative_context(&ActionType::PlayerDeath, state),
+            sound_cues: vec![],
+        };
+    }
+    ActionResult {
+        messages,
+        action_type: ActionType::DisplayOnly,
+        narrative_context: None,
+        sound_cues: vec![],
+    }
+}
+
+/// Rests turn by turn: the world keeps moving, and a hostile that shows up
+/// ends the rest in a fight.
+fn execute_rest(turns: u32, state: &mut WorldState) -> ActionResult {
+    let refusal = if matches!(state.game_mode, GameMode::InDialogue(_)) {
+        Some("Not in the middle of a conversation.".to_string())
+    } else {
+        find_hostile_npc_in_location(&state.player.location, state).map(|npc_id| {
+            let name = state.npcs.get(&npc_id).map_or("an enemy", |n| n.name.as_str());
+            format!("You can't rest with {} nearby.", name)
+        })
+    };
+    if let Some(text) = refusal {
+        return ActionResult {
+            messages: vec![OutputLine {
+                text: text.clone(),
+                line_type: LineType::Error,
+            }],
+            action_type: ActionType::Error { message: text },
+            narrative_context: None,
+            sound_cues: vec![],
+        };
+    }
+
+    let turns = turns.clamp(1, rest::MAX_REST_TURNS);
+    let mut messages = Vec::new();
+    let mut rested = 0;
+    let mut healed = 0;
+    let mut ambusher = None;
+    while rested < turns && ambusher.is_none() {
+        rested += 1;
+        state.player.turns_elapsed += 1;
+        messages.extend(clock::advance(state.clock.minutes_per_rest, state));
+        messages.extend(events::process_turn_events(state));
+        messages.extend(npc_movement::move_npcs(state));
+        encounters::tick(state);
+        if state.player.health <= 0 {
+            state.game_mode = GameMode::GameOver(EndingType::Death);
+            messages.push(OutputLine {
+                text: templates::describe_player_death(),
+                line_type: LineType::Combat,
+            });
+            return ActionResult {
+                messages,
+                action_type: ActionType::PlayerDeath,
+                narrative_context: build_narrative_context(&ActionType::PlayerDeath, state),
+                sound_cues: vec![],
+            };
+        }
+        healed += rest::heal_turn(state);
+        ambusher = rest::roll_ambush(state)
+            .or_else(|| encounters::roll_encounter(state))
+            .or_else(|| find_hostile_npc_in_location(&state.player.location, state));
+    }
+
+    messages.push(OutputLine {
+        text: templates::describe_rest(rested, healed, &state.player),
+        line_type: LineType::System,
+    });
+    if let Some(npc_id) = &ambusher {
+        let name = state.npcs.get(npc_id).map_or("Something", |n| n.name.as_str());
+        messages.push(OutputLine {
+            text: format!("{} finds you resting!", name),
+            line_type: LineType::Combat,
+        });
+        start_combat(npc_id, &mut messages, state);
+    }
+    let action_type = ActionType::Rested {
+        turns: rested,
+        interrupted: ambusher.is_some(),
+    };
+    ActionResult {
+        narrative_context: build_narrative_context(&action_type, state),
+        messages,
+        action_type,
+        sound_cues: vec![],
+    }
+}
+
+fn execute_time(state: &WorldState) -> ActionResult {
+    ActionResult {
+        messages: vec![OutputLine {
+            text: format!("It is {}.", templates::describe_time(&state.clock)),
+            line_type: LineType::System,
+        }],
+        action_type: ActionType::DisplayOnly,
+        narrative_context: None,
+        sound_cues: vec![],
+    }
+}
+
 fn execute_secret(word: &str, state: &mut WorldState) -> ActionResult {
     // Block secret commands during combat or dialogue
     if matches!(state.game_mode, GameMode::InCombat(_) | GameMode::InDialogue(_)) {
@@ -1350,6 +2240,27 @@ fn execute_secret(word: &str, state: &mut WorldState) -> ActionResult {
     }
 }
 
+fn execute_custom_verb(input: &str, script_id: &str, arg: &str, state: &mut WorldState) -> ActionResult {
+    state.player.turns_elapsed += 1;
+    let mut messages = Vec::new();
+    scripting::run_script(script_id, arg, state, &mut messages);
+    if messages.is_empty() {
+        messages.push(OutputLine {
+            text: "Nothing happens.".into(),
+            line_type: LineType::Narration,
+        });
+    }
+    let action_type = ActionType::EventTriggered {
+        event_description: input.to_string(),
+    };
+    ActionResult {
+        narrative_context: build_narrative_context(&action_type, state),
+        messages,
+        action_type,
+        sound_cues: vec![],
+    }
+}
+
 fn execute_help(state: &mut WorldState) -> ActionResult {
     let lines = templates::describe_help(&state.game_mode);
     ActionResult {
@@ -1389,6 +2300,7 @@ mod tests {
                 ambient_mood: Mood::Peaceful,
                 examine_details: Some("Scratches on the walls suggest a struggle.".into()),
                 revisit_description: Some("Room A feels familiar.".into()),
+                ..Default::default()
             },
         );
         state.locations.insert(
@@ -1406,6 +2318,7 @@ mod tests {
                 ambient_mood: Mood::Mysterious,
                 examine_details: None,
                 revisit_description: None,
+                ..Default::default()
             },
         );
         state.items.insert(
@@ -1424,6 +2337,7 @@ mod tests {
                 consumable: false,
                 key_id: None,
                 lore: Some("An ancient blade.".into()),
+                ..Default::default()
             },
         );
         state.items.insert(
@@ -1442,6 +2356,7 @@ mod tests {
                 consumable: true,
                 key_id: None,
                 lore: None,
+                ..Default::default()
             },
         );
         state.npcs.insert(
@@ -1462,6 +2377,7 @@ mod tests {
                 examine_text: Some("The guard wears a faded crest.".into()),
                 relationship: 0,
                 memory: vec![],
+                ..Default::default()
             },
         );
         state.player.location = "room_a".into();
@@ -1538,6 +2454,78 @@ mod tests {
         assert!(result.messages.iter().any(|m| m.text.contains("restored")));
     }
 
+    #[test]
+    fn test_take_and_use_one_of_a_stack() {
+        let mut state = make_test_world();
+        state.locations.get_mut("room_a").unwrap().items.push("potion".into());
+        let result = execute(GameCommand::Take("potion".into()), &mut state);
+        assert!(!result.messages.iter().any(|m| m.line_type == LineType::Error));
+        let room = state.locations.get("room_a").unwrap();
+        assert_eq!(room.items.iter().filter(|id| *id == "potion").count(), 1);
+
+        state.player.inventory.push("potion".into());
+        execute(GameCommand::Use("potion".into()), &mut state);
+        assert_eq!(state.player.inventory, vec!["potion".to_string()]);
+    }
+
+    #[test]
+    fn test_open_container_rolls_loot_once() {
+        let mut state = make_test_world();
+        state.items.insert(
+            "chest".into(),
+            Item {
+                id: "chest".into(),
+                name: "Iron Chest".into(),
+                description: "A heavy chest.".into(),
+                item_type: ItemType::Miscellaneous,
+                modifier: None,
+                usable: false,
+                consumable: false,
+                key_id: None,
+                lore: None,
+                loot_table: Some("chest_loot".into()),
+                ..Default::default()
+            },
+        );
+        state.loot_tables.insert(
+            "chest_loot".into(),
+            LootTable {
+                id: "chest_loot".into(),
+                entries: vec![LootEntry {
+                    item_id: "potion".into(),
+                    weight: 1,
+                    min_quantity: 2,
+                    max_quantity: 2,
+                    rarity: Rarity::Common,
+                    guaranteed: true,
+                    randomize: false,
+                }],
+                rolls: 0,
+                drop_chance: 100,
+            },
+        );
+        state.locations.get_mut("room_a").unwrap().items = vec!["chest".into()];
+
+        let result = execute(GameCommand::Open("chest".into()), &mut state);
+        assert!(result.messages[0].text.contains("Health Potion x2"));
+        let room = state.locations.get("room_a").unwrap();
+        assert_eq!(room.items.iter().filter(|id| *id == "potion").count(), 2);
+
+        let result = execute(GameCommand::Open("chest".into()), &mut state);
+        assert!(result.messages[0].text.contains("empty"));
+        assert_eq!(state.locations["room_a"].items[0], "chest_copy1");
+        assert!(state.opened_containers.contains("chest_copy1"));
+        assert!(state.player.quest_flags.is_empty());
+
+        // A chest of the same kind elsewhere is still full
+        state.locations.get_mut("room_b").unwrap().items = vec!["chest".into()];
+        state.player.location = "room_b".into();
+        let result = execute(GameCommand::Open("chest".into()), &mut state);
+        assert!(result.messages[0].text.contains("Health Potion x2"));
+        let result = execute(GameCommand::Open("sword".into()), &mut state);
+        assert!(result.messages.iter().any(|m| m.line_type == LineType::Error));
+    }
+
     #[test]
     fn test_equip_weapon() {
         let mut state = make_test_world();
@@ -1546,6 +2534,80 @@ mod tests {
         assert_eq!(state.player.equipped_weapon, Some("sword".into()));
     }
 
+    #[test]
+    fn test_equip_swaps_within_slot_and_stats_list_slots() {
+        let mut state = make_test_world();
+        for (id, name) in [("silver_ring", "Silver Ring"), ("gold_ring", "Gold Ring")] {
+            let mut ring = state.items["sword"].clone();
+            ring.id = id.into();
+            ring.name = name.into();
+            ring.item_type = ItemType::Miscellaneous;
+            ring.slot = Some(EquipSlot::Ring);
+            state.items.insert(id.into(), ring);
+            state.player.inventory.push(id.into());
+        }
+        state.player.inventory.push("sword".into());
+
+        execute(GameCommand::Equip("sword".into()), &mut state);
+        execute(GameCommand::Equip("silver ring".into()), &mut state);
+        let result = execute(GameCommand::Equip("gold ring".into()), &mut state);
+        assert!(result.messages.iter().any(|m| m.text.contains("Silver Ring")));
+        assert_eq!(state.player.equipped_ring, Some("gold_ring".into()));
+        assert_eq!(state.player.equipped_weapon, Some("sword".into()));
+
+        let stats = execute(GameCommand::Stats, &mut state);
+        assert!(stats.messages.iter().any(|m| m.text == "  Ring: Gold Ring"));
+        assert!(stats.messages.iter().any(|m| m.text == "  Boots: (empty)"));
+
+        execute(GameCommand::Drop("gold ring".into()), &mut state);
+        assert!(state.player.equipped_ring.is_none());
+    }
+
+    #[test]
+    fn test_repair_with_smith_kit_or_nothing() {
+        let mut state = world_builder::build_thornhold();
+        state.player.inventory.push("short_sword".into());
+        let wear = |state: &mut WorldState, current| {
+            state.items.get_mut("short_sword").unwrap().durability =
+                Some(Durability { current, max: 40 });
+        };
+
+        // The armorer's shade mends it fully
+        state.player.location = "armory".into();
+        wear(&mut state, 5);
+        execute(GameCommand::Repair("sword".into()), &mut state);
+        assert_eq!(state.items["short_sword"].durability.unwrap().current, 40);
+
+        // A kit restores its fixed amount and is used up
+        state.player.location = "courtyard".into();
+        wear(&mut state, 5);
+        state.player.inventory.push("tinkers_kit".into());
+        execute(GameCommand::Repair("sword".into()), &mut state);
+        assert_eq!(state.items["short_sword"].durability.unwrap().current, 25);
+        assert!(!state.player.inventory.contains(&"tinkers_kit".to_string()));
+
+        let result = execute(GameCommand::Repair("sword".into()), &mut state);
+        assert!(result.messages[0].text.contains("need a repair kit or a smith"));
+
+        // Using a kit directly patches the most worn equipped piece
+        state.player.inventory.push("tinkers_kit".into());
+        state.player.equipped_weapon = Some("short_sword".into());
+        execute(GameCommand::Use("tinker".into()), &mut state);
+        assert_eq!(state.items["short_sword"].durability.unwrap().current, 40);
+    }
+
+    #[test]
+    fn test_examine_shows_condition_when_enabled() {
+        let mut state = world_builder::build_thornhold();
+        state.player.location = "armory".into();
+        let result = execute(GameCommand::Look(Some("short sword".into())), &mut state);
+        assert!(!result.messages.iter().any(|m| m.text.starts_with("Condition")));
+
+        state.durability_enabled = true;
+        let result = execute(GameCommand::Look(Some("short sword".into())), &mut state);
+        assert!(result.messages.iter().any(|m| m.text == "Condition: Good (40/40)"));
+    }
+
     #[test]
     fn test_inventory_full() {
         let mut state = make_test_world();
@@ -1718,4 +2780,429 @@ mod tests {
         execute(GameCommand::Secret("abracadabra".into()), &mut state);
         assert_eq!(state.player.health, 55);
     }
+
+    #[test]
+    fn quest_log_lists_stage_checklist() {
+        let mut state = world_builder::build_thornhold();
+        let quest = state.quests.get_mut("rats_request").unwrap();
+        quest.active = true;
+        quest.stages = vec![QuestStage {
+            id: "descend".into(),
+            name: "Into the cellar".into(),
+            journal: "The cheese is below.".into(),
+            objectives: vec![
+                StageObjective {
+                    objective: QuestObjective::FetchItem("cellar_cheese".into()),
+                    optional: false,
+                    label: None,
+                },
+                StageObjective {
+                    objective: QuestObjective::ReachLocation("wine_cellar".into()),
+                    optional: true,
+                    label: Some("Explore the wine cellar".into()),
+                },
+            ],
+        }];
+        quest.progress.checked.push("descend:1".into());
+
+        let result = execute(GameCommand::QuestLog, &mut state);
+        let texts: Vec<&str> = result.messages.iter().map(|m| m.text.as_str()).collect();
+        assert!(texts.contains(&"    Stage 1/1: Into the cellar — The cheese is below."));
+        assert!(texts.iter().any(|t| t.starts_with("      [ ] Obtain the")));
+        assert!(texts.contains(&"      [x] Explore the wine cellar (optional)"));
+    }
+
+    #[test]
+    fn escorted_npc_follows_the_player() {
+        let mut state = world_builder::build_thornhold();
+        let quest = state.quests.get_mut("seek_the_vault").unwrap();
+        quest.active = true;
+        let escort = QuestObjective::Escort("merchant_ghost".into(), "kitchen".into());
+        quest.objective = Some(escort);
+
+        let result = execute(GameCommand::Go(Direction::East), &mut state);
+        assert!(result
+            .messages
+            .iter()
+            .any(|m| m.text.ends_with("follows you.")));
+        assert!(state.locations["great_hall"].npcs.contains(&"merchant_ghost".to_string()));
+        assert!(!state.locations["courtyard"].npcs.contains(&"merchant_ghost".to_string()));
+        assert!(!state.quests["seek_the_vault"].completed);
+
+        execute(GameCommand::Go(Direction::South), &mut state);
+        assert!(state.locations["kitchen"].npcs.contains(&"merchant_ghost".to_string()));
+        assert!(state.quests["seek_the_vault"].completed);
+
+        // Once delivered the NPC stays put
+        execute(GameCommand::Go(Direction::North), &mut state);
+        assert!(state.locations["kitchen"].npcs.contains(&"merchant_ghost".to_string()));
+    }
+
+    #[test]
+    fn any_command_checks_quest_progress() {
+        let mut state = world_builder::build_thornhold();
+        let quest = state.quests.get_mut("rats_request").unwrap();
+        quest.active = true;
+        quest.objective = Some(QuestObjective::SetFlag("bell_rung".into()));
+        state.player.quest_flags.insert("bell_rung".into(), true);
+
+        let result = execute(GameCommand::Inventory, &mut state);
+        assert!(result
+            .messages
+            .iter()
+            .any(|m| m.text.starts_with("Quest objective complete!")));
+        assert!(state.quests["rats_request"].completed);
+    }
+
+    #[test]
+    fn quest_log_lists_failed_quests() {
+        let mut state = world_builder::build_thornhold();
+        state.quests.get_mut("rats_request").unwrap().active = true;
+        quest::fail_quest("rats_request", "Gristle is dead.", &mut state);
+
+        let result = execute(GameCommand::QuestLog, &mut state);
+        let texts: Vec<&str> = result.messages.iter().map(|m| m.text.as_str()).collect();
+        assert!(texts.contains(&"Failed:"));
+        assert!(texts.contains(&"  - The Rat's Request (Gristle is dead.)"));
+    }
+
+    #[test]
+    fn ask_about_topic_from_the_room_and_in_conversation() {
+        let mut state = world_builder::build_thornhold();
+        state.topics.insert(
+            "merchant_ghost".into(),
+            TopicMap {
+                topics: vec![Topic {
+                    name: "journal".into(),
+                    aliases: vec![],
+                    ask: Some("My journal! Have you seen it?".into()),
+                    tell: Some("You found it? Bless you.".into()),
+                    condition: None,
+                    journal: None,
+                    sets_flag: None,
+                }],
+                fallback: None,
+            },
+        );
+
+        let cmd = crate::engine::parser::parse("ask merchant about the journal", &state.game_mode);
+        let result = execute(cmd, &mut state);
+        assert!(result.messages[0].text.ends_with("\"My journal! Have you seen it?\""));
+
+        execute(GameCommand::TalkTo("merchant".into()), &mut state);
+        let cmd = crate::engine::parser::parse("tell about journal", &state.game_mode);
+        let result = execute(cmd, &mut state);
+        assert!(result.messages[0].text.ends_with("\"You found it? Bless you.\""));
+
+        let result = execute(GameCommand::AskAbout("merchant".into(), "weather".into()), &mut state);
+        assert!(result.messages[0].text.ends_with("\"I know nothing about that.\""));
+    }
+
+    #[test]
+    fn theft_in_front_of_a_member_costs_reputation() {
+        let mut state = crate::engine::world_builder::build_thornhold();
+        state.player.location = "armory".into();
+
+        let result = execute(GameCommand::Take("whetstone".into()), &mut state);
+        let texts: Vec<&str> = result.messages.iter().map(|m| m.text.as_str()).collect();
+        assert!(texts.contains(&"The Armorer's Shade sees you take the Whetstone."));
+        assert!(texts.contains(&"Reputation with The Faithful Dead: -10 (Neutral)"));
+        assert_eq!(state.factions["wardens_host"].reputation, -55);
+
+        let result = execute(GameCommand::Reputation, &mut state);
+        let texts: Vec<&str> = result.messages.iter().map(|m| m.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            vec![
+                "--- Reputation ---",
+                "  The Faithful Dead: Neutral (-10)",
+                "  The Warden's Host: Hostile (-55)",
+            ]
+        );
+    }
+
+    #[test]
+    fn hostile_wanderer_arriving_with_the_player_starts_a_fight() {
+        let mut state = crate::engine::world_builder::build_thornhold();
+        state.npcs.get_mut("skeletal_guard").unwrap().movement = Some(NpcMovement::Schedule {
+            entries: vec![ScheduleEntry {
+                from: 0,
+                location: "great_hall".into(),
+                phase: None,
+            }],
+            cycle: None,
+        });
+
+        let result = execute(GameCommand::Go(Direction::East), &mut state);
+        let texts: Vec<&str> = result.messages.iter().map(|m| m.text.as_str()).collect();
+        assert!(texts.contains(&"Skeletal Guard arrives."));
+        assert_eq!(state.game_mode, GameMode::InCombat("skeletal_guard".into()));
+        assert!(!state.locations["barracks"].npcs.contains(&"skeletal_guard".to_string()));
+    }
+
+    #[test]
+    fn companions_follow_unless_told_to_wait() {
+        let mut state = world_builder::build_thornhold();
+        state.quests.get_mut("rats_request").unwrap().completed = true;
+        state.locations.get_mut("kitchen").unwrap().npcs.clear();
+        state.locations.get_mut("courtyard").unwrap().npcs.push("gristle_rat".into());
+
+        let result = execute(GameCommand::Recruit("gristle".into()), &mut state);
+        assert_eq!(result.messages[0].text, "Gristle joins you.");
+        let result = execute(GameCommand::Go(Direction::East), &mut state);
+        assert!(result.messages.iter().any(|m| m.text == "Gristle follows you."));
+        assert!(state.locations["great_hall"].npcs.contains(&"gristle_rat".to_string()));
+
+        execute(GameCommand::CompanionWait("gristle".into(), true), &mut state);
+        execute(GameCommand::Go(Direction::West), &mut state);
+        assert!(state.locations["great_hall"].npcs.contains(&"gristle_rat".to_string()));
+        let result = execute(GameCommand::Stats, &mut state);
+        assert!(result
+            .messages
+            .iter()
+            .any(|m| m.text.contains("Gristle (HP: 5/5) — waiting in")));
+    }
+
+    #[test]
+    fn moving_advances_the_clock_into_dusk() {
+        let mut state = world_builder::build_thornhold();
+        state.clock.minutes = 17 * 60 + 50;
+
+        let result = execute(GameCommand::Go(Direction::East), &mut state);
+        assert!(result
+            .messages
+            .iter()
+            .any(|m| m.text == templates::describe_phase_change(DayPhase::Dusk)));
+        let result = execute(GameCommand::Time, &mut state);
+        assert_eq!(result.messages[0].text, "It is Day 1, 18:05 (Dusk).");
+    }
+
+    #[test]
+    fn resting_heals_and_passes_time() {
+        let mut state = world_builder::build_thornhold();
+        state.player.health = 50;
+
+        let result = execute(GameCommand::Rest(4), &mut state);
+        assert_eq!(state.player.health, 70);
+        assert_eq!(state.player.turns_elapsed, 4);
+        assert_eq!(state.clock.hour(), 12);
+        assert!(matches!(
+            result.action_type,
+            ActionType::Rested {
+                turns: 4,
+                interrupted: false
+            }
+        ));
+        assert!(result
+            .messages
+            .iter()
+            .any(|m| m.text == "You rest for 4 turns and recover 20 HP. (HP: 70/100)"));
+    }
+
+    #[test]
+    fn resting_is_refused_near_hostiles() {
+        let mut state = world_builder::build_thornhold();
+        state.player.location = "barracks".into();
+        state.player.health = 50;
+
+        let result = execute(GameCommand::Rest(8), &mut state);
+        assert_eq!(result.messages[0].text, "You can't rest with Skeletal Guard nearby.");
+        assert_eq!(state.player.health, 50);
+        assert_eq!(state.player.turns_elapsed, 0);
+    }
+
+    #[test]
+    fn random_encounter_on_entering_starts_a_fight() {
+        let mut state = world_builder::build_thornhold();
+        state.player.location = "wine_cellar".into();
+        state.encounters[0].chance = 100;
+
+        let result = execute(GameCommand::Go(Direction::Down), &mut state);
+        let enemy_id = match &state.game_mode {
+            GameMode::InCombat(id) => id.clone(),
+            other => panic!("expected combat, got {:?}", other),
+        };
+        assert!(enemy_id.starts_with("crypt_dead_"));
+        let name = state.npcs[&enemy_id].name.clone();
+        assert!(result
+            .messages
+            .iter()
+            .any(|m| m.text == templates::describe_encounter(&name)));
+    }
+
+    /// Sneaks south into the barracks under each seed in turn until the
+    /// guard's detection roll goes the wanted way.
+    fn sneak_into_barracks(agility: i32, spotted: bool) -> (WorldState, ActionResult) {
+        (0..50)
+            .find_map(|seed| {
+                let mut state = world_builder::build_thornhold();
+                state.rng_seed = seed;
+                state.player.agility = agility;
+                let result = execute(GameCommand::Sneak(Direction::South), &mut state);
+                let fighting = matches!(state.game_mode, GameMode::InCombat(_));
+                (fighting == spotted).then_some((state, result))
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn sneaking_past_a_hostile_allows_a_sneak_attack() {
+        let (mut state, result) = sneak_into_barracks(50, false);
+        assert!(state.player.hidden);
+        assert!(result
+            .messages
+            .iter()
+            .any(|m| m.text == "Skeletal Guard hasn't noticed you."));
+        // Sneaking takes twice as long as walking
+        assert_eq!(state.clock.minutes, 8 * 60 + 2 * state.clock.minutes_per_move);
+
+        let result = execute(GameCommand::Attack("guard".into()), &mut state);
+        assert_eq!(result.messages[0].text, "You strike Skeletal Guard from the shadows!");
+        assert!(!state.player.hidden);
+
+        // Walking on, rather than sneaking, gives the player away again
+        let mut state = sneak_into_barracks(50, false).0;
+        execute(GameCommand::Go(Direction::North), &mut state);
+        assert!(!state.player.hidden);
+    }
+
+    #[test]
+    fn a_failed_sneak_lets_the_enemy_strike_first() {
+        let (state, result) = sneak_into_barracks(-20, true);
+        assert!(!state.player.hidden);
+        assert!(result.messages.iter().any(|m| m.text == "Skeletal Guard spots you!"));
+        assert!(state.player.health < state.player.max_health);
+    }
+
+    #[test]
+    fn hiding_keeps_the_player_out_of_sight() {
+        let mut state = world_builder::build_thornhold();
+        let result = execute(GameCommand::Hide, &mut state);
+        assert!(state.player.hidden);
+        assert_eq!(state.player.turns_elapsed, 1);
+        assert_eq!(result.messages[0].text, "You slip into the shadows and keep still.");
+
+        let result = execute(GameCommand::Hide, &mut state);
+        assert_eq!(result.messages[0].text, "You are already hidden.");
+        assert_eq!(state.player.turns_elapsed, 1);
+
+        state.player.hidden = false;
+        state.game_mode = GameMode::InCombat("skeletal_guard".into());
+        let result = execute(GameCommand::Hide, &mut state);
+        assert_eq!(result.messages[0].text, "You can't hide in the middle of a fight.");
+        assert!(!state.player.hidden);
+    }
+
+    #[test]
+    fn hidden_traps_spring_and_searches_open_hidden_exits() {
+        let mut state = world_builder::build_thornhold();
+        state.player.location = "cellar_entrance".into();
+        let result = execute(GameCommand::Go(Direction::Down), &mut state);
+        assert!(result
+            .messages
+            .iter()
+            .any(|m| m.text == templates::describe_trap_sprung("Tripwire")));
+        assert_eq!(state.player.health, 92);
+        assert!(state.locations["wine_cellar"]
+            .discovered_secrets
+            .contains(&"cellar_tripwire".to_string()));
+
+        state.player.location = "great_hall".into();
+        state.locations.get_mut("great_hall").unwrap().secrets[0].find_chance = 100;
+        execute(GameCommand::Search, &mut state);
+        assert_eq!(state.player.turns_elapsed, 2);
+        execute(GameCommand::Go(Direction::Down), &mut state);
+        assert_eq!(state.player.location, "hidden_vault");
+    }
+
+    #[test]
+    fn doors_bar_the_way_until_unlocked_and_show_their_state() {
+        let mut state = world_builder::build_thornhold();
+        state.player.location = "great_hall".into();
+        let result = execute(GameCommand::Look(None), &mut state);
+        assert!(result
+            .messages
+            .iter()
+            .any(|m| m.text.contains("East (iron-banded door, locked)")));
+
+        let result = execute(GameCommand::Go(Direction::East), &mut state);
+        assert!(matches!(result.action_type, ActionType::Error { .. }));
+        assert_eq!(state.player.location, "great_hall");
+
+        state.player.inventory.push("library_key".into());
+        let result = execute(GameCommand::Go(Direction::East), &mut state);
+        assert_eq!(state.player.location, "library");
+        assert!(result.sound_cues.contains(&SoundCue::DoorUnlock));
+        assert_eq!(state.doors["library_door"].state, DoorState::Open);
+
+        let result = execute(GameCommand::Close("west door".into()), &mut state);
+        assert!(!matches!(result.action_type, ActionType::Error { .. }));
+        assert_eq!(state.doors["library_door"].state, DoorState::Closed);
+        let result = execute(GameCommand::Bash("oak".into()), &mut state);
+        assert!(matches!(result.action_type, ActionType::Error { .. }));
+    }
+
+    #[test]
+    fn sealed_exits_say_no_key_opens_them() {
+        let mut state = world_builder::build_thornhold();
+        state.player.location = "chapel".into();
+        let result = execute(GameCommand::Go(Direction::South), &mut state);
+        assert_eq!(result.messages[0].text, templates::describe_sealed_exit(&Direction::South));
+        assert_eq!(state.player.location, "chapel");
+    }
+
+    #[test]
+    fn riddles_are_posed_and_fixtures_stay_put() {
+        let mut state = world_builder::build_thornhold();
+        state.player.location = "library".into();
+        let result = execute(GameCommand::Take("bust".into()), &mut state);
+        assert_eq!(result.messages[0].text, "The stone bust is fixed in place.");
+        let result = execute(GameCommand::Look(Some("bust".into())), &mut state);
+        assert!(result.messages.iter().any(|m| m.text.starts_with("A riddle:")));
+
+        // The merchant poses the bust's riddle in the courtyard
+        state.player.location = "courtyard".into();
+        state.npcs.get_mut("merchant_ghost").unwrap().riddle = Some("stone_bust".into());
+        let result = execute(GameCommand::TalkTo("merchant".into()), &mut state);
+        assert!(result.messages.iter().any(|m| m.text.starts_with("A riddle:")));
+        let turns = state.player.turns_elapsed;
+        let result = execute(GameCommand::Answer("the tracks".into()), &mut state);
+        assert_eq!(state.player.turns_elapsed, turns + 1);
+        assert!(result.messages.iter().any(|m| m.text.contains("vial rolls out")));
+        assert!(state.puzzles["bust_riddle"].solved);
+        assert!(matches!(state.game_mode, GameMode::InDialogue(_)));
+
+        let result = execute(GameCommand::Pull("bust".into()), &mut state);
+        assert!(matches!(result.action_type, ActionType::Error { .. }));
+    }
+
+    #[test]
+    fn event_sound_cues_reach_the_result() {
+        let mut state = world_builder::build_thornhold();
+        state.events.push(GameEvent {
+            id: None,
+            trigger: EventTrigger::OnEnter,
+            action: EventAction::PlaySound(SoundCue::AmbientTense),
+            one_shot: true,
+            fired: false,
+            location_id: "great_hall".into(),
+            condition: None,
+        });
+        let result = execute(GameCommand::Go(Direction::East), &mut state);
+        assert!(result.sound_cues.contains(&SoundCue::AmbientTense));
+        assert!(state.pending_sound_cues.is_empty());
+    }
+
+    #[test]
+    fn custom_verb_runs_its_script() {
+        let mut state = world_builder::build_thornhold();
+        state.player.location = "chapel".into();
+        let result = execute(GameCommand::Unknown("pray".into()), &mut state);
+        assert!(result.messages.iter().any(|m| m.text.contains("kneel")));
+        assert_eq!(state.player.quest_flags.get("prayed_at_altar"), Some(&true));
+
+        state.player.location = "courtyard".into();
+        let result = execute(GameCommand::Unknown("pray".into()), &mut state);
+        assert!(matches!(result.action_type, ActionType::Error { .. }));
+    }
 }
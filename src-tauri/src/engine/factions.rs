@@ -0,0 +1,252 @@
+use crate::engine::templates;
+use crate::models::*;
+
+/// Something the player did to a faction member.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Deed {
+    Killed,
+    /// Struck first while the member was peaceful.
+    Attacked,
+    /// Finished a quest the member gave.
+    Helped,
+    /// Took faction property in front of the member.
+    Stole,
+}
+
+impl Deed {
+    fn reputation_change(self) -> i32 {
+        match self {
+            Deed::Killed => -25,
+            Deed::Attacked => -10,
+            Deed::Helped => 15,
+            Deed::Stole => -10,
+        }
+    }
+}
+
+const MAX_REPUTATION: i32 = 100;
+
+/// The faction an NPC belongs to, if any.
+pub fn npc_faction<'a>(npc_id: &str, state: &'a WorldState) -> Option<&'a Faction> {
+    let faction_id = state.npcs.get(npc_id)?.faction.as_ref()?;
+    state.factions.get(faction_id)
+}
+
+/// Standing with the NPC's faction; NPCs outside any faction count as
+/// neutral.
+pub fn npc_tier(npc_id: &str, state: &WorldState) -> ReputationTier {
+    npc_faction(npc_id, state).map_or(ReputationTier::Neutral, Faction::tier)
+}
+
+/// Whether the NPC's faction still deals with the player: gives quests and
+/// offers services.
+pub fn will_deal(npc_id: &str, state: &WorldState) -> bool {
+    npc_tier(npc_id, state) >= ReputationTier::Neutral
+}
+
+/// Shifts reputation with the faction of the NPC the deed was done to.
+pub fn record_deed(npc_id: &str, deed: Deed, state: &mut WorldState) -> Vec<OutputLine> {
+    match npc_faction(npc_id, state).map(|f| f.id.clone()) {
+        Some(faction_id) => change_reputation(&faction_id, deed.reputation_change(), state),
+        None => Vec::new(),
+    }
+}
+
+/// Changes reputation with a faction. Allies share half the change and
+/// enemies take the opposite half.
+pub fn change_reputation(faction_id: &str, amount: i32, state: &mut WorldState) -> Vec<OutputLine> {
+    let Some(faction) = state.factions.get(faction_id).cloned() else {
+        return Vec::new();
+    };
+    let mut messages = Vec::new();
+    adjust(faction_id, amount, state, &mut messages);
+    for ally in &faction.allies {
+        adjust(ally, amount / 2, state, &mut messages);
+    }
+    for enemy in &faction.enemies {
+        adjust(enemy, -(amount / 2), state, &mut messages);
+    }
+    messages
+}
+
+fn adjust(faction_id: &str, amount: i32, state: &mut WorldState, messages: &mut Vec<OutputLine>) {
+    let Some(faction) = state.factions.get_mut(faction_id) else {
+        return;
+    };
+    let before = faction.tier();
+    let old = faction.reputation;
+    faction.reputation = old.saturating_add(amount).clamp(-MAX_REPUTATION, MAX_REPUTATION);
+    if faction.reputation == old {
+        return;
+    }
+    let after = faction.tier();
+    messages.push(OutputLine {
+        text: templates::describe_reputation_change(faction, faction.reputation - old),
+        line_type: LineType::System,
+    });
+
+    if (before == ReputationTier::Hostile) != (after == ReputationTier::Hostile) {
+        set_members_hostile(faction_id, after == ReputationTier::Hostile, state, messages);
+    }
+}
+
+/// Turns peaceful living members on the player, or calms the ones it turned
+/// once standing recovers. Members hostile for their own reasons stay so,
+/// as does the enemy the player is fighting.
+fn set_members_hostile(
+    faction_id: &str,
+    hostile: bool,
+    state: &mut WorldState,
+    messages: &mut Vec<OutputLine>,
+) {
+    let fighting = match &state.game_mode {
+        GameMode::InCombat(enemy_id) => Some(enemy_id.clone()),
+        _ => None,
+    };
+    let Some(faction) = state.factions.get_mut(faction_id) else {
+        return;
+    };
+    let turned = std::mem::take(&mut faction.turned_hostile);
+    let name = faction.name.clone();
+    let mut newly_turned = Vec::new();
+    for npc in state.npcs.values_mut() {
+        if npc.faction.as_deref() != Some(faction_id)
+            || npc.dialogue_state == DialogueState::Dead
+            || fighting.as_deref() == Some(npc.id.as_str())
+        {
+            continue;
+        }
+        if hostile && !npc.hostile {
+            npc.hostile = true;
+            npc.dialogue_state = DialogueState::Hostile;
+            newly_turned.push(npc.id.clone());
+        } else if !hostile && turned.contains(&npc.id) {
+            npc.hostile = false;
+            if npc.dialogue_state == DialogueState::Hostile {
+                npc.dialogue_state = DialogueState::Greeting;
+            }
+        }
+    }
+    if let Some(faction) = state.factions.get_mut(faction_id) {
+        faction.turned_hostile = newly_turned;
+    }
+    // Members who turn on the player leave the party
+    if hostile {
+        let npcs = &state.npcs;
+        state.companions.retain(|c| !npcs.get(&c.npc_id).is_some_and(|n| n.hostile));
+    }
+    messages.push(OutputLine {
+        text: if hostile {
+            format!("{} will now attack you on sight.", name)
+        } else {
+            format!("{} no longer wants you dead.", name)
+        },
+        line_type: LineType::System,
+    });
+}
+
+/// A faction member who sees the player take the item, when it's faction
+/// property.
+pub fn theft_witness(item_id: &str, state: &WorldState) -> Option<String> {
+    let loc = state.locations.get(&state.player.location)?;
+    loc.npcs
+        .iter()
+        .filter_map(|id| state.npcs.get(id))
+        .find(|npc| {
+            !npc.hostile
+                && npc.dialogue_state != DialogueState::Dead
+                && npc
+                    .faction
+                    .as_ref()
+                    .and_then(|f| state.factions.get(f))
+                    .is_some_and(|f| f.property.iter().any(|p| p == item_id))
+        })
+        .map(|npc| npc.id.clone())
+}
+
+/// One line per faction for the reputation view, sorted by name.
+pub fn describe_standings(state: &WorldState) -> Vec<String> {
+    let mut factions: Vec<&Faction> = state.factions.values().collect();
+    factions.sort_by(|a, b| a.name.cmp(&b.name));
+    factions
+        .into_iter()
+        .map(|f| {
+            format!(
+                "  {}: {} ({})",
+                f.name,
+                f.tier().display_name(),
+                f.reputation
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::world_builder;
+
+    #[test]
+    fn killing_a_member_ripples_to_allies_and_enemies() {
+        let mut state = world_builder::build_thornhold();
+        state.factions.get_mut("faithful_dead").unwrap().reputation = 0;
+        state.factions.get_mut("wardens_host").unwrap().reputation = -30;
+
+        let msgs = record_deed("skeletal_guard", Deed::Killed, &mut state);
+        assert_eq!(state.factions["wardens_host"].reputation, -55);
+        assert_eq!(state.factions["faithful_dead"].reputation, 12);
+        assert!(msgs
+            .iter()
+            .any(|m| m.text == "The Warden's Host will now attack you on sight."));
+        assert!(state.npcs["the_warden"].hostile);
+
+        // NPCs outside any faction don't move anything
+        assert!(record_deed("gristle_rat", Deed::Killed, &mut state).is_empty());
+    }
+
+    #[test]
+    fn recovering_standing_calms_members() {
+        let mut state = world_builder::build_thornhold();
+        change_reputation("faithful_dead", -80, &mut state);
+        assert!(state.npcs["ghost_cleric"].hostile);
+        assert!(!will_deal("ghost_cleric", &state));
+
+        change_reputation("faithful_dead", 60, &mut state);
+        let cleric = &state.npcs["ghost_cleric"];
+        assert!(!cleric.hostile);
+        assert_eq!(cleric.dialogue_state, DialogueState::Greeting);
+        // Unfriendly members still refuse quests and services
+        assert!(!will_deal("ghost_cleric", &state));
+    }
+
+    #[test]
+    fn recovering_standing_leaves_authored_hostility_alone() {
+        let mut state = world_builder::build_thornhold();
+        state.npcs.get_mut("ghost_cleric").unwrap().faction = Some("wardens_host".into());
+        state.factions.get_mut("wardens_host").unwrap().reputation = 0;
+        change_reputation("wardens_host", -60, &mut state);
+        assert!(state.npcs["ghost_cleric"].hostile);
+
+        change_reputation("wardens_host", 60, &mut state);
+        assert!(!state.npcs["ghost_cleric"].hostile);
+        // The guard and the Warden were hostile before the faction turned
+        assert!(state.npcs["skeletal_guard"].hostile);
+        assert!(state.npcs["the_warden"].hostile);
+    }
+
+    #[test]
+    fn extreme_changes_saturate() {
+        let mut state = world_builder::build_thornhold();
+        change_reputation("faithful_dead", i32::MIN, &mut state);
+        assert_eq!(state.factions["faithful_dead"].reputation, -100);
+        assert_eq!(state.factions["wardens_host"].reputation, 100);
+    }
+
+    #[test]
+    fn reputation_is_clamped() {
+        let mut state = world_builder::build_thornhold();
+        change_reputation("faithful_dead", 500, &mut state);
+        assert_eq!(state.factions["faithful_dead"].reputation, 100);
+        assert!(change_reputation("faithful_dead", 5, &mut state).is_empty());
+    }
+}
@@ -1,4 +1,5 @@
-use crate::models::WorldState;
+use crate::engine::doors;
+use crate::models::{WorldState, SEALED};
 use crate::models::settings::Difficulty;
 
 /// Returns a contextual hint for new players. Returns None once the player
@@ -47,7 +48,9 @@ pub fn get_contextual_hint(state: &WorldState) -> Option<String> {
     }
 
     // First locked exit encountered
-    if !loc.locked_exits.is_empty() {
+    let locked_door = loc.doors.keys().any(|dir| doors::is_barred(loc, dir, state));
+    let needs_key = loc.locked_exits.values().any(|key_id| key_id != SEALED);
+    if needs_key || locked_door {
         return Some("This exit is locked. Find the right key.".to_string());
     }
 
@@ -0,0 +1,266 @@
+use crate::engine::{combat, events};
+use crate::models::*;
+
+/// The combat opponent, or failing that the first living hostile NPC in the room.
+fn find_enemy(state: &WorldState) -> Option<String> {
+    if let GameMode::InCombat(enemy_id) = &state.game_mode {
+        return Some(enemy_id.clone());
+    }
+    state
+        .locations
+        .get(&state.player.location)?
+        .npcs
+        .iter()
+        .find(|id| {
+            state
+                .npcs
+                .get(*id)
+                .is_some_and(|n| n.hostile && n.dialogue_state != DialogueState::Dead)
+        })
+        .cloned()
+}
+
+/// Checks charges, cooldown and targets before an item's effects run.
+pub fn check_ready(item: &Item, state: &WorldState) -> Result<(), String> {
+    if item.charges == Some(0) {
+        return Err(format!("The {} is spent.", item.name));
+    }
+    if let Some(&ready_turn) = state.player.item_cooldowns.get(&item.id) {
+        if state.player.turns_elapsed < ready_turn {
+            return Err(format!(
+                "The {} needs {} more turn(s) to recover.",
+                item.name,
+                ready_turn - state.player.turns_elapsed
+            ));
+        }
+    }
+    let needs_enemy = item.effects.iter().any(|e| e.target == EffectTarget::Enemy);
+    if needs_enemy && find_enemy(state).is_none() {
+        return Err(format!("There's nothing here to use the {} on.", item.name));
+    }
+    Ok(())
+}
+
+fn apply_to_enemy(
+    action: &EventAction,
+    enemy_id: &str,
+    state: &mut WorldState,
+    messages: &mut Vec<OutputLine>,
+) {
+    let Some(npc) = state.npcs.get_mut(enemy_id) else {
+        return;
+    };
+    let text = match action {
+        EventAction::Damage(amount) => {
+            npc.health = npc.health.saturating_sub(*amount).max(0);
+            format!(
+                "The {} takes {} damage. (HP: {})",
+                npc.name, amount, npc.health
+            )
+        }
+        EventAction::Heal(amount) => {
+            npc.health = npc.health.saturating_add(*amount).min(npc.max_health);
+            format!("The {} recovers. (HP: {})", npc.name, npc.health)
+        }
+        EventAction::ApplyStatus(effect) => {
+            npc.status_effects.push(effect.clone());
+            format!("The {} is now affected by: {}", npc.name, effect.name)
+        }
+        EventAction::RemoveStatus(name) => {
+            npc.status_effects.retain(|e| e.name != *name);
+            format!("{} fades from the {}.", name, npc.name)
+        }
+        other => {
+            let location_id = state.player.location.clone();
+            events::apply_action(other.clone(), &location_id, state, messages);
+            return;
+        }
+    };
+    messages.push(OutputLine {
+        text,
+        line_type: LineType::Combat,
+    });
+}
+
+/// Runs an item's effects in order, then spends a charge and starts its
+/// cooldown. Call `check_ready` first.
+pub fn apply_item_effects(item: &Item, state: &mut WorldState) -> Vec<OutputLine> {
+    let mut messages = Vec::new();
+    let location_id = state.player.location.clone();
+    let enemy_id = find_enemy(state);
+
+    for effect in &item.effects {
+        match (effect.target, &enemy_id) {
+            (EffectTarget::Enemy, Some(enemy_id)) => {
+                apply_to_enemy(&effect.action, enemy_id, state, &mut messages)
+            }
+            _ => events::apply_action(effect.action.clone(), &location_id, state, &mut messages),
+        }
+    }
+
+    if let Some(stored) = state.items.get_mut(&item.id) {
+        if let Some(charges) = stored.charges.as_mut() {
+            *charges = charges.saturating_sub(1);
+            if *charges == 0 && !stored.consumable {
+                messages.push(OutputLine {
+                    text: format!("The {} is spent.", stored.name),
+                    line_type: LineType::System,
+                });
+            }
+        }
+    }
+    if item.cooldown > 0 {
+        state
+            .player
+            .item_cooldowns
+            .insert(item.id.clone(), state.player.turns_elapsed + item.cooldown);
+    }
+
+    if let Some(enemy_id) = enemy_id {
+        let slain = state
+            .npcs
+            .get(&enemy_id)
+            .is_some_and(|n| n.health <= 0 && n.dialogue_state != DialogueState::Dead);
+        if slain {
+            combat::defeat_enemy(state, &enemy_id, &mut messages);
+        }
+    }
+
+    messages.retain(|m| !m.text.is_empty());
+    messages
+}
+
+/// Whether using the item should take it out of the inventory: consumables
+/// go once their charges (if any) are spent.
+pub fn is_used_up(item_id: &str, state: &WorldState) -> bool {
+    state
+        .items
+        .get(item_id)
+        .is_some_and(|i| i.consumable && i.charges.is_none_or(|c| c == 0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{executor, parser::GameCommand, world_builder};
+
+    fn use_item(name: &str, state: &mut WorldState) -> ActionResult {
+        executor::execute(GameCommand::Use(name.into()), state)
+    }
+
+    fn in_combat_with_guard() -> WorldState {
+        let mut state = world_builder::build_thornhold();
+        state.player.location = "barracks".into();
+        state.game_mode = GameMode::InCombat("skeletal_guard".into());
+        state.player.inventory.push("sunstone".into());
+        state
+    }
+
+    #[test]
+    fn blessed_water_heals_and_cures_poison() {
+        let mut state = world_builder::build_thornhold();
+        state.player.health = 20;
+        state.player.status_effects.push(StatusEffect {
+            effect_type: StatusEffectType::Poison,
+            name: "Poison".into(),
+            turns_remaining: 3,
+            damage_per_turn: 2,
+            attack_modifier: 0,
+            defense_modifier: 0,
+        });
+        state.player.inventory.push("blessed_water".into());
+
+        use_item("blessed water", &mut state);
+        assert_eq!(state.player.health, 70);
+        assert!(state.player.status_effects.is_empty());
+        assert!(!state
+            .player
+            .inventory
+            .contains(&"blessed_water".to_string()));
+    }
+
+    #[test]
+    fn enemy_targeted_item_needs_a_target() {
+        let mut state = world_builder::build_thornhold();
+        state.player.inventory.push("sunstone".into());
+        let result = use_item("sunstone", &mut state);
+        assert!(matches!(result.action_type, ActionType::Error { .. }));
+        assert_eq!(state.items["sunstone"].charges, Some(3));
+    }
+
+    #[test]
+    fn enemy_targeted_item_damages_and_burns_opponent() {
+        let mut state = in_combat_with_guard();
+        use_item("sunstone", &mut state);
+        let guard = &state.npcs["skeletal_guard"];
+        assert_eq!(guard.health, 13);
+        assert!(guard.status_effects.iter().any(|e| e.name == "Burning"));
+        assert_eq!(state.player.health, state.player.max_health);
+        assert_eq!(state.items["sunstone"].charges, Some(2));
+        assert!(state.player.inventory.contains(&"sunstone".to_string()));
+    }
+
+    #[test]
+    fn cooldown_blocks_reuse_until_it_recovers() {
+        let mut state = in_combat_with_guard();
+        use_item("sunstone", &mut state);
+        let result = use_item("sunstone", &mut state);
+        assert!(result.messages[0].text.contains("recover"));
+        assert_eq!(state.items["sunstone"].charges, Some(2));
+
+        state.player.turns_elapsed += 3;
+        use_item("sunstone", &mut state);
+        assert_eq!(state.items["sunstone"].charges, Some(1));
+    }
+
+    #[test]
+    fn spent_item_refuses_further_use() {
+        let mut state = in_combat_with_guard();
+        state.items.get_mut("sunstone").unwrap().charges = Some(0);
+        let result = use_item("sunstone", &mut state);
+        assert!(result.messages[0].text.contains("spent"));
+        assert_eq!(state.npcs["skeletal_guard"].health, 25);
+    }
+
+    #[test]
+    fn slaying_blow_ends_combat() {
+        let mut state = in_combat_with_guard();
+        state.npcs.get_mut("skeletal_guard").unwrap().health = 5;
+        use_item("sunstone", &mut state);
+        assert_eq!(
+            state.npcs["skeletal_guard"].dialogue_state,
+            DialogueState::Dead
+        );
+        assert_eq!(state.game_mode, GameMode::Exploring);
+    }
+
+    #[test]
+    fn teleport_reveal_and_quest_effects() {
+        let mut state = world_builder::build_thornhold();
+        let quest_id = state.quests.keys().next().unwrap().clone();
+        let scroll = Item {
+            effects: vec![
+                ItemEffect {
+                    action: EventAction::Teleport("chapel".into()),
+                    target: EffectTarget::Player,
+                },
+                ItemEffect {
+                    action: EventAction::RevealExits,
+                    target: EffectTarget::Player,
+                },
+                ItemEffect {
+                    action: EventAction::StartQuest(quest_id.clone()),
+                    target: EffectTarget::Player,
+                },
+            ],
+            ..state.items["sacred_scroll"].clone()
+        };
+        state.items.insert("sacred_scroll".into(), scroll);
+        state.player.inventory.push("sacred_scroll".into());
+
+        use_item("sacred scroll", &mut state);
+        assert_eq!(state.player.location, "chapel");
+        assert!(state.locations["library"].visited);
+        assert!(state.quests[&quest_id].active);
+    }
+}
@@ -0,0 +1,273 @@
+use rand::Rng;
+
+use crate::engine::equipment;
+use crate::engine::random::{pick_weighted, world_rng};
+use crate::models::*;
+
+const RARITY_WEIGHTS: &[(Rarity, u32)] = &[
+    (Rarity::Common, 50),
+    (Rarity::Uncommon, 30),
+    (Rarity::Rare, 14),
+    (Rarity::Epic, 5),
+    (Rarity::Legendary, 1),
+];
+
+fn affix_slots(rarity: Rarity) -> (bool, bool) {
+    match rarity {
+        Rarity::Common => (false, false),
+        Rarity::Uncommon => (true, false),
+        Rarity::Rare | Rarity::Epic | Rarity::Legendary => (true, true),
+    }
+}
+
+/// Epic and Legendary items get stronger affix rolls.
+fn rarity_multiplier(rarity: Rarity) -> i32 {
+    match rarity {
+        Rarity::Epic => 2,
+        Rarity::Legendary => 3,
+        _ => 1,
+    }
+}
+
+fn roll_rarity(rng: &mut impl Rng, floor: Rarity) -> Rarity {
+    let eligible: Vec<&(Rarity, u32)> =
+        RARITY_WEIGHTS.iter().filter(|(r, _)| *r >= floor).collect();
+    pick_weighted(rng, &eligible, |(_, w)| *w)
+        .map(|(r, _)| *r)
+        .unwrap_or(floor)
+}
+
+fn pick_affix<'a>(
+    rng: &mut impl Rng,
+    affixes: &'a [Affix],
+    kind: AffixKind,
+    item_type: ItemType,
+    rarity: Rarity,
+) -> Option<&'a Affix> {
+    let eligible: Vec<&Affix> = affixes
+        .iter()
+        .filter(|a| a.kind == kind && a.min_rarity <= rarity)
+        .filter(|a| a.item_types.is_empty() || a.item_types.contains(&item_type))
+        .collect();
+    pick_weighted(rng, &eligible, |a| a.weight).copied()
+}
+
+/// Rolls a named variant of `base_id` with at least `floor` rarity and stores
+/// it in `state.items` under a fresh id. Returns the base id unchanged when
+/// the roll comes up plain, and `None` if the base item doesn't exist.
+pub fn generate_item(base_id: &str, floor: Rarity, state: &mut WorldState) -> Option<String> {
+    let base = state.items.get(base_id)?.clone();
+    let mut rng = world_rng(state);
+    let rarity = roll_rarity(&mut rng, floor.max(base.rarity));
+    let (wants_prefix, wants_suffix) = affix_slots(rarity);
+
+    let prefix = wants_prefix
+        .then(|| {
+            pick_affix(
+                &mut rng,
+                &state.affixes,
+                AffixKind::Prefix,
+                base.item_type,
+                rarity,
+            )
+        })
+        .flatten()
+        .cloned();
+    let suffix = wants_suffix
+        .then(|| {
+            pick_affix(
+                &mut rng,
+                &state.affixes,
+                AffixKind::Suffix,
+                base.item_type,
+                rarity,
+            )
+        })
+        .flatten()
+        .cloned();
+
+    if rarity == base.rarity && prefix.is_none() && suffix.is_none() {
+        return Some(base_id.to_string());
+    }
+
+    let multiplier = rarity_multiplier(rarity);
+    let mut modifier = base.modifier.unwrap_or_default();
+    let mut special_effects = base.special_effects.clone();
+    let mut affixes = base.affixes.clone();
+    for mut affix in prefix.iter().chain(suffix.iter()).cloned() {
+        affix.modifier.attack *= multiplier;
+        affix.modifier.defense *= multiplier;
+        affix.modifier.health *= multiplier;
+        modifier.attack += affix.modifier.attack;
+        modifier.defense += affix.modifier.defense;
+        modifier.health += affix.modifier.health;
+        if let Some(effect) = &affix.effect {
+            special_effects.push(effect.clone());
+        }
+        affixes.push(affix);
+    }
+
+    let mut name = base.name.clone();
+    if let Some(prefix) = &prefix {
+        name = format!("{} {}", prefix.name, name);
+    }
+    if let Some(suffix) = &suffix {
+        name = format!("{} {}", name, suffix.name);
+    }
+
+    state.next_item_serial += 1;
+    let id = format!("{}_gen{}", base_id, state.next_item_serial);
+    // Recorded like a worn copy, so anything asking for the base item
+    // accepts the variant
+    let root = equipment::base_id(base_id, state).to_string();
+    state.item_instances.insert(id.clone(), root);
+    state.items.insert(
+        id.clone(),
+        Item {
+            id: id.clone(),
+            name,
+            modifier: (modifier != StatModifier::default()).then_some(modifier),
+            rarity,
+            affixes,
+            special_effects,
+            ..base
+        },
+    );
+    Some(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn affix(id: &str, name: &str, kind: AffixKind, modifier: StatModifier) -> Affix {
+        Affix {
+            id: id.into(),
+            name: name.into(),
+            kind,
+            modifier,
+            effect: None,
+            min_rarity: Rarity::Common,
+            item_types: vec![],
+            weight: 1,
+        }
+    }
+
+    fn make_state() -> WorldState {
+        let mut state = WorldState {
+            rng_seed: 5,
+            ..WorldState::default()
+        };
+        state.items.insert(
+            "short_sword".into(),
+            Item {
+                id: "short_sword".into(),
+                name: "Short Sword".into(),
+                description: "A well-balanced blade.".into(),
+                item_type: ItemType::Weapon,
+                modifier: Some(StatModifier {
+                    attack: 3,
+                    defense: 0,
+                    health: 0,
+                }),
+                usable: false,
+                consumable: false,
+                key_id: None,
+                lore: None,
+                ..Default::default()
+            },
+        );
+        state.affixes = vec![
+            affix(
+                "keen",
+                "Keen",
+                AffixKind::Prefix,
+                StatModifier {
+                    attack: 2,
+                    defense: 0,
+                    health: 0,
+                },
+            ),
+            affix(
+                "warding",
+                "of Warding",
+                AffixKind::Suffix,
+                StatModifier {
+                    attack: 0,
+                    defense: 2,
+                    health: 0,
+                },
+            ),
+        ];
+        state
+    }
+
+    #[test]
+    fn rare_roll_gets_prefix_and_suffix() {
+        let mut state = make_state();
+        let id = generate_item("short_sword", Rarity::Rare, &mut state).unwrap();
+        let item = state.items.get(&id).unwrap();
+        assert_ne!(id, "short_sword");
+        assert!(item.name.starts_with("Keen Short Sword of Warding"));
+        assert!(item.rarity >= Rarity::Rare);
+        let modifier = item.modifier.unwrap();
+        let multiplier = rarity_multiplier(item.rarity);
+        assert_eq!(modifier.attack, 3 + 2 * multiplier);
+        assert_eq!(modifier.defense, 2 * multiplier);
+        assert_eq!(item.affixes.len(), 2);
+        assert_eq!(item.affixes[0].modifier.attack, 2 * multiplier);
+    }
+
+    #[test]
+    fn generated_ids_are_unique() {
+        let mut state = make_state();
+        let first = generate_item("short_sword", Rarity::Uncommon, &mut state).unwrap();
+        let second = generate_item("short_sword", Rarity::Uncommon, &mut state).unwrap();
+        assert_ne!(first, second);
+        assert!(state.items.contains_key(&first));
+        assert!(state.items.contains_key(&second));
+        assert_eq!(equipment::base_id(&first, &state), "short_sword");
+    }
+
+    #[test]
+    fn affix_effects_carry_over() {
+        let mut state = make_state();
+        state.affixes[0].effect = Some(SpecialEffect::Lifesteal { percent: 25 });
+        let id = generate_item("short_sword", Rarity::Uncommon, &mut state).unwrap();
+        let item = state.items.get(&id).unwrap();
+        assert_eq!(
+            item.special_effects,
+            vec![SpecialEffect::Lifesteal { percent: 25 }]
+        );
+    }
+
+    #[test]
+    fn affixes_respect_item_type() {
+        let mut state = make_state();
+        for affix in &mut state.affixes {
+            affix.item_types = vec![ItemType::Armor];
+        }
+        let id = generate_item("short_sword", Rarity::Rare, &mut state).unwrap();
+        let item = state.items.get(&id).unwrap();
+        assert!(item.affixes.is_empty());
+        assert_eq!(item.name, "Short Sword");
+    }
+
+    #[test]
+    fn unknown_base_item_yields_none() {
+        let mut state = make_state();
+        assert!(generate_item("missing", Rarity::Common, &mut state).is_none());
+    }
+
+    #[test]
+    fn generated_item_survives_serde_roundtrip() {
+        let mut state = make_state();
+        let id = generate_item("short_sword", Rarity::Rare, &mut state).unwrap();
+        let json = serde_json::to_string(&state).unwrap();
+        let loaded: WorldState = serde_json::from_str(&json).unwrap();
+        let item = loaded.items.get(&id).unwrap();
+        assert_eq!(item.name, state.items[&id].name);
+        assert_eq!(item.affixes, state.items[&id].affixes);
+        assert_eq!(loaded.next_item_serial, state.next_item_serial);
+    }
+}
@@ -0,0 +1,252 @@
+use rand::Rng;
+
+use crate::engine::item_generator;
+use crate::engine::random::{pick_weighted, roll_percent, world_rng};
+use crate::models::*;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LootDrop {
+    pub item_id: String,
+    pub quantity: u32,
+    pub rarity: Rarity,
+}
+
+fn difficulty_drop_multiplier(difficulty: &Difficulty) -> f64 {
+    match difficulty {
+        Difficulty::Easy => 1.25,
+        Difficulty::Normal => 1.0,
+        Difficulty::Hard => 0.75,
+    }
+}
+
+fn roll_quantity(rng: &mut impl Rng, entry: &LootEntry) -> u32 {
+    let min = entry.min_quantity.max(1);
+    let max = entry.max_quantity.max(min);
+    rng.gen_range(min..=max)
+}
+
+/// Rolls a loot table using the world seed. Unknown tables drop nothing.
+pub fn roll_loot(table_id: &str, state: &mut WorldState) -> Vec<LootDrop> {
+    let Some(table) = state.loot_tables.get(table_id).cloned() else {
+        return Vec::new();
+    };
+    let mut rng = world_rng(state);
+    let chance = ((table.drop_chance as f64) * difficulty_drop_multiplier(&state.difficulty))
+        .round()
+        .min(100.0) as u32;
+
+    let mut rolled: Vec<(&LootEntry, u32)> = Vec::new();
+    for entry in table.entries.iter().filter(|e| e.guaranteed) {
+        rolled.push((entry, roll_quantity(&mut rng, entry)));
+    }
+
+    let weighted: Vec<&LootEntry> = table.entries.iter().filter(|e| !e.guaranteed).collect();
+    for _ in 0..table.rolls {
+        if !roll_percent(&mut rng, chance) {
+            continue;
+        }
+        if let Some(entry) = pick_weighted(&mut rng, &weighted, |e| e.weight) {
+            rolled.push((entry, roll_quantity(&mut rng, entry)));
+        }
+    }
+
+    let mut drops: Vec<LootDrop> = Vec::new();
+    for (entry, quantity) in rolled {
+        if !entry.randomize {
+            add_drop(&mut drops, &entry.item_id, quantity, entry.rarity);
+            continue;
+        }
+        // Each randomized unit becomes its own generated item
+        for _ in 0..quantity {
+            if let Some(item_id) =
+                item_generator::generate_item(&entry.item_id, entry.rarity, state)
+            {
+                let rarity = state.items.get(&item_id).map_or(entry.rarity, |i| i.rarity);
+                add_drop(&mut drops, &item_id, 1, rarity);
+            }
+        }
+    }
+    drops
+}
+
+fn add_drop(drops: &mut Vec<LootDrop>, item_id: &str, quantity: u32, rarity: Rarity) {
+    if let Some(existing) = drops.iter_mut().find(|d| d.item_id == item_id) {
+        existing.quantity += quantity;
+    } else {
+        drops.push(LootDrop {
+            item_id: item_id.to_string(),
+            quantity,
+            rarity,
+        });
+    }
+}
+
+/// Expands drops into one item id per unit, ready to push into a room or inventory.
+pub fn drop_item_ids(drops: &[LootDrop]) -> Vec<String> {
+    drops
+        .iter()
+        .flat_map(|d| std::iter::repeat_n(d.item_id.clone(), d.quantity as usize))
+        .collect()
+}
+
+/// Formats drops as "Rare Silver Ring, Glowing Elixir x2". Common drops carry no tier label.
+pub fn describe_drops(
+    drops: &[LootDrop],
+    items: &std::collections::HashMap<String, Item>,
+) -> Vec<String> {
+    drops
+        .iter()
+        .filter_map(|d| {
+            let item = items.get(&d.item_id)?;
+            let mut name = if d.rarity == Rarity::Common {
+                item.name.clone()
+            } else {
+                format!("{} {}", d.rarity.display_name(), item.name)
+            };
+            if d.quantity > 1 {
+                name.push_str(&format!(" x{}", d.quantity));
+            }
+            Some(name)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(item_id: &str, weight: u32, guaranteed: bool) -> LootEntry {
+        LootEntry {
+            item_id: item_id.into(),
+            weight,
+            min_quantity: 1,
+            max_quantity: 1,
+            rarity: Rarity::Common,
+            guaranteed,
+            randomize: false,
+        }
+    }
+
+    fn make_state(table: LootTable) -> WorldState {
+        let mut state = WorldState {
+            rng_seed: 17,
+            ..WorldState::default()
+        };
+        state.loot_tables.insert(table.id.clone(), table);
+        state
+    }
+
+    #[test]
+    fn guaranteed_entries_always_drop() {
+        let mut state = make_state(LootTable {
+            id: "chest".into(),
+            entries: vec![entry("gold", 1, true), entry("gem", 1, false)],
+            rolls: 1,
+            drop_chance: 0,
+        });
+        for _ in 0..10 {
+            let drops = roll_loot("chest", &mut state);
+            assert_eq!(drops.len(), 1);
+            assert_eq!(drops[0].item_id, "gold");
+        }
+    }
+
+    #[test]
+    fn quantity_stays_in_range() {
+        let mut table_entry = entry("arrow", 1, true);
+        table_entry.min_quantity = 2;
+        table_entry.max_quantity = 5;
+        let mut state = make_state(LootTable {
+            id: "quiver".into(),
+            entries: vec![table_entry],
+            rolls: 0,
+            drop_chance: 100,
+        });
+        for _ in 0..20 {
+            let drops = roll_loot("quiver", &mut state);
+            assert!((2..=5).contains(&drops[0].quantity));
+            assert_eq!(drop_item_ids(&drops).len(), drops[0].quantity as usize);
+        }
+    }
+
+    #[test]
+    fn same_seed_rolls_same_loot() {
+        let table = LootTable {
+            id: "mixed".into(),
+            entries: vec![
+                entry("a", 1, false),
+                entry("b", 1, false),
+                entry("c", 1, false),
+            ],
+            rolls: 3,
+            drop_chance: 60,
+        };
+        let mut first = make_state(table.clone());
+        let mut second = make_state(table);
+        for _ in 0..5 {
+            assert_eq!(
+                roll_loot("mixed", &mut first),
+                roll_loot("mixed", &mut second)
+            );
+        }
+    }
+
+    #[test]
+    fn hard_difficulty_drops_less() {
+        let table = LootTable {
+            id: "bones".into(),
+            entries: vec![entry("bone", 1, false)],
+            rolls: 1,
+            drop_chance: 50,
+        };
+        let count = |difficulty: Difficulty| {
+            let mut state = make_state(table.clone());
+            state.difficulty = difficulty;
+            (0..400)
+                .filter(|_| !roll_loot("bones", &mut state).is_empty())
+                .count()
+        };
+        assert!(count(Difficulty::Easy) > count(Difficulty::Hard));
+    }
+
+    #[test]
+    fn describe_drops_labels_rarity_and_quantity() {
+        let mut items = std::collections::HashMap::new();
+        for (id, name) in [("ring", "Silver Ring"), ("potion", "Glowing Elixir")] {
+            items.insert(
+                id.to_string(),
+                Item {
+                    id: id.into(),
+                    name: name.into(),
+                    description: String::new(),
+                    item_type: ItemType::Miscellaneous,
+                    modifier: None,
+                    usable: false,
+                    consumable: false,
+                    key_id: None,
+                    lore: None,
+                    ..Default::default()
+                },
+            );
+        }
+        let drops = vec![
+            LootDrop {
+                item_id: "ring".into(),
+                quantity: 1,
+                rarity: Rarity::Rare,
+            },
+            LootDrop {
+                item_id: "potion".into(),
+                quantity: 2,
+                rarity: Rarity::Common,
+            },
+        ];
+        assert_eq!(
+            describe_drops(&drops, &items),
+            vec![
+                "Rare Silver Ring".to_string(),
+                "Glowing Elixir x2".to_string()
+            ]
+        );
+    }
+}
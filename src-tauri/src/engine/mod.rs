@@ -1,13 +1,31 @@
 pub mod achievement_checker;
+pub mod clock;
 pub mod combat;
+pub mod companions;
+pub mod conditions;
 pub mod crafting;
 pub mod dialogue;
+pub mod doors;
 pub mod dungeon_generator;
+pub mod encounters;
+pub mod equipment;
 pub mod events;
 pub mod executor;
+pub mod factions;
 pub mod hints;
+pub mod item_effects;
+pub mod item_generator;
+pub mod loot;
 pub mod module_loader;
+pub mod npc_movement;
+pub mod puzzles;
 pub mod parser;
 pub mod quest;
+pub mod random;
+pub mod rest;
+pub mod scripting;
+pub mod search;
+pub mod stealth;
 pub mod templates;
+pub mod topics;
 pub mod world_builder;
@@ -1,6 +1,12 @@
 use std::path::Path;
 
-use crate::models::WorldState;
+use crate::engine::{companions, dungeon_generator, encounters, parser, puzzles, scripting};
+use crate::models::{
+    Condition, DialogueTree, DifficultyScaling, Door, EncounterTable, EnemyTemplate, EquipSlot,
+    EventAction, Faction, Fixture, FixtureKind, HiddenFeature, HiddenKind, Location, LootTable,
+    NpcMovement, ProceduralRegion, Puzzle, QuestObjective, SpecialEffect, TopicMap, WorldState,
+    MINUTES_PER_DAY, RIDDLE_SOLVED,
+};
 
 pub const MAX_MODULE_FILE_BYTES: usize = 512 * 1024;
 
@@ -10,6 +16,56 @@ const MAX_NPCS: usize = 256;
 const MAX_QUESTS: usize = 128;
 const MAX_EVENTS: usize = 256;
 const MAX_RECIPES: usize = 128;
+const MAX_REGIONS: usize = 8;
+const MAX_LOOT_TABLES: usize = 64;
+const MAX_AFFIXES: usize = 64;
+const MAX_ITEM_SETS: usize = 32;
+const MAX_SET_BONUSES: usize = 8;
+const MAX_DURABILITY: u32 = 10_000;
+const MAX_ITEM_EFFECTS: usize = 8;
+const MAX_ITEM_COOLDOWN: u32 = 1_000;
+const MAX_SEQUENCE_ACTIONS: usize = 16;
+const MAX_TIMERS: usize = 64;
+const MAX_TIMER_DELAY: u32 = 1_000;
+const MAX_SCRIPTS: usize = 64;
+const MAX_SCRIPT_LEN: usize = 8_000;
+const MAX_CUSTOM_VERBS: usize = 32;
+const MAX_DIALOGUE_NODES: usize = 64;
+/// Replies are picked by a single digit.
+const MAX_DIALOGUE_CHOICES: usize = 9;
+const MAX_SKILL_DIFFICULTY: i32 = 40;
+const MAX_TOPICS: usize = 64;
+const MAX_FACTIONS: usize = 32;
+const MAX_REPUTATION: i32 = 100;
+const MAX_RELATIONSHIP_CHANGE: i32 = 100;
+const MAX_HEAL: i32 = 1_000;
+const MAX_CONDITION_DEPTH: usize = 8;
+const MAX_CONDITION_TERMS: usize = 16;
+const MAX_LOOT_ENTRIES: usize = 32;
+const MAX_LOOT_ROLLS: u32 = 8;
+const MAX_LOOT_QUANTITY: u32 = 16;
+const MAX_REGION_DEPTH: usize = 16;
+const MAX_REGION_TABLE_ENTRIES: usize = 32;
+const MAX_ENCOUNTER_TABLES: usize = 16;
+const MAX_ACTIVE_ENCOUNTERS: usize = 8;
+const MAX_ENEMY_STAT: i32 = 1_000;
+const MAX_SCALING_STEP: i32 = 100;
+const MAX_LOCATION_SECRETS: usize = 16;
+const MAX_TRAP_ACTIONS: usize = 8;
+const MAX_DOORS: usize = 128;
+const MAX_LOCK_DIFFICULTY: u32 = 100;
+const MAX_LOCKPICK_BONUS: u32 = 100;
+const MAX_LIFESTEAL_PERCENT: i32 = 100;
+const MAX_POISON_DAMAGE: i32 = 50;
+const MAX_POISON_TURNS: i32 = 20;
+const MAX_FIXTURES: usize = 128;
+const MAX_LOCATION_FIXTURES: usize = 16;
+const MAX_LEVER_POSITIONS: usize = 8;
+const MAX_COMBINATION_LENGTH: usize = 8;
+const MAX_RIDDLE_ANSWERS: usize = 16;
+const MAX_PUZZLES: usize = 64;
+const MAX_PUZZLE_FIXTURES: usize = 16;
+const MAX_PUZZLE_ACTIONS: usize = 16;
 const MAX_JOURNAL_ENTRIES: usize = 256;
 const MAX_DIALOGUE_HISTORY_ENTRIES: usize = 256;
 const MAX_COMMAND_LOG_ENTRIES: usize = 512;
@@ -23,6 +79,11 @@ const MAX_LOCATION_NPCS: usize = 32;
 const MAX_NPC_ITEMS: usize = 32;
 const MAX_NPC_MEMORY_ENTRIES: usize = 64;
 const MAX_QUEST_REWARDS: usize = 16;
+const MAX_QUEST_STAGES: usize = 16;
+const MAX_STAGE_OBJECTIVES: usize = 16;
+const MAX_QUEST_OUTCOMES: usize = 8;
+const MAX_OBJECTIVE_COUNT: u32 = 1000;
+const MAX_QUEST_LINKS: usize = 16;
 const MAX_RECIPE_INPUTS: usize = 8;
 const MAX_ID_LEN: usize = 64;
 const MAX_SHORT_TEXT_LEN: usize = 120;
@@ -93,6 +154,10 @@ pub fn validate_module_state(state: &WorldState) -> Result<(), String> {
     check_count("quests", state.quests.len(), MAX_QUESTS)?;
     check_count("events", state.events.len(), MAX_EVENTS)?;
     check_count("recipes", state.recipes.len(), MAX_RECIPES)?;
+    check_count("regions", state.regions.len(), MAX_REGIONS)?;
+    check_count("loot tables", state.loot_tables.len(), MAX_LOOT_TABLES)?;
+    check_count("affixes", state.affixes.len(), MAX_AFFIXES)?;
+    check_count("item sets", state.item_sets.len(), MAX_ITEM_SETS)?;
     check_count("journal entries", state.journal.len(), MAX_JOURNAL_ENTRIES)?;
     check_count(
         "dialogue history entries",
@@ -248,6 +313,96 @@ pub fn validate_module_state(state: &WorldState) -> Result<(), String> {
         }
     }
 
+    for (loc_id, loc) in &state.locations {
+        check_count(
+            format!("location '{}'.secrets", loc_id),
+            loc.secrets.len(),
+            MAX_LOCATION_SECRETS,
+        )?;
+        for (i, feature) in loc.secrets.iter().enumerate() {
+            if loc.secrets[..i].iter().any(|f| f.id == feature.id) {
+                return Err(format!(
+                    "Location '{}' has secret '{}' more than once.",
+                    loc_id, feature.id
+                ));
+            }
+            validate_hidden_feature(state, loc, feature)?;
+        }
+    }
+
+    check_count("doors", state.doors.len(), MAX_DOORS)?;
+    for (door_key, door) in &state.doors {
+        validate_door(state, door_key, door)?;
+    }
+    for (loc_id, loc) in &state.locations {
+        for (dir, door_id) in &loc.doors {
+            if !state.doors.contains_key(door_id) {
+                return Err(format!(
+                    "Location '{}' has door '{}' which doesn't exist.",
+                    loc_id, door_id
+                ));
+            }
+            // Doors may hang in an exit that is still hidden
+            let hidden_exit = loc.secrets.iter().find_map(|s| match &s.kind {
+                HiddenKind::Exit(d, dest) if d == dir => Some(dest),
+                _ => None,
+            });
+            let Some(dest_id) = loc.exits.get(dir).or(hidden_exit) else {
+                return Err(format!(
+                    "Location '{}' has door '{}' on {:?}, where there is no exit.",
+                    loc_id, door_id, dir
+                ));
+            };
+            // Both sides share the door, so both must hang it
+            let far_door = state
+                .locations
+                .get(dest_id)
+                .and_then(|dest| dest.doors.get(&dir.opposite()));
+            if far_door != Some(door_id) {
+                return Err(format!(
+                    "Location '{}' has door '{}' on {:?}, but '{}' doesn't have it on {:?}.",
+                    loc_id,
+                    door_id,
+                    dir,
+                    dest_id,
+                    dir.opposite()
+                ));
+            }
+            if let Some(room) = state.doors[door_id]
+                .opens_from
+                .as_ref()
+                .filter(|room| *room != loc_id && *room != dest_id)
+            {
+                return Err(format!(
+                    "Door '{}' opens from '{}', which is neither '{}' nor '{}'.",
+                    door_id, room, loc_id, dest_id
+                ));
+            }
+        }
+    }
+
+    check_count("fixtures", state.fixtures.len(), MAX_FIXTURES)?;
+    for (fixture_key, fixture) in &state.fixtures {
+        validate_fixture(fixture_key, fixture)?;
+    }
+    for (loc_id, loc) in &state.locations {
+        check_count(
+            format!("location '{}'.fixtures", loc_id),
+            loc.fixtures.len(),
+            MAX_LOCATION_FIXTURES,
+        )?;
+        if let Some(id) = loc.fixtures.iter().find(|id| !state.fixtures.contains_key(*id)) {
+            return Err(format!(
+                "Location '{}' has fixture '{}' which doesn't exist.",
+                loc_id, id
+            ));
+        }
+    }
+    check_count("puzzles", state.puzzles.len(), MAX_PUZZLES)?;
+    for (puzzle_key, puzzle) in &state.puzzles {
+        validate_puzzle(state, puzzle_key, puzzle)?;
+    }
+
     // NPC references in locations must exist
     for (loc_id, loc) in &state.locations {
         for npc_id in &loc.npcs {
@@ -300,6 +455,51 @@ pub fn validate_module_state(state: &WorldState) -> Result<(), String> {
         }
     }
 
+    for slot in EquipSlot::ALL {
+        let Some(item_id) = state.player.equipped(slot) else {
+            continue;
+        };
+        check_string_len("player equipped item id", item_id, MAX_ID_LEN)?;
+        match state.items.get(item_id) {
+            None => {
+                return Err(format!(
+                    "Player equipped '{}' which doesn't exist.",
+                    item_id
+                ))
+            }
+            Some(item) if item.equip_slot() != Some(slot) => {
+                return Err(format!(
+                    "Player equipped '{}' in the {} slot, but it doesn't fit there.",
+                    item_id,
+                    slot.display_name()
+                ))
+            }
+            Some(_) => {}
+        }
+    }
+
+    for (set_key, set) in &state.item_sets {
+        check_string_len(format!("item set key '{}'", set_key), set_key, MAX_ID_LEN)?;
+        if set.id != *set_key {
+            return Err(format!(
+                "Item set map key '{}' must match set id '{}'.",
+                set_key, set.id
+            ));
+        }
+        check_string_len(format!("item set '{}'.name", set_key), &set.name, MAX_SHORT_TEXT_LEN)?;
+        check_count(
+            format!("item set '{}' bonuses", set_key),
+            set.bonuses.len(),
+            MAX_SET_BONUSES,
+        )?;
+        if set.bonuses.iter().any(|b| b.pieces == 0) {
+            return Err(format!(
+                "Item set '{}' has a bonus that needs zero pieces.",
+                set_key
+            ));
+        }
+    }
+
     for (item_key, item) in &state.items {
         check_string_len(format!("item key '{}'", item_key), item_key, MAX_ID_LEN)?;
         check_string_len(format!("item '{}'.id", item_key), &item.id, MAX_ID_LEN)?;
@@ -329,6 +529,55 @@ pub fn validate_module_state(state: &WorldState) -> Result<(), String> {
             item.lore.as_deref(),
             MAX_LONG_TEXT_LEN,
         )?;
+        check_loot_table_ref(state, format!("Item '{}'", item_key), item.loot_table.as_deref())?;
+        if let Some(durability) = item.durability {
+            if durability.max == 0 || durability.max > MAX_DURABILITY {
+                return Err(format!(
+                    "Item '{}' max durability must be between 1 and {}.",
+                    item_key, MAX_DURABILITY
+                ));
+            }
+            if durability.current > durability.max {
+                return Err(format!(
+                    "Item '{}' durability exceeds its maximum.",
+                    item_key
+                ));
+            }
+        }
+        for effect in &item.special_effects {
+            check_special_effect(format!("Item '{}'", item_key), effect)?;
+        }
+        if item.lockpick.is_some_and(|bonus| bonus > MAX_LOCKPICK_BONUS) {
+            return Err(format!(
+                "Item '{}' lockpick bonus must be between 0 and {}.",
+                item_key, MAX_LOCKPICK_BONUS
+            ));
+        }
+        if item.repair_amount == Some(0) {
+            return Err(format!("Item '{}' repair amount must be positive.", item_key));
+        }
+        check_count(
+            format!("item '{}' effects", item_key),
+            item.effects.len(),
+            MAX_ITEM_EFFECTS,
+        )?;
+        for effect in &item.effects {
+            check_event_action(state, format!("Item '{}' effect", item_key), &effect.action)?;
+        }
+        if item.cooldown > MAX_ITEM_COOLDOWN {
+            return Err(format!(
+                "Item '{}' cooldown exceeds limit of {}.",
+                item_key, MAX_ITEM_COOLDOWN
+            ));
+        }
+        if let Some(set_id) = item.set_id.as_deref() {
+            if !state.item_sets.contains_key(set_id) {
+                return Err(format!(
+                    "Item '{}' belongs to set '{}' which doesn't exist.",
+                    item_key, set_id
+                ));
+            }
+        }
     }
 
     for (npc_key, npc) in &state.npcs {
@@ -391,6 +640,56 @@ pub fn validate_module_state(state: &WorldState) -> Result<(), String> {
                 MAX_MEMORY_EVENT_LEN,
             )?;
         }
+        check_loot_table_ref(state, format!("NPC '{}'", npc_key), npc.loot_table.as_deref())?;
+        if let Some(movement) = &npc.movement {
+            validate_npc_movement(state, npc_key, movement)?;
+        }
+        if let Some(faction_id) = &npc.faction {
+            if !state.factions.contains_key(faction_id) {
+                return Err(format!(
+                    "NPC '{}' faction '{}' doesn't exist.",
+                    npc_key, faction_id
+                ));
+            }
+        }
+        if let Some(condition) = &npc.recruit_condition {
+            check_condition(state, format!("NPC '{}' recruitCondition", npc_key), condition)?;
+        }
+        if let Some(riddle) = &npc.riddle {
+            let kind = state.fixtures.get(riddle).map(|f| &f.kind);
+            if !matches!(kind, Some(FixtureKind::Riddle { .. })) {
+                return Err(format!(
+                    "NPC '{}' riddle '{}' isn't a riddle fixture.",
+                    npc_key, riddle
+                ));
+            }
+        }
+    }
+
+    check_count("companions", state.companions.len(), companions::MAX_COMPANIONS)?;
+    for (i, companion) in state.companions.iter().enumerate() {
+        if !state.npcs.contains_key(&companion.npc_id) {
+            return Err(format!("Companion '{}' doesn't exist.", companion.npc_id));
+        }
+        if state.companions[..i].iter().any(|c| c.npc_id == companion.npc_id) {
+            return Err(format!("Companion '{}' is listed twice.", companion.npc_id));
+        }
+    }
+
+    check_count("factions", state.factions.len(), MAX_FACTIONS)?;
+    for (faction_key, faction) in &state.factions {
+        validate_faction(state, faction_key, faction)?;
+    }
+
+    let clock = &state.clock;
+    for (label, minutes) in [
+        ("minutesPerAction", clock.minutes_per_action),
+        ("minutesPerMove", clock.minutes_per_move),
+        ("minutesPerRest", clock.minutes_per_rest),
+    ] {
+        if minutes > MINUTES_PER_DAY {
+            return Err(format!("Clock {} can't be longer than a day.", label));
+        }
     }
 
     for (quest_key, quest) in &state.quests {
@@ -442,45 +741,291 @@ pub fn validate_module_state(state: &WorldState) -> Result<(), String> {
             }
         }
         match &quest.objective {
-            crate::models::QuestObjective::FetchItem(item_id) => {
-                check_string_len(
-                    format!("quest '{}'.objective.fetchItem", quest_key),
-                    item_id,
-                    MAX_ID_LEN,
-                )?;
-                if !state.items.contains_key(item_id) {
-                    return Err(format!(
-                        "Quest '{}' objective item '{}' doesn't exist.",
-                        quest_key, item_id
-                    ));
+            Some(objective) if quest.stages.is_empty() => {
+                check_objective(state, quest_key, objective)?
+            }
+            None if quest.stages.is_empty() => {
+                return Err(format!("Quest '{}' needs an objective or stages.", quest_key));
+            }
+            _ => {}
+        }
+        check_count(
+            format!("quest '{}'.stages", quest_key),
+            quest.stages.len(),
+            MAX_QUEST_STAGES,
+        )?;
+        let mut stage_ids = std::collections::HashSet::new();
+        for stage in &quest.stages {
+            check_string_len(format!("quest '{}' stage id", quest_key), &stage.id, MAX_ID_LEN)?;
+            if !stage_ids.insert(stage.id.as_str()) {
+                return Err(format!(
+                    "Quest '{}' has duplicate stage '{}'.",
+                    quest_key, stage.id
+                ));
+            }
+            check_string_len(
+                format!("quest '{}' stage '{}'.name", quest_key, stage.id),
+                &stage.name,
+                MAX_SHORT_TEXT_LEN,
+            )?;
+            if stage.journal.len() > MAX_LONG_TEXT_LEN {
+                return Err(format!(
+                    "quest '{}' stage '{}'.journal exceeds {} characters.",
+                    quest_key, stage.id, MAX_LONG_TEXT_LEN
+                ));
+            }
+            check_count(
+                format!("quest '{}' stage '{}' objectives", quest_key, stage.id),
+                stage.objectives.len(),
+                MAX_STAGE_OBJECTIVES,
+            )?;
+            if stage.objectives.iter().all(|o| o.optional) {
+                return Err(format!(
+                    "Quest '{}' stage '{}' needs at least one required objective.",
+                    quest_key, stage.id
+                ));
+            }
+            for objective in &stage.objectives {
+                check_objective(state, quest_key, &objective.objective)?;
+            }
+        }
+        check_count(
+            format!("quest '{}'.outcomes", quest_key),
+            quest.outcomes.len(),
+            MAX_QUEST_OUTCOMES,
+        )?;
+        let mut outcome_ids = std::collections::HashSet::new();
+        for outcome in &quest.outcomes {
+            let owner = format!("Quest '{}' outcome '{}'", quest_key, outcome.id);
+            check_string_len(format!("{} id", owner), &outcome.id, MAX_ID_LEN)?;
+            if !outcome_ids.insert(outcome.id.as_str()) {
+                return Err(format!(
+                    "Quest '{}' has duplicate outcome '{}'.",
+                    quest_key, outcome.id
+                ));
+            }
+            check_string_len(
+                format!("{} description", owner),
+                &outcome.description,
+                MAX_LONG_TEXT_LEN,
+            )?;
+            if let Some(condition) = &outcome.condition {
+                check_condition(state, &owner, condition)?;
+            }
+            for reward_id in &outcome.reward {
+                if !state.items.contains_key(reward_id) {
+                    return Err(format!("{} reward item '{}' doesn't exist.", owner, reward_id));
+                }
+            }
+            if let Some(follow_up) = &outcome.follow_up {
+                if !state.quests.contains_key(follow_up) {
+                    return Err(format!("{} follow-up quest '{}' doesn't exist.", owner, follow_up));
                 }
             }
-            crate::models::QuestObjective::KillNpc(npc_id) => {
-                check_string_len(
-                    format!("quest '{}'.objective.killNpc", quest_key),
-                    npc_id,
-                    MAX_ID_LEN,
-                )?;
-                if !state.npcs.contains_key(npc_id) {
+        }
+        check_count(
+            format!("quest '{}'.requiredQuests", quest_key),
+            quest.required_quests.len(),
+            MAX_QUEST_LINKS,
+        )?;
+        check_count(
+            format!("quest '{}'.locksOut", quest_key),
+            quest.locks_out.len(),
+            MAX_QUEST_LINKS,
+        )?;
+        for linked in quest.required_quests.iter().chain(&quest.locks_out) {
+            if linked == quest_key {
+                return Err(format!("Quest '{}' can't refer to itself.", quest_key));
+            }
+            if !state.quests.contains_key(linked) {
+                return Err(format!(
+                    "Quest '{}' refers to quest '{}' which doesn't exist.",
+                    quest_key, linked
+                ));
+            }
+        }
+        check_count(
+            format!("quest '{}'.requiredFlags", quest_key),
+            quest.required_flags.len(),
+            MAX_QUEST_LINKS,
+        )?;
+        for flag in &quest.required_flags {
+            check_string_len(format!("quest '{}' required flag", quest_key), flag, MAX_ID_LEN)?;
+        }
+        check_count(
+            format!("quest '{}'.failConditions", quest_key),
+            quest.fail_conditions.len(),
+            MAX_QUEST_LINKS,
+        )?;
+        for failure in &quest.fail_conditions {
+            match failure {
+                crate::models::QuestFailure::NpcDies(npc_id) if !state.npcs.contains_key(npc_id) => {
                     return Err(format!(
-                        "Quest '{}' objective NPC '{}' doesn't exist.",
+                        "Quest '{}' fail condition NPC '{}' doesn't exist.",
                         quest_key, npc_id
                     ));
                 }
-            }
-            crate::models::QuestObjective::ReachLocation(location_id) => {
-                check_string_len(
-                    format!("quest '{}'.objective.reachLocation", quest_key),
-                    location_id,
-                    MAX_ID_LEN,
-                )?;
-                if !state.locations.contains_key(location_id) {
+                crate::models::QuestFailure::ItemDestroyed(item_id)
+                    if !state.locations.values().any(|l| l.items.contains(item_id))
+                        && !state.npcs.values().any(|n| n.items.contains(item_id))
+                        && !state.player.inventory.contains(item_id) =>
+                {
                     return Err(format!(
-                        "Quest '{}' objective location '{}' doesn't exist.",
-                        quest_key, location_id
+                        "Quest '{}' fail condition item '{}' isn't placed in the world.",
+                        quest_key, item_id
                     ));
                 }
+                crate::models::QuestFailure::TimeLimit(0) => {
+                    return Err(format!("Quest '{}' time limit must be at least 1.", quest_key));
+                }
+                _ => {}
+            }
+        }
+        check_count(
+            format!("quest '{}'.onFail", quest_key),
+            quest.on_fail.len(),
+            MAX_SEQUENCE_ACTIONS,
+        )?;
+        for action in &quest.on_fail {
+            check_event_action(state, format!("Quest '{}' onFail", quest_key), action)?;
+        }
+    }
+
+    let mut affix_ids = std::collections::HashSet::new();
+    for affix in &state.affixes {
+        check_string_len("affix.id", &affix.id, MAX_ID_LEN)?;
+        check_string_len(format!("affix '{}'.name", affix.id), &affix.name, MAX_SHORT_TEXT_LEN)?;
+        if !affix_ids.insert(affix.id.as_str()) {
+            return Err(format!("Affix id '{}' is used more than once.", affix.id));
+        }
+        if let Some(effect) = &affix.effect {
+            check_special_effect(format!("Affix '{}'", affix.id), effect)?;
+        }
+    }
+
+    for (table_key, table) in &state.loot_tables {
+        validate_loot_table(state, table_key, table)?;
+    }
+
+    let mut generated = std::collections::HashSet::new();
+    let mut entries = std::collections::HashSet::new();
+    for (i, region) in state.regions.iter().enumerate() {
+        if state.regions[..i].iter().any(|r| r.id == region.id) {
+            return Err(format!("Region '{}' is defined more than once.", region.id));
+        }
+        validate_region(state, region)?;
+        // Generated rooms and enemies must not replace authored ones or
+        // another region's
+        let (rooms, npcs) = dungeon_generator::generated_ids(region);
+        if let Some(id) = rooms.iter().find(|id| state.locations.contains_key(*id)) {
+            return Err(format!(
+                "Region '{}' would generate room '{}', which already exists.",
+                region.id, id
+            ));
+        }
+        if let Some(id) = npcs.iter().find(|id| state.npcs.contains_key(*id)) {
+            return Err(format!(
+                "Region '{}' would generate NPC '{}', which already exists.",
+                region.id, id
+            ));
+        }
+        if let Some(id) = rooms.into_iter().chain(npcs).find(|id| !generated.insert(id.clone())) {
+            return Err(format!(
+                "Region '{}' would generate '{}', which another region also generates.",
+                region.id, id
+            ));
+        }
+        if !entries.insert((&region.entry_location, region.entry_direction)) {
+            return Err(format!(
+                "Region '{}' enters from {:?} of '{}', which another region already uses.",
+                region.id, region.entry_direction, region.entry_location
+            ));
+        }
+    }
+
+    check_count("encounter tables", state.encounters.len(), MAX_ENCOUNTER_TABLES)?;
+    for (i, table) in state.encounters.iter().enumerate() {
+        if state.encounters[..i].iter().any(|t| t.id == table.id) {
+            return Err(format!("Encounter table '{}' is defined more than once.", table.id));
+        }
+        validate_encounter_table(state, table)?;
+    }
+
+    check_count("scripts", state.scripts.len(), MAX_SCRIPTS)?;
+    for (script_id, source) in &state.scripts {
+        check_string_len("script id", script_id, MAX_ID_LEN)?;
+        check_string_len(format!("script '{}'", script_id), source, MAX_SCRIPT_LEN)?;
+        scripting::check_script(source, state)
+            .map_err(|e| format!("Script '{}': {}", script_id, e))?;
+    }
+    check_count("custom verbs", state.custom_verbs.len(), MAX_CUSTOM_VERBS)?;
+    for verb in &state.custom_verbs {
+        for word in std::iter::once(&verb.verb).chain(&verb.aliases) {
+            check_string_len("custom verb", word, MAX_ID_LEN)?;
+            if word.chars().any(|c| c.is_whitespace() || c.is_uppercase()) {
+                return Err(format!(
+                    "Custom verb '{}' must be a single lowercase word.",
+                    word
+                ));
+            }
+            if parser::is_builtin_verb(word) {
+                return Err(format!(
+                    "Custom verb '{}' is already a built-in command.",
+                    word
+                ));
+            }
+        }
+        if !state.scripts.contains_key(&verb.script) {
+            return Err(format!(
+                "Custom verb '{}' script '{}' doesn't exist.",
+                verb.verb, verb.script
+            ));
+        }
+        if let Some(loc_id) = &verb.location {
+            if !state.locations.contains_key(loc_id) {
+                return Err(format!(
+                    "Custom verb '{}' location '{}' doesn't exist.",
+                    verb.verb, loc_id
+                ));
+            }
+        }
+    }
+
+    check_count("dialogue trees", state.dialogues.len(), MAX_NPCS)?;
+    for (npc_id, tree) in &state.dialogues {
+        validate_dialogue_tree(state, npc_id, tree)?;
+    }
+
+    check_count("topic maps", state.topics.len(), MAX_NPCS)?;
+    for (npc_id, map) in &state.topics {
+        validate_topic_map(state, npc_id, map)?;
+    }
+
+    let mut event_ids = std::collections::HashSet::new();
+    for event in &state.events {
+        if let Some(id) = event.id.as_deref() {
+            check_string_len("event.id", id, MAX_ID_LEN)?;
+            if !event_ids.insert(id) {
+                return Err(format!("Duplicate event id '{}'.", id));
+            }
+        }
+    }
+    check_count("timers", state.timers.len(), MAX_TIMERS)?;
+    for timer in &state.timers {
+        check_event_id_ref(state, "Timer", &timer.event_id)?;
+    }
+
+    for event in &state.events {
+        let owner = format!("Event at '{}'", event.location_id);
+        check_event_action(state, &owner, &event.action)?;
+        if let Some(condition) = &event.condition {
+            if condition.depth() > MAX_CONDITION_DEPTH {
+                return Err(format!(
+                    "{} condition exceeds nesting limit of {}.",
+                    owner, MAX_CONDITION_DEPTH
+                ));
             }
+            check_condition(state, &owner, condition)?;
         }
     }
 
@@ -504,6 +1049,20 @@ pub fn validate_module_state(state: &WorldState) -> Result<(), String> {
             }
         }
         check_string_len("recipe.hint", &recipe.hint, MAX_HINT_LEN)?;
+        if recipe.repairs {
+            if !recipe.inputs.contains(&recipe.output) {
+                return Err(format!(
+                    "Repair recipe '{}' output '{}' must be one of its inputs.",
+                    recipe.id, recipe.output
+                ));
+            }
+            if state.items[&recipe.output].durability.is_none() {
+                return Err(format!(
+                    "Repair recipe '{}' output '{}' has no durability.",
+                    recipe.id, recipe.output
+                ));
+            }
+        }
     }
 
     for entry in &state.journal {
@@ -558,130 +1117,1846 @@ pub fn validate_module_state(state: &WorldState) -> Result<(), String> {
             context.room_npc_names.len(),
             MAX_LOCATION_NPCS,
         )?;
+        check_count(
+            "narrative context companionNames",
+            context.companion_names.len(),
+            companions::MAX_COMPANIONS,
+        )?;
     }
 
     Ok(())
 }
 
-fn check_count(label: impl AsRef<str>, count: usize, max: usize) -> Result<(), String> {
-    if count > max {
-        return Err(format!("{} exceeds limit of {}.", label.as_ref(), max));
+fn validate_region(state: &WorldState, region: &ProceduralRegion) -> Result<(), String> {
+    check_string_len("region.id", &region.id, MAX_ID_LEN)?;
+    let Some(entry) = state.locations.get(&region.entry_location) else {
+        return Err(format!(
+            "Region '{}' entry location '{}' doesn't exist.",
+            region.id, region.entry_location
+        ));
+    };
+    if entry.exits.contains_key(&region.entry_direction) {
+        return Err(format!(
+            "Region '{}' enters from {:?} of '{}', which already has an exit there.",
+            region.id, region.entry_direction, region.entry_location
+        ));
     }
-    Ok(())
-}
+    if region.min_depth > region.max_depth {
+        return Err(format!(
+            "Region '{}' minDepth is greater than maxDepth.",
+            region.id
+        ));
+    }
+    if region.max_depth > MAX_REGION_DEPTH {
+        return Err(format!(
+            "Region '{}' maxDepth exceeds limit of {}.",
+            region.id, MAX_REGION_DEPTH
+        ));
+    }
+    if region.enemy_chance > 100 || region.loot_chance > 100 {
+        return Err(format!(
+            "Region '{}' chances must be between 0 and 100.",
+            region.id
+        ));
+    }
+    if region.room_templates.is_empty() {
+        return Err(format!("Region '{}' needs at least one room template.", region.id));
+    }
+    check_count(
+        format!("region '{}'.roomTemplates", region.id),
+        region.room_templates.len(),
+        MAX_REGION_TABLE_ENTRIES,
+    )?;
+    check_count(
+        format!("region '{}'.enemyTable", region.id),
+        region.enemy_table.len(),
+        MAX_REGION_TABLE_ENTRIES,
+    )?;
 
-fn check_string_len(label: impl AsRef<str>, value: &str, max: usize) -> Result<(), String> {
-    if value.trim().is_empty() {
-        return Err(format!("{} cannot be empty.", label.as_ref()));
+    for template in region.room_templates.iter().chain(region.final_room.iter()) {
+        check_string_len("region room name", &template.name, MAX_SHORT_TEXT_LEN)?;
+        check_string_len(
+            "region room description",
+            &template.description,
+            MAX_LONG_TEXT_LEN,
+        )?;
     }
-    if value.len() > max {
-        return Err(format!("{} exceeds {} characters.", label.as_ref(), max));
+
+    let owner = format!("Region '{}'", region.id);
+    for enemy in region.enemy_table.iter().chain(region.boss.iter()) {
+        check_enemy_template(state, &owner, enemy)?;
+    }
+    check_scaling(&owner, &region.scaling)?;
+
+    check_loot_table_ref(
+        state,
+        format!("Region '{}'", region.id),
+        region.loot_table.as_deref(),
+    )?;
+    for item_id in &region.final_items {
+        if !state.items.contains_key(item_id) {
+            return Err(format!(
+                "Region '{}' loot item '{}' doesn't exist.",
+                region.id, item_id
+            ));
+        }
     }
+
     Ok(())
 }
 
-fn check_optional_string_len(
-    label: impl AsRef<str>,
-    value: Option<&str>,
+fn validate_encounter_table(state: &WorldState, table: &EncounterTable) -> Result<(), String> {
+    check_string_len("encounter table id", &table.id, MAX_ID_LEN)?;
+    let owner = format!("Encounter table '{}'", table.id);
+    if table.chance > 100 {
+        return Err(format!("{} chance must be between 0 and 100.", owner));
+    }
+    if table.enemies.is_empty() {
+        return Err(format!("{} needs at least one enemy.", owner));
+    }
+    if table.locations.is_empty() && table.regions.is_empty() {
+        return Err(format!("{} covers no locations or regions.", owner));
+    }
+    if !(1..=MAX_ACTIVE_ENCOUNTERS).contains(&table.max_active) {
+        return Err(format!(
+            "{} maxActive must be between 1 and {}.",
+            owner, MAX_ACTIVE_ENCOUNTERS
+        ));
+    }
+    check_count(
+        format!("{} enemies", owner),
+        table.enemies.len(),
+        MAX_REGION_TABLE_ENTRIES,
+    )?;
+    check_count(format!("{} locations", owner), table.locations.len(), MAX_LOCATIONS)?;
+    if let Some(id) = table.locations.iter().find(|id| !state.locations.contains_key(*id)) {
+        return Err(format!("{} location '{}' doesn't exist.", owner, id));
+    }
+    if let Some(id) = table
+        .regions
+        .iter()
+        .find(|id| !state.regions.iter().any(|r| r.id == **id))
+    {
+        return Err(format!("{} region '{}' doesn't exist.", owner, id));
+    }
+    for enemy in &table.enemies {
+        check_enemy_template(state, &owner, enemy)?;
+    }
+    check_scaling(&owner, &table.scaling)?;
+    // Spawns must not replace authored NPCs
+    if let Some(id) = state.npcs.keys().find(|id| encounters::spawns_as(table, id)) {
+        return Err(format!("{} would spawn NPC '{}', which already exists.", owner, id));
+    }
+    Ok(())
+}
+
+fn validate_hidden_feature(
+    state: &WorldState,
+    loc: &Location,
+    feature: &HiddenFeature,
+) -> Result<(), String> {
+    check_string_len("secret id", &feature.id, MAX_ID_LEN)?;
+    let owner = format!("Location '{}' secret '{}'", loc.id, feature.id);
+    check_string_len(format!("{} foundText", owner), &feature.found_text, MAX_LONG_TEXT_LEN)?;
+    if feature.find_chance > 100 {
+        return Err(format!("{} findChance must be between 0 and 100.", owner));
+    }
+    check_count(format!("{} revealedBy", owner), feature.revealed_by.len(), MAX_ITEMS)?;
+    if let Some(id) = feature.revealed_by.iter().find(|id| !state.items.contains_key(*id)) {
+        return Err(format!("{} revealing item '{}' doesn't exist.", owner, id));
+    }
+    match &feature.kind {
+        HiddenKind::Exit(direction, dest) => {
+            if !state.locations.contains_key(dest) {
+                return Err(format!("{} leads to '{}' which doesn't exist.", owner, dest));
+            }
+            if loc.exits.contains_key(direction) && !loc.discovered_secrets.contains(&feature.id) {
+                return Err(format!(
+                    "{} hides an exit {:?} that is already open.",
+                    owner, direction
+                ));
+            }
+        }
+        HiddenKind::Item(item_id) if !state.items.contains_key(item_id) => {
+            return Err(format!("{} item '{}' doesn't exist.", owner, item_id));
+        }
+        HiddenKind::Item(_) => {}
+        HiddenKind::Trap(trap) => {
+            check_string_len(format!("{} name", owner), &trap.name, MAX_SHORT_TEXT_LEN)?;
+            if trap.disarm_chance > 100 {
+                return Err(format!("{} disarmChance must be between 0 and 100.", owner));
+            }
+            check_count(format!("{} onTrigger", owner), trap.on_trigger.len(), MAX_TRAP_ACTIONS)?;
+            check_count(format!("{} onDisarm", owner), trap.on_disarm.len(), MAX_TRAP_ACTIONS)?;
+            for action in trap.on_trigger.iter().chain(&trap.on_disarm) {
+                check_event_action(state, &owner, action)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn check_enemy_template(
+    state: &WorldState,
+    owner: &str,
+    enemy: &EnemyTemplate,
+) -> Result<(), String> {
+    check_string_len(format!("{} enemy id", owner), &enemy.id, MAX_ID_LEN)?;
+    check_string_len(format!("{} enemy name", owner), &enemy.name, MAX_SHORT_TEXT_LEN)?;
+    check_string_len(
+        format!("{} enemy description", owner),
+        &enemy.description,
+        MAX_LONG_TEXT_LEN,
+    )?;
+    check_optional_string_len(
+        format!("{} enemy examineText", owner),
+        enemy.examine_text.as_deref(),
+        MAX_LONG_TEXT_LEN,
+    )?;
+    let stats = [enemy.health, enemy.attack, enemy.defense];
+    if enemy.health < 1 || stats.iter().any(|stat| !(0..=MAX_ENEMY_STAT).contains(stat)) {
+        return Err(format!(
+            "{} enemy '{}' needs health from 1, and attack and defense from 0, up to {}.",
+            owner, enemy.id, MAX_ENEMY_STAT
+        ));
+    }
+    check_count(format!("{} enemy items", owner), enemy.items.len(), MAX_NPC_ITEMS)?;
+    check_loot_table_ref(
+        state,
+        format!("{} enemy '{}'", owner, enemy.id),
+        enemy.loot_table.as_deref(),
+    )?;
+    for item_id in &enemy.items {
+        if !state.items.contains_key(item_id) {
+            return Err(format!(
+                "{} enemy '{}' item '{}' doesn't exist.",
+                owner, enemy.id, item_id
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn check_scaling(owner: &str, scaling: &DifficultyScaling) -> Result<(), String> {
+    let steps = [
+        scaling.health_per_depth,
+        scaling.attack_per_depth,
+        scaling.defense_per_depth,
+        scaling.health_per_level,
+        scaling.attack_per_level,
+        scaling.defense_per_level,
+    ];
+    if steps.iter().any(|step| !(-MAX_SCALING_STEP..=MAX_SCALING_STEP).contains(step)) {
+        return Err(format!(
+            "{} scaling steps must be between -{} and {}.",
+            owner, MAX_SCALING_STEP, MAX_SCALING_STEP
+        ));
+    }
+    Ok(())
+}
+
+fn check_special_effect(owner: String, effect: &SpecialEffect) -> Result<(), String> {
+    match *effect {
+        SpecialEffect::Poison {
+            damage_per_turn,
+            turns,
+        } => {
+            if !(1..=MAX_POISON_DAMAGE).contains(&damage_per_turn) {
+                return Err(format!(
+                    "{} poison damage must be between 1 and {}.",
+                    owner, MAX_POISON_DAMAGE
+                ));
+            }
+            if !(1..=MAX_POISON_TURNS).contains(&turns) {
+                return Err(format!(
+                    "{} poison turns must be between 1 and {}.",
+                    owner, MAX_POISON_TURNS
+                ));
+            }
+        }
+        SpecialEffect::Lifesteal { percent } => {
+            if !(1..=MAX_LIFESTEAL_PERCENT).contains(&percent) {
+                return Err(format!(
+                    "{} lifesteal percent must be between 1 and {}.",
+                    owner, MAX_LIFESTEAL_PERCENT
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn validate_door(state: &WorldState, door_key: &str, door: &Door) -> Result<(), String> {
+    check_string_len("door id", door_key, MAX_ID_LEN)?;
+    if door.id != door_key {
+        return Err(format!(
+            "Door '{}' has id '{}'; ids must match their keys.",
+            door_key, door.id
+        ));
+    }
+    check_string_len(format!("door '{}' name", door_key), &door.name, MAX_SHORT_TEXT_LEN)?;
+    if let Some(key_id) = door.key_id.as_ref().filter(|id| !state.items.contains_key(*id)) {
+        return Err(format!("Door '{}' key '{}' doesn't exist.", door_key, key_id));
+    }
+    if door.lock_difficulty > MAX_LOCK_DIFFICULTY {
+        return Err(format!(
+            "Door '{}' lockDifficulty must be between 0 and {}.",
+            door_key, MAX_LOCK_DIFFICULTY
+        ));
+    }
+    if let Some(room) = door.opens_from.as_ref().filter(|id| !state.locations.contains_key(*id)) {
+        return Err(format!("Door '{}' opens from '{}' which doesn't exist.", door_key, room));
+    }
+    Ok(())
+}
+
+fn validate_fixture(fixture_key: &str, fixture: &Fixture) -> Result<(), String> {
+    check_string_len("fixture id", fixture_key, MAX_ID_LEN)?;
+    let owner = format!("Fixture '{}'", fixture_key);
+    if fixture.id != fixture_key {
+        return Err(format!("{} has id '{}'; ids must match their keys.", owner, fixture.id));
+    }
+    check_string_len(format!("{} name", owner), &fixture.name, MAX_SHORT_TEXT_LEN)?;
+    if !fixture.description.is_empty() {
+        check_string_len(
+            format!("{} description", owner),
+            &fixture.description,
+            MAX_LONG_TEXT_LEN,
+        )?;
+    }
+    match &fixture.kind {
+        FixtureKind::Lever { positions } => {
+            if positions.is_empty() {
+                return Err(format!("{} needs at least one position.", owner));
+            }
+            check_count(format!("{} positions", owner), positions.len(), MAX_LEVER_POSITIONS)?;
+            for (i, position) in positions.iter().enumerate() {
+                check_string_len(format!("{} position", owner), position, MAX_SHORT_TEXT_LEN)?;
+                if positions[..i].contains(position) {
+                    return Err(format!("{} has position '{}' more than once.", owner, position));
+                }
+            }
+        }
+        FixtureKind::Combination { length } => {
+            if !(1..=MAX_COMBINATION_LENGTH).contains(length) {
+                return Err(format!(
+                    "{} length must be between 1 and {}.",
+                    owner, MAX_COMBINATION_LENGTH
+                ));
+            }
+        }
+        FixtureKind::Riddle { question, answers } => {
+            check_string_len(format!("{} question", owner), question, MAX_LONG_TEXT_LEN)?;
+            if answers.is_empty() {
+                return Err(format!("{} needs at least one answer.", owner));
+            }
+            check_count(format!("{} answers", owner), answers.len(), MAX_RIDDLE_ANSWERS)?;
+            for answer in answers {
+                check_string_len(format!("{} answer", owner), answer, MAX_SHORT_TEXT_LEN)?;
+                // Articles and punctuation are dropped when matching
+                if puzzles::normalize_answer(answer).is_empty() {
+                    return Err(format!("{} answer '{}' can never be given.", owner, answer));
+                }
+            }
+        }
+    }
+    if !fixture.state.is_empty() && !fixture.can_reach(&fixture.state) {
+        return Err(format!("{} starts in unknown state '{}'.", owner, fixture.state));
+    }
+    Ok(())
+}
+
+/// Checks a puzzle can be solved: each fixture it needs is somewhere the
+/// player can work it and can be brought to the state the solution asks.
+fn validate_puzzle(state: &WorldState, puzzle_key: &str, puzzle: &Puzzle) -> Result<(), String> {
+    check_string_len("puzzle id", puzzle_key, MAX_ID_LEN)?;
+    let owner = format!("Puzzle '{}'", puzzle_key);
+    if puzzle.id != puzzle_key {
+        return Err(format!("{} has id '{}'; ids must match their keys.", owner, puzzle.id));
+    }
+    if !state.locations.contains_key(&puzzle.location_id) {
+        return Err(format!(
+            "{} location '{}' doesn't exist.",
+            owner, puzzle.location_id
+        ));
+    }
+    if let Some(text) = &puzzle.solved_text {
+        check_string_len(format!("{} solvedText", owner), text, MAX_LONG_TEXT_LEN)?;
+    }
+    if puzzle.solution.is_empty() {
+        return Err(format!("{} has an empty solution.", owner));
+    }
+    check_count(format!("{} solution", owner), puzzle.solution.len(), MAX_PUZZLE_FIXTURES)?;
+    let mut solution: Vec<(&String, &String)> = puzzle.solution.iter().collect();
+    solution.sort();
+    for (fixture_id, wanted) in solution {
+        let Some(fixture) = state.fixtures.get(fixture_id) else {
+            return Err(format!("{} fixture '{}' doesn't exist.", owner, fixture_id));
+        };
+        let placed = state.locations.values().any(|l| l.fixtures.contains(fixture_id))
+            || state
+                .npcs
+                .values()
+                .any(|n| !n.hostile && n.riddle.as_ref() == Some(fixture_id));
+        if !placed {
+            return Err(format!(
+                "{} can't be solved: fixture '{}' is in no room and posed by no peaceful NPC.",
+                owner, fixture_id
+            ));
+        }
+        let riddle = matches!(fixture.kind, FixtureKind::Riddle { .. });
+        if !fixture.can_reach(wanted) {
+            let hint = if riddle {
+                format!(" Riddles can only be '{}'.", RIDDLE_SOLVED)
+            } else {
+                String::new()
+            };
+            return Err(format!(
+                "{} can't be solved: fixture '{}' can never be '{}'.{}",
+                owner, fixture_id, wanted, hint
+            ));
+        }
+    }
+    // Otherwise it would go off the first time any fixture is worked
+    let already_met = puzzle
+        .solution
+        .iter()
+        .all(|(id, wanted)| state.fixtures.get(id).is_some_and(|f| f.current() == wanted));
+    if !puzzle.solved && already_met {
+        return Err(format!("{} is already solved by its fixtures' starting states.", owner));
+    }
+    check_count(format!("{} onSolve", owner), puzzle.on_solve.len(), MAX_PUZZLE_ACTIONS)?;
+    for action in &puzzle.on_solve {
+        check_event_action(state, &owner, action)?;
+    }
+    Ok(())
+}
+
+fn validate_loot_table(state: &WorldState, table_key: &str, table: &LootTable) -> Result<(), String> {
+    check_string_len(format!("loot table key '{}'", table_key), table_key, MAX_ID_LEN)?;
+    if table.id != table_key {
+        return Err(format!(
+            "Loot table map key '{}' must match table id '{}'.",
+            table_key, table.id
+        ));
+    }
+    check_count(
+        format!("loot table '{}'.entries", table_key),
+        table.entries.len(),
+        MAX_LOOT_ENTRIES,
+    )?;
+    if table.rolls > MAX_LOOT_ROLLS {
+        return Err(format!(
+            "Loot table '{}' rolls exceeds limit of {}.",
+            table_key, MAX_LOOT_ROLLS
+        ));
+    }
+    if table.drop_chance > 100 {
+        return Err(format!(
+            "Loot table '{}' dropChance must be between 0 and 100.",
+            table_key
+        ));
+    }
+    for entry in &table.entries {
+        if !state.items.contains_key(&entry.item_id) {
+            return Err(format!(
+                "Loot table '{}' item '{}' doesn't exist.",
+                table_key, entry.item_id
+            ));
+        }
+        if entry.min_quantity > entry.max_quantity || entry.max_quantity > MAX_LOOT_QUANTITY {
+            return Err(format!(
+                "Loot table '{}' item '{}' has an invalid quantity range.",
+                table_key, entry.item_id
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn check_objective(
+    state: &WorldState,
+    quest_key: &str,
+    objective: &QuestObjective,
+) -> Result<(), String> {
+    let item = |item_id: &str| {
+        check_string_len(format!("quest '{}' objective item", quest_key), item_id, MAX_ID_LEN)?;
+        if !state.items.contains_key(item_id) {
+            return Err(format!(
+                "Quest '{}' objective item '{}' doesn't exist.",
+                quest_key, item_id
+            ));
+        }
+        Ok(())
+    };
+    let npc = |npc_id: &str| {
+        check_string_len(format!("quest '{}' objective NPC", quest_key), npc_id, MAX_ID_LEN)?;
+        if !state.npcs.contains_key(npc_id) {
+            return Err(format!(
+                "Quest '{}' objective NPC '{}' doesn't exist.",
+                quest_key, npc_id
+            ));
+        }
+        Ok(())
+    };
+    let location = |location_id: &str| {
+        check_string_len(
+            format!("quest '{}' objective location", quest_key),
+            location_id,
+            MAX_ID_LEN,
+        )?;
+        if !state.locations.contains_key(location_id) {
+            return Err(format!(
+                "Quest '{}' objective location '{}' doesn't exist.",
+                quest_key, location_id
+            ));
+        }
+        Ok(())
+    };
+    let count = |n: u32| {
+        if n == 0 || n > MAX_OBJECTIVE_COUNT {
+            return Err(format!(
+                "Quest '{}' objective count must be between 1 and {}.",
+                quest_key, MAX_OBJECTIVE_COUNT
+            ));
+        }
+        Ok(())
+    };
+
+    match objective {
+        QuestObjective::FetchItem(item_id) => item(item_id),
+        QuestObjective::KillNpc(npc_id) | QuestObjective::TalkTo(npc_id) => npc(npc_id),
+        QuestObjective::ReachLocation(location_id) => location(location_id),
+        QuestObjective::CraftItem(recipe_id) => {
+            if !state.recipes.iter().any(|r| r.id == *recipe_id) {
+                return Err(format!(
+                    "Quest '{}' objective recipe '{}' doesn't exist.",
+                    quest_key, recipe_id
+                ));
+            }
+            Ok(())
+        }
+        QuestObjective::UseItemAt(item_id, location_id) => {
+            item(item_id)?;
+            location(location_id)?;
+            if !state.items[item_id].usable {
+                return Err(format!(
+                    "Quest '{}' objective item '{}' can't be used.",
+                    quest_key, item_id
+                ));
+            }
+            Ok(())
+        }
+        QuestObjective::CollectCount(item_id, n) => {
+            item(item_id)?;
+            count(*n)
+        }
+        QuestObjective::DiscoverSecret(word) => check_string_len(
+            format!("quest '{}' objective secret", quest_key),
+            word,
+            MAX_SHORT_TEXT_LEN,
+        ),
+        QuestObjective::Escort(npc_id, location_id) => {
+            npc(npc_id)?;
+            location(location_id)
+        }
+        QuestObjective::SurviveTurns(n) => count(*n),
+        QuestObjective::SetFlag(flag) => check_string_len(
+            format!("quest '{}' objective flag", quest_key),
+            flag,
+            MAX_ID_LEN,
+        ),
+    }
+}
+
+fn validate_npc_movement(
+    state: &WorldState,
+    npc_key: &str,
+    movement: &NpcMovement,
+) -> Result<(), String> {
+    let owner = format!("NPC '{}' movement", npc_key);
+    let rooms: Vec<&String> = match movement {
+        NpcMovement::Patrol { route, every } => {
+            if route.is_empty() || *every == 0 {
+                return Err(format!("{} needs a route and a step interval.", owner));
+            }
+            for (i, room) in route.iter().enumerate() {
+                let next = &route[(i + 1) % route.len()];
+                let adjacent = state
+                    .locations
+                    .get(room)
+                    .is_some_and(|l| route.len() == 1 || l.exits.values().any(|d| d == next));
+                if state.locations.contains_key(room) && !adjacent {
+                    return Err(format!(
+                        "{} route step '{}' -> '{}' has no exit.",
+                        owner, room, next
+                    ));
+                }
+            }
+            route.iter().collect()
+        }
+        NpcMovement::Schedule { entries, cycle } => {
+            if entries.is_empty() || *cycle == Some(0) {
+                return Err(format!("{} needs entries and a non-zero cycle.", owner));
+            }
+            if let Some(entry) = entries.iter().find(|e| cycle.is_some_and(|c| e.from >= c)) {
+                return Err(format!(
+                    "{} entry at turn {} falls outside its cycle.",
+                    owner, entry.from
+                ));
+            }
+            entries.iter().map(|e| &e.location).collect()
+        }
+        NpcMovement::Wander { rooms, chance } => {
+            if rooms.is_empty() || !(1..=100).contains(chance) {
+                return Err(format!("{} needs rooms and a chance of 1-100.", owner));
+            }
+            rooms.iter().collect()
+        }
+    };
+    check_count(&owner, rooms.len(), MAX_LOCATIONS)?;
+    if let Some(room) = rooms
+        .iter()
+        .find(|id| !state.locations.contains_key(id.as_str()))
+    {
+        return Err(format!("{} location '{}' doesn't exist.", owner, room));
+    }
+    Ok(())
+}
+
+fn validate_faction(state: &WorldState, key: &str, faction: &Faction) -> Result<(), String> {
+    check_string_len(format!("faction key '{}'", key), key, MAX_ID_LEN)?;
+    if faction.id != key {
+        return Err(format!(
+            "Faction key '{}' does not match faction id '{}'.",
+            key, faction.id
+        ));
+    }
+    check_string_len(
+        format!("faction '{}'.name", key),
+        &faction.name,
+        MAX_SHORT_TEXT_LEN,
+    )?;
+    if faction.description.len() > MAX_LONG_TEXT_LEN {
+        return Err(format!("Faction '{}' description is too long.", key));
+    }
+    if !(-MAX_REPUTATION..=MAX_REPUTATION).contains(&faction.reputation) {
+        return Err(format!(
+            "Faction '{}' reputation must be between -{} and {}.",
+            key, MAX_REPUTATION, MAX_REPUTATION
+        ));
+    }
+    check_count(
+        format!("faction '{}' relations", key),
+        faction.allies.len() + faction.enemies.len(),
+        MAX_FACTIONS,
+    )?;
+    for other in faction.allies.iter().chain(&faction.enemies) {
+        if other == key {
+            return Err(format!("Faction '{}' can't be its own ally or enemy.", key));
+        }
+        if !state.factions.contains_key(other) {
+            return Err(format!(
+                "Faction '{}' relation '{}' doesn't exist.",
+                key, other
+            ));
+        }
+    }
+    check_count(
+        format!("faction '{}' property", key),
+        faction.property.len(),
+        MAX_ITEMS,
+    )?;
+    for item_id in &faction.property {
+        if !state.items.contains_key(item_id) {
+            return Err(format!(
+                "Faction '{}' property item '{}' doesn't exist.",
+                key, item_id
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn validate_topic_map(state: &WorldState, npc_id: &str, map: &TopicMap) -> Result<(), String> {
+    if !state.npcs.contains_key(npc_id) {
+        return Err(format!("Topic map NPC '{}' doesn't exist.", npc_id));
+    }
+    check_count(
+        format!("NPC '{}' topics", npc_id),
+        map.topics.len(),
+        MAX_TOPICS,
+    )?;
+    if let Some(fallback) = &map.fallback {
+        check_string_len(
+            format!("NPC '{}' topic fallback", npc_id),
+            fallback,
+            MAX_LONG_TEXT_LEN,
+        )?;
+    }
+    let mut names = std::collections::HashSet::new();
+    for topic in &map.topics {
+        let owner = format!("NPC '{}' topic '{}'", npc_id, topic.name);
+        for word in std::iter::once(&topic.name).chain(&topic.aliases) {
+            check_string_len(format!("{} name", owner), word, MAX_ID_LEN)?;
+            if word.chars().any(|c| c.is_uppercase()) {
+                return Err(format!("{} name '{}' must be lowercase.", owner, word));
+            }
+            if !names.insert(word.as_str()) {
+                return Err(format!("NPC '{}' has duplicate topic '{}'.", npc_id, word));
+            }
+        }
+        if topic.ask.is_none() && topic.tell.is_none() {
+            return Err(format!("{} needs an ask or tell reply.", owner));
+        }
+        for reply in topic.ask.iter().chain(&topic.tell).chain(&topic.journal) {
+            check_string_len(format!("{} text", owner), reply, MAX_LONG_TEXT_LEN)?;
+        }
+        if let Some(flag) = &topic.sets_flag {
+            check_string_len(format!("{} flag", owner), flag, MAX_ID_LEN)?;
+        }
+        if let Some(condition) = &topic.condition {
+            check_condition(state, &owner, condition)?;
+        }
+    }
+    Ok(())
+}
+
+fn validate_dialogue_tree(
+    state: &WorldState,
+    npc_id: &str,
+    tree: &DialogueTree,
+) -> Result<(), String> {
+    if !state.npcs.contains_key(npc_id) {
+        return Err(format!("Dialogue tree NPC '{}' doesn't exist.", npc_id));
+    }
+    check_count(
+        format!("dialogue '{}' nodes", npc_id),
+        tree.nodes.len(),
+        MAX_DIALOGUE_NODES,
+    )?;
+    let node_ref = |owner: &str, node_id: &str| {
+        if !tree.nodes.contains_key(node_id) {
+            return Err(format!("{} node '{}' doesn't exist.", owner, node_id));
+        }
+        Ok(())
+    };
+    node_ref(&format!("Dialogue '{}' start", npc_id), &tree.start)?;
+    for (node_id, node) in &tree.nodes {
+        let owner = format!("Dialogue '{}' node '{}'", npc_id, node_id);
+        check_string_len(format!("{} id", owner), node_id, MAX_ID_LEN)?;
+        check_string_len(format!("{} text", owner), &node.text, MAX_LONG_TEXT_LEN)?;
+        check_count(
+            format!("{} effects", owner),
+            node.effects.len(),
+            MAX_SEQUENCE_ACTIONS,
+        )?;
+        for action in &node.effects {
+            check_event_action(state, &owner, action)?;
+        }
+        check_count(
+            format!("{} choices", owner),
+            node.choices.len(),
+            MAX_DIALOGUE_CHOICES,
+        )?;
+        for choice in &node.choices {
+            check_string_len(
+                format!("{} choice", owner),
+                &choice.text,
+                MAX_SHORT_TEXT_LEN,
+            )?;
+            if let Some(condition) = &choice.condition {
+                check_condition(state, &owner, condition)?;
+            }
+            if let Some(check) = &choice.skill_check {
+                if !(1..=MAX_SKILL_DIFFICULTY).contains(&check.difficulty) {
+                    return Err(format!(
+                        "{} skill check difficulty must be between 1 and {}.",
+                        owner, MAX_SKILL_DIFFICULTY
+                    ));
+                }
+            } else if choice.fail_next.is_some() {
+                return Err(format!(
+                    "{} choice '{}' has a failure node but no skill check.",
+                    owner, choice.text
+                ));
+            }
+            check_count(
+                format!("{} choice effects", owner),
+                choice.effects.len(),
+                MAX_SEQUENCE_ACTIONS,
+            )?;
+            for action in &choice.effects {
+                check_event_action(state, &owner, action)?;
+            }
+            for next in choice.next.iter().chain(&choice.fail_next) {
+                node_ref(&owner, next)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Checks that every id an event action names exists.
+fn check_event_action(
+    state: &WorldState,
+    owner: impl AsRef<str>,
+    action: &EventAction,
+) -> Result<(), String> {
+    let owner = owner.as_ref();
+    let missing = |kind: &str, id: &str| format!("{} {} '{}' doesn't exist.", owner, kind, id);
+    match action {
+        EventAction::GiveLoot(table_id) => check_loot_table_ref(state, owner, Some(table_id))?,
+        EventAction::GiveItem(item_id) | EventAction::RemoveItem(item_id)
+            if !state.items.contains_key(item_id) =>
+        {
+            return Err(missing("item", item_id));
+        }
+        EventAction::Teleport(loc_id)
+        | EventAction::ChangeDescription(loc_id, _)
+        | EventAction::AddExit(_, loc_id)
+            if !state.locations.contains_key(loc_id) =>
+        {
+            return Err(missing("location", loc_id));
+        }
+        EventAction::MoveNpc(npc_id, loc_id) => {
+            if !state.npcs.contains_key(npc_id) {
+                return Err(missing("npc", npc_id));
+            }
+            if !state.locations.contains_key(loc_id) {
+                return Err(missing("location", loc_id));
+            }
+        }
+        EventAction::SetNpcHostile(npc_id, _) if !state.npcs.contains_key(npc_id) => {
+            return Err(missing("npc", npc_id));
+        }
+        EventAction::ChangeRelationship(npc_id, amount) => {
+            if !state.npcs.contains_key(npc_id) {
+                return Err(missing("npc", npc_id));
+            }
+            if !(-MAX_RELATIONSHIP_CHANGE..=MAX_RELATIONSHIP_CHANGE).contains(amount) {
+                return Err(format!(
+                    "{} relationship change for '{}' must be between -{} and {}.",
+                    owner, npc_id, MAX_RELATIONSHIP_CHANGE, MAX_RELATIONSHIP_CHANGE
+                ));
+            }
+        }
+        EventAction::Heal(amount) if !(1..=MAX_HEAL).contains(amount) => {
+            return Err(format!("{} heal must be between 1 and {}.", owner, MAX_HEAL));
+        }
+        EventAction::ChangeReputation(faction_id, amount) => {
+            if !state.factions.contains_key(faction_id) {
+                return Err(missing("faction", faction_id));
+            }
+            if !(-MAX_REPUTATION..=MAX_REPUTATION).contains(amount) {
+                return Err(format!(
+                    "{} reputation change for '{}' must be between -{} and {}.",
+                    owner, faction_id, MAX_REPUTATION, MAX_REPUTATION
+                ));
+            }
+        }
+        EventAction::StartQuest(quest_id)
+        | EventAction::CompleteQuest(quest_id)
+        | EventAction::FailQuest(quest_id)
+            if !state.quests.contains_key(quest_id) =>
+        {
+            return Err(missing("quest", quest_id));
+        }
+        EventAction::Schedule(event_id, turns)
+        | EventAction::ScheduleRepeating(event_id, turns) => {
+            check_event_id_ref(state, owner, event_id)?;
+            if *turns == 0 || *turns > MAX_TIMER_DELAY {
+                return Err(format!(
+                    "{} timer for '{}' must be between 1 and {} turns.",
+                    owner, event_id, MAX_TIMER_DELAY
+                ));
+            }
+        }
+        EventAction::CancelTimer(event_id) => check_event_id_ref(state, owner, event_id)?,
+        EventAction::RunScript(script_id) if !state.scripts.contains_key(script_id) => {
+            return Err(missing("script", script_id));
+        }
+        EventAction::Sequence(actions) => {
+            check_count(
+                format!("{} sequence actions", owner),
+                actions.len(),
+                MAX_SEQUENCE_ACTIONS,
+            )?;
+            for action in actions {
+                check_event_action(state, owner, action)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn check_event_id_ref(state: &WorldState, owner: &str, event_id: &str) -> Result<(), String> {
+    if !state.events.iter().any(|e| e.id.as_deref() == Some(event_id)) {
+        return Err(format!("{} event '{}' doesn't exist.", owner, event_id));
+    }
+    Ok(())
+}
+
+fn check_condition(
+    state: &WorldState,
+    owner: impl AsRef<str>,
+    condition: &Condition,
+) -> Result<(), String> {
+    let owner = owner.as_ref();
+    let missing = |kind: &str, id: &str| {
+        format!("{} condition {} '{}' doesn't exist.", owner, kind, id)
+    };
+    match condition {
+        Condition::Flag(flag) => check_string_len("condition flag", flag, MAX_ID_LEN)?,
+        Condition::HasItem(item_id) if !state.items.contains_key(item_id) => {
+            return Err(missing("item", item_id));
+        }
+        Condition::NpcState(npc_id, _) | Condition::RelationshipAtLeast(npc_id, _)
+            if !state.npcs.contains_key(npc_id) =>
+        {
+            return Err(missing("npc", npc_id));
+        }
+        Condition::QuestCompleted(quest_id) if !state.quests.contains_key(quest_id) => {
+            return Err(missing("quest", quest_id));
+        }
+        Condition::ReputationAtLeast(faction_id, _)
+            if !state.factions.contains_key(faction_id) =>
+        {
+            return Err(missing("faction", faction_id));
+        }
+        Condition::Visited(loc_id) | Condition::AtLocation(loc_id)
+            if !state.locations.contains_key(loc_id) =>
+        {
+            return Err(missing("location", loc_id));
+        }
+        Condition::TurnBetween(from, to) if from > to => {
+            return Err(format!(
+                "{} condition turn range {}..{} is empty.",
+                owner, from, to
+            ));
+        }
+        Condition::All(terms) | Condition::Any(terms) => {
+            check_count(
+                format!("{} condition terms", owner),
+                terms.len(),
+                MAX_CONDITION_TERMS,
+            )?;
+            for term in terms {
+                check_condition(state, owner, term)?;
+            }
+        }
+        Condition::Not(inner) => check_condition(state, owner, inner)?,
+        _ => {}
+    }
+    Ok(())
+}
+
+fn check_loot_table_ref(
+    state: &WorldState,
+    owner: impl AsRef<str>,
+    table_id: Option<&str>,
+) -> Result<(), String> {
+    if let Some(table_id) = table_id {
+        if !state.loot_tables.contains_key(table_id) {
+            return Err(format!(
+                "{} loot table '{}' doesn't exist.",
+                owner.as_ref(),
+                table_id
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn check_count(label: impl AsRef<str>, count: usize, max: usize) -> Result<(), String> {
+    if count > max {
+        return Err(format!("{} exceeds limit of {}.", label.as_ref(), max));
+    }
+    Ok(())
+}
+
+fn check_string_len(label: impl AsRef<str>, value: &str, max: usize) -> Result<(), String> {
+    if value.trim().is_empty() {
+        return Err(format!("{} cannot be empty.", label.as_ref()));
+    }
+    if value.len() > max {
+        return Err(format!("{} exceeds {} characters.", label.as_ref(), max));
+    }
+    Ok(())
+}
+
+fn check_optional_string_len(
+    label: impl AsRef<str>,
+    value: Option<&str>,
     max: usize,
 ) -> Result<(), String> {
     if let Some(value) = value {
         check_string_len(label, value, max)?;
     }
-    Ok(())
-}
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::world_builder;
+
+    #[test]
+    fn thornhold_validates() {
+        let state = world_builder::build_thornhold();
+        assert!(validate_module_state(&state).is_ok());
+    }
+
+    #[test]
+    fn invalid_start_location_rejected() {
+        let mut state = world_builder::build_thornhold();
+        state.player.location = "nonexistent".into();
+        let result = validate_module_state(&state);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("not found"));
+    }
+
+    #[test]
+    fn invalid_exit_rejected() {
+        let mut state = world_builder::build_thornhold();
+        if let Some(loc) = state.locations.get_mut("courtyard") {
+            loc.exits
+                .insert(crate::models::Direction::Up, "does_not_exist".into());
+        }
+        let result = validate_module_state(&state);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("doesn't exist"));
+    }
+
+    #[test]
+    fn load_module_from_file() {
+        let state = world_builder::build_thornhold();
+        let json = serde_json::to_string_pretty(&state).unwrap();
+        let tmp = std::env::temp_dir().join("test_module.json");
+        std::fs::write(&tmp, &json).unwrap();
+
+        let loaded = load_module(&tmp).unwrap();
+        assert_eq!(loaded.locations.len(), state.locations.len());
+        assert!(loaded.initialized);
+
+        std::fs::remove_file(&tmp).ok();
+    }
+
+    #[test]
+    fn rejects_oversized_module_json() {
+        let oversized = "x".repeat(MAX_MODULE_FILE_BYTES + 1);
+        let result = parse_module_json(&oversized);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("size limit"));
+    }
+
+    #[test]
+    fn rejects_module_with_too_many_locations() {
+        let mut state = world_builder::build_thornhold();
+        for index in 0..(MAX_LOCATIONS + 1) {
+            let id = format!("extra_room_{index}");
+            state.locations.insert(
+                id.clone(),
+                crate::models::Location {
+                    id: id.clone(),
+                    name: format!("Room {index}"),
+                    description: "A safe test room.".into(),
+                    items: vec![],
+                    npcs: vec![],
+                    exits: std::collections::HashMap::new(),
+                    locked_exits: std::collections::HashMap::new(),
+                    visited: false,
+                    discovered_secrets: vec![],
+                    ambient_mood: crate::models::Mood::Peaceful,
+                    examine_details: None,
+                    revisit_description: None,
+                    ..Default::default()
+                },
+            );
+        }
+
+        let result = validate_module_state(&state);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("locations exceeds limit"));
+    }
+
+    #[test]
+    fn rejects_module_with_overlong_description() {
+        let mut state = world_builder::build_thornhold();
+        state.locations.get_mut("courtyard").unwrap().description =
+            "x".repeat(MAX_LONG_TEXT_LEN + 1);
+
+        let result = validate_module_state(&state);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("description exceeds"));
+    }
+
+    #[test]
+    fn rejects_region_with_unknown_final_item() {
+        let mut state = world_builder::build_thornhold();
+        state.regions[0].final_items[0] = "missing_chest".into();
+
+        let result = validate_module_state(&state);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("loot item 'missing_chest'"));
+    }
+
+    #[test]
+    fn rejects_item_in_unknown_set() {
+        let mut state = world_builder::build_thornhold();
+        state.items.get_mut("iron_shield").unwrap().set_id = Some("missing_set".into());
+
+        let result = validate_module_state(&state);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("set 'missing_set'"));
+    }
+
+    #[test]
+    fn rejects_item_equipped_in_wrong_slot() {
+        let mut state = world_builder::build_thornhold();
+        state.player.equipped_ring = Some("iron_shield".into());
+
+        let result = validate_module_state(&state);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("doesn't fit there"));
+    }
+
+    #[test]
+    fn rejects_durability_above_max() {
+        let mut state = world_builder::build_thornhold();
+        state.items.get_mut("short_sword").unwrap().durability =
+            Some(crate::models::Durability { current: 50, max: 40 });
+
+        let result = validate_module_state(&state);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("exceeds its maximum"));
+    }
+
+    #[test]
+    fn rejects_repair_recipe_output_outside_inputs() {
+        let mut state = world_builder::build_thornhold();
+        let recipe = state.recipes.iter_mut().find(|r| r.repairs).unwrap();
+        recipe.output = "leather_armor".into();
+
+        let result = validate_module_state(&state);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("must be one of its inputs"));
+    }
+
+    #[test]
+    fn rejects_item_effect_with_unknown_destination() {
+        let mut state = world_builder::build_thornhold();
+        state.items.get_mut("sunstone").unwrap().effects.push(crate::models::ItemEffect {
+            action: EventAction::Teleport("nowhere".into()),
+            target: crate::models::EffectTarget::Player,
+        });
+
+        let result = validate_module_state(&state);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("location 'nowhere'"));
+    }
+
+    #[test]
+    fn rejects_event_starting_unknown_quest() {
+        let mut state = world_builder::build_thornhold();
+        state.events[0].action = EventAction::StartQuest("missing_quest".into());
+
+        let result = validate_module_state(&state);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("quest 'missing_quest'"));
+    }
+
+    #[test]
+    fn rejects_condition_naming_unknown_npc() {
+        let mut state = world_builder::build_thornhold();
+        state.events[0].condition = Some(Condition::All(vec![
+            Condition::HealthBelow(30),
+            Condition::Not(Box::new(Condition::NpcState(
+                "phantom".into(),
+                crate::models::DialogueState::Dead,
+            ))),
+        ]));
+
+        let result = validate_module_state(&state);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("npc 'phantom'"));
+    }
+
+    #[test]
+    fn rejects_empty_condition_turn_range() {
+        let mut state = world_builder::build_thornhold();
+        state.events[0].condition = Some(Condition::TurnBetween(10, 5));
+
+        let result = validate_module_state(&state);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("is empty"));
+    }
+
+    #[test]
+    fn rejects_unparsable_condition() {
+        let state = world_builder::build_thornhold();
+        let mut json = serde_json::to_value(&state).unwrap();
+        json["events"][0]["condition"] = serde_json::json!({ "hpAbove": 5 });
+
+        let result = parse_module_json(&json.to_string());
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("Invalid module JSON"));
+    }
+
+    #[test]
+    fn rejects_sequence_step_moving_unknown_npc() {
+        let mut state = world_builder::build_thornhold();
+        state.events[0].action = EventAction::Sequence(vec![
+            EventAction::Message("The torches gutter.".into()),
+            EventAction::MoveNpc("phantom".into(), "chapel".into()),
+        ]);
+
+        let result = validate_module_state(&state);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("npc 'phantom'"));
+    }
+
+    #[test]
+    fn rejects_schedule_of_unknown_event() {
+        let mut state = world_builder::build_thornhold();
+        state.events[0].action = EventAction::Schedule("ceiling_collapse".into(), 5);
+
+        let result = validate_module_state(&state);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("event 'ceiling_collapse'"));
+    }
+
+    #[test]
+    fn rejects_zero_turn_repeating_timer() {
+        let mut state = world_builder::build_thornhold();
+        state.events[1].id = Some("tremor".into());
+        state.events[0].action = EventAction::ScheduleRepeating("tremor".into(), 0);
+
+        let result = validate_module_state(&state);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("between 1 and"));
+    }
+
+    #[test]
+    fn rejects_duplicate_event_ids() {
+        let mut state = world_builder::build_thornhold();
+        state.events[0].id = Some("tremor".into());
+        state.events[1].id = Some("tremor".into());
+
+        let result = validate_module_state(&state);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("Duplicate event id"));
+    }
+
+    #[test]
+    fn rejects_script_with_syntax_error() {
+        let mut state = world_builder::build_thornhold();
+        state
+            .scripts
+            .insert("broken".into(), "say(\"hi\")\nif flag(\"x\") say(\"y\")".into());
+
+        let result = validate_module_state(&state);
+        assert!(result.is_err());
+        let error = result.unwrap_err();
+        assert!(error.contains("Script 'broken'"));
+        assert!(error.contains("line 2"));
+    }
+
+    #[test]
+    fn rejects_custom_verb_with_unknown_script() {
+        let mut state = world_builder::build_thornhold();
+        state.custom_verbs[0].script = "missing_script".into();
+
+        let result = validate_module_state(&state);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("script 'missing_script'"));
+    }
+
+    #[test]
+    fn rejects_custom_verb_shadowed_by_a_built_in() {
+        let mut state = world_builder::build_thornhold();
+        state.custom_verbs[0].aliases.push("pull".into());
+
+        let result = validate_module_state(&state);
+        assert!(result
+            .unwrap_err()
+            .contains("Custom verb 'pull' is already a built-in command."));
+    }
+
+    #[test]
+    fn rejects_quest_outcome_with_unknown_follow_up() {
+        let mut state = world_builder::build_thornhold();
+        state
+            .quests
+            .get_mut("rats_request")
+            .unwrap()
+            .outcomes
+            .push(crate::models::QuestOutcome {
+                id: "fed".into(),
+                description: "Gristle is fed.".into(),
+                condition: None,
+                reward: vec![],
+                follow_up: Some("missing_quest".into()),
+            });
+
+        let result = validate_module_state(&state);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("follow-up quest 'missing_quest'"));
+    }
+
+    #[test]
+    fn rejects_quest_stage_with_only_optional_objectives() {
+        let mut state = world_builder::build_thornhold();
+        state.quests.get_mut("rats_request").unwrap().stages = vec![crate::models::QuestStage {
+            id: "search".into(),
+            name: "Search the cellar".into(),
+            journal: String::new(),
+            objectives: vec![crate::models::StageObjective {
+                objective: crate::models::QuestObjective::ReachLocation("wine_cellar".into()),
+                optional: true,
+                label: None,
+            }],
+        }];
+
+        let result = validate_module_state(&state);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("at least one required objective"));
+    }
+
+    #[test]
+    fn staged_quests_need_no_single_objective() {
+        let mut state = world_builder::build_thornhold();
+        let quest = state.quests.get_mut("rats_request").unwrap();
+        quest.objective = None;
+        let result = validate_module_state(&state);
+        assert!(result.unwrap_err().contains("needs an objective or stages"));
+
+        let quest = state.quests.get_mut("rats_request").unwrap();
+        quest.stages = vec![crate::models::QuestStage {
+            id: "search".into(),
+            name: "Search the cellar".into(),
+            journal: String::new(),
+            objectives: vec![crate::models::StageObjective {
+                objective: crate::models::QuestObjective::ReachLocation("wine_cellar".into()),
+                optional: false,
+                label: None,
+            }],
+        }];
+        assert!(validate_module_state(&state).is_ok());
+
+        // A leftover objective on a staged quest is never checked or used
+        let quest = state.quests.get_mut("rats_request").unwrap();
+        quest.objective = Some(crate::models::QuestObjective::FetchItem("moon_cheese".into()));
+        assert!(validate_module_state(&state).is_ok());
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::engine::world_builder;
+    #[test]
+    fn rejects_duplicate_quest_outcomes() {
+        let mut state = world_builder::build_thornhold();
+        let outcome = crate::models::QuestOutcome {
+            id: "shared".into(),
+            description: "Gristle shares the cheese.".into(),
+            condition: None,
+            reward: vec![],
+            follow_up: None,
+        };
+        let quest = state.quests.get_mut("rats_request").unwrap();
+        quest.outcomes = vec![outcome.clone(), outcome];
+
+        let result = validate_module_state(&state);
+        assert!(result.unwrap_err().contains("duplicate outcome 'shared'"));
+    }
 
     #[test]
-    fn thornhold_validates() {
-        let state = world_builder::build_thornhold();
-        assert!(validate_module_state(&state).is_ok());
+    fn rejects_quest_stage_with_unknown_objective_item() {
+        let mut state = world_builder::build_thornhold();
+        state.quests.get_mut("rats_request").unwrap().stages = vec![crate::models::QuestStage {
+            id: "search".into(),
+            name: "Search the cellar".into(),
+            journal: String::new(),
+            objectives: vec![crate::models::StageObjective {
+                objective: crate::models::QuestObjective::FetchItem("moon_cheese".into()),
+                optional: false,
+                label: None,
+            }],
+        }];
+
+        let result = validate_module_state(&state);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("'moon_cheese'"));
     }
 
     #[test]
-    fn invalid_start_location_rejected() {
+    fn rejects_invalid_new_objectives() {
         let mut state = world_builder::build_thornhold();
-        state.player.location = "nonexistent".into();
+        state.quests.get_mut("rats_request").unwrap().objective =
+            Some(crate::models::QuestObjective::CraftItem("moon_pie".into()));
+        let result = validate_module_state(&state);
+        assert!(result.unwrap_err().contains("recipe 'moon_pie'"));
+
+        state.quests.get_mut("rats_request").unwrap().objective =
+            Some(crate::models::QuestObjective::CollectCount("bone_fragment".into(), 0));
+        let result = validate_module_state(&state);
+        assert!(result.unwrap_err().contains("count must be between 1"));
+
+        state.quests.get_mut("rats_request").unwrap().objective =
+            Some(crate::models::QuestObjective::UseItemAt("torn_tapestry".into(), "chapel".into()));
+        let result = validate_module_state(&state);
+        assert!(result.unwrap_err().contains("can't be used"));
+    }
+
+    #[test]
+    fn rejects_quest_requiring_unknown_quest() {
+        let mut state = world_builder::build_thornhold();
+        state
+            .quests
+            .get_mut("rats_request")
+            .unwrap()
+            .required_quests
+            .push("missing_quest".into());
+
         let result = validate_module_state(&state);
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("not found"));
+        assert!(result.unwrap_err().contains("quest 'missing_quest'"));
     }
 
     #[test]
-    fn invalid_exit_rejected() {
+    fn rejects_fail_condition_on_unplaced_item() {
         let mut state = world_builder::build_thornhold();
-        if let Some(loc) = state.locations.get_mut("courtyard") {
-            loc.exits
-                .insert(crate::models::Direction::Up, "does_not_exist".into());
-        }
+        state
+            .quests
+            .get_mut("rats_request")
+            .unwrap()
+            .fail_conditions
+            .push(crate::models::QuestFailure::ItemDestroyed("dungeon_heart_shard".into()));
+
         let result = validate_module_state(&state);
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("doesn't exist"));
+        assert!(result.unwrap_err().contains("isn't placed in the world"));
     }
 
     #[test]
-    fn load_module_from_file() {
-        let state = world_builder::build_thornhold();
-        let json = serde_json::to_string_pretty(&state).unwrap();
-        let tmp = std::env::temp_dir().join("test_module.json");
-        std::fs::write(&tmp, &json).unwrap();
+    fn rejects_dialogue_choice_to_unknown_node() {
+        let mut state = world_builder::build_thornhold();
+        state.dialogues.insert(
+            "gristle_rat".into(),
+            crate::models::DialogueTree {
+                start: "hello".into(),
+                nodes: std::collections::HashMap::from([(
+                    "hello".to_string(),
+                    crate::models::DialogueNode {
+                        text: "Squeak?".into(),
+                        effects: vec![],
+                        choices: vec![crate::models::DialogueChoice {
+                            text: "Cheese?".into(),
+                            condition: None,
+                            skill_check: None,
+                            effects: vec![],
+                            next: Some("cheese".into()),
+                            fail_next: None,
+                        }],
+                    },
+                )]),
+            },
+        );
 
-        let loaded = load_module(&tmp).unwrap();
-        assert_eq!(loaded.locations.len(), state.locations.len());
-        assert!(loaded.initialized);
+        let result = validate_module_state(&state);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("node 'cheese' doesn't exist"));
+    }
 
-        std::fs::remove_file(&tmp).ok();
+    #[test]
+    fn rejects_topic_without_reply() {
+        let mut state = world_builder::build_thornhold();
+        state.topics.insert(
+            "gristle_rat".into(),
+            crate::models::TopicMap {
+                topics: vec![crate::models::Topic {
+                    name: "cellar".into(),
+                    aliases: vec![],
+                    ask: None,
+                    tell: None,
+                    condition: None,
+                    journal: None,
+                    sets_flag: None,
+                }],
+                fallback: None,
+            },
+        );
+
+        let result = validate_module_state(&state);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("needs an ask or tell reply"));
     }
 
     #[test]
-    fn rejects_oversized_module_json() {
-        let oversized = "x".repeat(MAX_MODULE_FILE_BYTES + 1);
-        let result = parse_module_json(&oversized);
+    fn rejects_unknown_faction_relation() {
+        let mut state = world_builder::build_thornhold();
+        state
+            .factions
+            .get_mut("wardens_host")
+            .unwrap()
+            .allies
+            .push("crypt_cult".into());
+
+        let result = validate_module_state(&state);
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("size limit"));
+        assert!(result.unwrap_err().contains("relation 'crypt_cult' doesn't exist"));
     }
 
     #[test]
-    fn rejects_module_with_too_many_locations() {
+    fn rejects_npc_in_unknown_faction() {
         let mut state = world_builder::build_thornhold();
-        for index in 0..(MAX_LOCATIONS + 1) {
-            let id = format!("extra_room_{index}");
-            state.locations.insert(
-                id.clone(),
-                crate::models::Location {
-                    id: id.clone(),
-                    name: format!("Room {index}"),
-                    description: "A safe test room.".into(),
-                    items: vec![],
-                    npcs: vec![],
-                    exits: std::collections::HashMap::new(),
-                    locked_exits: std::collections::HashMap::new(),
-                    visited: false,
-                    discovered_secrets: vec![],
-                    ambient_mood: crate::models::Mood::Peaceful,
-                    examine_details: None,
-                    revisit_description: None,
+        state.npcs.get_mut("gristle_rat").unwrap().faction = Some("rat_king".into());
+
+        let result = validate_module_state(&state);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("faction 'rat_king' doesn't exist"));
+    }
+
+    #[test]
+    fn rejects_patrol_between_unconnected_rooms() {
+        let mut state = world_builder::build_thornhold();
+        state.npcs.get_mut("merchant_ghost").unwrap().movement = Some(NpcMovement::Patrol {
+            route: vec!["courtyard".into(), "chapel".into()],
+            every: 1,
+        });
+
+        let result = validate_module_state(&state);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("'courtyard' -> 'chapel' has no exit"));
+    }
+
+    #[test]
+    fn rejects_unknown_companion() {
+        let mut state = world_builder::build_thornhold();
+        state.companions.push(crate::models::Companion {
+            npc_id: "nobody".into(),
+            waiting: false,
+        });
+
+        let result = validate_module_state(&state);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("Companion 'nobody' doesn't exist"));
+    }
+
+    #[test]
+    fn rejects_clock_costs_over_a_day() {
+        let mut state = world_builder::build_thornhold();
+        state.clock.minutes_per_rest = 2 * MINUTES_PER_DAY;
+
+        let result = validate_module_state(&state);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("minutesPerRest"));
+    }
+
+    #[test]
+    fn rejects_encounter_table_in_unknown_location() {
+        let mut state = world_builder::build_thornhold();
+        state.encounters[0].locations.push("moon_base".into());
+
+        let result = validate_module_state(&state);
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .contains("Encounter table 'crypt_dead' location 'moon_base' doesn't exist"));
+    }
+
+    #[test]
+    fn rejects_runaway_enemy_stats_and_scaling() {
+        let mut state = world_builder::build_thornhold();
+        state.encounters[0].enemies[0].health = i32::MAX;
+        let result = validate_module_state(&state);
+        assert!(result
+            .unwrap_err()
+            .contains("Encounter table 'crypt_dead' enemy 'bones' needs health from 1"));
+
+        let mut state = world_builder::build_thornhold();
+        state.regions[0].scaling.health_per_level = i32::MAX;
+        let result = validate_module_state(&state);
+        assert!(result.unwrap_err().contains("scaling steps must be between -100 and 100"));
+    }
+
+    #[test]
+    fn rejects_npc_an_encounter_table_would_replace() {
+        let mut state = world_builder::build_thornhold();
+        let mut npc = state.npcs["skeletal_guard"].clone();
+        npc.id = "crypt_dead_bones_1".into();
+        state.npcs.insert(npc.id.clone(), npc);
+
+        let result = validate_module_state(&state);
+        assert!(result.unwrap_err().contains(
+            "Encounter table 'crypt_dead' would spawn NPC 'crypt_dead_bones_1', which already exists"
+        ));
+    }
+
+    #[test]
+    fn rejects_hidden_exit_to_unknown_location() {
+        let mut state = world_builder::build_thornhold();
+        let great_hall = state.locations.get_mut("great_hall").unwrap();
+        great_hall.secrets[0].kind =
+            HiddenKind::Exit(crate::models::Direction::Down, "moon_base".into());
+
+        let result = validate_module_state(&state);
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .contains("Location 'great_hall' secret 'vault_trapdoor' leads to 'moon_base'"));
+    }
+
+    #[test]
+    fn rejects_door_with_unknown_key() {
+        let mut state = world_builder::build_thornhold();
+        state.doors.get_mut("library_door").unwrap().key_id = Some("skeleton_key".into());
+
+        let result = validate_module_state(&state);
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .contains("Door 'library_door' key 'skeleton_key' doesn't exist"));
+    }
+
+    #[test]
+    fn rejects_runaway_lockpick_bonus() {
+        let mut state = world_builder::build_thornhold();
+        state.items.get_mut("short_sword").unwrap().lockpick = Some(u32::MAX);
+        let result = validate_module_state(&state);
+        assert!(result
+            .unwrap_err()
+            .contains("Item 'short_sword' lockpick bonus must be between 0 and 100"));
+    }
+
+    #[test]
+    fn rejects_doors_hung_on_one_side_or_barred_from_elsewhere() {
+        let mut state = world_builder::build_thornhold();
+        state.locations.get_mut("library").unwrap().doors.clear();
+        let result = validate_module_state(&state);
+        assert!(result.unwrap_err().contains(
+            "Location 'great_hall' has door 'library_door' on East, but 'library' doesn't have it"
+        ));
+
+        let mut state = world_builder::build_thornhold();
+        state.doors.get_mut("library_door").unwrap().opens_from = Some("chapel".into());
+        let result = validate_module_state(&state);
+        assert!(result.unwrap_err().contains("Door 'library_door' opens from 'chapel', which is"));
+
+        state.doors.get_mut("library_door").unwrap().opens_from = Some("library".into());
+        assert!(validate_module_state(&state).is_ok());
+    }
+
+    #[test]
+    fn rejects_unreachable_puzzle_solution() {
+        let mut state = world_builder::build_thornhold();
+        state.fixtures.insert(
+            "gate_lever".into(),
+            Fixture {
+                id: "gate_lever".into(),
+                name: "rusted lever".into(),
+                description: String::new(),
+                kind: FixtureKind::Lever {
+                    positions: vec!["up".into(), "down".into()],
                 },
-            );
-        }
+                state: String::new(),
+            },
+        );
+        let puzzle = state.puzzles.get_mut("bust_riddle").unwrap();
+        puzzle.solution.insert("gate_lever".into(), "down".into());
+
+        // Nowhere to pull it
+        let result = validate_module_state(&state);
+        assert!(result.unwrap_err().contains("fixture 'gate_lever' is in no room"));
+
+        let library = state.locations.get_mut("library").unwrap();
+        library.fixtures.push("gate_lever".into());
+        assert!(validate_module_state(&state).is_ok());
+
+        // The lever starts up, the riddle unsolved: still work to do
+        let puzzle = state.puzzles.get_mut("bust_riddle").unwrap();
+        puzzle.solution.insert("gate_lever".into(), "up".into());
+        assert!(validate_module_state(&state).is_ok());
+        let puzzle = state.puzzles.get_mut("bust_riddle").unwrap();
+        puzzle.solution.remove("stone_bust");
+        let result = validate_module_state(&state);
+        assert!(result
+            .unwrap_err()
+            .contains("Puzzle 'bust_riddle' is already solved by its fixtures' starting states"));
+
+        let puzzle = state.puzzles.get_mut("bust_riddle").unwrap();
+        puzzle.solution.insert("gate_lever".into(), "sideways".into());
+        let result = validate_module_state(&state);
+        assert!(result
+            .unwrap_err()
+            .contains("fixture 'gate_lever' can never be 'sideways'"));
+    }
+
+    #[test]
+    fn rejects_riddles_posed_only_by_hostiles() {
+        let mut state = world_builder::build_thornhold();
+        state.fixtures.insert(
+            "cleric_riddle".into(),
+            Fixture {
+                id: "cleric_riddle".into(),
+                name: "cleric's riddle".into(),
+                description: String::new(),
+                kind: FixtureKind::Riddle {
+                    question: "What walks these halls but never tires?".into(),
+                    answers: vec!["ghost".into()],
+                },
+                state: String::new(),
+            },
+        );
+        let puzzle = state.puzzles.get_mut("bust_riddle").unwrap();
+        puzzle.solution.insert("cleric_riddle".into(), RIDDLE_SOLVED.into());
+        state.npcs.get_mut("ghost_cleric").unwrap().riddle = Some("cleric_riddle".into());
+        assert!(validate_module_state(&state).is_ok());
+
+        state.npcs.get_mut("ghost_cleric").unwrap().hostile = true;
+        let result = validate_module_state(&state);
+        assert!(result
+            .unwrap_err()
+            .contains("fixture 'cleric_riddle' is in no room and posed by no peaceful NPC"));
+    }
+
+    #[test]
+    fn rejects_unbounded_special_effects() {
+        let mut state = world_builder::build_thornhold();
+        state.affixes[0].effect = Some(SpecialEffect::Lifesteal { percent: i32::MAX });
+        let result = validate_module_state(&state);
+        assert!(result.unwrap_err().contains("lifesteal percent must be between 1 and 100"));
+
+        state.affixes[0].effect = Some(SpecialEffect::Lifesteal { percent: -50 });
+        assert!(validate_module_state(&state).is_err());
+
+        state.affixes[0].effect = None;
+        state.items.get_mut("short_sword").unwrap().special_effects =
+            vec![SpecialEffect::Poison { damage_per_turn: 5, turns: 1_000 }];
+        let result = validate_module_state(&state);
+        assert!(result
+            .unwrap_err()
+            .contains("Item 'short_sword' poison turns must be between 1 and 20"));
+    }
+
+    #[test]
+    fn rejects_duplicate_affix_ids() {
+        let mut state = world_builder::build_thornhold();
+        let first = state.affixes[0].clone();
+        state.affixes.push(first);
 
         let result = validate_module_state(&state);
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("locations exceeds limit"));
+        assert!(result.unwrap_err().contains("used more than once"));
     }
 
     #[test]
-    fn rejects_module_with_overlong_description() {
+    fn rejects_loot_table_with_unknown_item() {
         let mut state = world_builder::build_thornhold();
-        state.locations.get_mut("courtyard").unwrap().description =
-            "x".repeat(MAX_LONG_TEXT_LEN + 1);
+        state
+            .loot_tables
+            .get_mut("dungeon_lurker")
+            .unwrap()
+            .entries[0]
+            .item_id = "missing_potion".into();
 
         let result = validate_module_state(&state);
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("description exceeds"));
+        assert!(result.unwrap_err().contains("item 'missing_potion' doesn't exist"));
+    }
+
+    #[test]
+    fn rejects_unknown_loot_table_reference() {
+        let mut state = world_builder::build_thornhold();
+        state.npcs.get_mut("skeletal_guard").unwrap().loot_table = Some("nope".into());
+
+        let result = validate_module_state(&state);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("loot table 'nope' doesn't exist"));
+    }
+
+    #[test]
+    fn rejects_region_with_inverted_depth() {
+        let mut state = world_builder::build_thornhold();
+        state.regions[0].min_depth = 6;
+        state.regions[0].max_depth = 3;
+
+        let result = validate_module_state(&state);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("minDepth"));
+    }
+
+    #[test]
+    fn rejects_regions_that_would_overwrite_the_world() {
+        let mut state = world_builder::build_thornhold();
+        let region = state.regions[0].clone();
+        state.regions.push(region);
+        let result = validate_module_state(&state);
+        assert!(result.unwrap_err().contains("defined more than once"));
+
+        let mut state = world_builder::build_thornhold();
+        let mut vault = state.locations["hidden_vault"].clone();
+        vault.id = "dungeon_d1_r1".into();
+        vault.items.clear();
+        state.locations.insert(vault.id.clone(), vault);
+        let result = validate_module_state(&state);
+        assert!(result.unwrap_err().contains("would generate room 'dungeon_d1_r1'"));
+
+        let mut state = world_builder::build_thornhold();
+        let mut guard = state.npcs["skeletal_guard"].clone();
+        guard.id = "dungeon_guard_0".into();
+        state.npcs.insert(guard.id.clone(), guard);
+        let result = validate_module_state(&state);
+        assert!(result.unwrap_err().contains("would generate NPC 'dungeon_guard_0'"));
+
+        let mut state = world_builder::build_thornhold();
+        state.regions[0].entry_location = "great_hall".into();
+        state.regions[0].entry_direction = crate::models::Direction::East;
+        let result = validate_module_state(&state);
+        assert!(result.unwrap_err().contains("already has an exit there"));
+    }
+
+    #[test]
+    fn rejects_runaway_relationship_changes() {
+        let mut state = world_builder::build_thornhold();
+        state.events[0].action = EventAction::ChangeRelationship("merchant_ghost".into(), i32::MAX);
+        let result = validate_module_state(&state);
+        assert!(result
+            .unwrap_err()
+            .contains("relationship change for 'merchant_ghost' must be between"));
+
+        state.events[0].action = EventAction::ChangeRelationship("merchant_ghost".into(), -100);
+        assert!(validate_module_state(&state).is_ok());
+    }
+
+    #[test]
+    fn rejects_negative_or_runaway_heals() {
+        let mut state = world_builder::build_thornhold();
+        state.events[0].action = EventAction::Heal(-20);
+        let result = validate_module_state(&state);
+        assert!(result.unwrap_err().contains("heal must be between 1 and 1000"));
+
+        state.events[0].action = EventAction::Heal(i32::MAX);
+        assert!(validate_module_state(&state).is_err());
+
+        state.events[0].action = EventAction::Heal(15);
+        assert!(validate_module_state(&state).is_ok());
+    }
+
+    #[test]
+    fn rejects_runaway_reputation() {
+        let mut state = world_builder::build_thornhold();
+        state.events[0].action = EventAction::ChangeReputation("wardens_host".into(), i32::MIN);
+        let result = validate_module_state(&state);
+        assert!(result
+            .unwrap_err()
+            .contains("reputation change for 'wardens_host' must be between"));
+
+        state.events[0].action = EventAction::ChangeReputation("wardens_host".into(), 100);
+        assert!(validate_module_state(&state).is_ok());
+
+        state.factions.get_mut("wardens_host").unwrap().reputation = i32::MIN;
+        let result = validate_module_state(&state);
+        assert!(result
+            .unwrap_err()
+            .contains("Faction 'wardens_host' reputation must be between"));
     }
 }
@@ -0,0 +1,289 @@
+use rand::Rng;
+
+use crate::engine::{companions, doors, quest, random, templates};
+use crate::models::*;
+
+/// Moves every NPC with a movement behavior one step, reporting arrivals
+/// and departures the player can see. NPCs that are dead, fighting or
+/// talking with the player, escorted or in the party stay put.
+pub fn move_npcs(state: &mut WorldState) -> Vec<OutputLine> {
+    let mut messages = Vec::new();
+    let busy = match &state.game_mode {
+        GameMode::InCombat(npc_id) | GameMode::InDialogue(npc_id) => Some(npc_id.clone()),
+        _ => None,
+    };
+    let escorted = quest::escorted_npcs(state);
+
+    let mut npc_ids: Vec<String> = state
+        .npcs
+        .values()
+        .filter(|npc| npc.movement.is_some() && npc.dialogue_state != DialogueState::Dead)
+        .map(|npc| npc.id.clone())
+        .filter(|id| busy.as_ref() != Some(id) && !escorted.contains(id))
+        .filter(|id| !companions::is_companion(id, state))
+        .collect();
+    npc_ids.sort();
+
+    for npc_id in npc_ids {
+        let Some(from) = npc_location(&npc_id, state) else {
+            continue;
+        };
+        let Some(to) = next_location(&npc_id, &from, state) else {
+            continue;
+        };
+        if to == from || !state.locations.contains_key(&to) {
+            continue;
+        }
+        move_npc(&npc_id, &from, &to, state, &mut messages);
+    }
+    messages
+}
+
+/// The room an NPC currently stands in.
+fn npc_location(npc_id: &str, state: &WorldState) -> Option<String> {
+    let mut location_ids: Vec<&String> = state.locations.keys().collect();
+    location_ids.sort();
+    location_ids
+        .into_iter()
+        .find(|id| state.locations[*id].npcs.iter().any(|n| n == npc_id))
+        .cloned()
+}
+
+fn next_location(npc_id: &str, from: &str, state: &mut WorldState) -> Option<String> {
+    let turn = state.player.turns_elapsed;
+    match state.npcs.get(npc_id)?.movement.clone()? {
+        NpcMovement::Patrol { route, every } => {
+            if !turn.is_multiple_of(every.max(1)) {
+                return None;
+            }
+            let next = match route.iter().position(|id| id == from) {
+                Some(pos) => route.get((pos + 1) % route.len()),
+                None => route.first(),
+            };
+            next.cloned()
+        }
+        NpcMovement::Schedule { entries, cycle } => {
+            let phase = state.clock.phase();
+            if let Some(entry) = entries.iter().find(|e| e.phase == Some(phase)) {
+                return Some(entry.location.clone());
+            }
+            let timed: Vec<&ScheduleEntry> = entries.iter().filter(|e| e.phase.is_none()).collect();
+            let now = cycle.map_or(turn, |c| turn % c.max(1));
+            let current = timed.iter().filter(|e| e.from <= now).max_by_key(|e| e.from);
+            // Before the first entry of a cycle, the last one still holds
+            let current = match (current, cycle) {
+                (Some(entry), _) => Some(entry),
+                (None, Some(_)) => timed.iter().max_by_key(|e| e.from),
+                (None, None) => None,
+            };
+            current.map(|e| e.location.clone())
+        }
+        NpcMovement::Wander { rooms, chance } => {
+            let mut rng = random::world_rng(state);
+            if !random::roll_percent(&mut rng, chance) {
+                return None;
+            }
+            let loc = state.locations.get(from)?;
+            let mut exits: Vec<(&Direction, &String)> = loc
+                .exits
+                .iter()
+                .filter(|(dir, dest)| !doors::is_barred(loc, dir, state) && rooms.contains(dest))
+                .collect();
+            exits.sort_by_key(|(dir, _)| dir.to_string());
+            if exits.is_empty() {
+                return None;
+            }
+            Some(exits[rng.gen_range(0..exits.len())].1.clone())
+        }
+    }
+}
+
+fn move_npc(
+    npc_id: &str,
+    from: &str,
+    to: &str,
+    state: &mut WorldState,
+    messages: &mut Vec<OutputLine>,
+) {
+    let leaving_by = exit_towards(from, to, state);
+    let arriving_from = exit_towards(to, from, state);
+    if let Some(loc) = state.locations.get_mut(from) {
+        loc.npcs.retain(|id| id != npc_id);
+    }
+    if let Some(loc) = state.locations.get_mut(to) {
+        loc.npcs.push(npc_id.to_string());
+    }
+
+    let name = state
+        .npcs
+        .get(npc_id)
+        .map(|n| n.name.clone())
+        .unwrap_or_default();
+    let text = if from == state.player.location {
+        templates::describe_npc_leaves(&name, leaving_by.as_ref())
+    } else if to == state.player.location {
+        templates::describe_npc_arrives(&name, arriving_from.as_ref())
+    } else {
+        return;
+    };
+    messages.push(OutputLine {
+        text,
+        line_type: LineType::Narration,
+    });
+}
+
+/// The exit of `from` that leads to `to`, if the rooms are adjacent.
+fn exit_towards(from: &str, to: &str, state: &WorldState) -> Option<Direction> {
+    state
+        .locations
+        .get(from)?
+        .exits
+        .iter()
+        .find(|(_, dest)| *dest == to)
+        .map(|(dir, _)| *dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::world_builder;
+
+    fn set_movement(state: &mut WorldState, npc_id: &str, movement: NpcMovement) {
+        state.npcs.get_mut(npc_id).unwrap().movement = Some(movement);
+    }
+
+    #[test]
+    fn patrol_walks_the_route_and_reports_to_the_player() {
+        let mut state = world_builder::build_thornhold();
+        set_movement(
+            &mut state,
+            "merchant_ghost",
+            NpcMovement::Patrol {
+                route: vec!["courtyard".into(), "great_hall".into()],
+                every: 2,
+            },
+        );
+
+        state.player.turns_elapsed = 1;
+        assert!(move_npcs(&mut state).is_empty());
+        assert_eq!(npc_location("merchant_ghost", &state).unwrap(), "courtyard");
+
+        state.player.turns_elapsed = 2;
+        let msgs = move_npcs(&mut state);
+        assert_eq!(msgs[0].text, "The Dead Merchant leaves to the East.");
+        assert_eq!(
+            npc_location("merchant_ghost", &state).unwrap(),
+            "great_hall"
+        );
+
+        state.player.turns_elapsed = 4;
+        let msgs = move_npcs(&mut state);
+        assert_eq!(msgs[0].text, "The Dead Merchant arrives from the East.");
+        assert_eq!(npc_location("merchant_ghost", &state).unwrap(), "courtyard");
+    }
+
+    #[test]
+    fn schedule_follows_the_cycle() {
+        let mut state = world_builder::build_thornhold();
+        set_movement(
+            &mut state,
+            "merchant_ghost",
+            NpcMovement::Schedule {
+                entries: vec![
+                    ScheduleEntry {
+                        from: 5,
+                        location: "chapel".into(),
+                        phase: None,
+                    },
+                    ScheduleEntry {
+                        from: 10,
+                        location: "courtyard".into(),
+                        phase: None,
+                    },
+                ],
+                cycle: Some(12),
+            },
+        );
+
+        state.player.turns_elapsed = 3;
+        move_npcs(&mut state);
+        assert_eq!(npc_location("merchant_ghost", &state).unwrap(), "courtyard");
+        state.player.turns_elapsed = 7;
+        assert!(move_npcs(&mut state)[0]
+            .text
+            .starts_with("The Dead Merchant leaves"));
+        assert_eq!(npc_location("merchant_ghost", &state).unwrap(), "chapel");
+        // Turn 13 is early in the next cycle, so the last slot still holds
+        state.player.turns_elapsed = 13;
+        move_npcs(&mut state);
+        assert_eq!(npc_location("merchant_ghost", &state).unwrap(), "courtyard");
+    }
+
+    #[test]
+    fn time_of_day_entries_win_while_they_last() {
+        let mut state = world_builder::build_thornhold();
+        set_movement(
+            &mut state,
+            "merchant_ghost",
+            NpcMovement::Schedule {
+                entries: vec![
+                    ScheduleEntry {
+                        from: 0,
+                        phase: None,
+                        location: "courtyard".into(),
+                    },
+                    ScheduleEntry {
+                        from: 0,
+                        phase: Some(DayPhase::Night),
+                        location: "chapel".into(),
+                    },
+                ],
+                cycle: None,
+            },
+        );
+
+        move_npcs(&mut state);
+        assert_eq!(npc_location("merchant_ghost", &state).unwrap(), "courtyard");
+        state.clock.minutes = 23 * 60;
+        move_npcs(&mut state);
+        assert_eq!(npc_location("merchant_ghost", &state).unwrap(), "chapel");
+        state.clock.minutes += 8 * 60;
+        move_npcs(&mut state);
+        assert_eq!(npc_location("merchant_ghost", &state).unwrap(), "courtyard");
+    }
+
+    #[test]
+    fn wanderers_stay_within_their_rooms() {
+        let mut state = world_builder::build_thornhold();
+        set_movement(
+            &mut state,
+            "merchant_ghost",
+            NpcMovement::Wander {
+                rooms: vec!["courtyard".into(), "barracks".into()],
+                chance: 100,
+            },
+        );
+        for turn in 0..10 {
+            state.player.turns_elapsed = turn;
+            move_npcs(&mut state);
+            let at = npc_location("merchant_ghost", &state).unwrap();
+            assert!(at == "courtyard" || at == "barracks");
+        }
+    }
+
+    #[test]
+    fn talking_partner_stays_put() {
+        let mut state = world_builder::build_thornhold();
+        set_movement(
+            &mut state,
+            "merchant_ghost",
+            NpcMovement::Patrol {
+                route: vec!["courtyard".into(), "great_hall".into()],
+                every: 1,
+            },
+        );
+        state.game_mode = GameMode::InDialogue("merchant_ghost".into());
+        assert!(move_npcs(&mut state).is_empty());
+        assert_eq!(npc_location("merchant_ghost", &state).unwrap(), "courtyard");
+    }
+}
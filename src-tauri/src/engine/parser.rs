@@ -1,21 +1,55 @@
+use crate::engine::rest::DEFAULT_SLEEP_TURNS;
 use crate::models::{Direction, GameMode};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum GameCommand {
     Look(Option<String>),
     Go(Direction),
+    /// Move quietly, slipping past hostiles that fail to notice.
+    Sneak(Direction),
+    Hide,
+    Search,
+    Disarm(String),
     Take(String),
     Drop(String),
     Use(String),
+    Open(String),
+    Close(String),
+    /// Pick the lock of a door with a lockpick.
+    PickLock(String),
+    Bash(String),
+    /// Pull a lever on to its next position.
+    Pull(String),
+    /// Push a lever back to its previous position.
+    Push(String),
+    /// Set a combination lock: fixture, then combination.
+    SetFixture(String, String),
+    /// Answer the riddle at hand.
+    Answer(String),
     Equip(String),
+    Repair(String),
     Unequip(String),
     TalkTo(String),
+    /// Ask an NPC about a topic. An empty NPC means the one you're talking to.
+    AskAbout(String, String),
+    /// Tell an NPC about a topic. An empty NPC means the one you're talking to.
+    TellAbout(String, String),
     Attack(String),
+    Recruit(String),
+    Dismiss(String),
+    /// Give an item to an NPC.
+    Give(String, String),
+    /// Tell a companion to wait (true) or to follow again (false).
+    CompanionWait(String, bool),
     Flee,
     Inventory,
+    Stats,
     Map,
     QuestLog,
     Journal,
+    Reputation,
+    Time,
+    Rest(u32),
     Craft(String, Option<String>),
     Secret(String),
     Help,
@@ -52,8 +86,18 @@ pub fn parse(input: &str, game_mode: &GameMode) -> GameCommand {
             }
             "inventory" | "inv" | "i" => return GameCommand::Inventory,
             "help" | "?" | "h" => return GameCommand::Help,
-            _ => return GameCommand::Unknown(cleaned),
+            _ => {}
         }
+        if let Some(topic) = cleaned.strip_prefix("ask about ") {
+            return GameCommand::AskAbout(String::new(), strip_articles(topic));
+        }
+        if let Some(topic) = cleaned.strip_prefix("tell about ") {
+            return GameCommand::TellAbout(String::new(), strip_articles(topic));
+        }
+        if let Some(reply) = cleaned.strip_prefix("answer ") {
+            return GameCommand::Answer(reply.to_string());
+        }
+        return GameCommand::Unknown(cleaned);
     }
 
     // In combat mode, restrict commands
@@ -64,6 +108,7 @@ pub fn parse(input: &str, game_mode: &GameMode) -> GameCommand {
             | GameCommand::Flee
             | GameCommand::Use(_)
             | GameCommand::Inventory
+            | GameCommand::Stats
             | GameCommand::Help => return result,
             _ => {
                 return GameCommand::Unknown(
@@ -99,6 +144,27 @@ fn parse_command(cleaned: &str) -> GameCommand {
             }
         }
 
+        "sneak" | "creep" => {
+            let target = strip_articles(rest);
+            if let Some(dir) = Direction::parse(&target) {
+                GameCommand::Sneak(dir)
+            } else if target.is_empty() {
+                GameCommand::Unknown("Sneak where?".to_string())
+            } else {
+                GameCommand::Unknown(format!("Unknown direction: {}", target))
+            }
+        }
+        "hide" => GameCommand::Hide,
+        "search" | "investigate" => GameCommand::Search,
+        "disarm" => {
+            let target = strip_articles(rest);
+            if target.is_empty() {
+                GameCommand::Unknown("Disarm what?".to_string())
+            } else {
+                GameCommand::Disarm(target)
+            }
+        }
+
         // Look
         "look" | "l" | "examine" | "inspect" | "x" => {
             let target = strip_articles(rest);
@@ -109,6 +175,67 @@ fn parse_command(cleaned: &str) -> GameCommand {
             }
         }
 
+        // Doors
+        "close" | "shut" => {
+            let target = strip_articles(rest);
+            if target.is_empty() {
+                GameCommand::Unknown("Close what?".to_string())
+            } else {
+                GameCommand::Close(target)
+            }
+        }
+        "pick" | "lockpick"
+            if verb == "lockpick" || strip_articles(rest).split(' ').next() == Some("lock") =>
+        {
+            // "pick lock on the door", "lockpick east"
+            let target = strip_articles(rest);
+            let target = target.strip_prefix("lock").unwrap_or(&target).trim_start();
+            let target = target
+                .strip_prefix("on ")
+                .or_else(|| target.strip_prefix("of "))
+                .unwrap_or(target);
+            if target.is_empty() {
+                GameCommand::Unknown("Pick which lock?".to_string())
+            } else {
+                GameCommand::PickLock(target.to_string())
+            }
+        }
+        "bash" | "kick" | "force" | "smash" => {
+            let target = strip_articles(rest);
+            if target.is_empty() {
+                GameCommand::Unknown("Bash what?".to_string())
+            } else {
+                GameCommand::Bash(target)
+            }
+        }
+
+        // Puzzles: "pull the lever", "set dial to 3-1-4", "answer footsteps"
+        "pull" | "turn" | "flip" | "toggle" | "push" | "press" => {
+            let target = strip_articles(rest);
+            let pushing = matches!(verb, "push" | "press");
+            if target.is_empty() {
+                let question = if pushing { "Push what?" } else { "Pull what?" };
+                GameCommand::Unknown(question.to_string())
+            } else if pushing {
+                GameCommand::Push(target)
+            } else {
+                GameCommand::Pull(target)
+            }
+        }
+        "set" | "dial" | "spin" => match rest.split_once(" to ") {
+            Some((fixture, value)) if !strip_articles(fixture).is_empty() && !value.is_empty() => {
+                GameCommand::SetFixture(strip_articles(fixture), value.trim().to_string())
+            }
+            _ => GameCommand::Unknown("Set what to what?".to_string()),
+        },
+        "answer" | "reply" => {
+            if rest.is_empty() {
+                GameCommand::Unknown("Answer what?".to_string())
+            } else {
+                GameCommand::Answer(rest.to_string())
+            }
+        }
+
         // Take
         "take" | "get" | "grab" | "pick" => {
             let target = if verb == "pick" {
@@ -143,6 +270,26 @@ fn parse_command(cleaned: &str) -> GameCommand {
             }
         }
 
+        // Open a container or door
+        "open" | "loot" | "unlock" => {
+            let target = strip_articles(rest);
+            if target.is_empty() {
+                GameCommand::Unknown("Open what?".to_string())
+            } else {
+                GameCommand::Open(target)
+            }
+        }
+
+        // Repair
+        "repair" | "mend" | "fix" => {
+            let target = strip_articles(rest);
+            if target.is_empty() {
+                GameCommand::Unknown("Repair what?".to_string())
+            } else {
+                GameCommand::Repair(target)
+            }
+        }
+
         // Equip
         "equip" | "wield" | "wear" => {
             let target = strip_articles(rest);
@@ -163,6 +310,48 @@ fn parse_command(cleaned: &str) -> GameCommand {
             }
         }
 
+        // Topics: "ask gristle about cellar", "tell priest about amulet"
+        "ask" | "tell" if rest.contains(" about ") || rest.starts_with("about ") => {
+            let (npc, topic) = match rest.strip_prefix("about ") {
+                Some(topic) => ("", topic),
+                None => rest.split_once(" about ").unwrap_or_default(),
+            };
+            let (npc, topic) = (strip_articles(npc), strip_articles(topic));
+            if topic.is_empty() {
+                GameCommand::Unknown("About what?".to_string())
+            } else if verb == "ask" {
+                GameCommand::AskAbout(npc, topic)
+            } else {
+                GameCommand::TellAbout(npc, topic)
+            }
+        }
+
+        // Companions: "recruit gristle", "tell gristle to wait"
+        "recruit" | "dismiss" => {
+            let target = strip_articles(rest);
+            if target.is_empty() {
+                GameCommand::Unknown("Whom?".to_string())
+            } else if verb == "recruit" {
+                GameCommand::Recruit(target)
+            } else {
+                GameCommand::Dismiss(target)
+            }
+        }
+        "tell" | "order"
+            if rest.ends_with(" to wait")
+                || rest.ends_with(" to stay")
+                || rest.ends_with(" to follow") =>
+        {
+            let (npc, order) = rest.rsplit_once(" to ").unwrap_or_default();
+            GameCommand::CompanionWait(strip_articles(npc), order != "follow")
+        }
+        "give" | "hand" => match rest.split_once(" to ") {
+            Some((item, npc)) if !item.is_empty() => {
+                GameCommand::Give(strip_articles(item), strip_articles(npc))
+            }
+            _ => GameCommand::Unknown("Give what to whom?".to_string()),
+        },
+
         // Talk
         "talk" | "speak" | "ask" | "chat" => {
             let target = strip_articles(rest);
@@ -186,11 +375,24 @@ fn parse_command(cleaned: &str) -> GameCommand {
         // Flee
         "flee" | "run" | "escape" => GameCommand::Flee,
 
+        // Rest: "rest", "sleep", "sleep 4 turns"
+        "rest" | "sleep" | "wait" => match rest.split_whitespace().next() {
+            None if verb == "sleep" => GameCommand::Rest(DEFAULT_SLEEP_TURNS),
+            None => GameCommand::Rest(1),
+            Some(count) => match count.parse() {
+                Ok(turns) => GameCommand::Rest(turns),
+                Err(_) => GameCommand::Unknown("Rest for how many turns?".to_string()),
+            },
+        },
+
         // Meta
         "inventory" | "inv" | "i" => GameCommand::Inventory,
+        "stats" | "status" | "character" | "equipment" => GameCommand::Stats,
         "map" | "m" => GameCommand::Map,
         "quests" | "journal" | "quest" => GameCommand::QuestLog,
         "codex" | "notes" | "lore" => GameCommand::Journal,
+        "reputation" | "standing" | "factions" => GameCommand::Reputation,
+        "time" | "clock" => GameCommand::Time,
         "help" | "?" => GameCommand::Help,
 
         // Save/Load
@@ -235,10 +437,27 @@ fn parse_command(cleaned: &str) -> GameCommand {
     }
 }
 
+/// Whether the parser already claims `word` as a command, alone or with
+/// something after it. Custom verbs only see input the parser doesn't know.
+pub fn is_builtin_verb(word: &str) -> bool {
+    [word.to_string(), format!("{} thing", word)]
+        .into_iter()
+        .any(|input| !matches!(parse_command(&input), GameCommand::Unknown(msg) if msg == input))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn builtin_verbs_are_known() {
+        for verb in ["pull", "set", "answer", "hide", "close", "bash", "n", "sleep", "xyzzy"] {
+            assert!(is_builtin_verb(verb), "{verb}");
+        }
+        assert!(!is_builtin_verb("pray"));
+        assert!(!is_builtin_verb("dance"));
+    }
+
     fn exploring() -> GameMode {
         GameMode::Exploring
     }
@@ -325,6 +544,55 @@ mod tests {
             parse("unequip shield", &exploring()),
             GameCommand::Unequip("shield".to_string())
         );
+        assert_eq!(
+            parse("open the chest", &exploring()),
+            GameCommand::Open("chest".to_string())
+        );
+        assert_eq!(
+            parse("repair the short sword", &exploring()),
+            GameCommand::Repair("short sword".to_string())
+        );
+    }
+
+    #[test]
+    fn parse_topics() {
+        assert_eq!(
+            parse("ask gristle about the cellar", &exploring()),
+            GameCommand::AskAbout("gristle".to_string(), "cellar".to_string())
+        );
+        assert_eq!(
+            parse("tell priest about amulet", &exploring()),
+            GameCommand::TellAbout("priest".to_string(), "amulet".to_string())
+        );
+        assert_eq!(
+            parse("ask about cellar", &GameMode::InDialogue("gristle_rat".into())),
+            GameCommand::AskAbout(String::new(), "cellar".to_string())
+        );
+        assert_eq!(
+            parse("ask gristle", &exploring()),
+            GameCommand::TalkTo("gristle".to_string())
+        );
+    }
+
+    #[test]
+    fn parse_companion_commands() {
+        assert_eq!(
+            parse("recruit the rat", &exploring()),
+            GameCommand::Recruit("rat".to_string())
+        );
+        assert_eq!(
+            parse("tell gristle to wait", &exploring()),
+            GameCommand::CompanionWait("gristle".to_string(), true)
+        );
+        assert_eq!(
+            parse("tell gristle to follow", &exploring()),
+            GameCommand::CompanionWait("gristle".to_string(), false)
+        );
+        assert_eq!(
+            parse("give the short sword to gristle", &exploring()),
+            GameCommand::Give("short sword".to_string(), "gristle".to_string())
+        );
+        assert!(matches!(parse("give sword", &exploring()), GameCommand::Unknown(_)));
     }
 
     #[test]
@@ -347,6 +615,8 @@ mod tests {
         assert_eq!(parse("inv", &exploring()), GameCommand::Inventory);
         assert_eq!(parse("i", &exploring()), GameCommand::Inventory);
         assert_eq!(parse("map", &exploring()), GameCommand::Map);
+        assert_eq!(parse("stats", &exploring()), GameCommand::Stats);
+        assert_eq!(parse("equipment", &exploring()), GameCommand::Stats);
         assert_eq!(parse("quests", &exploring()), GameCommand::QuestLog);
         assert_eq!(parse("help", &exploring()), GameCommand::Help);
     }
@@ -455,5 +725,82 @@ mod tests {
         assert_eq!(parse("codex", &exploring()), GameCommand::Journal);
         assert_eq!(parse("notes", &exploring()), GameCommand::Journal);
         assert_eq!(parse("lore", &exploring()), GameCommand::Journal);
+        assert_eq!(parse("standing", &exploring()), GameCommand::Reputation);
+        assert_eq!(parse("time", &exploring()), GameCommand::Time);
+    }
+
+    #[test]
+    fn parse_rest() {
+        assert_eq!(parse("rest", &exploring()), GameCommand::Rest(1));
+        assert_eq!(parse("sleep", &exploring()), GameCommand::Rest(DEFAULT_SLEEP_TURNS));
+        assert_eq!(parse("sleep 4 turns", &exploring()), GameCommand::Rest(4));
+        assert!(matches!(parse("sleep soundly", &exploring()), GameCommand::Unknown(_)));
+    }
+
+    #[test]
+    fn parse_sneak_and_hide() {
+        assert_eq!(
+            parse("sneak north", &exploring()),
+            GameCommand::Sneak(Direction::North)
+        );
+        assert_eq!(parse("creep to the e", &exploring()), GameCommand::Sneak(Direction::East));
+        assert!(matches!(parse("sneak", &exploring()), GameCommand::Unknown(_)));
+        assert_eq!(parse("hide", &exploring()), GameCommand::Hide);
+    }
+
+    #[test]
+    fn parse_door_commands() {
+        assert_eq!(
+            parse("close the east door", &exploring()),
+            GameCommand::Close("east door".to_string())
+        );
+        assert_eq!(
+            parse("pick the lock on the door", &exploring()),
+            GameCommand::PickLock("door".to_string())
+        );
+        assert_eq!(
+            parse("lockpick east", &exploring()),
+            GameCommand::PickLock("east".to_string())
+        );
+        assert_eq!(parse("kick door", &exploring()), GameCommand::Bash("door".to_string()));
+        // Picking things up is unaffected
+        assert_eq!(
+            parse("pick up lockpicks", &exploring()),
+            GameCommand::Take("lockpicks".to_string())
+        );
+    }
+
+    #[test]
+    fn parse_puzzle_commands() {
+        assert_eq!(
+            parse("pull the rusted lever", &exploring()),
+            GameCommand::Pull("rusted lever".to_string())
+        );
+        assert_eq!(parse("press lever", &exploring()), GameCommand::Push("lever".to_string()));
+        assert_eq!(
+            parse("set the dial to 3-1-4", &exploring()),
+            GameCommand::SetFixture("dial".to_string(), "3-1-4".to_string())
+        );
+        assert!(matches!(parse("set dial", &exploring()), GameCommand::Unknown(_)));
+        assert_eq!(
+            parse("answer a shadow", &exploring()),
+            GameCommand::Answer("a shadow".to_string())
+        );
+        assert_eq!(
+            parse("answer footsteps", &GameMode::InDialogue("sphinx".into())),
+            GameCommand::Answer("footsteps".to_string())
+        );
+        assert!(matches!(parse("pull", &exploring()), GameCommand::Unknown(_)));
+    }
+
+    #[test]
+    fn parse_search_and_disarm() {
+        assert_eq!(parse("search", &exploring()), GameCommand::Search);
+        assert_eq!(parse("search the room", &exploring()), GameCommand::Search);
+        assert_eq!(
+            parse("disarm the tripwire", &exploring()),
+            GameCommand::Disarm("tripwire".to_string())
+        );
+        assert!(matches!(parse("disarm", &exploring()), GameCommand::Unknown(_)));
     }
 }
@@ -0,0 +1,309 @@
+use crate::engine::{events, templates};
+use crate::models::*;
+
+/// Folds a reply to a riddle down to its words: case, punctuation, articles
+/// and a leading "it is" don't count.
+pub fn normalize_answer(text: &str) -> String {
+    let cleaned: String = text
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect();
+    let words: Vec<&str> = cleaned
+        .split_whitespace()
+        .filter(|w| !matches!(*w, "a" | "an" | "the"))
+        .collect();
+    let words = match words.as_slice() {
+        ["it", "is", rest @ ..] | ["it", "s", rest @ ..] | ["its", rest @ ..] => rest,
+        all => all,
+    };
+    words.join(" ")
+}
+
+/// Whether a reply matches any of a riddle's answers.
+pub fn answer_matches(reply: &str, answers: &[String]) -> bool {
+    let reply = normalize_answer(reply);
+    !reply.is_empty() && answers.iter().any(|answer| normalize_answer(answer) == reply)
+}
+
+/// Finds the fixture in the player's room that a command names.
+pub fn find_fixture(target: &str, state: &WorldState) -> Option<String> {
+    let loc = state.locations.get(&state.player.location)?;
+    let target = target.to_lowercase();
+    loc.fixtures
+        .iter()
+        .find(|id| {
+            state.fixtures.get(*id).is_some_and(|f| {
+                f.name.to_lowercase().contains(&target) || id.to_lowercase() == target
+            })
+        })
+        .cloned()
+}
+
+fn fixture(fixture_id: &str, state: &WorldState) -> Result<Fixture, String> {
+    state
+        .fixtures
+        .get(fixture_id)
+        .cloned()
+        .ok_or_else(|| "There is no such thing here.".to_string())
+}
+
+fn set_state(fixture_id: &str, new_state: String, state: &mut WorldState) {
+    if let Some(fixture) = state.fixtures.get_mut(fixture_id) {
+        fixture.state = new_state;
+    }
+}
+
+fn line(text: String) -> OutputLine {
+    OutputLine {
+        text,
+        line_type: LineType::System,
+    }
+}
+
+/// Solves every puzzle whose fixtures now all stand as its solution asks,
+/// running its actions.
+pub fn check_puzzles(state: &mut WorldState, messages: &mut Vec<OutputLine>) {
+    let mut solved: Vec<String> = state
+        .puzzles
+        .iter()
+        .filter(|(_, puzzle)| {
+            !puzzle.solved
+                && puzzle.solution.iter().all(|(id, wanted)| {
+                    state.fixtures.get(id).is_some_and(|f| f.current() == wanted)
+                })
+        })
+        .map(|(id, _)| id.clone())
+        .collect();
+    solved.sort();
+
+    for id in solved {
+        let Some(puzzle) = state.puzzles.get_mut(&id) else {
+            continue;
+        };
+        puzzle.solved = true;
+        let puzzle = puzzle.clone();
+        messages.push(line(
+            puzzle
+                .solved_text
+                .unwrap_or_else(templates::describe_puzzle_solved),
+        ));
+        for action in puzzle.on_solve {
+            events::apply_action(action, &puzzle.location_id, state, messages);
+        }
+    }
+}
+
+/// Pulls a lever on to its next position, or pushes it back to the one
+/// before, wrapping around at either end.
+pub fn shift(
+    fixture_id: &str,
+    forward: bool,
+    state: &mut WorldState,
+) -> Result<Vec<OutputLine>, String> {
+    let fixture = fixture(fixture_id, state)?;
+    let positions = match &fixture.kind {
+        FixtureKind::Lever { positions } if !positions.is_empty() => positions,
+        _ => return Err(format!("The {} doesn't budge.", fixture.name)),
+    };
+    let count = positions.len();
+    let idx = positions
+        .iter()
+        .position(|p| p == fixture.current())
+        .unwrap_or(0);
+    let next = if forward {
+        (idx + 1) % count
+    } else {
+        (idx + count - 1) % count
+    };
+    let position = positions[next].clone();
+    let verb = if forward { "pull" } else { "push" };
+    let mut messages = vec![line(templates::describe_fixture_moved(
+        verb,
+        &fixture.name,
+        &position,
+    ))];
+    set_state(fixture_id, position, state);
+    check_puzzles(state, &mut messages);
+    Ok(messages)
+}
+
+/// Sets a combination lock. Separators such as "3-1-4" are ignored.
+pub fn set(
+    fixture_id: &str,
+    value: &str,
+    state: &mut WorldState,
+) -> Result<Vec<OutputLine>, String> {
+    let fixture = fixture(fixture_id, state)?;
+    let FixtureKind::Combination { length } = fixture.kind else {
+        return Err(format!("You can't set the {}.", fixture.name));
+    };
+    let digits: String = value.chars().filter(|c| c.is_ascii_digit()).collect();
+    let stray = value
+        .chars()
+        .any(|c| !c.is_ascii_digit() && !matches!(c, ' ' | '-' | ','));
+    if stray || digits.len() != length {
+        return Err(format!(
+            "The {} takes a combination of {} digits.",
+            fixture.name, length
+        ));
+    }
+    let mut messages = vec![line(templates::describe_fixture_set(&fixture.name, &digits))];
+    set_state(fixture_id, digits, state);
+    check_puzzles(state, &mut messages);
+    Ok(messages)
+}
+
+fn unsolved_riddle(fixture_id: &str, state: &WorldState) -> bool {
+    state.fixtures.get(fixture_id).is_some_and(|f| {
+        matches!(f.kind, FixtureKind::Riddle { .. }) && f.state != RIDDLE_SOLVED
+    })
+}
+
+/// The riddle an answer goes to: the one posed by the NPC being talked to,
+/// else one fixed in the room, else one an NPC here poses.
+fn open_riddle(state: &WorldState) -> Option<String> {
+    if let GameMode::InDialogue(npc_id) = &state.game_mode {
+        let riddle = state.npcs.get(npc_id).and_then(|npc| npc.riddle.as_ref());
+        if let Some(id) = riddle.filter(|id| unsolved_riddle(id, state)) {
+            return Some(id.clone());
+        }
+    }
+    let loc = state.locations.get(&state.player.location)?;
+    loc.fixtures
+        .iter()
+        .find(|id| unsolved_riddle(id, state))
+        .or_else(|| {
+            loc.npcs
+                .iter()
+                .filter_map(|id| state.npcs.get(id))
+                .filter(|npc| npc.dialogue_state != DialogueState::Dead)
+                .filter_map(|npc| npc.riddle.as_ref())
+                .find(|id| unsolved_riddle(id, state))
+        })
+        .cloned()
+}
+
+/// Answers the riddle at hand. A wrong answer only costs time.
+pub fn answer(reply: &str, state: &mut WorldState) -> Result<Vec<OutputLine>, String> {
+    let fixture_id =
+        open_riddle(state).ok_or_else(|| "There is no riddle here to answer.".to_string())?;
+    let fixture = fixture(&fixture_id, state)?;
+    let FixtureKind::Riddle { answers, .. } = &fixture.kind else {
+        return Err("There is no riddle here to answer.".to_string());
+    };
+    let correct = answer_matches(reply, answers);
+    let mut messages = vec![line(templates::describe_riddle_answer(correct))];
+    if correct {
+        set_state(&fixture_id, RIDDLE_SOLVED.to_string(), state);
+        check_puzzles(state, &mut messages);
+    }
+    Ok(messages)
+}
+
+/// The riddle an NPC puts to the player, while it is unanswered.
+pub fn riddle_prompt(npc_id: &str, state: &WorldState) -> Option<String> {
+    let fixture_id = state.npcs.get(npc_id)?.riddle.as_ref()?;
+    match &state.fixtures.get(fixture_id)?.kind {
+        FixtureKind::Riddle { question, .. } if unsolved_riddle(fixture_id, state) => {
+            Some(templates::describe_riddle(question))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::world_builder;
+    use std::collections::HashMap;
+
+    fn place(fixture: Fixture, state: &mut WorldState) {
+        let loc = state.locations.get_mut(&state.player.location).unwrap();
+        loc.fixtures.push(fixture.id.clone());
+        state.fixtures.insert(fixture.id.clone(), fixture);
+    }
+
+    #[test]
+    fn levers_and_locks_solve_a_puzzle_together() {
+        let mut state = world_builder::build_thornhold();
+        place(
+            Fixture {
+                id: "gate_lever".into(),
+                name: "rusted lever".into(),
+                description: String::new(),
+                kind: FixtureKind::Lever {
+                    positions: vec!["up".into(), "middle".into(), "down".into()],
+                },
+                state: String::new(),
+            },
+            &mut state,
+        );
+        place(
+            Fixture {
+                id: "gate_lock".into(),
+                name: "brass lock".into(),
+                description: String::new(),
+                kind: FixtureKind::Combination { length: 3 },
+                state: String::new(),
+            },
+            &mut state,
+        );
+        state.puzzles.insert(
+            "gate".into(),
+            Puzzle {
+                id: "gate".into(),
+                location_id: "courtyard".into(),
+                solution: HashMap::from([
+                    ("gate_lever".into(), "down".into()),
+                    ("gate_lock".into(), "314".into()),
+                ]),
+                on_solve: vec![EventAction::GiveItem("torch".into())],
+                solved_text: None,
+                solved: false,
+            },
+        );
+
+        // Pushing wraps round from the first position to the last
+        let lever = find_fixture("lever", &state).unwrap();
+        shift(&lever, false, &mut state).unwrap();
+        assert_eq!(state.fixtures[&lever].current(), "down");
+        assert!(!state.puzzles["gate"].solved);
+
+        let lock = find_fixture("brass", &state).unwrap();
+        assert!(set(&lock, "31", &mut state).is_err());
+        assert!(set(&lock, "3a4", &mut state).is_err());
+        let msgs = set(&lock, "3-1-4", &mut state).unwrap();
+        assert_eq!(msgs[1].text, templates::describe_puzzle_solved());
+        assert!(state.puzzles["gate"].solved);
+        assert!(state.player.inventory.contains(&"torch".to_string()));
+
+        // Solved puzzles stay solved and don't pay out twice
+        shift(&lever, true, &mut state).unwrap();
+        set(&lock, "314", &mut state).unwrap();
+        assert_eq!(
+            state.player.inventory.iter().filter(|id| *id == "torch").count(),
+            1
+        );
+        assert!(set(&lever, "314", &mut state).is_err());
+    }
+
+    #[test]
+    fn riddles_accept_any_synonym() {
+        let answers = vec!["footsteps".to_string(), "steps".to_string()];
+        assert!(answer_matches("Footsteps!", &answers));
+        assert!(answer_matches("it's the steps", &answers));
+        assert!(!answer_matches("shadows", &answers));
+        assert!(!answer_matches("the", &answers));
+
+        let mut state = world_builder::build_thornhold();
+        assert!(answer("footsteps", &mut state).is_err());
+        state.player.location = "library".into();
+        let msgs = answer("shadows", &mut state).unwrap();
+        assert_eq!(msgs[0].text, templates::describe_riddle_answer(false));
+        let msgs = answer("Footprints.", &mut state).unwrap();
+        assert_eq!(msgs[0].text, templates::describe_riddle_answer(true));
+        assert!(state.puzzles["bust_riddle"].solved);
+        assert!(answer("footsteps", &mut state).is_err());
+    }
+}
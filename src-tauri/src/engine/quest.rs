@@ -1,37 +1,585 @@
+use crate::engine::{conditions, equipment, events, factions, templates};
 use crate::models::*;
 
-pub fn check_quest_progress(state: &mut WorldState) -> Vec<OutputLine> {
-    let mut messages = Vec::new();
+/// Things the player does that talk, craft and use objectives wait for.
+pub enum QuestActivity<'a> {
+    TalkedTo(&'a str),
+    Crafted(&'a str),
+    /// An item used at a location.
+    UsedItem(&'a str, &'a str),
+}
+
+/// How many of an item the player carries, counting worn copies and
+/// generated variants of it.
+fn carried_count(item_id: &str, state: &WorldState) -> u32 {
+    state
+        .player
+        .inventory
+        .iter()
+        .filter(|id| equipment::base_id(id, state) == item_id)
+        .count() as u32
+}
+
+/// Current and target counts for objectives that build up over time.
+/// Survival counts from when the objective's stage opened.
+fn objective_count(
+    objective: &QuestObjective,
+    quest: &Quest,
+    stage_id: &str,
+    state: &WorldState,
+) -> Option<(u32, u32)> {
+    match objective {
+        QuestObjective::CollectCount(item_id, n) => {
+            Some((carried_count(item_id, state).min(*n), *n))
+        }
+        QuestObjective::SurviveTurns(n) => {
+            let progress = &quest.progress;
+            let started = progress
+                .stage_started
+                .get(stage_id)
+                .copied()
+                .or(progress.started_turn)
+                .unwrap_or(state.player.turns_elapsed);
+            Some((
+                state.player.turns_elapsed.saturating_sub(started).min(*n),
+                *n,
+            ))
+        }
+        _ => None,
+    }
+}
+
+fn objective_met(
+    objective: &QuestObjective,
+    quest: &Quest,
+    stage_id: &str,
+    key: &str,
+    state: &WorldState,
+) -> bool {
+    let done = || {
+        quest
+            .progress
+            .counts
+            .get(key)
+            .is_some_and(|count| *count > 0)
+    };
+    match objective {
+        QuestObjective::FetchItem(item_id) => {
+            equipment::carried_position(item_id, state).is_some()
+        }
+        QuestObjective::KillNpc(npc_id) => state
+            .npcs
+            .get(npc_id)
+            .map(|n| n.dialogue_state == DialogueState::Dead)
+            .unwrap_or(false),
+        QuestObjective::ReachLocation(loc_id) => state.player.location == *loc_id,
+        QuestObjective::TalkTo(_)
+        | QuestObjective::CraftItem(_)
+        | QuestObjective::UseItemAt(..) => done(),
+        QuestObjective::CollectCount(..) | QuestObjective::SurviveTurns(_) => {
+            objective_count(objective, quest, stage_id, state).is_some_and(|(have, n)| have >= n)
+        }
+        QuestObjective::DiscoverSecret(word) => state.player.discovered_secrets.contains(word),
+        QuestObjective::Escort(npc_id, destination) => {
+            state
+                .npcs
+                .get(npc_id)
+                .is_some_and(|n| n.dialogue_state != DialogueState::Dead)
+                && state
+                    .locations
+                    .get(destination)
+                    .is_some_and(|l| l.npcs.contains(npc_id))
+        }
+        QuestObjective::SetFlag(flag) => {
+            state.player.quest_flags.get(flag).copied().unwrap_or(false)
+        }
+    }
+}
+
+fn item_name<'a>(id: &'a str, state: &'a WorldState) -> &'a str {
+    state.items.get(id).map_or(id, |i| i.name.as_str())
+}
+
+fn npc_name<'a>(id: &'a str, state: &'a WorldState) -> &'a str {
+    state.npcs.get(id).map_or(id, |n| n.name.as_str())
+}
+
+fn location_name<'a>(id: &'a str, state: &'a WorldState) -> &'a str {
+    state.locations.get(id).map_or(id, |l| l.name.as_str())
+}
+
+/// Lowercase plural of an item name for counts, e.g. "bone shards".
+fn plural_name(name: &str, n: u32) -> String {
+    let name = name.to_lowercase();
+    if n == 1 || name.ends_with('s') {
+        name
+    } else {
+        format!("{}s", name)
+    }
+}
 
-    let quests: Vec<(String, Quest)> = state
+/// Partial progress text such as "2/5 bone shards".
+fn count_text(objective: &QuestObjective, have: u32, n: u32, state: &WorldState) -> String {
+    match objective {
+        QuestObjective::CollectCount(item_id, _) => {
+            format!(
+                "{}/{} {}",
+                have,
+                n,
+                plural_name(item_name(item_id, state), n)
+            )
+        }
+        _ => format!("{}/{} turns", have, n),
+    }
+}
+
+/// Partial progress of a single-objective quest, e.g. "2/5 bone shards".
+pub fn count_progress(quest: &Quest, state: &WorldState) -> Option<String> {
+    let objective = quest.objective.as_ref()?;
+    let (have, n) = objective_count(objective, quest, "main", state)?;
+    Some(count_text(objective, have, n, state))
+}
+
+/// Quest log text for an objective.
+pub fn objective_label(objective: &StageObjective, state: &WorldState) -> String {
+    if let Some(label) = &objective.label {
+        return label.clone();
+    }
+    match &objective.objective {
+        QuestObjective::FetchItem(id) => format!("Obtain the {}", item_name(id, state)),
+        QuestObjective::KillNpc(id) => format!("Defeat {}", npc_name(id, state)),
+        QuestObjective::ReachLocation(id) => format!("Reach {}", location_name(id, state)),
+        QuestObjective::TalkTo(id) => format!("Speak with {}", npc_name(id, state)),
+        QuestObjective::CraftItem(recipe_id) => {
+            let output = state
+                .recipes
+                .iter()
+                .find(|r| r.id == *recipe_id)
+                .map_or(recipe_id.as_str(), |r| item_name(&r.output, state));
+            format!("Craft the {}", output)
+        }
+        QuestObjective::UseItemAt(item_id, loc_id) => format!(
+            "Use the {} at {}",
+            item_name(item_id, state),
+            location_name(loc_id, state)
+        ),
+        QuestObjective::CollectCount(item_id, n) => {
+            format!(
+                "Collect {} {}",
+                n,
+                plural_name(item_name(item_id, state), *n)
+            )
+        }
+        QuestObjective::DiscoverSecret(_) => "Uncover a hidden secret".to_string(),
+        QuestObjective::Escort(npc_id, loc_id) => format!(
+            "Escort {} to {}",
+            npc_name(npc_id, state),
+            location_name(loc_id, state)
+        ),
+        QuestObjective::SurviveTurns(n) => format!("Survive {} turns", n),
+        QuestObjective::SetFlag(flag) => format!("Bring about '{}'", flag.replace('_', " ")),
+    }
+}
+
+fn objective_key(stage: &QuestStage, index: usize) -> String {
+    format!("{}:{}", stage.id, index)
+}
+
+/// Stages still to finish. Ordered quests only work on the first of them.
+pub fn open_stages(quest: &Quest) -> Vec<QuestStage> {
+    let mut stages: Vec<QuestStage> = quest
+        .stage_list()
+        .into_iter()
+        .filter(|s| !quest.progress.completed_stages.contains(&s.id))
+        .collect();
+    if !quest.unordered_stages {
+        stages.truncate(1);
+    }
+    stages
+}
+
+/// Checklist lines for a stage, e.g. `[x] Obtain the Sacred Scroll`.
+pub fn describe_stage_objectives(quest: &Quest, stage: &QuestStage, state: &WorldState) -> Vec<String> {
+    stage
+        .objectives
+        .iter()
+        .enumerate()
+        .map(|(idx, objective)| {
+            let mark = if quest.progress.checked.contains(&objective_key(stage, idx)) {
+                "x"
+            } else {
+                " "
+            };
+            let optional = if objective.optional { " (optional)" } else { "" };
+            let count = match objective_count(&objective.objective, quest, &stage.id, state) {
+                Some((have, n)) if mark == " " => format!(" ({}/{})", have, n),
+                _ => String::new(),
+            };
+            format!("[{}] {}{}{}", mark, objective_label(objective, state), count, optional)
+        })
+        .collect()
+}
+
+/// Reports a collect objective's count when it has gone up since last time.
+fn report_collect_progress(
+    quest: &Quest,
+    objective: &QuestObjective,
+    stage_id: &str,
+    key: &str,
+    progress: &mut QuestProgress,
+    state: &WorldState,
+    messages: &mut Vec<OutputLine>,
+) {
+    if !matches!(objective, QuestObjective::CollectCount(..)) {
+        return;
+    }
+    let Some((have, n)) = objective_count(objective, quest, stage_id, state) else {
+        return;
+    };
+    let reported = progress.reported_counts.insert(key.to_string(), have).unwrap_or(0);
+    if have > reported {
+        messages.push(OutputLine {
+            text: format!("{}: {}", quest.name, count_text(objective, have, n, state)),
+            line_type: LineType::System,
+        });
+    }
+}
+
+/// Counts an activity toward matching objectives of open quest stages.
+pub fn record_activity(activity: QuestActivity, state: &mut WorldState) {
+    let mut quest_ids: Vec<String> = state
         .quests
         .iter()
         .filter(|(_, q)| q.active && !q.completed)
-        .map(|(id, q)| (id.clone(), q.clone()))
+        .map(|(id, _)| id.clone())
         .collect();
+    quest_ids.sort();
 
-    for (quest_id, quest) in quests {
-        let completed = match &quest.objective {
-            QuestObjective::FetchItem(item_id) => {
-                state.player.inventory.contains(item_id)
+    for quest_id in quest_ids {
+        let quest = &state.quests[&quest_id];
+        let mut keys = Vec::new();
+        for stage in open_stages(quest) {
+            for (idx, objective) in stage.objectives.iter().enumerate() {
+                let matched = match (&objective.objective, &activity) {
+                    (QuestObjective::TalkTo(npc), QuestActivity::TalkedTo(talked)) => npc == talked,
+                    (QuestObjective::CraftItem(recipe), QuestActivity::Crafted(crafted)) => {
+                        recipe == crafted
+                    }
+                    (QuestObjective::UseItemAt(item, loc), QuestActivity::UsedItem(used, at)) => {
+                        item == used && loc == at
+                    }
+                    _ => false,
+                };
+                if matched {
+                    keys.push(objective_key(&stage, idx));
+                }
             }
-            QuestObjective::KillNpc(npc_id) => {
-                state
-                    .npcs
-                    .get(npc_id)
-                    .map(|n| n.dialogue_state == DialogueState::Dead)
-                    .unwrap_or(false)
+        }
+        if let Some(quest) = state.quests.get_mut(&quest_id) {
+            for key in keys {
+                *quest.progress.counts.entry(key).or_insert(0) += 1;
+            }
+        }
+    }
+}
+
+/// NPCs being escorted from the player's location: alive, not hostile and
+/// named by an open escort objective whose destination isn't reached yet.
+pub fn escorted_npcs(state: &WorldState) -> Vec<String> {
+    let here = state.locations.get(&state.player.location);
+    let mut escorted = Vec::new();
+    for quest in state.quests.values().filter(|q| q.active && !q.completed) {
+        for stage in open_stages(quest) {
+            for objective in &stage.objectives {
+                let QuestObjective::Escort(npc_id, destination) = &objective.objective else {
+                    continue;
+                };
+                let follows = here.is_some_and(|l| l.npcs.contains(npc_id) && l.id != *destination)
+                    && state
+                        .npcs
+                        .get(npc_id)
+                        .is_some_and(|n| !n.hostile && n.dialogue_state != DialogueState::Dead);
+                if follows && !escorted.contains(npc_id) {
+                    escorted.push(npc_id.clone());
+                }
             }
-            QuestObjective::ReachLocation(loc_id) => {
-                state.player.location == *loc_id
+        }
+    }
+    escorted.sort();
+    escorted
+}
+
+/// Checks off met objectives and finishes stages; returns whether every
+/// stage is now complete.
+fn advance_stages(quest_id: &str, state: &mut WorldState, messages: &mut Vec<OutputLine>) -> bool {
+    loop {
+        let turn = state.player.turns_elapsed;
+        if let Some(q) = state.quests.get_mut(quest_id) {
+            for stage in open_stages(q) {
+                q.progress.stage_started.entry(stage.id).or_insert(turn);
             }
+        }
+        let Some(quest) = state.quests.get(quest_id).cloned() else {
+            return false;
         };
+        let staged = !quest.stages.is_empty();
+        let open = open_stages(&quest);
+        if open.is_empty() {
+            return true;
+        }
+
+        let mut progress = quest.progress.clone();
+        let mut finished_stage = false;
+        for stage in &open {
+            for (idx, objective) in stage.objectives.iter().enumerate() {
+                let key = objective_key(stage, idx);
+                if progress.checked.contains(&key) {
+                    continue;
+                }
+                if !objective_met(&objective.objective, &quest, &stage.id, &key, state) {
+                    report_collect_progress(
+                        &quest,
+                        &objective.objective,
+                        &stage.id,
+                        &key,
+                        &mut progress,
+                        state,
+                        messages,
+                    );
+                    continue;
+                }
+                progress.checked.push(key);
+                if staged {
+                    messages.push(OutputLine {
+                        text: format!("Objective complete: {}", objective_label(objective, state)),
+                        line_type: LineType::System,
+                    });
+                }
+            }
+            let done = stage
+                .objectives
+                .iter()
+                .enumerate()
+                .all(|(idx, o)| o.optional || progress.checked.contains(&objective_key(stage, idx)));
+            if done {
+                progress.completed_stages.push(stage.id.clone());
+                finished_stage = true;
+                if staged {
+                    messages.push(OutputLine {
+                        text: format!("Stage complete: {}", stage.name),
+                        line_type: LineType::System,
+                    });
+                }
+            }
+        }
+
+        if let Some(q) = state.quests.get_mut(quest_id) {
+            q.progress = progress;
+        }
+        if !finished_stage {
+            return false;
+        }
+        // Announce the stage that opens next in an ordered quest
+        if let Some(quest) = state.quests.get(quest_id) {
+            if !quest.unordered_stages {
+                if let Some(next) = open_stages(quest).first() {
+                    let text = if next.journal.is_empty() {
+                        format!("New stage: {}", next.name)
+                    } else {
+                        format!("New stage: {} — {}", next.name, next.journal)
+                    };
+                    messages.push(OutputLine {
+                        text,
+                        line_type: LineType::System,
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Picks the quest's outcome from the world as it stands now.
+pub fn resolve_outcome(quest_id: &str, state: &mut WorldState) {
+    let Some(quest) = state.quests.get(quest_id) else {
+        return;
+    };
+    if quest.progress.outcome.is_some() {
+        return;
+    }
+    let chosen = quest
+        .outcomes
+        .iter()
+        .find(|o| conditions::holds(o.condition.as_ref(), state))
+        .map(|o| o.id.clone());
+    if let Some(q) = state.quests.get_mut(quest_id) {
+        q.progress.outcome = chosen;
+    }
+}
+
+/// Starts the follow-up quest of the chosen outcome, if any.
+pub fn start_follow_up(quest_id: &str, state: &mut WorldState) -> Vec<OutputLine> {
+    let Some(follow_up) = state
+        .quests
+        .get(quest_id)
+        .and_then(|q| q.chosen_outcome())
+        .and_then(|o| o.follow_up.clone())
+    else {
+        return Vec::new();
+    };
+    match state.quests.get_mut(&follow_up) {
+        Some(next) if !next.active && !next.completed && !next.failed => {
+            next.active = true;
+            vec![OutputLine {
+                text: crate::engine::templates::describe_quest_started(next),
+                line_type: LineType::System,
+            }]
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Whether a giver may offer the quest: required quests are complete,
+/// required flags are set, the giver's faction still deals with the player,
+/// and the quest hasn't failed or been locked out.
+pub fn prerequisites_met(quest: &Quest, state: &WorldState) -> bool {
+    !quest.failed
+        && factions::will_deal(&quest.giver, state)
+        && quest
+            .required_quests
+            .iter()
+            .all(|id| state.quests.get(id).is_some_and(|q| q.completed))
+        && quest
+            .required_flags
+            .iter()
+            .all(|flag| state.player.quest_flags.get(flag).copied().unwrap_or(false))
+}
+
+/// Whether an item still exists anywhere: carried, lying in a room, hidden
+/// in one or held by an NPC.
+fn item_exists(item_id: &str, state: &WorldState) -> bool {
+    let is_copy = |id: &String| equipment::base_id(id, state) == item_id;
+    let still_hidden = |loc: &Location| {
+        loc.secrets.iter().any(|secret| {
+            matches!(&secret.kind, HiddenKind::Item(id) if is_copy(id))
+                && !loc.discovered_secrets.contains(&secret.id)
+        })
+    };
+    state.player.inventory.iter().any(is_copy)
+        || state.locations.values().any(|l| l.items.iter().any(is_copy) || still_hidden(l))
+        || state.npcs.values().any(|n| n.items.iter().any(is_copy))
+}
+
+/// The reason an active quest fails now, if any of its fail conditions hold.
+fn failure_reason(quest: &Quest, state: &WorldState) -> Option<String> {
+    let turn = state.player.turns_elapsed;
+    quest
+        .fail_conditions
+        .iter()
+        .find_map(|failure| match failure {
+            QuestFailure::NpcDies(npc_id) => state
+                .npcs
+                .get(npc_id)
+                .filter(|n| n.dialogue_state == DialogueState::Dead)
+                .map(|n| format!("{} is dead.", n.name)),
+            QuestFailure::Deadline(deadline) => {
+                (turn > *deadline).then(|| "You ran out of time.".to_string())
+            }
+            QuestFailure::TimeLimit(limit) => {
+                let started = quest.progress.started_turn.unwrap_or(turn);
+                (turn > started.saturating_add(*limit)).then(|| "You ran out of time.".to_string())
+            }
+            QuestFailure::ItemDestroyed(item_id) => (!item_exists(item_id, state))
+                .then(|| format!("The {} is gone.", item_name(item_id, state))),
+        })
+}
+
+/// Fails a quest: applies its consequences and closes off the quests it
+/// locks out.
+pub fn fail_quest(quest_id: &str, reason: &str, state: &mut WorldState) -> Vec<OutputLine> {
+    let mut messages = Vec::new();
+    let Some(quest) = state.quests.get_mut(quest_id) else {
+        return messages;
+    };
+    if quest.completed || quest.failed {
+        return messages;
+    }
+    quest.failed = true;
+    quest.active = false;
+    quest.progress.failure = Some(reason.to_string());
+    let quest = quest.clone();
+
+    // The giver has nothing more to say about it
+    if let Some(giver) = state.npcs.get_mut(&quest.giver) {
+        if matches!(
+            giver.dialogue_state,
+            DialogueState::QuestOffered | DialogueState::QuestActive
+        ) {
+            giver.dialogue_state = DialogueState::Familiar;
+        }
+    }
+    messages.push(OutputLine {
+        text: templates::describe_quest_failed(&quest, reason),
+        line_type: LineType::System,
+    });
+
+    let location_id = state.player.location.clone();
+    for action in quest.on_fail.iter().cloned() {
+        events::apply_action(action, &location_id, state, &mut messages);
+    }
+
+    for locked_id in &quest.locks_out {
+        let Some(locked) = state.quests.get_mut(locked_id) else {
+            continue;
+        };
+        if locked.completed || locked.failed {
+            continue;
+        }
+        locked.failed = true;
+        locked.active = false;
+        locked.progress.failure = Some(format!("Closed off when {} failed.", quest.name));
+        messages.push(OutputLine {
+            text: format!("Quest no longer available: {}", locked.name),
+            line_type: LineType::System,
+        });
+    }
+    messages
+}
+
+pub fn check_quest_progress(state: &mut WorldState) -> Vec<OutputLine> {
+    let mut messages = Vec::new();
+
+    let mut quest_ids: Vec<String> = state
+        .quests
+        .iter()
+        .filter(|(_, q)| q.active && !q.completed)
+        .map(|(id, _)| id.clone())
+        .collect();
+    quest_ids.sort();
+
+    for quest_id in quest_ids {
+        let turn = state.player.turns_elapsed;
+        if let Some(q) = state.quests.get_mut(&quest_id) {
+            q.progress.started_turn.get_or_insert(turn);
+        }
+        if let Some(reason) = failure_reason(&state.quests[&quest_id], state) {
+            messages.extend(fail_quest(&quest_id, &reason, state));
+            continue;
+        }
+        let completed = advance_stages(&quest_id, state, &mut messages);
 
         if completed {
             if let Some(q) = state.quests.get_mut(&quest_id) {
                 q.completed = true;
                 q.completed_turn = Some(state.player.turns_elapsed);
             }
+            resolve_outcome(&quest_id, state);
+            let quest = state.quests[&quest_id].clone();
+            if let Some(outcome) = quest.chosen_outcome() {
+                messages.push(OutputLine {
+                    text: outcome.description.clone(),
+                    line_type: LineType::Narration,
+                });
+            }
 
             // Check if quest giver is nearby for auto-complete
             let giver_nearby = state
@@ -91,11 +639,12 @@ mod tests {
                 name: "Fetch Quest".into(),
                 description: "Get the item.".into(),
                 giver: "npc1".into(),
-                objective: QuestObjective::FetchItem("magic_item".into()),
+                objective: Some(QuestObjective::FetchItem("magic_item".into())),
                 reward: vec![],
                 completed: false,
                 active: true,
                 completed_turn: None,
+                ..Default::default()
             },
         );
         state.npcs.insert(
@@ -116,6 +665,7 @@ mod tests {
                 examine_text: None,
                 relationship: 0,
                 memory: vec![],
+                ..Default::default()
             },
         );
         state.locations.insert(
@@ -133,6 +683,7 @@ mod tests {
                 ambient_mood: Mood::Peaceful,
                 examine_details: None,
                 revisit_description: None,
+                ..Default::default()
             },
         );
 
@@ -158,11 +709,12 @@ mod tests {
                 name: "Kill Quest".into(),
                 description: "Kill the enemy.".into(),
                 giver: "npc1".into(),
-                objective: QuestObjective::KillNpc("enemy".into()),
+                objective: Some(QuestObjective::KillNpc("enemy".into())),
                 reward: vec![],
                 completed: false,
                 active: true,
                 completed_turn: None,
+                ..Default::default()
             },
         );
         state.npcs.insert(
@@ -183,6 +735,7 @@ mod tests {
                 examine_text: None,
                 relationship: 0,
                 memory: vec![],
+                ..Default::default()
             },
         );
         state.npcs.insert(
@@ -203,6 +756,7 @@ mod tests {
                 examine_text: None,
                 relationship: 0,
                 memory: vec![],
+                ..Default::default()
             },
         );
         state.locations.insert(
@@ -220,6 +774,7 @@ mod tests {
                 ambient_mood: Mood::Peaceful,
                 examine_details: None,
                 revisit_description: None,
+                ..Default::default()
             },
         );
 
@@ -233,4 +788,357 @@ mod tests {
         assert!(!msgs.is_empty());
         assert!(state.quests.get("kill_quest").unwrap().completed);
     }
+
+    fn staged_quest(state: &mut WorldState) {
+        let quest = state.quests.get_mut("rats_request").unwrap();
+        quest.active = true;
+        quest.stages = vec![
+            QuestStage {
+                id: "descend".into(),
+                name: "Into the cellar".into(),
+                journal: "Gristle says the cheese is below.".into(),
+                objectives: vec![
+                    StageObjective {
+                        objective: QuestObjective::ReachLocation("wine_cellar".into()),
+                        optional: false,
+                        label: None,
+                    },
+                    StageObjective {
+                        objective: QuestObjective::FetchItem("torch".into()),
+                        optional: true,
+                        label: Some("Bring a light".into()),
+                    },
+                ],
+            },
+            QuestStage {
+                id: "cheese".into(),
+                name: "The cheese".into(),
+                journal: String::new(),
+                objectives: vec![StageObjective {
+                    objective: QuestObjective::FetchItem("cellar_cheese".into()),
+                    optional: false,
+                    label: None,
+                }],
+            },
+        ];
+    }
+
+    #[test]
+    fn ordered_stages_advance_one_at_a_time() {
+        let mut state = crate::engine::world_builder::build_thornhold();
+        staged_quest(&mut state);
+
+        // The second stage's objective doesn't count while the first is open
+        state.player.inventory.push("cellar_cheese".into());
+        let msgs = check_quest_progress(&mut state);
+        assert!(msgs.is_empty());
+
+        state.player.location = "wine_cellar".into();
+        let msgs = check_quest_progress(&mut state);
+        let texts: Vec<&str> = msgs.iter().map(|m| m.text.as_str()).collect();
+        assert!(texts.contains(&"Stage complete: Into the cellar"));
+        assert!(texts.iter().any(|t| t.contains("Objective complete: Obtain the")));
+        let quest = &state.quests["rats_request"];
+        assert!(quest.completed);
+        assert_eq!(quest.progress.completed_stages, vec!["descend", "cheese"]);
+        // The optional objective was never met
+        let first = &quest.stages[0];
+        assert_eq!(
+            describe_stage_objectives(quest, first, &state)[1],
+            "[ ] Bring a light (optional)"
+        );
+    }
+
+    #[test]
+    fn unordered_stages_finish_in_any_order() {
+        let mut state = crate::engine::world_builder::build_thornhold();
+        staged_quest(&mut state);
+        state.quests.get_mut("rats_request").unwrap().unordered_stages = true;
+
+        state.player.inventory.push("cellar_cheese".into());
+        check_quest_progress(&mut state);
+        let quest = &state.quests["rats_request"];
+        assert_eq!(quest.progress.completed_stages, vec!["cheese"]);
+        assert!(!quest.completed);
+
+        state.player.location = "wine_cellar".into();
+        check_quest_progress(&mut state);
+        assert!(state.quests["rats_request"].completed);
+    }
+
+    #[test]
+    fn outcome_follows_world_state_and_starts_follow_up() {
+        let mut state = crate::engine::world_builder::build_thornhold();
+        let quest = state.quests.get_mut("rats_request").unwrap();
+        quest.active = true;
+        quest.outcomes = vec![
+            QuestOutcome {
+                id: "shared".into(),
+                description: "You shared the cheese with the rat.".into(),
+                condition: Some(Condition::Flag("shared_cheese".into())),
+                reward: vec!["torch".into()],
+                follow_up: Some("seek_the_vault".into()),
+            },
+            QuestOutcome {
+                id: "kept".into(),
+                description: "Gristle eyes you sourly.".into(),
+                condition: None,
+                reward: vec![],
+                follow_up: None,
+            },
+        ];
+        state.player.quest_flags.insert("shared_cheese".into(), true);
+        state.player.inventory.push("cellar_cheese".into());
+
+        let msgs = check_quest_progress(&mut state);
+        assert!(msgs
+            .iter()
+            .any(|m| m.text == "You shared the cheese with the rat."));
+        let quest = &state.quests["rats_request"];
+        assert_eq!(quest.progress.outcome.as_deref(), Some("shared"));
+        assert_eq!(quest.rewards(), vec!["health_potion", "torch"]);
+
+        let started = start_follow_up("rats_request", &mut state);
+        assert_eq!(started.len(), 1);
+        assert!(state.quests["seek_the_vault"].active);
+    }
+
+    fn activate_with(state: &mut WorldState, objective: QuestObjective) {
+        let quest = state.quests.get_mut("rats_request").unwrap();
+        quest.active = true;
+        quest.objective = Some(objective);
+    }
+
+    #[test]
+    fn collect_count_reports_partial_progress() {
+        let mut state = crate::engine::world_builder::build_thornhold();
+        activate_with(
+            &mut state,
+            QuestObjective::CollectCount("bone_fragment".into(), 3),
+        );
+
+        state.player.inventory.push("bone_fragment".into());
+        let msgs = check_quest_progress(&mut state);
+        assert_eq!(msgs[0].text, "The Rat's Request: 1/3 bone fragments");
+        assert!(check_quest_progress(&mut state).is_empty());
+
+        // Generated variants count toward the total, look-alike ids don't
+        state
+            .item_instances
+            .insert("bone_fragment_gen1".into(), "bone_fragment".into());
+        state.player.inventory.push("bone_fragment_gen1".into());
+        state.player.inventory.push("bone_fragment_generator".into());
+        let msgs = check_quest_progress(&mut state);
+        assert_eq!(msgs[0].text, "The Rat's Request: 2/3 bone fragments");
+        assert_eq!(
+            count_progress(&state.quests["rats_request"], &state).as_deref(),
+            Some("2/3 bone fragments")
+        );
+
+        state.player.inventory.push("bone_fragment".into());
+        check_quest_progress(&mut state);
+        assert!(state.quests["rats_request"].completed);
+    }
+
+    #[test]
+    fn activities_count_toward_matching_objectives() {
+        let mut state = crate::engine::world_builder::build_thornhold();
+        let quest = state.quests.get_mut("rats_request").unwrap();
+        quest.active = true;
+        quest.stages = vec![QuestStage {
+            id: "errands".into(),
+            name: "Errands".into(),
+            journal: String::new(),
+            objectives: [
+                QuestObjective::TalkTo("gristle_rat".into()),
+                QuestObjective::CraftItem("lantern_torch".into()),
+                QuestObjective::UseItemAt("blessed_water".into(), "chapel".into()),
+            ]
+            .into_iter()
+            .map(|objective| StageObjective {
+                objective,
+                optional: false,
+                label: None,
+            })
+            .collect(),
+        }];
+
+        record_activity(QuestActivity::TalkedTo("merchant_ghost"), &mut state);
+        record_activity(QuestActivity::UsedItem("blessed_water", "kitchen"), &mut state);
+        check_quest_progress(&mut state);
+        assert!(state.quests["rats_request"].progress.checked.is_empty());
+
+        record_activity(QuestActivity::TalkedTo("gristle_rat"), &mut state);
+        record_activity(QuestActivity::Crafted("lantern_torch"), &mut state);
+        let msgs = check_quest_progress(&mut state);
+        assert!(msgs
+            .iter()
+            .any(|m| m.text == "Objective complete: Speak with Gristle"));
+        assert!(!state.quests["rats_request"].completed);
+
+        record_activity(QuestActivity::UsedItem("blessed_water", "chapel"), &mut state);
+        check_quest_progress(&mut state);
+        assert!(state.quests["rats_request"].completed);
+    }
+
+    #[test]
+    fn survive_turns_counts_from_quest_start() {
+        let mut state = crate::engine::world_builder::build_thornhold();
+        state.player.turns_elapsed = 10;
+        activate_with(&mut state, QuestObjective::SurviveTurns(3));
+
+        check_quest_progress(&mut state);
+        assert_eq!(state.quests["rats_request"].progress.started_turn, Some(10));
+
+        state.player.turns_elapsed = 12;
+        check_quest_progress(&mut state);
+        assert_eq!(
+            count_progress(&state.quests["rats_request"], &state).as_deref(),
+            Some("2/3 turns")
+        );
+        assert!(!state.quests["rats_request"].completed);
+
+        state.player.turns_elapsed = 13;
+        check_quest_progress(&mut state);
+        assert!(state.quests["rats_request"].completed);
+    }
+
+    #[test]
+    fn survive_turns_counts_from_its_stage_opening() {
+        let mut state = crate::engine::world_builder::build_thornhold();
+        staged_quest(&mut state);
+        state.quests.get_mut("rats_request").unwrap().stages[1].objectives[0].objective =
+            QuestObjective::SurviveTurns(3);
+        check_quest_progress(&mut state);
+
+        state.player.turns_elapsed = 5;
+        state.player.location = "wine_cellar".into();
+        check_quest_progress(&mut state);
+        let quest = &state.quests["rats_request"];
+        assert_eq!(quest.progress.stage_started["cheese"], 5);
+        assert!(!quest.completed);
+
+        state.player.turns_elapsed = 7;
+        check_quest_progress(&mut state);
+        assert!(!state.quests["rats_request"].completed);
+        state.player.turns_elapsed = 8;
+        check_quest_progress(&mut state);
+        assert!(state.quests["rats_request"].completed);
+    }
+
+    #[test]
+    fn secret_and_flag_objectives() {
+        let mut state = crate::engine::world_builder::build_thornhold();
+        activate_with(&mut state, QuestObjective::DiscoverSecret("xyzzy".into()));
+        state.player.discovered_secrets.push("xyzzy".into());
+        check_quest_progress(&mut state);
+        assert!(state.quests["rats_request"].completed);
+
+        let mut state = crate::engine::world_builder::build_thornhold();
+        activate_with(&mut state, QuestObjective::SetFlag("bell_rung".into()));
+        check_quest_progress(&mut state);
+        assert!(!state.quests["rats_request"].completed);
+        state.player.quest_flags.insert("bell_rung".into(), true);
+        check_quest_progress(&mut state);
+        assert!(state.quests["rats_request"].completed);
+    }
+
+    #[test]
+    fn npc_death_fails_quest_with_consequences() {
+        let mut state = crate::engine::world_builder::build_thornhold();
+        let quest = state.quests.get_mut("rats_request").unwrap();
+        quest.active = true;
+        quest.fail_conditions = vec![QuestFailure::NpcDies("gristle_rat".into())];
+        quest.on_fail = vec![EventAction::ChangeRelationship(
+            "merchant_ghost".into(),
+            -10,
+        )];
+        quest.locks_out = vec!["seek_the_vault".into()];
+        state.npcs.get_mut("gristle_rat").unwrap().dialogue_state = DialogueState::QuestActive;
+        assert!(check_quest_progress(&mut state).is_empty());
+
+        state.npcs.get_mut("gristle_rat").unwrap().dialogue_state = DialogueState::Dead;
+        let msgs = check_quest_progress(&mut state);
+        assert_eq!(
+            msgs[0].text,
+            "Quest Failed: The Rat's Request — Gristle is dead."
+        );
+        let quest = &state.quests["rats_request"];
+        assert!(quest.failed && !quest.active);
+        assert_eq!(state.npcs["merchant_ghost"].relationship, -10);
+        assert!(state.quests["seek_the_vault"].failed);
+        assert!(!prerequisites_met(&state.quests["seek_the_vault"], &state));
+
+        // Failed quests stay failed even once the objective is met
+        state.player.inventory.push("cellar_cheese".into());
+        check_quest_progress(&mut state);
+        assert!(!state.quests["rats_request"].completed);
+    }
+
+    #[test]
+    fn time_limits_and_destroyed_items_fail_quests() {
+        let mut state = crate::engine::world_builder::build_thornhold();
+        state.player.turns_elapsed = 5;
+        let quest = state.quests.get_mut("rats_request").unwrap();
+        quest.active = true;
+        quest.fail_conditions = vec![QuestFailure::TimeLimit(3)];
+        check_quest_progress(&mut state);
+        state.player.turns_elapsed = 8;
+        check_quest_progress(&mut state);
+        assert!(!state.quests["rats_request"].failed);
+        state.player.turns_elapsed = 9;
+        check_quest_progress(&mut state);
+        assert!(state.quests["rats_request"].failed);
+
+        let mut state = crate::engine::world_builder::build_thornhold();
+        let quest = state.quests.get_mut("rats_request").unwrap();
+        quest.active = true;
+        quest.fail_conditions = vec![
+            QuestFailure::Deadline(50),
+            QuestFailure::ItemDestroyed("cellar_cheese".into()),
+        ];
+        for loc in state.locations.values_mut() {
+            loc.items.retain(|id| id != "cellar_cheese");
+        }
+        check_quest_progress(&mut state);
+        let quest = &state.quests["rats_request"];
+        assert!(quest.failed);
+        assert_eq!(
+            quest.progress.failure.as_deref(),
+            Some("The Aged Cellar Cheese is gone.")
+        );
+    }
+
+    #[test]
+    fn hidden_items_are_not_destroyed() {
+        let mut state = crate::engine::world_builder::build_thornhold();
+        for loc in state.locations.values_mut() {
+            loc.items.retain(|id| id != "cellar_cheese");
+        }
+        state.locations.get_mut("kitchen").unwrap().secrets.push(HiddenFeature {
+            id: "cheese_cupboard".into(),
+            kind: HiddenKind::Item("cellar_cheese".into()),
+            find_chance: 50,
+            revealed_by: vec![],
+            found_text: "A wheel of cheese sits behind a loose board.".into(),
+        });
+        let quest = state.quests.get_mut("rats_request").unwrap();
+        quest.active = true;
+        quest.fail_conditions = vec![QuestFailure::ItemDestroyed("cellar_cheese".into())];
+        check_quest_progress(&mut state);
+        assert!(!state.quests["rats_request"].failed);
+    }
+
+    #[test]
+    fn prerequisites_need_completed_quests() {
+        let mut state = crate::engine::world_builder::build_thornhold();
+        state
+            .quests
+            .get_mut("seek_the_vault")
+            .unwrap()
+            .required_quests = vec!["rats_request".into()];
+        assert!(!prerequisites_met(&state.quests["seek_the_vault"], &state));
+        state.quests.get_mut("rats_request").unwrap().completed = true;
+        assert!(prerequisites_met(&state.quests["seek_the_vault"], &state));
+    }
 }
@@ -0,0 +1,61 @@
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::models::*;
+
+const SEED_STEP: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// Returns an RNG derived from the world seed and advances the seed, so every
+/// call yields a fresh but reproducible sequence that survives save/load.
+pub fn world_rng(state: &mut WorldState) -> StdRng {
+    state.rng_seed = state.rng_seed.wrapping_add(SEED_STEP);
+    StdRng::seed_from_u64(state.rng_seed)
+}
+
+/// Picks an entry by weight. Returns `None` if the slice is empty or every
+/// weight is zero.
+pub fn pick_weighted<'a, T>(
+    rng: &mut impl Rng,
+    entries: &'a [T],
+    weight: impl Fn(&T) -> u32,
+) -> Option<&'a T> {
+    let dist = WeightedIndex::new(entries.iter().map(&weight)).ok()?;
+    entries.get(dist.sample(rng))
+}
+
+/// Rolls a percent chance (0-100).
+pub fn roll_percent(rng: &mut impl Rng, chance: u32) -> bool {
+    rng.gen_range(0..100) < chance
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn world_rng_is_reproducible_from_seed() {
+        let mut a = WorldState {
+            rng_seed: 42,
+            ..WorldState::default()
+        };
+        let mut b = a.clone();
+        let x: u32 = world_rng(&mut a).gen();
+        let y: u32 = world_rng(&mut b).gen();
+        assert_eq!(x, y);
+        assert_eq!(a.rng_seed, b.rng_seed);
+        assert_ne!(a.rng_seed, 42);
+    }
+
+    #[test]
+    fn pick_weighted_skips_zero_weights() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let entries = [("never", 0), ("always", 5)];
+        for _ in 0..20 {
+            let picked = pick_weighted(&mut rng, &entries, |e| e.1).unwrap();
+            assert_eq!(picked.0, "always");
+        }
+        let empty: [(&str, u32); 0] = [];
+        assert!(pick_weighted(&mut rng, &empty, |e| e.1).is_none());
+    }
+}
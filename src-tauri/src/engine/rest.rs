@@ -0,0 +1,103 @@
+use crate::engine::{clock, companions, random};
+use crate::models::*;
+
+/// Turns slept when no count is given.
+pub const DEFAULT_SLEEP_TURNS: u32 = 8;
+/// Longest single rest, in turns.
+pub const MAX_REST_TURNS: u32 = 24;
+/// Percent chance per turn of rest that a hostile nearby finds the player
+/// in a dangerous or dark room, before the time of day scales it.
+const AMBUSH_CHANCE: u32 = 10;
+
+/// Percent of max HP regained per turn of rest.
+pub fn heal_percent(difficulty: &Difficulty) -> i32 {
+    match difficulty {
+        Difficulty::Easy => 10,
+        Difficulty::Normal => 5,
+        Difficulty::Hard => 3,
+    }
+}
+
+/// Heals the player for one turn of rest and returns the HP regained.
+pub fn heal_turn(state: &mut WorldState) -> i32 {
+    let player = &mut state.player;
+    let amount = (player.max_health * heal_percent(&state.difficulty) / 100).max(1);
+    let before = player.health;
+    player.health = (player.health + amount).min(player.max_health);
+    player.health - before
+}
+
+/// Rolls whether a hostile NPC in a neighboring room stumbles on the
+/// resting player. Only dangerous and dark rooms are risky. The ambusher is
+/// moved into the player's room and returned.
+pub fn roll_ambush(state: &mut WorldState) -> Option<String> {
+    let here = state.locations.get(&state.player.location)?;
+    if !matches!(here.ambient_mood, Mood::Dangerous | Mood::Dark) {
+        return None;
+    }
+    let mut exits: Vec<(&Direction, &String)> = here.exits.iter().collect();
+    exits.sort_by_key(|(dir, _)| dir.to_string());
+    let ambusher = exits.into_iter().find_map(|(_, room)| {
+        let room = state.locations.get(room)?;
+        room.npcs
+            .iter()
+            .find(|id| {
+                !companions::is_companion(id, state)
+                    && state
+                        .npcs
+                        .get(*id)
+                        .is_some_and(|n| n.hostile && n.dialogue_state != DialogueState::Dead)
+            })
+            .map(|id| (room.id.clone(), id.clone()))
+    });
+    let (from, npc_id) = ambusher?;
+
+    let chance = clock::spawn_chance(AMBUSH_CHANCE, state);
+    let mut rng = random::world_rng(state);
+    if !random::roll_percent(&mut rng, chance) {
+        return None;
+    }
+    if let Some(loc) = state.locations.get_mut(&from) {
+        loc.npcs.retain(|id| *id != npc_id);
+    }
+    let to = state.player.location.clone();
+    if let Some(loc) = state.locations.get_mut(&to) {
+        loc.npcs.push(npc_id.clone());
+    }
+    Some(npc_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::world_builder;
+
+    #[test]
+    fn healing_scales_with_difficulty() {
+        let mut state = world_builder::build_thornhold();
+        state.player.health = 50;
+        state.difficulty = Difficulty::Easy;
+        assert_eq!(heal_turn(&mut state), 10);
+        state.difficulty = Difficulty::Hard;
+        assert_eq!(heal_turn(&mut state), 3);
+        state.player.health = 99;
+        assert_eq!(heal_turn(&mut state), 1);
+    }
+
+    #[test]
+    fn only_risky_rooms_with_hostiles_nearby_ambush() {
+        let mut state = world_builder::build_thornhold();
+        // The courtyard is peaceful, though the barracks guard is next door
+        assert!((0..50).all(|_| roll_ambush(&mut state).is_none()));
+
+        state.locations.get_mut("courtyard").unwrap().ambient_mood = Mood::Dark;
+        let ambusher = (0..200).find_map(|_| roll_ambush(&mut state));
+        assert_eq!(ambusher.as_deref(), Some("skeletal_guard"));
+        assert!(state.locations["courtyard"]
+            .npcs
+            .contains(&"skeletal_guard".to_string()));
+        assert!(!state.locations["barracks"]
+            .npcs
+            .contains(&"skeletal_guard".to_string()));
+    }
+}
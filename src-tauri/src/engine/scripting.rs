@@ -0,0 +1,1196 @@
+//! A small sandboxed scripting language for module logic.
+//!
+//! Scripts are statements separated by newlines or `;`, with `#` comments:
+//!
+//! ```text
+//! let pulls = 0
+//! if has_item("iron_key") and not flag("gate_open") {
+//!     say("The gate grinds open.")
+//!     set_flag("gate_open")
+//! } else {
+//!     say("Nothing happens.")
+//! }
+//! ```
+//!
+//! Values are integers, booleans and strings. Besides `let`, assignment,
+//! `if`/`else`, `while` and `return`, all a script can do is call the
+//! builtins listed in `BUILTINS`. Every statement and loop test costs one
+//! step, and a run stops once it exceeds `MAX_STEPS`. Joining strings fails
+//! once the result would be longer than `MAX_STRING_LEN` bytes.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use crate::engine::{equipment, events};
+use crate::models::*;
+
+const MAX_STEPS: u32 = 10_000;
+const MAX_NESTING: usize = 32;
+const MAX_STRING_LEN: usize = 4_000;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Int(i64),
+    Str(String),
+    Ident(String),
+    Sym(&'static str),
+}
+
+const SYMBOLS: &[&str] = &[
+    "==", "!=", "<=", ">=", "(", ")", "{", "}", ",", ";", "=", "<", ">", "+", "-", "*", "/", "%",
+];
+
+const KEYWORDS: &[&str] = &[
+    "let", "if", "else", "while", "return", "and", "or", "not", "true", "false",
+];
+
+fn lex(source: &str) -> Result<Vec<(Token, usize)>, String> {
+    let mut tokens = Vec::new();
+    for (idx, line) in source.lines().enumerate() {
+        let line_no = idx + 1;
+        let mut chars = line.char_indices().peekable();
+        while let Some(&(start, c)) = chars.peek() {
+            if c.is_whitespace() {
+                chars.next();
+            } else if c == '#' {
+                break;
+            } else if c.is_ascii_digit() {
+                let mut end = start;
+                while let Some(&(i, d)) = chars.peek() {
+                    if !d.is_ascii_digit() {
+                        break;
+                    }
+                    end = i + 1;
+                    chars.next();
+                }
+                let value = line[start..end]
+                    .parse()
+                    .map_err(|_| format!("line {}: number is too large", line_no))?;
+                tokens.push((Token::Int(value), line_no));
+            } else if c.is_alphabetic() || c == '_' {
+                let mut end = start;
+                while let Some(&(i, d)) = chars.peek() {
+                    if !(d.is_alphanumeric() || d == '_') {
+                        break;
+                    }
+                    end = i + d.len_utf8();
+                    chars.next();
+                }
+                tokens.push((Token::Ident(line[start..end].to_string()), line_no));
+            } else if c == '"' {
+                chars.next();
+                let mut text = String::new();
+                let mut closed = false;
+                while let Some((_, d)) = chars.next() {
+                    match d {
+                        '"' => {
+                            closed = true;
+                            break;
+                        }
+                        '\\' => match chars.next() {
+                            Some((_, 'n')) => text.push('\n'),
+                            Some((_, e @ ('"' | '\\'))) => text.push(e),
+                            _ => return Err(format!("line {}: invalid escape in string", line_no)),
+                        },
+                        _ => text.push(d),
+                    }
+                }
+                if !closed {
+                    return Err(format!("line {}: unterminated string", line_no));
+                }
+                tokens.push((Token::Str(text), line_no));
+            } else {
+                let rest = &line[start..];
+                let Some(sym) = SYMBOLS.iter().find(|s| rest.starts_with(**s)) else {
+                    return Err(format!("line {}: unexpected character '{}'", line_no, c));
+                };
+                for _ in 0..sym.len() {
+                    chars.next();
+                }
+                tokens.push((Token::Sym(sym), line_no));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    And,
+    Or,
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    Int(i64),
+    Str(String),
+    Bool(bool),
+    Var(String, usize),
+    Call(String, Vec<Expr>, usize),
+    Neg(Box<Expr>, usize),
+    Not(Box<Expr>, usize),
+    Binary(BinOp, Box<Expr>, Box<Expr>, usize),
+}
+
+#[derive(Debug, Clone)]
+enum Stmt {
+    Let(String, Expr, usize),
+    Assign(String, Expr, usize),
+    If(Expr, Vec<Stmt>, Vec<Stmt>, usize),
+    While(Expr, Vec<Stmt>, usize),
+    Call(String, Vec<Expr>, usize),
+    Return,
+}
+
+/// What a builtin's argument must be. Id kinds are strings that module
+/// validation checks against the world when written as literals.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Param {
+    Text,
+    Flag,
+    Item,
+    Npc,
+    Location,
+    NpcState,
+    Faction,
+    Int,
+    Bool,
+}
+
+struct Builtin {
+    name: &'static str,
+    params: &'static [Param],
+    returns_value: bool,
+}
+
+const BUILTINS: &[Builtin] = &[
+    Builtin {
+        name: "say",
+        params: &[Param::Text],
+        returns_value: false,
+    },
+    Builtin {
+        name: "flag",
+        params: &[Param::Flag],
+        returns_value: true,
+    },
+    Builtin {
+        name: "set_flag",
+        params: &[Param::Flag],
+        returns_value: false,
+    },
+    Builtin {
+        name: "clear_flag",
+        params: &[Param::Flag],
+        returns_value: false,
+    },
+    Builtin {
+        name: "has_item",
+        params: &[Param::Item],
+        returns_value: true,
+    },
+    Builtin {
+        name: "give_item",
+        params: &[Param::Item],
+        returns_value: false,
+    },
+    Builtin {
+        name: "remove_item",
+        params: &[Param::Item],
+        returns_value: false,
+    },
+    Builtin {
+        name: "location",
+        params: &[],
+        returns_value: true,
+    },
+    Builtin {
+        name: "move_player",
+        params: &[Param::Location],
+        returns_value: false,
+    },
+    Builtin {
+        name: "npc_here",
+        params: &[Param::Npc],
+        returns_value: true,
+    },
+    Builtin {
+        name: "npc_state",
+        params: &[Param::Npc],
+        returns_value: true,
+    },
+    Builtin {
+        name: "set_npc_state",
+        params: &[Param::Npc, Param::NpcState],
+        returns_value: false,
+    },
+    Builtin {
+        name: "set_hostile",
+        params: &[Param::Npc, Param::Bool],
+        returns_value: false,
+    },
+    Builtin {
+        name: "relationship",
+        params: &[Param::Npc],
+        returns_value: true,
+    },
+    Builtin {
+        name: "change_relationship",
+        params: &[Param::Npc, Param::Int],
+        returns_value: false,
+    },
+    Builtin {
+        name: "reputation",
+        params: &[Param::Faction],
+        returns_value: true,
+    },
+    Builtin {
+        name: "change_reputation",
+        params: &[Param::Faction, Param::Int],
+        returns_value: false,
+    },
+    Builtin {
+        name: "turn",
+        params: &[],
+        returns_value: true,
+    },
+    Builtin {
+        name: "hour",
+        params: &[],
+        returns_value: true,
+    },
+];
+
+fn builtin(name: &str) -> Option<&'static Builtin> {
+    BUILTINS.iter().find(|b| b.name == name)
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    depth: usize,
+    declared: HashSet<String>,
+}
+
+impl Parser {
+    fn line(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .or_else(|| self.tokens.last())
+            .map_or(1, |(_, line)| *line)
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(t, _)| t)
+    }
+
+    fn is_sym(&self, sym: &str) -> bool {
+        matches!(self.peek(), Some(Token::Sym(s)) if *s == sym)
+    }
+
+    fn is_keyword(&self, word: &str) -> bool {
+        matches!(self.peek(), Some(Token::Ident(w)) if w == word)
+    }
+
+    fn error<T>(&self, message: impl fmt::Display) -> Result<T, String> {
+        Err(format!("line {}: {}", self.line(), message))
+    }
+
+    fn expect_sym(&mut self, sym: &str) -> Result<(), String> {
+        if self.is_sym(sym) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            self.error(format!("expected '{}'", sym))
+        }
+    }
+
+    fn ident(&mut self) -> Result<String, String> {
+        match self.peek() {
+            Some(Token::Ident(name)) if !KEYWORDS.contains(&name.as_str()) => {
+                let name = name.clone();
+                self.pos += 1;
+                Ok(name)
+            }
+            _ => self.error("expected a name"),
+        }
+    }
+
+    fn nest(&mut self) -> Result<(), String> {
+        self.depth += 1;
+        if self.depth > MAX_NESTING {
+            return self.error("script is nested too deeply");
+        }
+        Ok(())
+    }
+
+    fn program(&mut self) -> Result<Vec<Stmt>, String> {
+        let mut stmts = Vec::new();
+        while self.peek().is_some() {
+            if self.is_sym(";") {
+                self.pos += 1;
+                continue;
+            }
+            stmts.push(self.statement()?);
+        }
+        Ok(stmts)
+    }
+
+    fn block(&mut self) -> Result<Vec<Stmt>, String> {
+        self.nest()?;
+        self.expect_sym("{")?;
+        let mut stmts = Vec::new();
+        while !self.is_sym("}") {
+            if self.peek().is_none() {
+                return self.error("missing '}'");
+            }
+            if self.is_sym(";") {
+                self.pos += 1;
+                continue;
+            }
+            stmts.push(self.statement()?);
+        }
+        self.pos += 1;
+        self.depth -= 1;
+        Ok(stmts)
+    }
+
+    fn statement(&mut self) -> Result<Stmt, String> {
+        let line = self.line();
+        if self.is_keyword("let") {
+            self.pos += 1;
+            let name = self.ident()?;
+            self.expect_sym("=")?;
+            let value = self.expr()?;
+            self.declared.insert(name.clone());
+            return Ok(Stmt::Let(name, value, line));
+        }
+        if self.is_keyword("if") {
+            return self.if_statement();
+        }
+        if self.is_keyword("while") {
+            self.pos += 1;
+            let test = self.expr()?;
+            let body = self.block()?;
+            return Ok(Stmt::While(test, body, line));
+        }
+        if self.is_keyword("return") {
+            self.pos += 1;
+            return Ok(Stmt::Return);
+        }
+        let name = self.ident()?;
+        if self.is_sym("(") {
+            let args = self.call_args(&name)?;
+            return Ok(Stmt::Call(name, args, line));
+        }
+        if self.is_sym("=") {
+            if !self.declared.contains(&name) {
+                return self.error(format!("unknown variable '{}'; declare it with let", name));
+            }
+            self.pos += 1;
+            let value = self.expr()?;
+            return Ok(Stmt::Assign(name, value, line));
+        }
+        self.error(format!("expected a call or assignment after '{}'", name))
+    }
+
+    fn if_statement(&mut self) -> Result<Stmt, String> {
+        let line = self.line();
+        self.pos += 1;
+        let test = self.expr()?;
+        let then = self.block()?;
+        let otherwise = if self.is_keyword("else") {
+            self.pos += 1;
+            if self.is_keyword("if") {
+                self.nest()?;
+                let nested = self.if_statement()?;
+                self.depth -= 1;
+                vec![nested]
+            } else {
+                self.block()?
+            }
+        } else {
+            Vec::new()
+        };
+        Ok(Stmt::If(test, then, otherwise, line))
+    }
+
+    fn call_args(&mut self, name: &str) -> Result<Vec<Expr>, String> {
+        let Some(def) = builtin(name) else {
+            return self.error(format!("unknown function '{}'", name));
+        };
+        self.expect_sym("(")?;
+        let mut args = Vec::new();
+        while !self.is_sym(")") {
+            if !args.is_empty() {
+                self.expect_sym(",")?;
+            }
+            args.push(self.expr()?);
+        }
+        self.pos += 1;
+        if args.len() != def.params.len() {
+            return self.error(format!(
+                "{}() takes {} argument(s), got {}",
+                name,
+                def.params.len(),
+                args.len()
+            ));
+        }
+        Ok(args)
+    }
+
+    fn expr(&mut self) -> Result<Expr, String> {
+        self.nest()?;
+        let expr = self.or_expr();
+        self.depth -= 1;
+        expr
+    }
+
+    fn or_expr(&mut self) -> Result<Expr, String> {
+        let mut left = self.and_expr()?;
+        while self.is_keyword("or") {
+            let line = self.line();
+            self.pos += 1;
+            let right = self.and_expr()?;
+            left = Expr::Binary(BinOp::Or, Box::new(left), Box::new(right), line);
+        }
+        Ok(left)
+    }
+
+    fn and_expr(&mut self) -> Result<Expr, String> {
+        let mut left = self.not_expr()?;
+        while self.is_keyword("and") {
+            let line = self.line();
+            self.pos += 1;
+            let right = self.not_expr()?;
+            left = Expr::Binary(BinOp::And, Box::new(left), Box::new(right), line);
+        }
+        Ok(left)
+    }
+
+    fn not_expr(&mut self) -> Result<Expr, String> {
+        if self.is_keyword("not") {
+            let line = self.line();
+            self.pos += 1;
+            self.nest()?;
+            let inner = self.not_expr()?;
+            self.depth -= 1;
+            return Ok(Expr::Not(Box::new(inner), line));
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Expr, String> {
+        let left = self.sum()?;
+        let op = match self.peek() {
+            Some(Token::Sym("==")) => BinOp::Eq,
+            Some(Token::Sym("!=")) => BinOp::Ne,
+            Some(Token::Sym("<")) => BinOp::Lt,
+            Some(Token::Sym("<=")) => BinOp::Le,
+            Some(Token::Sym(">")) => BinOp::Gt,
+            Some(Token::Sym(">=")) => BinOp::Ge,
+            _ => return Ok(left),
+        };
+        let line = self.line();
+        self.pos += 1;
+        let right = self.sum()?;
+        Ok(Expr::Binary(op, Box::new(left), Box::new(right), line))
+    }
+
+    fn sum(&mut self) -> Result<Expr, String> {
+        let mut left = self.term()?;
+        loop {
+            let op = match self.peek() {
+                Some(Token::Sym("+")) => BinOp::Add,
+                Some(Token::Sym("-")) => BinOp::Sub,
+                _ => return Ok(left),
+            };
+            let line = self.line();
+            self.pos += 1;
+            let right = self.term()?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right), line);
+        }
+    }
+
+    fn term(&mut self) -> Result<Expr, String> {
+        let mut left = self.unary()?;
+        loop {
+            let op = match self.peek() {
+                Some(Token::Sym("*")) => BinOp::Mul,
+                Some(Token::Sym("/")) => BinOp::Div,
+                Some(Token::Sym("%")) => BinOp::Rem,
+                _ => return Ok(left),
+            };
+            let line = self.line();
+            self.pos += 1;
+            let right = self.unary()?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right), line);
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        if self.is_sym("-") {
+            let line = self.line();
+            self.pos += 1;
+            self.nest()?;
+            let inner = self.unary()?;
+            self.depth -= 1;
+            return Ok(Expr::Neg(Box::new(inner), line));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Expr, String> {
+        let line = self.line();
+        let Some(token) = self.peek().cloned() else {
+            return self.error("unexpected end of script");
+        };
+        match token {
+            Token::Int(value) => {
+                self.pos += 1;
+                Ok(Expr::Int(value))
+            }
+            Token::Str(text) => {
+                self.pos += 1;
+                Ok(Expr::Str(text))
+            }
+            Token::Sym("(") => {
+                self.pos += 1;
+                let inner = self.expr()?;
+                self.expect_sym(")")?;
+                Ok(inner)
+            }
+            Token::Ident(word) if word == "true" || word == "false" => {
+                self.pos += 1;
+                Ok(Expr::Bool(word == "true"))
+            }
+            Token::Ident(_) => {
+                let name = self.ident()?;
+                if self.is_sym("(") {
+                    let args = self.call_args(&name)?;
+                    if builtin(&name).is_some_and(|b| !b.returns_value) {
+                        return Err(format!("line {}: {}() doesn't return a value", line, name));
+                    }
+                    return Ok(Expr::Call(name, args, line));
+                }
+                if !self.declared.contains(&name) {
+                    return Err(format!("line {}: unknown variable '{}'", line, name));
+                }
+                Ok(Expr::Var(name, line))
+            }
+            Token::Sym(sym) => self.error(format!("unexpected '{}'", sym)),
+        }
+    }
+}
+
+fn parse(source: &str) -> Result<Vec<Stmt>, String> {
+    let mut parser = Parser {
+        tokens: lex(source)?,
+        pos: 0,
+        depth: 0,
+        declared: HashSet::from(["arg".to_string()]),
+    };
+    parser.program()
+}
+
+/// Checks the ids a call names literally against the world.
+fn check_call(name: &str, args: &[Expr], line: usize, state: &WorldState) -> Result<(), String> {
+    let Some(def) = builtin(name) else {
+        return Ok(());
+    };
+    for (param, arg) in def.params.iter().zip(args) {
+        let Expr::Str(id) = arg else {
+            continue;
+        };
+        let known = match param {
+            Param::Item => state.items.contains_key(id),
+            Param::Npc => state.npcs.contains_key(id),
+            Param::Location => state.locations.contains_key(id),
+            Param::NpcState => parse_npc_state(id).is_some(),
+            Param::Faction => state.factions.contains_key(id),
+            _ => true,
+        };
+        if !known {
+            let kind = match param {
+                Param::Item => "item",
+                Param::Npc => "npc",
+                Param::Location => "location",
+                Param::Faction => "faction",
+                _ => "npc state",
+            };
+            return Err(format!("line {}: {} '{}' doesn't exist", line, kind, id));
+        }
+    }
+    Ok(())
+}
+
+fn check_expr(expr: &Expr, state: &WorldState) -> Result<(), String> {
+    match expr {
+        Expr::Call(name, args, line) => {
+            check_call(name, args, *line, state)?;
+            args.iter().try_for_each(|a| check_expr(a, state))
+        }
+        Expr::Neg(inner, _) | Expr::Not(inner, _) => check_expr(inner, state),
+        Expr::Binary(_, left, right, _) => {
+            check_expr(left, state)?;
+            check_expr(right, state)
+        }
+        _ => Ok(()),
+    }
+}
+
+fn check_block(stmts: &[Stmt], state: &WorldState) -> Result<(), String> {
+    for stmt in stmts {
+        match stmt {
+            Stmt::Let(_, value, _) | Stmt::Assign(_, value, _) => check_expr(value, state)?,
+            Stmt::If(test, then, otherwise, _) => {
+                check_expr(test, state)?;
+                check_block(then, state)?;
+                check_block(otherwise, state)?;
+            }
+            Stmt::While(test, body, _) => {
+                check_expr(test, state)?;
+                check_block(body, state)?;
+            }
+            Stmt::Call(name, args, line) => {
+                check_call(name, args, *line, state)?;
+                args.iter().try_for_each(|a| check_expr(a, state))?;
+            }
+            Stmt::Return => {}
+        }
+    }
+    Ok(())
+}
+
+/// Parses a script and checks the item, NPC and location ids it names.
+pub fn check_script(source: &str, state: &WorldState) -> Result<(), String> {
+    let program = parse(source)?;
+    check_block(&program, state)
+}
+
+fn parse_npc_state(name: &str) -> Option<DialogueState> {
+    serde_json::from_value(serde_json::Value::String(name.to_string())).ok()
+}
+
+fn npc_state_name(dialogue_state: &DialogueState) -> String {
+    serde_json::to_value(dialogue_state)
+        .ok()
+        .and_then(|v| v.as_str().map(String::from))
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Int(i64),
+    Bool(bool),
+    Str(String),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Int(n) => write!(f, "{}", n),
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::Str(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+enum Flow {
+    Next,
+    Return,
+}
+
+struct Interpreter<'a> {
+    state: &'a mut WorldState,
+    messages: &'a mut Vec<OutputLine>,
+    vars: HashMap<String, Value>,
+    steps: u32,
+}
+
+impl Interpreter<'_> {
+    fn step(&mut self, line: usize) -> Result<(), String> {
+        self.steps += 1;
+        if self.steps > MAX_STEPS {
+            return Err(format!(
+                "line {}: step limit of {} exceeded",
+                line, MAX_STEPS
+            ));
+        }
+        Ok(())
+    }
+
+    fn run_block(&mut self, stmts: &[Stmt]) -> Result<Flow, String> {
+        for stmt in stmts {
+            if let Flow::Return = self.run(stmt)? {
+                return Ok(Flow::Return);
+            }
+        }
+        Ok(Flow::Next)
+    }
+
+    fn run(&mut self, stmt: &Stmt) -> Result<Flow, String> {
+        match stmt {
+            Stmt::Let(name, value, line) | Stmt::Assign(name, value, line) => {
+                self.step(*line)?;
+                let value = self.eval(value)?;
+                self.vars.insert(name.clone(), value);
+            }
+            Stmt::If(test, then, otherwise, line) => {
+                self.step(*line)?;
+                let branch = if self.eval_bool(test, *line)? {
+                    then
+                } else {
+                    otherwise
+                };
+                return self.run_block(branch);
+            }
+            Stmt::While(test, body, line) => loop {
+                self.step(*line)?;
+                if !self.eval_bool(test, *line)? {
+                    break;
+                }
+                if let Flow::Return = self.run_block(body)? {
+                    return Ok(Flow::Return);
+                }
+            },
+            Stmt::Call(name, args, line) => {
+                self.step(*line)?;
+                self.call(name, args, *line)?;
+            }
+            Stmt::Return => return Ok(Flow::Return),
+        }
+        Ok(Flow::Next)
+    }
+
+    fn eval_bool(&mut self, expr: &Expr, line: usize) -> Result<bool, String> {
+        match self.eval(expr)? {
+            Value::Bool(b) => Ok(b),
+            other => Err(format!(
+                "line {}: expected true or false, got {}",
+                line, other
+            )),
+        }
+    }
+
+    fn eval(&mut self, expr: &Expr) -> Result<Value, String> {
+        match expr {
+            Expr::Int(n) => Ok(Value::Int(*n)),
+            Expr::Str(s) => Ok(Value::Str(s.clone())),
+            Expr::Bool(b) => Ok(Value::Bool(*b)),
+            Expr::Var(name, line) => self
+                .vars
+                .get(name)
+                .cloned()
+                .ok_or_else(|| format!("line {}: '{}' is used before it is set", line, name)),
+            Expr::Call(name, args, line) => self.call(name, args, *line),
+            Expr::Neg(inner, line) => match self.eval(inner)? {
+                Value::Int(n) => n
+                    .checked_neg()
+                    .map(Value::Int)
+                    .ok_or_else(|| format!("line {}: number overflow", line)),
+                other => Err(format!("line {}: can't negate {}", line, other)),
+            },
+            Expr::Not(inner, line) => Ok(Value::Bool(!self.eval_bool(inner, *line)?)),
+            Expr::Binary(BinOp::And, left, right, line) => Ok(Value::Bool(
+                self.eval_bool(left, *line)? && self.eval_bool(right, *line)?,
+            )),
+            Expr::Binary(BinOp::Or, left, right, line) => Ok(Value::Bool(
+                self.eval_bool(left, *line)? || self.eval_bool(right, *line)?,
+            )),
+            Expr::Binary(op, left, right, line) => {
+                let left = self.eval(left)?;
+                let right = self.eval(right)?;
+                binary(*op, left, right, *line)
+            }
+        }
+    }
+
+    fn call(&mut self, name: &str, args: &[Expr], line: usize) -> Result<Value, String> {
+        let def =
+            builtin(name).ok_or_else(|| format!("line {}: unknown function '{}'", line, name))?;
+        let mut values = Vec::with_capacity(args.len());
+        for (param, arg) in def.params.iter().zip(args) {
+            let value = self.eval(arg)?;
+            let ok = match param {
+                Param::Text => true,
+                Param::Int => matches!(value, Value::Int(_)),
+                Param::Bool => matches!(value, Value::Bool(_)),
+                _ => matches!(value, Value::Str(_)),
+            };
+            if !ok {
+                return Err(format!(
+                    "line {}: {}() got the wrong kind of argument: {}",
+                    line, name, value
+                ));
+            }
+            values.push(value);
+        }
+        let text = |i: usize| values[i].to_string();
+        let here = self.state.player.location.clone();
+        let unit = Value::Bool(true);
+
+        let result = match name {
+            "say" => {
+                self.messages.push(OutputLine {
+                    text: text(0),
+                    line_type: LineType::Narration,
+                });
+                unit
+            }
+            "flag" => Value::Bool(
+                self.state
+                    .player
+                    .quest_flags
+                    .get(&text(0))
+                    .copied()
+                    .unwrap_or(false),
+            ),
+            "set_flag" => {
+                self.state.player.quest_flags.insert(text(0), true);
+                unit
+            }
+            "clear_flag" => {
+                self.state.player.quest_flags.remove(&text(0));
+                unit
+            }
+            "has_item" => {
+                Value::Bool(equipment::carried_position(&text(0), self.state).is_some())
+            }
+            "give_item" => self.action(EventAction::GiveItem(text(0)), &here),
+            "remove_item" => self.action(EventAction::RemoveItem(text(0)), &here),
+            "location" => Value::Str(here),
+            "move_player" => self.action(EventAction::Teleport(text(0)), &here),
+            "npc_here" => Value::Bool(
+                self.state
+                    .locations
+                    .get(&here)
+                    .is_some_and(|loc| loc.npcs.contains(&text(0))),
+            ),
+            "npc_state" => Value::Str(
+                self.state
+                    .npcs
+                    .get(&text(0))
+                    .map(|npc| npc_state_name(&npc.dialogue_state))
+                    .unwrap_or_default(),
+            ),
+            "set_npc_state" => {
+                let dialogue_state = parse_npc_state(&text(1))
+                    .ok_or_else(|| format!("line {}: unknown npc state '{}'", line, text(1)))?;
+                if let Some(npc) = self.state.npcs.get_mut(&text(0)) {
+                    npc.dialogue_state = dialogue_state;
+                }
+                unit
+            }
+            "set_hostile" => {
+                let hostile = values[1] == Value::Bool(true);
+                self.action(EventAction::SetNpcHostile(text(0), hostile), &here)
+            }
+            "relationship" => Value::Int(
+                self.state
+                    .npcs
+                    .get(&text(0))
+                    .map_or(0, |npc| i64::from(npc.relationship)),
+            ),
+            "change_relationship" => {
+                let Value::Int(amount) = values[1] else {
+                    return Err(format!("line {}: expected a number", line));
+                };
+                let amount =
+                    i32::try_from(amount).map_err(|_| format!("line {}: number overflow", line))?;
+                self.action(EventAction::ChangeRelationship(text(0), amount), &here)
+            }
+            "reputation" => Value::Int(
+                self.state
+                    .factions
+                    .get(&text(0))
+                    .map_or(0, |faction| i64::from(faction.reputation)),
+            ),
+            "change_reputation" => {
+                let Value::Int(amount) = values[1] else {
+                    return Err(format!("line {}: expected a number", line));
+                };
+                let amount =
+                    i32::try_from(amount).map_err(|_| format!("line {}: number overflow", line))?;
+                self.action(EventAction::ChangeReputation(text(0), amount), &here)
+            }
+            "turn" => Value::Int(i64::from(self.state.player.turns_elapsed)),
+            "hour" => Value::Int(i64::from(self.state.clock.hour())),
+            _ => return Err(format!("line {}: unknown function '{}'", line, name)),
+        };
+        Ok(result)
+    }
+
+    fn action(&mut self, action: EventAction, location_id: &str) -> Value {
+        events::apply_action(action, location_id, self.state, self.messages);
+        Value::Bool(true)
+    }
+}
+
+fn binary(op: BinOp, left: Value, right: Value, line: usize) -> Result<Value, String> {
+    let overflow = || format!("line {}: number overflow", line);
+    match (op, left, right) {
+        (BinOp::Eq, l, r) => Ok(Value::Bool(l == r)),
+        (BinOp::Ne, l, r) => Ok(Value::Bool(l != r)),
+        (BinOp::Add, Value::Int(a), Value::Int(b)) => {
+            a.checked_add(b).map(Value::Int).ok_or_else(overflow)
+        }
+        (BinOp::Add, l @ Value::Str(_), r) | (BinOp::Add, l, r @ Value::Str(_)) => {
+            let joined = format!("{}{}", l, r);
+            if joined.len() > MAX_STRING_LEN {
+                return Err(format!(
+                    "line {}: string longer than {} bytes",
+                    line, MAX_STRING_LEN
+                ));
+            }
+            Ok(Value::Str(joined))
+        }
+        (BinOp::Sub, Value::Int(a), Value::Int(b)) => {
+            a.checked_sub(b).map(Value::Int).ok_or_else(overflow)
+        }
+        (BinOp::Mul, Value::Int(a), Value::Int(b)) => {
+            a.checked_mul(b).map(Value::Int).ok_or_else(overflow)
+        }
+        (BinOp::Div | BinOp::Rem, Value::Int(_), Value::Int(0)) => {
+            Err(format!("line {}: division by zero", line))
+        }
+        (BinOp::Div, Value::Int(a), Value::Int(b)) => {
+            a.checked_div(b).map(Value::Int).ok_or_else(overflow)
+        }
+        (BinOp::Rem, Value::Int(a), Value::Int(b)) => {
+            a.checked_rem(b).map(Value::Int).ok_or_else(overflow)
+        }
+        (BinOp::Lt, Value::Int(a), Value::Int(b)) => Ok(Value::Bool(a < b)),
+        (BinOp::Le, Value::Int(a), Value::Int(b)) => Ok(Value::Bool(a <= b)),
+        (BinOp::Gt, Value::Int(a), Value::Int(b)) => Ok(Value::Bool(a > b)),
+        (BinOp::Ge, Value::Int(a), Value::Int(b)) => Ok(Value::Bool(a >= b)),
+        (op, l, r) => Err(format!(
+            "line {}: can't apply {:?} to {} and {}",
+            line, op, l, r
+        )),
+    }
+}
+
+/// Runs a module script. `arg` is available to the script as the variable
+/// `arg`. Errors stop the script and are reported as an error line; changes
+/// made before the error stand.
+pub fn run_script(
+    script_id: &str,
+    arg: &str,
+    state: &mut WorldState,
+    messages: &mut Vec<OutputLine>,
+) {
+    let result = match state.scripts.get(script_id) {
+        Some(source) => parse(source),
+        None => Err(format!("script '{}' doesn't exist", script_id)),
+    };
+    let result = result.and_then(|program| {
+        let mut interpreter = Interpreter {
+            state,
+            messages,
+            vars: HashMap::from([("arg".to_string(), Value::Str(arg.to_string()))]),
+            steps: 0,
+        };
+        interpreter.run_block(&program).map(|_| ())
+    });
+    if let Err(error) = result {
+        messages.push(OutputLine {
+            text: format!("Script '{}' failed: {}", script_id, error),
+            line_type: LineType::Error,
+        });
+    }
+}
+
+/// Finds a custom verb available here for the player's input, returning the
+/// script to run and the rest of the input as its argument.
+pub fn match_custom_verb(input: &str, state: &WorldState) -> Option<(String, String)> {
+    let (word, rest) = input.split_once(' ').unwrap_or((input, ""));
+    state
+        .custom_verbs
+        .iter()
+        .filter(|v| {
+            v.location
+                .as_ref()
+                .is_none_or(|loc| *loc == state.player.location)
+        })
+        .find(|v| v.matches(word))
+        .map(|v| (v.script.clone(), rest.trim().to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::world_builder;
+
+    fn run(source: &str, state: &mut WorldState) -> Vec<OutputLine> {
+        state.scripts.insert("test".into(), source.into());
+        let mut messages = Vec::new();
+        run_script("test", "", state, &mut messages);
+        messages
+    }
+
+    #[test]
+    fn parse_errors_report_line_numbers() {
+        let state = world_builder::build_thornhold();
+        let cases = [
+            (
+                "say(\"hi\")\nsing(\"la\")",
+                "line 2: unknown function 'sing'",
+            ),
+            ("set_flag(\"a\", \"b\")", "takes 1 argument(s), got 2"),
+            ("say(x)", "unknown variable 'x'"),
+            ("say(\"oops)", "unterminated string"),
+            ("if true {\n say(\"a\")\n", "missing '}'"),
+            ("let x = say(\"a\")", "doesn't return a value"),
+        ];
+        for (source, expected) in cases {
+            let error = check_script(source, &state).unwrap_err();
+            assert!(error.contains(expected), "{source:?}: {error}");
+        }
+    }
+
+    #[test]
+    fn check_rejects_unknown_literal_ids() {
+        let state = world_builder::build_thornhold();
+        let error = check_script("give_item(\"golden_goose\")", &state).unwrap_err();
+        assert!(error.contains("item 'golden_goose'"));
+        let error = check_script("set_npc_state(\"the_warden\", \"sulking\")", &state).unwrap_err();
+        assert!(error.contains("npc state 'sulking'"));
+        assert!(check_script("set_npc_state(\"the_warden\", \"familiar\")", &state).is_ok());
+        let error = check_script("change_reputation(\"crypt_cult\", 5)", &state).unwrap_err();
+        assert!(error.contains("faction 'crypt_cult'"));
+    }
+
+    #[test]
+    fn scripts_read_and_change_the_world() {
+        let mut state = world_builder::build_thornhold();
+        let messages = run(
+            r#"
+            if not flag("lever") and location() == "courtyard" {
+                set_flag("lever")
+                give_item("stale_bread")
+                set_hostile("merchant_ghost", true)
+                say("Click.")
+            } else {
+                say("Nothing.")
+            }
+            "#,
+            &mut state,
+        );
+        assert!(messages.iter().any(|m| m.text == "Click."));
+        assert_eq!(state.player.quest_flags.get("lever"), Some(&true));
+        assert!(state.player.inventory.contains(&"stale_bread".to_string()));
+        assert_eq!(
+            state.npcs["merchant_ghost"].dialogue_state,
+            DialogueState::Hostile
+        );
+
+        let messages = run(
+            "if npc_state(\"merchant_ghost\") == \"hostile\" { say(\"Boo\") }",
+            &mut state,
+        );
+        assert_eq!(messages[0].text, "Boo");
+    }
+
+    #[test]
+    fn scripts_read_and_change_reputation() {
+        let mut state = world_builder::build_thornhold();
+        run("change_reputation(\"faithful_dead\", 20)", &mut state);
+        assert_eq!(state.factions["faithful_dead"].reputation, 20);
+        assert_eq!(state.factions["wardens_host"].reputation, -70);
+
+        let messages = run(
+            "if reputation(\"faithful_dead\") >= 20 { say(\"Welcome\") }",
+            &mut state,
+        );
+        assert_eq!(messages.last().unwrap().text, "Welcome");
+    }
+
+    #[test]
+    fn loops_and_arithmetic() {
+        let mut state = WorldState::default();
+        let messages = run(
+            "let total = 0; let i = 1\nwhile i <= 4 { total = total + i * 2; i = i + 1 }\nsay(\"total \" + total)",
+            &mut state,
+        );
+        assert_eq!(messages[0].text, "total 20");
+    }
+
+    #[test]
+    fn runaway_loop_hits_step_limit() {
+        let mut state = WorldState::default();
+        let messages = run(
+            "say(\"before\")\nwhile true { set_flag(\"spin\") }",
+            &mut state,
+        );
+        assert_eq!(messages[0].text, "before");
+        assert_eq!(messages[1].line_type, LineType::Error);
+        assert!(messages[1].text.contains("step limit"));
+    }
+
+    #[test]
+    fn doubling_strings_hits_length_limit() {
+        let mut state = WorldState::default();
+        let messages = run("let s = \"ab\"\nwhile true { s = s + s }", &mut state);
+        assert_eq!(messages.len(), 1);
+        assert!(messages[0].text.contains("string longer than 4000 bytes"));
+    }
+
+    #[test]
+    fn runtime_errors_stop_the_script() {
+        let mut state = WorldState::default();
+        let messages = run("let x = 0\nsay(\"a\")\nsay(10 / x)\nsay(\"b\")", &mut state);
+        assert_eq!(messages.len(), 2);
+        assert!(messages[1].text.contains("line 3: division by zero"));
+    }
+
+    #[test]
+    fn return_ends_early_and_arg_is_available() {
+        let mut state = WorldState::default();
+        state.scripts.insert(
+            "echo".into(),
+            "if arg == \"\" { return }\nsay(\"You said \" + arg)".into(),
+        );
+        let mut messages = Vec::new();
+        run_script("echo", "", &mut state, &mut messages);
+        assert!(messages.is_empty());
+        run_script("echo", "hello", &mut state, &mut messages);
+        assert_eq!(messages[0].text, "You said hello");
+    }
+
+    #[test]
+    fn custom_verb_runs_only_where_it_belongs() {
+        let mut state = world_builder::build_thornhold();
+        assert!(match_custom_verb("pray", &state).is_none());
+
+        state.player.location = "chapel".into();
+        let (script, arg) = match_custom_verb("kneel before altar", &state).unwrap();
+        assert_eq!(script, "chapel_prayer");
+        assert_eq!(arg, "before altar");
+    }
+
+    #[test]
+    fn event_action_runs_script() {
+        let mut state = WorldState::default();
+        state.scripts.insert("bell".into(), "say(\"Bong.\")".into());
+        let mut messages = Vec::new();
+        events::apply_action(
+            EventAction::RunScript("bell".into()),
+            "",
+            &mut state,
+            &mut messages,
+        );
+        assert_eq!(messages[0].text, "Bong.");
+    }
+}
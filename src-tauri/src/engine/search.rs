@@ -0,0 +1,244 @@
+use crate::engine::{equipment, events, random, stealth, templates};
+use crate::models::*;
+
+/// Indices of the features in the player's room not yet found.
+fn undiscovered(state: &WorldState) -> Vec<usize> {
+    let Some(loc) = state.locations.get(&state.player.location) else {
+        return Vec::new();
+    };
+    (0..loc.secrets.len())
+        .filter(|&idx| !loc.discovered_secrets.contains(&loc.secrets[idx].id))
+        .collect()
+}
+
+fn feature(idx: usize, state: &WorldState) -> Option<&HiddenFeature> {
+    state.locations.get(&state.player.location)?.secrets.get(idx)
+}
+
+/// Whether the player carries an item that gives the feature away.
+fn revealed(feature: &HiddenFeature, state: &WorldState) -> bool {
+    feature
+        .revealed_by
+        .iter()
+        .any(|id| equipment::carried_position(id, state).is_some())
+}
+
+/// Marks a feature of the player's room as found and brings it into play:
+/// exits open, items turn up and traps can be stepped around.
+fn discover(idx: usize, state: &mut WorldState, messages: &mut Vec<OutputLine>) {
+    let Some(loc) = state.locations.get_mut(&state.player.location) else {
+        return;
+    };
+    let feature = loc.secrets[idx].clone();
+    loc.discovered_secrets.push(feature.id);
+    match feature.kind {
+        HiddenKind::Exit(direction, dest) => {
+            loc.exits.insert(direction, dest);
+        }
+        HiddenKind::Item(item_id) => loc.items.push(item_id),
+        HiddenKind::Trap(_) => {}
+    }
+    messages.push(OutputLine {
+        text: feature.found_text,
+        line_type: LineType::Narration,
+    });
+}
+
+/// Marks a trap spent and runs its actions.
+fn set_off(idx: usize, disarmed: bool, state: &mut WorldState, messages: &mut Vec<OutputLine>) {
+    let loc_id = state.player.location.clone();
+    let Some(loc) = state.locations.get_mut(&loc_id) else {
+        return;
+    };
+    let feature = &mut loc.secrets[idx];
+    let HiddenKind::Trap(trap) = &mut feature.kind else {
+        return;
+    };
+    trap.spent = true;
+    let (text, actions) = if disarmed {
+        (templates::describe_trap_disarmed(&trap.name), trap.on_disarm.clone())
+    } else {
+        (templates::describe_trap_sprung(&trap.name), trap.on_trigger.clone())
+    };
+    if !loc.discovered_secrets.contains(&feature.id) {
+        loc.discovered_secrets.push(feature.id.clone());
+    }
+    messages.push(OutputLine {
+        text,
+        line_type: if disarmed {
+            LineType::System
+        } else {
+            LineType::Combat
+        },
+    });
+    for action in actions {
+        events::apply_action(action, &loc_id, state, messages);
+    }
+}
+
+/// On entering a room: carried items give away what they reveal, then any
+/// trap still hidden springs.
+pub fn enter_room(state: &mut WorldState) -> Vec<OutputLine> {
+    let mut messages = Vec::new();
+    for idx in undiscovered(state) {
+        if feature(idx, state).is_some_and(|f| revealed(f, state)) {
+            discover(idx, state, &mut messages);
+        }
+    }
+    for idx in undiscovered(state) {
+        if matches!(feature(idx, state).map(|f| &f.kind), Some(HiddenKind::Trap(t)) if !t.spent) {
+            set_off(idx, false, state, &mut messages);
+        }
+    }
+    messages
+}
+
+/// Searches the player's room, rolling for each feature not yet found. A
+/// dark room hides things twice as well unless the player carries a light.
+pub fn search(state: &mut WorldState) -> Vec<OutputLine> {
+    let dark = state
+        .locations
+        .get(&state.player.location)
+        .is_some_and(|loc| loc.ambient_mood == Mood::Dark)
+        && !stealth::carrying_light(state);
+    let mut rng = random::world_rng(state);
+    let found: Vec<usize> = undiscovered(state)
+        .into_iter()
+        .filter(|&idx| {
+            let Some(feature) = feature(idx, state) else {
+                return false;
+            };
+            let chance = if dark {
+                feature.find_chance / 2
+            } else {
+                feature.find_chance
+            };
+            revealed(feature, state) || random::roll_percent(&mut rng, chance)
+        })
+        .collect();
+
+    let mut messages = Vec::new();
+    for idx in found {
+        discover(idx, state, &mut messages);
+    }
+    if messages.is_empty() {
+        messages.push(OutputLine {
+            text: "You search carefully but find nothing out of the ordinary.".into(),
+            line_type: LineType::Narration,
+        });
+    }
+    messages
+}
+
+/// Tries to disarm a trap the player has found in their room. A botched
+/// attempt springs it.
+pub fn disarm(target: &str, state: &mut WorldState) -> Result<Vec<OutputLine>, String> {
+    let target = target.to_lowercase();
+    let loc = state
+        .locations
+        .get(&state.player.location)
+        .ok_or("You are nowhere.")?;
+    let found = loc.secrets.iter().enumerate().find_map(|(idx, s)| match &s.kind {
+        HiddenKind::Trap(trap)
+            if !trap.spent
+                && loc.discovered_secrets.contains(&s.id)
+                && trap.name.to_lowercase().contains(&target) =>
+        {
+            Some((idx, trap.disarm_chance))
+        }
+        _ => None,
+    });
+    let Some((idx, chance)) = found else {
+        return Err(format!("You haven't found a trap called '{}' here.", target));
+    };
+    let mut rng = random::world_rng(state);
+    let disarmed = random::roll_percent(&mut rng, chance);
+    let mut messages = Vec::new();
+    set_off(idx, disarmed, state, &mut messages);
+    Ok(messages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::world_builder;
+
+    fn trap(find_chance: u32, disarm_chance: u32) -> HiddenFeature {
+        HiddenFeature {
+            id: "tripwire".into(),
+            kind: HiddenKind::Trap(Trap {
+                name: "Tripwire".into(),
+                on_trigger: vec![EventAction::Damage(10)],
+                on_disarm: vec![EventAction::GiveItem("torch".into())],
+                disarm_chance,
+                spent: false,
+            }),
+            find_chance,
+            revealed_by: vec!["lit_lantern".into()],
+            found_text: "A wire glints across the floor.".into(),
+        }
+    }
+
+    fn place(feature: HiddenFeature, state: &mut WorldState) {
+        let loc = state.locations.get_mut(&state.player.location).unwrap();
+        loc.secrets.push(feature);
+    }
+
+    #[test]
+    fn searching_turns_up_exits_and_items() {
+        let mut state = world_builder::build_thornhold();
+        place(
+            HiddenFeature {
+                id: "loose_flagstone".into(),
+                kind: HiddenKind::Item("torch".into()),
+                find_chance: 100,
+                revealed_by: vec![],
+                found_text: "Under a loose flagstone lies a torch.".into(),
+            },
+            &mut state,
+        );
+        place(
+            HiddenFeature {
+                id: "grate".into(),
+                kind: HiddenKind::Exit(Direction::Down, "wine_cellar".into()),
+                find_chance: 0,
+                revealed_by: vec![],
+                found_text: "A grate leads down.".into(),
+            },
+            &mut state,
+        );
+
+        let msgs = search(&mut state);
+        assert_eq!(msgs[0].text, "Under a loose flagstone lies a torch.");
+        let courtyard = &state.locations["courtyard"];
+        assert!(courtyard.items.contains(&"torch".to_string()));
+        assert_eq!(courtyard.discovered_secrets, vec!["loose_flagstone".to_string()]);
+        assert!(!courtyard.exits.contains_key(&Direction::Down));
+
+        // Found things stay found; the grate never turns up
+        let msgs = search(&mut state);
+        assert!(msgs[0].text.contains("find nothing"));
+    }
+
+    #[test]
+    fn hidden_traps_spring_unless_given_away() {
+        let mut state = world_builder::build_thornhold();
+        place(trap(0, 100), &mut state);
+        let msgs = enter_room(&mut state);
+        assert_eq!(msgs[0].text, templates::describe_trap_sprung("Tripwire"));
+        assert_eq!(state.player.health, 90);
+        // Spent traps stay quiet
+        assert!(enter_room(&mut state).is_empty());
+
+        let mut state = world_builder::build_thornhold();
+        place(trap(0, 100), &mut state);
+        state.player.inventory.push("lit_lantern".into());
+        let msgs = enter_room(&mut state);
+        assert_eq!(msgs[0].text, "A wire glints across the floor.");
+        assert_eq!(state.player.health, 100);
+
+        assert!(disarm("wire", &mut state).is_ok());
+        assert!(state.player.inventory.contains(&"torch".to_string()));
+        assert!(disarm("wire", &mut state).is_err());
+    }
+}
@@ -0,0 +1,136 @@
+use crate::engine::{companions, random};
+use crate::models::*;
+
+/// Damage multiplier for a blow against an enemy that hasn't noticed the
+/// player.
+pub const SNEAK_ATTACK_MULTIPLIER: f64 = 2.0;
+
+pub fn carrying_light(state: &WorldState) -> bool {
+    state
+        .player
+        .inventory
+        .iter()
+        .any(|id| state.items.get(id).is_some_and(|item| item.light))
+}
+
+/// Stealth gained from shadow: dark rooms and the night hide the player,
+/// unless they carry a light.
+pub fn darkness(state: &WorldState) -> i32 {
+    if carrying_light(state) {
+        return 0;
+    }
+    let dark_room = state
+        .locations
+        .get(&state.player.location)
+        .is_some_and(|loc| loc.ambient_mood == Mood::Dark);
+    let phase = match state.clock.phase() {
+        DayPhase::Day => 0,
+        DayPhase::Dawn | DayPhase::Dusk => 1,
+        DayPhase::Night => 3,
+    };
+    if dark_room {
+        phase + 3
+    } else {
+        phase
+    }
+}
+
+/// Stealth lost to worn armor: heavier pieces defend more and clank louder,
+/// so each point of armor defense costs a point.
+pub fn armor_penalty(state: &WorldState) -> i32 {
+    state
+        .player
+        .equipped_items()
+        .into_iter()
+        .filter_map(|(_, id)| state.items.get(id))
+        .filter(|item| item.item_type == ItemType::Armor)
+        .filter_map(|item| item.modifier.as_ref())
+        .map(|modifier| modifier.defense.max(0))
+        .sum()
+}
+
+pub fn stealth_rating(state: &WorldState) -> i32 {
+    state.player.agility + darkness(state) - armor_penalty(state)
+}
+
+/// Percent chance that each hostile nearby notices the player.
+pub fn detection_chance(state: &WorldState) -> u32 {
+    (60 - 5 * stealth_rating(state)).clamp(5, 95) as u32
+}
+
+/// Living hostiles in the player's room, companions aside.
+pub fn hostiles_here(state: &WorldState) -> Vec<String> {
+    state
+        .locations
+        .get(&state.player.location)
+        .map(|loc| {
+            loc.npcs
+                .iter()
+                .filter(|id| {
+                    !companions::is_companion(id, state)
+                        && state
+                            .npcs
+                            .get(*id)
+                            .is_some_and(|n| n.hostile && n.dialogue_state != DialogueState::Dead)
+                })
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Every hostile in the player's room rolls to notice them. Returns the
+/// first that does.
+pub fn detected_by(state: &mut WorldState) -> Option<String> {
+    let chance = detection_chance(state);
+    let hostiles = hostiles_here(state);
+    let mut rng = random::world_rng(state);
+    hostiles
+        .into_iter()
+        .find(|_| random::roll_percent(&mut rng, chance))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::world_builder;
+
+    #[test]
+    fn darkness_and_armor_shape_the_rating() {
+        let mut state = world_builder::build_thornhold();
+        assert_eq!(stealth_rating(&state), 5);
+        assert_eq!(detection_chance(&state), 35);
+
+        state.player.location = "wine_cellar".into();
+        state.clock.minutes = 22 * 60;
+        assert_eq!(darkness(&state), 6);
+        state.player.inventory.push("lit_lantern".into());
+        assert_eq!(darkness(&state), 0);
+
+        let armor_id = state
+            .items
+            .values()
+            .find(|i| i.item_type == ItemType::Armor && i.modifier.is_some_and(|m| m.defense > 0))
+            .map(|i| i.id.clone())
+            .unwrap();
+        let defense = state.items[&armor_id].modifier.unwrap().defense;
+        state.player.equipped_armor = Some(armor_id);
+        assert_eq!(stealth_rating(&state), 5 - defense);
+    }
+
+    #[test]
+    fn only_living_hostiles_roll_to_notice() {
+        let mut state = world_builder::build_thornhold();
+        state.player.location = "barracks".into();
+        assert_eq!(hostiles_here(&state), vec!["skeletal_guard".to_string()]);
+        state.player.agility = -20;
+        let spotter = (0..20).find_map(|_| detected_by(&mut state));
+        assert_eq!(spotter.as_deref(), Some("skeletal_guard"));
+
+        state.player.agility = 50;
+        assert_eq!(detection_chance(&state), 5);
+        state.npcs.get_mut("skeletal_guard").unwrap().dialogue_state = DialogueState::Dead;
+        assert!(hostiles_here(&state).is_empty());
+        assert!(detected_by(&mut state).is_none());
+    }
+}
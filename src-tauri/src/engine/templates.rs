@@ -1,3 +1,4 @@
+use crate::engine::equipment;
 use crate::models::*;
 use std::collections::HashMap;
 
@@ -5,17 +6,22 @@ pub fn describe_location(
     location: &Location,
     items: &HashMap<String, Item>,
     npcs: &HashMap<String, Npc>,
+    doors: &HashMap<String, Door>,
+    fixtures: &HashMap<String, Fixture>,
     first_visit: bool,
+    phase: DayPhase,
 ) -> Vec<String> {
     let mut lines = Vec::new();
+    // A description for this time of day replaces the usual one
+    let variant = location.phase_descriptions.get(&phase);
 
     if first_visit {
         lines.push(format!("--- {} ---", location.name));
-        lines.push(location.description.clone());
+        lines.push(variant.unwrap_or(&location.description).clone());
     } else {
         lines.push(format!("--- {} (revisited) ---", location.name));
-        if let Some(revisit) = &location.revisit_description {
-            lines.push(revisit.clone());
+        if let Some(text) = variant.or(location.revisit_description.as_ref()) {
+            lines.push(text.clone());
         } else {
             lines.push(location.description.clone());
         }
@@ -49,6 +55,30 @@ pub fn describe_location(
         lines.push(format!("Present: {}", npc_names.join(", ")));
     }
 
+    // Traps the player has found but not yet dealt with
+    let trap_names: Vec<&str> = location
+        .secrets
+        .iter()
+        .filter(|s| location.discovered_secrets.contains(&s.id))
+        .filter_map(|s| match &s.kind {
+            HiddenKind::Trap(trap) if !trap.spent => Some(trap.name.as_str()),
+            _ => None,
+        })
+        .collect();
+    if !trap_names.is_empty() {
+        lines.push(format!("Traps: {}", trap_names.join(", ")));
+    }
+
+    // Levers, locks and riddles fixed in place
+    let fixture_names: Vec<String> = location
+        .fixtures
+        .iter()
+        .filter_map(|id| fixtures.get(id).map(describe_fixture_state))
+        .collect();
+    if !fixture_names.is_empty() {
+        lines.push(format!("Fixtures: {}", fixture_names.join(", ")));
+    }
+
     // Exits
     let mut exit_strs: Vec<String> = location
         .exits
@@ -56,6 +86,8 @@ pub fn describe_location(
         .map(|d| {
             if location.locked_exits.contains_key(d) {
                 format!("{} (locked)", d.display_name())
+            } else if let Some(door) = location.doors.get(d).and_then(|id| doors.get(id)) {
+                format!("{} ({}, {})", d.display_name(), door.name, door.state.display_name())
             } else {
                 d.display_name().to_string()
             }
@@ -81,6 +113,14 @@ pub fn describe_use(item_name: &str, effect: &str) -> String {
     format!("You use the {}. {}", item_name, effect)
 }
 
+pub fn describe_container_opened(container_name: &str, contents: &[String]) -> String {
+    if contents.is_empty() {
+        format!("You open the {}. It's empty.", container_name)
+    } else {
+        format!("You open the {}. Inside you find: {}", container_name, contents.join(", "))
+    }
+}
+
 pub fn describe_equip(item_name: &str) -> String {
     format!("You equip the {}.", item_name)
 }
@@ -89,6 +129,21 @@ pub fn describe_unequip(item_name: &str) -> String {
     format!("You unequip the {}.", item_name)
 }
 
+pub fn describe_item_worn(item_name: &str) -> String {
+    format!("Your {} is badly worn and close to breaking.", item_name)
+}
+
+pub fn describe_item_broken(item_name: &str) -> String {
+    format!("Your {} breaks! It's useless until repaired.", item_name)
+}
+
+pub fn describe_repair(item_name: &str, durability: &Durability) -> String {
+    format!(
+        "The {} is repaired. (Condition: {}/{})",
+        item_name, durability.current, durability.max
+    )
+}
+
 pub fn describe_combat_attack(
     attacker: &str,
     defender: &str,
@@ -177,6 +232,161 @@ pub fn describe_quest_completed(quest: &Quest) -> String {
     format!("Quest Complete: {}!", quest.name)
 }
 
+pub fn describe_quest_failed(quest: &Quest, reason: &str) -> String {
+    format!("Quest Failed: {} — {}", quest.name, reason)
+}
+
+pub fn describe_reputation_change(faction: &Faction, change: i32) -> String {
+    format!(
+        "Reputation with {}: {:+} ({})",
+        faction.name,
+        change,
+        faction.tier().display_name()
+    )
+}
+
+pub fn describe_npc_leaves(name: &str, direction: Option<&Direction>) -> String {
+    match direction {
+        Some(Direction::Up) => format!("{} climbs up and out of sight.", name),
+        Some(Direction::Down) => format!("{} heads down and out of sight.", name),
+        Some(dir) => format!("{} leaves to the {}.", name, dir.display_name()),
+        None => format!("{} leaves.", name),
+    }
+}
+
+pub fn describe_npc_arrives(name: &str, direction: Option<&Direction>) -> String {
+    match direction {
+        Some(Direction::Up) => format!("{} arrives from above.", name),
+        Some(Direction::Down) => format!("{} arrives from below.", name),
+        Some(dir) => format!("{} arrives from the {}.", name, dir.display_name()),
+        None => format!("{} arrives.", name),
+    }
+}
+
+pub fn describe_phase_change(phase: DayPhase) -> &'static str {
+    match phase {
+        DayPhase::Dawn => "Dawn breaks, grey light creeping over the horizon.",
+        DayPhase::Day => "The sun climbs into the sky.",
+        DayPhase::Dusk => "Dusk settles in, and the shadows grow long.",
+        DayPhase::Night => "Night falls.",
+    }
+}
+
+pub fn describe_rest(turns: u32, healed: i32, player: &Player) -> String {
+    let span = if turns == 1 {
+        "a turn".to_string()
+    } else {
+        format!("{} turns", turns)
+    };
+    if healed > 0 {
+        format!(
+            "You rest for {} and recover {} HP. (HP: {}/{})",
+            span, healed, player.health, player.max_health
+        )
+    } else {
+        format!("You rest for {}. (HP: {}/{})", span, player.health, player.max_health)
+    }
+}
+
+pub fn describe_time(clock: &WorldClock) -> String {
+    format!(
+        "Day {}, {:02}:{:02} ({})",
+        clock.day(),
+        clock.hour(),
+        clock.minute(),
+        clock.phase().display_name()
+    )
+}
+
+pub fn describe_encounter(name: &str) -> String {
+    format!("{} emerges from the shadows!", name)
+}
+
+pub fn describe_unnoticed(names: &[String]) -> String {
+    let verb = if names.len() == 1 { "hasn't" } else { "haven't" };
+    format!("{} {} noticed you.", names.join(", "), verb)
+}
+
+pub fn describe_stealth(rating: i32, hidden: bool) -> String {
+    if hidden {
+        format!("Stealth: {} (hidden)", rating)
+    } else {
+        format!("Stealth: {}", rating)
+    }
+}
+
+pub fn describe_spotted(name: &str) -> String {
+    format!("{} spots you!", name)
+}
+
+pub fn describe_sneak_attack(name: &str) -> String {
+    format!("You strike {} from the shadows!", name)
+}
+
+pub fn describe_trap_sprung(name: &str) -> String {
+    format!("Click! You set off a hidden trap: {}!", name)
+}
+
+pub fn describe_trap_disarmed(name: &str) -> String {
+    format!("You carefully disarm the {}.", name)
+}
+
+pub fn describe_fixture_moved(verb: &str, name: &str, position: &str) -> String {
+    format!("You {} the {}. It now stands {}.", verb, name, position)
+}
+
+pub fn describe_fixture_set(name: &str, combination: &str) -> String {
+    format!("You set the {} to {}.", name, combination)
+}
+
+pub fn describe_riddle(question: &str) -> String {
+    format!("A riddle: \"{}\"", question)
+}
+
+pub fn describe_riddle_answer(correct: bool) -> String {
+    if correct {
+        "That is the answer!".to_string()
+    } else {
+        "That is not the answer.".to_string()
+    }
+}
+
+pub fn describe_puzzle_solved() -> String {
+    "Somewhere, something gives way with a heavy clunk.".to_string()
+}
+
+/// A fixture as listed in a room: its name and where it stands.
+fn describe_fixture_state(fixture: &Fixture) -> String {
+    match (&fixture.kind, fixture.current()) {
+        (FixtureKind::Riddle { .. }, RIDDLE_SOLVED) => format!("{} (solved)", fixture.name),
+        (_, "") | (FixtureKind::Riddle { .. }, _) => fixture.name.clone(),
+        (_, state) => format!("{} ({})", fixture.name, state),
+    }
+}
+
+pub fn describe_examine_fixture(fixture: &Fixture) -> Vec<String> {
+    let mut lines = vec![format!("--- {} ---", fixture.name)];
+    if !fixture.description.is_empty() {
+        lines.push(fixture.description.clone());
+    }
+    match &fixture.kind {
+        FixtureKind::Lever { positions } => lines.push(format!(
+            "It can stand {}; it stands {}.",
+            positions.join(", "),
+            fixture.current()
+        )),
+        FixtureKind::Combination { length } if fixture.state.is_empty() => {
+            lines.push(format!("It takes a combination of {} digits.", length))
+        }
+        FixtureKind::Combination { .. } => lines.push(format!("It is set to {}.", fixture.state)),
+        FixtureKind::Riddle { .. } if fixture.state == RIDDLE_SOLVED => {
+            lines.push("Its riddle has been answered.".to_string())
+        }
+        FixtureKind::Riddle { question, .. } => lines.push(describe_riddle(question)),
+    }
+    lines
+}
+
 pub fn describe_event_message(message: &str) -> String {
     message.to_string()
 }
@@ -196,12 +406,20 @@ pub fn describe_inventory(player: &Player, items: &HashMap<String, Item>) -> Vec
     if player.inventory.is_empty() {
         lines.push("Your inventory is empty.".to_string());
     } else {
+        let mut listed = std::collections::HashSet::new();
         for item_id in &player.inventory {
+            if !listed.insert(item_id) {
+                continue;
+            }
             if let Some(item) = items.get(item_id) {
                 let mut desc = item.name.clone();
+                let count = player.inventory.iter().filter(|id| *id == item_id).count();
+                if count > 1 {
+                    desc.push_str(&format!(" x{}", count));
+                }
                 if player.equipped_weapon.as_ref() == Some(item_id) {
                     desc.push_str(" (wielded)");
-                } else if player.equipped_armor.as_ref() == Some(item_id) {
+                } else if player.is_equipped(item_id) {
                     desc.push_str(" (worn)");
                 }
                 lines.push(format!("  - {}", desc));
@@ -211,35 +429,40 @@ pub fn describe_inventory(player: &Player, items: &HashMap<String, Item>) -> Vec
     lines
 }
 
-pub fn describe_stats(player: &Player, items: &HashMap<String, Item>) -> Vec<String> {
+pub fn describe_stats(
+    player: &Player,
+    items: &HashMap<String, Item>,
+    item_sets: &HashMap<String, ItemSet>,
+    clock: &WorldClock,
+) -> Vec<String> {
     let mut lines = Vec::new();
-    let weapon_bonus = player
-        .equipped_weapon
-        .as_ref()
-        .and_then(|id| items.get(id))
-        .and_then(|i| i.modifier.as_ref())
-        .map(|m| m.attack)
-        .unwrap_or(0);
-    let armor_bonus = player
-        .equipped_armor
-        .as_ref()
-        .and_then(|id| items.get(id))
-        .and_then(|i| i.modifier.as_ref())
-        .map(|m| m.defense)
-        .unwrap_or(0);
+    let gear = equipment::equipment_bonus(player, items, item_sets);
 
     lines.push(format!("HP: {}/{}", player.health, player.max_health));
-    lines.push(format!(
-        "Attack: {} (+{})",
-        player.attack + weapon_bonus,
-        weapon_bonus
-    ));
-    lines.push(format!(
-        "Defense: {} (+{})",
-        player.defense + armor_bonus,
-        armor_bonus
-    ));
+    lines.push(format!("Attack: {} ({:+})", player.attack + gear.attack, gear.attack));
+    lines.push(format!("Defense: {} ({:+})", player.defense + gear.defense, gear.defense));
+    lines.push("Equipment:".to_string());
+    for slot in EquipSlot::ALL {
+        let name = match player.equipped(slot).and_then(|id| items.get(id)) {
+            Some(item) if item.is_broken() => format!("{} (broken)", item.name),
+            Some(item) => item.name.clone(),
+            None => "(empty)".to_string(),
+        };
+        lines.push(format!("  {}: {}", slot.display_name(), name));
+    }
+    for (set_id, worn) in equipment::worn_set_pieces(player, items) {
+        let Some(set) = item_sets.get(&set_id) else {
+            continue;
+        };
+        let stats = describe_modifier(&equipment::set_bonus(set, worn));
+        if stats.is_empty() {
+            lines.push(format!("Set: {} ({} pieces)", set.name, worn));
+        } else {
+            lines.push(format!("Set: {} ({} pieces): {}", set.name, worn, stats.join(", ")));
+        }
+    }
     lines.push(format!("Turns: {}", player.turns_elapsed));
+    lines.push(format!("Time: {}", describe_time(clock)));
     lines
 }
 
@@ -284,6 +507,17 @@ pub fn describe_locked_door(direction: &Direction) -> String {
     )
 }
 
+pub fn describe_sealed_exit(direction: &Direction) -> String {
+    format!(
+        "The way {} is sealed. No key will open it.",
+        direction.display_name()
+    )
+}
+
+pub fn describe_door_locked(name: &str) -> String {
+    format!("The {} is locked.", name)
+}
+
 pub fn describe_door_unlocked(direction: &Direction, key_name: &str) -> String {
     format!(
         "You use the {} to unlock the way {}.",
@@ -292,25 +526,70 @@ pub fn describe_door_unlocked(direction: &Direction, key_name: &str) -> String {
     )
 }
 
-pub fn describe_examine_item(item: &Item) -> Vec<String> {
+fn describe_modifier(modifier: &StatModifier) -> Vec<String> {
+    let mut stats = Vec::new();
+    if modifier.attack != 0 {
+        stats.push(format!("Attack {:+}", modifier.attack));
+    }
+    if modifier.defense != 0 {
+        stats.push(format!("Defense {:+}", modifier.defense));
+    }
+    if modifier.health != 0 {
+        stats.push(format!("Health {:+}", modifier.health));
+    }
+    stats
+}
+
+pub fn describe_special_effect(effect: &SpecialEffect) -> String {
+    match effect {
+        SpecialEffect::Poison {
+            damage_per_turn,
+            turns,
+        } => format!(
+            "On hit: poisons for {} damage a turn over {} turns",
+            damage_per_turn, turns
+        ),
+        SpecialEffect::Lifesteal { percent } => {
+            format!("On hit: drains {}% of damage dealt as health", percent)
+        }
+    }
+}
+
+pub fn describe_examine_item(item: &Item, show_condition: bool) -> Vec<String> {
     let mut lines = Vec::new();
     lines.push(format!("--- {} ---", item.name));
     lines.push(item.description.clone());
+    if item.rarity != Rarity::Common {
+        lines.push(format!("Rarity: {}", item.rarity.display_name()));
+    }
+    if let Some(slot) = item.equip_slot() {
+        lines.push(format!("Slot: {}", slot.display_name()));
+    }
+    if let Some(durability) = item.durability.filter(|_| show_condition) {
+        lines.push(format!(
+            "Condition: {} ({}/{})",
+            durability.condition(),
+            durability.current,
+            durability.max
+        ));
+    }
     if let Some(modifier) = &item.modifier {
-        let mut stats = Vec::new();
-        if modifier.attack != 0 {
-            stats.push(format!("Attack {:+}", modifier.attack));
-        }
-        if modifier.defense != 0 {
-            stats.push(format!("Defense {:+}", modifier.defense));
-        }
-        if modifier.health != 0 {
-            stats.push(format!("Health {:+}", modifier.health));
-        }
+        let stats = describe_modifier(modifier);
         if !stats.is_empty() {
             lines.push(format!("Stats: {}", stats.join(", ")));
         }
     }
+    for affix in &item.affixes {
+        let stats = describe_modifier(&affix.modifier);
+        if stats.is_empty() {
+            lines.push(format!("Affix: {}", affix.name));
+        } else {
+            lines.push(format!("Affix: {} ({})", affix.name, stats.join(", ")));
+        }
+    }
+    for effect in &item.special_effects {
+        lines.push(describe_special_effect(effect));
+    }
     if let Some(lore) = &item.lore {
         lines.push(format!("Lore: {}", lore));
     }
@@ -359,19 +638,38 @@ pub fn describe_help(game_mode: &GameMode) -> Vec<String> {
             lines.push("  use <item>    - Use an item".to_string());
             lines.push("  flee          - Try to escape".to_string());
             lines.push("  inventory     - Check your items".to_string());
+            lines.push("  stats         - Check your equipment".to_string());
         }
         GameMode::InDialogue(_) => {
             lines.push("Dialogue mode:".to_string());
             lines.push("  Type your response to speak".to_string());
+            lines.push("  1, 2, ...     - Pick a numbered reply".to_string());
+            lines.push("  ask about <topic> / tell about <topic>".to_string());
+            lines.push("  answer <text> - Answer a riddle".to_string());
             lines.push("  leave/goodbye - End conversation".to_string());
             lines.push("  inventory     - Check your items".to_string());
         }
         _ => {
             lines.push("Movement:  go <direction>, north/south/east/west/up/down".to_string());
+            lines.push("Stealth:   sneak <direction>, hide, attack from hiding".to_string());
+            lines.push("Explore:   search, disarm <trap>".to_string());
+            lines.push("Doors:     open/close/pick/bash <door>".to_string());
+            lines.push(
+                "Puzzles:   pull/push <lever>, set <lock> to <code>, answer <reply>".to_string(),
+            );
             lines.push("Look:      look, examine <target>".to_string());
-            lines.push("Items:     take/drop/use/equip/unequip <item>".to_string());
+            lines.push("Items:     take/drop/use/open/equip/unequip/repair <item>".to_string());
             lines.push("Interact:  talk to <npc>, attack <target>".to_string());
-            lines.push("Info:      inventory, map, quests, help".to_string());
+            lines.push("Topics:    ask/tell <npc> about <topic>".to_string());
+            lines.push(
+                "Party:     recruit/dismiss <npc>, give <item> to <npc>, tell <npc> to wait/follow"
+                    .to_string(),
+            );
+            lines.push("Rest:      rest, sleep [turns]".to_string());
+            lines.push(
+                "Info:      inventory, stats, time, map, quests, codex, reputation, help"
+                    .to_string(),
+            );
             lines.push("Game:      save [name], load [name]".to_string());
         }
     }
@@ -397,6 +695,7 @@ mod tests {
             ambient_mood: Mood::Peaceful,
                 examine_details: None,
                 revisit_description: None,
+            ..Default::default()
         };
         let mut items = HashMap::new();
         items.insert(
@@ -411,6 +710,7 @@ mod tests {
                 consumable: false,
                 key_id: None,
                 lore: None,
+                ..Default::default()
             },
         );
         let mut npcs = HashMap::new();
@@ -432,10 +732,19 @@ mod tests {
                 examine_text: None,
                 relationship: 0,
                 memory: vec![],
+                ..Default::default()
             },
         );
 
-        let lines = describe_location(&loc, &items, &npcs, true);
+        let lines = describe_location(
+            &loc,
+            &items,
+            &npcs,
+            &HashMap::new(),
+            &HashMap::new(),
+            true,
+            DayPhase::Day,
+        );
         assert!(lines[0].contains("Test Room"));
         assert!(lines[1].contains("A small room"));
         assert!(lines.iter().any(|l| l.contains("Short Sword")));
@@ -454,4 +763,68 @@ mod tests {
         let dialogue = describe_help(&GameMode::InDialogue("npc".into()));
         assert!(dialogue.iter().any(|l| l.contains("Dialogue")));
     }
+
+    #[test]
+    fn describe_examine_explains_affixes() {
+        let item = Item {
+            id: "short_sword_gen1".into(),
+            name: "Venomous Short Sword".into(),
+            description: "A well-balanced blade.".into(),
+            item_type: ItemType::Weapon,
+            modifier: Some(StatModifier {
+                attack: 4,
+                defense: 0,
+                health: 0,
+            }),
+            usable: false,
+            consumable: false,
+            key_id: None,
+            lore: None,
+            rarity: Rarity::Uncommon,
+            affixes: vec![Affix {
+                id: "venomous".into(),
+                name: "Venomous".into(),
+                kind: AffixKind::Prefix,
+                modifier: StatModifier {
+                    attack: 1,
+                    defense: 0,
+                    health: 0,
+                },
+                effect: Some(SpecialEffect::Poison {
+                    damage_per_turn: 2,
+                    turns: 3,
+                }),
+                min_rarity: Rarity::Uncommon,
+                item_types: vec![ItemType::Weapon],
+                weight: 1,
+            }],
+            special_effects: vec![SpecialEffect::Poison {
+                damage_per_turn: 2,
+                turns: 3,
+            }],
+            ..Default::default()
+        };
+        let lines = describe_examine_item(&item, true);
+        assert!(lines.contains(&"Rarity: Uncommon".to_string()));
+        assert!(lines.contains(&"Affix: Venomous (Attack +1)".to_string()));
+        assert!(lines.iter().any(|l| l.contains("poisons for 2 damage")));
+    }
+
+    #[test]
+    fn stats_sign_gear_bonuses() {
+        let mut state = crate::engine::world_builder::build_thornhold();
+        state.items.get_mut("short_sword").unwrap().modifier = Some(StatModifier {
+            attack: -2,
+            defense: 1,
+            health: 5,
+        });
+        let player = Player {
+            equipped_weapon: Some("short_sword".into()),
+            ..Player::default()
+        };
+        let items = state.items;
+        let lines = describe_stats(&player, &items, &HashMap::new(), &WorldClock::default());
+        assert!(lines.contains(&format!("Attack: {} (-2)", player.attack - 2)));
+        assert!(lines.contains(&format!("Defense: {} (+1)", player.defense + 1)));
+    }
 }
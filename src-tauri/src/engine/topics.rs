@@ -0,0 +1,167 @@
+use crate::engine::{conditions, quest};
+use crate::models::*;
+
+/// Topics the NPC will currently talk about, by name.
+pub fn known_topics(npc_id: &str, state: &WorldState) -> Vec<String> {
+    state
+        .topics
+        .get(npc_id)
+        .map(|map| {
+            map.topics
+                .iter()
+                .filter(|t| conditions::holds(t.condition.as_ref(), state))
+                .map(|t| t.name.clone())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Asks (or tells) an NPC about a topic. The first time a topic comes up it
+/// unlocks its journal entry and flag.
+pub fn discuss(npc_id: &str, word: &str, telling: bool, state: &mut WorldState) -> Vec<OutputLine> {
+    let Some(npc) = state.npcs.get(npc_id).cloned() else {
+        return Vec::new();
+    };
+    let map = state.topics.get(npc_id);
+    let topic = map
+        .and_then(|m| {
+            m.topics
+                .iter()
+                .find(|t| t.matches(word) && conditions::holds(t.condition.as_ref(), state))
+        })
+        .cloned();
+    let reply = topic.as_ref().and_then(|t| {
+        if telling {
+            t.tell.clone()
+        } else {
+            t.ask.clone()
+        }
+    });
+
+    quest::record_activity(quest::QuestActivity::TalkedTo(npc_id), state);
+    if let Some(npc_mut) = state.npcs.get_mut(npc_id) {
+        npc_mut.memory.push(NpcMemory {
+            turn: state.player.turns_elapsed,
+            event: format!("{} about {}", if telling { "told" } else { "asked" }, word),
+        });
+        while npc_mut.memory.len() > 20 {
+            npc_mut.memory.remove(0);
+        }
+    }
+
+    let (Some(topic), Some(reply)) = (topic, reply) else {
+        let fallback = state
+            .topics
+            .get(npc_id)
+            .and_then(|m| m.fallback.clone())
+            .unwrap_or_else(|| "I know nothing about that.".to_string());
+        return vec![OutputLine {
+            text: format!("{}: \"{}\"", npc.name, fallback),
+            line_type: LineType::Dialogue,
+        }];
+    };
+
+    let mut messages = vec![OutputLine {
+        text: format!("{}: \"{}\"", npc.name, reply),
+        line_type: LineType::Dialogue,
+    }];
+    if let Some(flag) = &topic.sets_flag {
+        state.player.quest_flags.insert(flag.clone(), true);
+    }
+    if let Some(content) = &topic.journal {
+        let id = format!("topic_{}_{}", npc_id, topic.name.replace(' ', "_"));
+        if !state.journal.iter().any(|e| e.id == id) {
+            let title = format!("{} on {}", npc.name, topic.name);
+            messages.push(OutputLine {
+                text: format!("Codex updated: {}", title),
+                line_type: LineType::System,
+            });
+            state.journal.push(JournalEntry {
+                id,
+                category: JournalCategory::Lore,
+                title,
+                content: content.clone(),
+                discovered_turn: state.player.turns_elapsed,
+            });
+        }
+    }
+    messages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::world_builder;
+
+    fn gristle_topics(state: &mut WorldState) {
+        state.topics.insert(
+            "gristle_rat".into(),
+            TopicMap {
+                topics: vec![
+                    Topic {
+                        name: "cellar".into(),
+                        aliases: vec!["wine cellar".into()],
+                        ask: Some("The cheese is down there.".into()),
+                        tell: None,
+                        condition: None,
+                        journal: Some("Gristle swears the cellar hides cheese.".into()),
+                        sets_flag: Some("heard_of_cellar".into()),
+                    },
+                    Topic {
+                        name: "warden".into(),
+                        aliases: vec![],
+                        ask: Some("Don't say that name!".into()),
+                        tell: None,
+                        condition: Some(Condition::Flag("heard_of_cellar".into())),
+                        journal: None,
+                        sets_flag: None,
+                    },
+                ],
+                fallback: Some("Squeak?".into()),
+            },
+        );
+    }
+
+    #[test]
+    fn asking_about_a_topic_unlocks_journal_and_flag() {
+        let mut state = world_builder::build_thornhold();
+        gristle_topics(&mut state);
+
+        let msgs = discuss("gristle_rat", "wine cellar", false, &mut state);
+        assert_eq!(msgs[0].text, "Gristle: \"The cheese is down there.\"");
+        assert_eq!(msgs[1].text, "Codex updated: Gristle on cellar");
+        assert_eq!(state.player.quest_flags.get("heard_of_cellar"), Some(&true));
+        assert!(state
+            .journal
+            .iter()
+            .any(|e| e.id == "topic_gristle_rat_cellar"));
+
+        // Only the first mention adds the entry
+        let msgs = discuss("gristle_rat", "cellar", false, &mut state);
+        assert_eq!(msgs.len(), 1);
+    }
+
+    #[test]
+    fn unknown_and_locked_topics_get_the_fallback() {
+        let mut state = world_builder::build_thornhold();
+        gristle_topics(&mut state);
+        assert_eq!(known_topics("gristle_rat", &state), vec!["cellar"]);
+
+        let msgs = discuss("gristle_rat", "warden", false, &mut state);
+        assert_eq!(msgs[0].text, "Gristle: \"Squeak?\"");
+        // A topic with no reply for telling also falls back
+        let msgs = discuss("gristle_rat", "cellar", true, &mut state);
+        assert_eq!(msgs[0].text, "Gristle: \"Squeak?\"");
+
+        state
+            .player
+            .quest_flags
+            .insert("heard_of_cellar".into(), true);
+        assert_eq!(
+            known_topics("gristle_rat", &state),
+            vec!["cellar", "warden"]
+        );
+        let msgs = discuss("gristle_rat", "warden", false, &mut state);
+        assert_eq!(msgs[0].text, "Gristle: \"Don't say that name!\"");
+    }
+}
@@ -10,6 +10,17 @@ pub fn build_thornhold() -> WorldState {
         quests: build_quests(),
         events: build_events(),
         recipes: build_recipes(),
+        regions: build_regions(),
+        loot_tables: build_loot_tables(),
+        affixes: build_affixes(),
+        item_sets: build_item_sets(),
+        scripts: build_scripts(),
+        custom_verbs: build_custom_verbs(),
+        factions: build_factions(),
+        encounters: build_encounters(),
+        doors: build_doors(),
+        fixtures: build_fixtures(),
+        puzzles: build_puzzles(),
         initialized: true,
         ..Default::default()
     }
@@ -34,6 +45,11 @@ fn build_locations() -> HashMap<String, Location> {
         ambient_mood: Mood::Peaceful,
         examine_details: Some("The flagstones bear scorch marks from an ancient battle. Faded carvings on the walls depict merchants trading goods. A broken fountain stands in the center, its basin cracked and dry.".into()),
         revisit_description: Some("The courtyard is as bleak as before. The cold wind still whispers.".into()),
+        phase_descriptions: HashMap::from([
+            (DayPhase::Dusk, "The last light bleeds from the sky above the courtyard. Shadows pool between the broken flagstones, and the whispers grow louder.".into()),
+            (DayPhase::Night, "Moonlight silvers the cracked flagstones. Pale shapes drift at the edge of sight, and the wind's whispers have become voices.".into()),
+        ]),
+        ..Default::default()
     });
 
     locs.insert("great_hall".into(), Location {
@@ -48,14 +64,21 @@ fn build_locations() -> HashMap<String, Location> {
             (Direction::South, "kitchen".into()),
             (Direction::Up, "tower_apex".into()),
         ]),
-        locked_exits: HashMap::from([
-            (Direction::East, "library_key".into()),
-        ]),
+        locked_exits: HashMap::new(),
         visited: false,
         discovered_secrets: vec![],
         ambient_mood: Mood::Mysterious,
         examine_details: Some("The banners bear the crest of House Thornhold — a tower wreathed in thorns. Claw marks gouge the stone floor near the fireplace. A faint draft comes from behind the eastern wall.".into()),
         revisit_description: Some("The great hall looms in familiar shadow. The cold fireplace watches like a dark eye.".into()),
+        secrets: vec![HiddenFeature {
+            id: "vault_trapdoor".into(),
+            kind: HiddenKind::Exit(Direction::Down, "hidden_vault".into()),
+            find_chance: 20,
+            revealed_by: vec![],
+            found_text: "Beneath a rotten rug by the fireplace, your fingers find the edge of a trapdoor. Steps lead down into the dark.".into(),
+        }],
+        doors: HashMap::from([(Direction::East, "library_door".into())]),
+        ..Default::default()
     });
 
     locs.insert("tower_apex".into(), Location {
@@ -73,6 +96,7 @@ fn build_locations() -> HashMap<String, Location> {
         ambient_mood: Mood::Tense,
         examine_details: Some("From here you can see the entire ruin spread below. Scratches on the window frame suggest someone — or something — tried to climb in. A weathervane creaks overhead, pointing eternally north.".into()),
         revisit_description: None,
+        ..Default::default()
     });
 
     locs.insert("library".into(), Location {
@@ -91,13 +115,16 @@ fn build_locations() -> HashMap<String, Location> {
         ambient_mood: Mood::Mysterious,
         examine_details: Some("Many books have been deliberately torn apart. One shelf holds a collection of sealed scrolls. The dust on the floor shows no footprints — you are the first visitor in ages.".into()),
         revisit_description: Some("The library's dusty silence greets you once more.".into()),
+        doors: HashMap::from([(Direction::West, "library_door".into())]),
+        fixtures: vec!["stone_bust".into()],
+        ..Default::default()
     });
 
     locs.insert("barracks".into(), Location {
         id: "barracks".into(),
         name: "The Barracks".into(),
         description: "Rows of collapsed bunks fill this room. Rusted weapons hang on racks. Something moves in the shadows — bones scraping against stone.".into(),
-        items: vec!["iron_shield".into()],
+        items: vec!["iron_shield".into(), "thieves_picks".into()],
         npcs: vec!["skeletal_guard".into()],
         exits: HashMap::from([
             (Direction::North, "courtyard".into()),
@@ -110,6 +137,7 @@ fn build_locations() -> HashMap<String, Location> {
         ambient_mood: Mood::Tense,
         examine_details: None,
         revisit_description: None,
+        ..Default::default()
     });
 
     locs.insert("kitchen".into(), Location {
@@ -130,13 +158,14 @@ fn build_locations() -> HashMap<String, Location> {
         ambient_mood: Mood::Peaceful,
         examine_details: None,
         revisit_description: None,
+        ..Default::default()
     });
 
     locs.insert("chapel".into(), Location {
         id: "chapel".into(),
         name: "The Chapel".into(),
         description: "Stained glass windows cast colored shadows across stone pews. An altar stands at the far end, still bearing offerings from ages past. A sense of peace lingers here.".into(),
-        items: vec!["silver_chalice".into()],
+        items: vec!["silver_chalice".into(), "sunstone".into()],
         npcs: vec![],
         exits: HashMap::from([
             (Direction::North, "library".into()),
@@ -144,21 +173,22 @@ fn build_locations() -> HashMap<String, Location> {
             (Direction::South, "crypt_passage".into()),
         ]),
         locked_exits: HashMap::from([
-            (Direction::South, "chapel_seal".into()),
+            (Direction::South, SEALED.into()),
         ]),
         visited: false,
         discovered_secrets: vec![],
         ambient_mood: Mood::Sacred,
         examine_details: Some("The stained glass depicts the founding of Thornhold. The altar bears scratch marks, as if something tried to deface it. A faint warmth radiates from the stone.".into()),
         revisit_description: Some("The chapel's colored light washes over you again. The altar waits patiently.".into()),
+        ..Default::default()
     });
 
     locs.insert("armory".into(), Location {
         id: "armory".into(),
         name: "The Armory".into(),
         description: "Weapon racks and armor stands fill this room. Most are rusted beyond use, but a few pieces remain serviceable. The air smells of oil and old metal.".into(),
-        items: vec!["short_sword".into(), "leather_armor".into()],
-        npcs: vec![],
+        items: vec!["short_sword".into(), "leather_armor".into(), "whetstone".into()],
+        npcs: vec!["armorer_shade".into()],
         exits: HashMap::from([
             (Direction::North, "barracks".into()),
         ]),
@@ -168,6 +198,7 @@ fn build_locations() -> HashMap<String, Location> {
         ambient_mood: Mood::Tense,
         examine_details: None,
         revisit_description: None,
+        ..Default::default()
     });
 
     locs.insert("cellar_entrance".into(), Location {
@@ -186,6 +217,7 @@ fn build_locations() -> HashMap<String, Location> {
         ambient_mood: Mood::Dark,
         examine_details: None,
         revisit_description: None,
+        ..Default::default()
     });
 
     locs.insert("wine_cellar".into(), Location {
@@ -204,6 +236,23 @@ fn build_locations() -> HashMap<String, Location> {
         ambient_mood: Mood::Dark,
         examine_details: None,
         revisit_description: None,
+        secrets: vec![HiddenFeature {
+            id: "cellar_tripwire".into(),
+            kind: HiddenKind::Trap(Trap {
+                name: "Tripwire".into(),
+                on_trigger: vec![
+                    EventAction::Message("A wire snaps underfoot and a rusted blade swings out of the dark!".into()),
+                    EventAction::Damage(8),
+                ],
+                on_disarm: vec![EventAction::Message("You coil the wire away. The blade could have taken your head off.".into())],
+                disarm_chance: 70,
+                spent: false,
+            }),
+            find_chance: 60,
+            revealed_by: vec!["lit_lantern".into()],
+            found_text: "A thin wire is strung between two barrels at ankle height.".into(),
+        }],
+        ..Default::default()
     });
 
     locs.insert("crypt_passage".into(), Location {
@@ -223,6 +272,7 @@ fn build_locations() -> HashMap<String, Location> {
         ambient_mood: Mood::Dark,
         examine_details: None,
         revisit_description: None,
+        ..Default::default()
     });
 
     locs.insert("deep_chamber".into(), Location {
@@ -241,6 +291,7 @@ fn build_locations() -> HashMap<String, Location> {
         ambient_mood: Mood::Dangerous,
         examine_details: Some("The runes on the walls shift when you look away. The fungi pulse in a rhythm like a heartbeat. Chains embedded in the far wall have been snapped, links scattered across the floor.".into()),
         revisit_description: None,
+        ..Default::default()
     });
 
     locs.insert("final_sanctum".into(), Location {
@@ -258,6 +309,7 @@ fn build_locations() -> HashMap<String, Location> {
         ambient_mood: Mood::Dangerous,
         examine_details: Some("The chains binding the figure are inscribed with names — perhaps those who placed them. The eldritch light emanates from a crack in the floor. The air tastes of copper and ozone.".into()),
         revisit_description: None,
+        ..Default::default()
     });
 
     locs.insert("hidden_vault".into(), Location {
@@ -275,11 +327,68 @@ fn build_locations() -> HashMap<String, Location> {
         ambient_mood: Mood::Mysterious,
         examine_details: Some("The shelves hold trinkets from across the ages — a child's toy, a soldier's medal, a lover's locket. Each tells a story of Thornhold's past.".into()),
         revisit_description: Some("The hidden vault is as you left it. The treasures gleam in the dim light.".into()),
+        ..Default::default()
     });
 
     locs
 }
 
+fn build_doors() -> HashMap<String, Door> {
+    HashMap::from([(
+        "library_door".into(),
+        Door {
+            id: "library_door".into(),
+            name: "iron-banded door".into(),
+            state: DoorState::Locked,
+            key_id: Some("library_key".into()),
+            lock_difficulty: 40,
+            sealed: false,
+            opens_from: None,
+            broken: false,
+        },
+    )])
+}
+
+fn build_fixtures() -> HashMap<String, Fixture> {
+    HashMap::from([(
+        "stone_bust".into(),
+        Fixture {
+            id: "stone_bust".into(),
+            name: "stone bust".into(),
+            description: "A bust of some forgotten scholar, its stone lips parted as if mid-word. \
+                Words are carved into its plinth."
+                .into(),
+            kind: FixtureKind::Riddle {
+                question: "The more of me you take, the more of me you leave behind. What am I?"
+                    .into(),
+                answers: vec![
+                    "footsteps".into(),
+                    "footprints".into(),
+                    "steps".into(),
+                    "tracks".into(),
+                ],
+            },
+            state: String::new(),
+        },
+    )])
+}
+
+fn build_puzzles() -> HashMap<String, Puzzle> {
+    HashMap::from([(
+        "bust_riddle".into(),
+        Puzzle {
+            id: "bust_riddle".into(),
+            location_id: "library".into(),
+            solution: HashMap::from([("stone_bust".into(), RIDDLE_SOLVED.into())]),
+            on_solve: vec![EventAction::GiveItem("health_potion".into())],
+            solved_text: Some(
+                "The bust's jaw grinds open, and a small vial rolls out into your hand.".into(),
+            ),
+            solved: false,
+        },
+    )])
+}
+
 fn build_items() -> HashMap<String, Item> {
     let mut items = HashMap::new();
 
@@ -294,6 +403,9 @@ fn build_items() -> HashMap<String, Item> {
         consumable: false,
         key_id: None,
         lore: Some("Forged by the smiths of Thornhold in its golden age. The maker's mark — a tiny tower — is etched near the hilt.".into()),
+        set_id: Some("garrison".into()),
+        durability: Some(Durability { current: 40, max: 40 }),
+        ..Default::default()
     });
 
     items.insert("rusty_dagger".into(), Item {
@@ -306,6 +418,8 @@ fn build_items() -> HashMap<String, Item> {
         consumable: false,
         key_id: None,
         lore: None,
+        durability: Some(Durability { current: 25, max: 25 }),
+        ..Default::default()
     });
 
     // Armor
@@ -319,6 +433,9 @@ fn build_items() -> HashMap<String, Item> {
         consumable: false,
         key_id: None,
         lore: None,
+        set_id: Some("garrison".into()),
+        durability: Some(Durability { current: 40, max: 40 }),
+        ..Default::default()
     });
 
     items.insert("iron_shield".into(), Item {
@@ -331,6 +448,10 @@ fn build_items() -> HashMap<String, Item> {
         consumable: false,
         key_id: None,
         lore: None,
+        slot: Some(EquipSlot::Shield),
+        set_id: Some("garrison".into()),
+        durability: Some(Durability { current: 60, max: 60 }),
+        ..Default::default()
     });
 
     // Consumables
@@ -344,6 +465,7 @@ fn build_items() -> HashMap<String, Item> {
         consumable: true,
         key_id: None,
         lore: None,
+        ..Default::default()
     });
 
     items.insert("stale_bread".into(), Item {
@@ -356,6 +478,7 @@ fn build_items() -> HashMap<String, Item> {
         consumable: true,
         key_id: None,
         lore: None,
+        ..Default::default()
     });
 
     items.insert("cellar_cheese".into(), Item {
@@ -368,6 +491,7 @@ fn build_items() -> HashMap<String, Item> {
         consumable: true,
         key_id: None,
         lore: None,
+        ..Default::default()
     });
 
     // Keys
@@ -381,6 +505,7 @@ fn build_items() -> HashMap<String, Item> {
         consumable: false,
         key_id: Some("library".into()),
         lore: None,
+        ..Default::default()
     });
 
     // Scrolls
@@ -394,6 +519,7 @@ fn build_items() -> HashMap<String, Item> {
         consumable: true,
         key_id: None,
         lore: Some("Written by the last priest of Thornhold before the fall. The ink shimmers with divine power that has endured centuries.".into()),
+        ..Default::default()
     });
 
     // Quest items
@@ -407,6 +533,7 @@ fn build_items() -> HashMap<String, Item> {
         consumable: false,
         key_id: None,
         lore: None,
+        ..Default::default()
     });
 
     items.insert("silver_chalice".into(), Item {
@@ -419,6 +546,7 @@ fn build_items() -> HashMap<String, Item> {
         consumable: false,
         key_id: None,
         lore: None,
+        ..Default::default()
     });
 
     items.insert("ancient_amulet".into(), Item {
@@ -431,6 +559,8 @@ fn build_items() -> HashMap<String, Item> {
         consumable: false,
         key_id: None,
         lore: Some("One of the sealing artifacts used to bind The Forgotten One. Its power has weakened over the centuries but still resonates with protective magic.".into()),
+        slot: Some(EquipSlot::Amulet),
+        ..Default::default()
     });
 
     items.insert("mysterious_orb".into(), Item {
@@ -443,6 +573,7 @@ fn build_items() -> HashMap<String, Item> {
         consumable: false,
         key_id: None,
         lore: Some("The concentrated essence of The Forgotten One's power. Holding it grants visions of a world before Thornhold, when gods walked the earth.".into()),
+        ..Default::default()
     });
 
     // Miscellaneous
@@ -456,6 +587,7 @@ fn build_items() -> HashMap<String, Item> {
         consumable: false,
         key_id: None,
         lore: None,
+        ..Default::default()
     });
 
     items.insert("torn_tapestry".into(), Item {
@@ -468,6 +600,7 @@ fn build_items() -> HashMap<String, Item> {
         consumable: false,
         key_id: None,
         lore: None,
+        ..Default::default()
     });
 
     items.insert("old_spyglass".into(), Item {
@@ -480,6 +613,7 @@ fn build_items() -> HashMap<String, Item> {
         consumable: false,
         key_id: None,
         lore: None,
+        ..Default::default()
     });
 
     items.insert("quill_pen".into(), Item {
@@ -492,6 +626,7 @@ fn build_items() -> HashMap<String, Item> {
         consumable: false,
         key_id: None,
         lore: None,
+        ..Default::default()
     });
 
     items.insert("dusty_tome".into(), Item {
@@ -504,6 +639,7 @@ fn build_items() -> HashMap<String, Item> {
         consumable: false,
         key_id: None,
         lore: Some("Chronicles the founding of Thornhold as a prison for an ancient being. The final chapter, written in a shaking hand, warns that the binding weakens with each passing century.".into()),
+        ..Default::default()
     });
 
     items.insert("empty_bottle".into(), Item {
@@ -516,6 +652,7 @@ fn build_items() -> HashMap<String, Item> {
         consumable: false,
         key_id: None,
         lore: None,
+        ..Default::default()
     });
 
     items.insert("bone_fragment".into(), Item {
@@ -528,6 +665,7 @@ fn build_items() -> HashMap<String, Item> {
         consumable: false,
         key_id: None,
         lore: None,
+        ..Default::default()
     });
 
     items.insert("torch".into(), Item {
@@ -540,6 +678,7 @@ fn build_items() -> HashMap<String, Item> {
         consumable: false,
         key_id: None,
         lore: None,
+        ..Default::default()
     });
 
     // Crafted items
@@ -553,6 +692,7 @@ fn build_items() -> HashMap<String, Item> {
         consumable: true,
         key_id: None,
         lore: Some("Resourcefulness in desperate times. The tapestry of Thornhold's history now serves to heal.".into()),
+        ..Default::default()
     });
 
     items.insert("lit_lantern".into(), Item {
@@ -565,6 +705,8 @@ fn build_items() -> HashMap<String, Item> {
         consumable: false,
         key_id: None,
         lore: Some("Even the oldest tools can serve again when given purpose.".into()),
+        light: true,
+        ..Default::default()
     });
 
     items.insert("bone_talisman".into(), Item {
@@ -577,6 +719,8 @@ fn build_items() -> HashMap<String, Item> {
         consumable: false,
         key_id: None,
         lore: Some("The silver purifies while the bone remembers. Together they ward against the darkness below.".into()),
+        slot: Some(EquipSlot::Amulet),
+        ..Default::default()
     });
 
     items.insert("vault_amulet".into(), Item {
@@ -589,6 +733,8 @@ fn build_items() -> HashMap<String, Item> {
         consumable: false,
         key_id: None,
         lore: Some("One of the original warding stones of Thornhold. Only those who know the old words can find where it is hidden.".into()),
+        slot: Some(EquipSlot::Amulet),
+        ..Default::default()
     });
 
     // NEW ITEMS - Phase 2 Content Expansion
@@ -603,6 +749,7 @@ fn build_items() -> HashMap<String, Item> {
         consumable: false,
         key_id: None,
         lore: Some("Forged in the void between life and death, this blade cuts through both flesh and spirit. The greatest treasure of Thornhold's armory.".into()),
+        ..Default::default()
     });
 
     items.insert("blessed_water".into(), Item {
@@ -615,6 +762,41 @@ fn build_items() -> HashMap<String, Item> {
         consumable: true,
         key_id: None,
         lore: Some("Water drawn from the sacred spring beneath the chapel, blessed in the old rituals. It purifies body and soul.".into()),
+        effects: vec![
+            ItemEffect { action: EventAction::Heal(50), target: EffectTarget::Player },
+            ItemEffect { action: EventAction::RemoveStatus("Poison".into()), target: EffectTarget::Player },
+        ],
+        ..Default::default()
+    });
+
+    items.insert("sunstone".into(), Item {
+        id: "sunstone".into(),
+        name: "Sunstone".into(),
+        description: "A palm-sized amber stone that holds the chapel's light. It flares when raised against the dark.".into(),
+        item_type: ItemType::Miscellaneous,
+        modifier: None,
+        usable: true,
+        consumable: false,
+        key_id: None,
+        lore: Some("The clerics set sunstones into the chapel windows. This one fell loose long ago, and it still remembers the sun.".into()),
+        rarity: Rarity::Uncommon,
+        effects: vec![
+            ItemEffect { action: EventAction::Damage(12), target: EffectTarget::Enemy },
+            ItemEffect {
+                action: EventAction::ApplyStatus(StatusEffect {
+                    effect_type: StatusEffectType::Burning,
+                    name: "Burning".into(),
+                    turns_remaining: 2,
+                    damage_per_turn: 3,
+                    attack_modifier: 0,
+                    defense_modifier: 0,
+                }),
+                target: EffectTarget::Enemy,
+            },
+        ],
+        charges: Some(3),
+        cooldown: 3,
+        ..Default::default()
     });
 
     items.insert("master_key".into(), Item {
@@ -627,6 +809,7 @@ fn build_items() -> HashMap<String, Item> {
         consumable: false,
         key_id: Some("universal".into()),
         lore: Some("The Lord of Thornhold's personal key, capable of opening any lock within the fortress. Long thought lost.".into()),
+        ..Default::default()
     });
 
     items.insert("dungeon_heart_shard".into(), Item {
@@ -639,6 +822,7 @@ fn build_items() -> HashMap<String, Item> {
         consumable: false,
         key_id: None,
         lore: Some("A piece of the Dungeon Heart itself. Those who bear it gain power, but at what cost?".into()),
+        ..Default::default()
     });
 
     items.insert("treasure_map".into(), Item {
@@ -651,6 +835,7 @@ fn build_items() -> HashMap<String, Item> {
         consumable: false,
         key_id: None,
         lore: Some("The merchant's last map, marking locations of hidden treasures he never retrieved.".into()),
+        ..Default::default()
     });
 
     items.insert("mithril_mail".into(), Item {
@@ -663,6 +848,7 @@ fn build_items() -> HashMap<String, Item> {
         consumable: false,
         key_id: None,
         lore: Some("Crafted by master smiths of a forgotten age. Mithril never tarnishes, never breaks.".into()),
+        ..Default::default()
     });
 
     items.insert("phoenix_feather".into(), Item {
@@ -675,6 +861,7 @@ fn build_items() -> HashMap<String, Item> {
         consumable: true,
         key_id: None,
         lore: Some("From the phoenix that nested atop Thornhold's highest tower. Only one feather falls per century.".into()),
+        ..Default::default()
     });
 
     items.insert("ancient_grimoire".into(), Item {
@@ -687,6 +874,89 @@ fn build_items() -> HashMap<String, Item> {
         consumable: false,
         key_id: None,
         lore: Some("The collective knowledge of Thornhold's sorcerers. Reading it grants power, but risks madness.".into()),
+        ..Default::default()
+    });
+
+    // Procedural dungeon wing loot (placed by the region generator)
+    items.insert("whetstone".into(), Item {
+        id: "whetstone".into(),
+        name: "Whetstone".into(),
+        description: "A grey sharpening stone, worn hollow in the middle from years of use.".into(),
+        item_type: ItemType::Miscellaneous,
+        modifier: None,
+        usable: false,
+        consumable: false,
+        key_id: None,
+        lore: None,
+        ..Default::default()
+    });
+
+    items.insert("tinkers_kit".into(), Item {
+        id: "tinkers_kit".into(),
+        name: "Tinker's Kit".into(),
+        description: "A leather roll of rivets, thread and oil. Enough to patch up one piece of gear.".into(),
+        item_type: ItemType::Consumable,
+        modifier: None,
+        usable: true,
+        consumable: true,
+        key_id: None,
+        lore: None,
+        repair_amount: Some(20),
+        ..Default::default()
+    });
+
+    items.insert("thieves_picks".into(), Item {
+        id: "thieves_picks".into(),
+        name: "Thieves' Picks".into(),
+        description: "A ring of slender hooked wires, the kind a burglar keeps up a sleeve.".into(),
+        item_type: ItemType::Miscellaneous,
+        modifier: None,
+        usable: false,
+        consumable: false,
+        key_id: None,
+        lore: None,
+        rarity: Rarity::Uncommon,
+        lockpick: Some(20),
+        ..Default::default()
+    });
+
+    items.insert("dungeon_treasure".into(), Item {
+        id: "dungeon_treasure".into(),
+        name: "Dungeon Treasure".into(),
+        description: "A chest of ancient gold coins, gemstones, and a mysterious crystal shard.".into(),
+        item_type: ItemType::Quest,
+        modifier: Some(StatModifier { attack: 2, defense: 2, health: 0 }),
+        usable: false,
+        consumable: false,
+        key_id: None,
+        lore: Some("The accumulated wealth of centuries, guarded by a creature that long forgot why it hoards.".into()),
+        ..Default::default()
+    });
+
+    items.insert("dungeon_health_potion".into(), Item {
+        id: "dungeon_health_potion".into(),
+        name: "Glowing Elixir".into(),
+        description: "A vial of luminescent liquid found deep underground. It radiates warmth.".into(),
+        item_type: ItemType::Consumable,
+        modifier: Some(StatModifier { attack: 0, defense: 0, health: 25 }),
+        usable: true,
+        consumable: true,
+        key_id: None,
+        lore: None,
+        ..Default::default()
+    });
+
+    items.insert("dungeon_key_shard".into(), Item {
+        id: "dungeon_key_shard".into(),
+        name: "Key Shard".into(),
+        description: "A fragment of an ancient key. It hums with residual magic.".into(),
+        item_type: ItemType::Miscellaneous,
+        modifier: None,
+        usable: false,
+        consumable: false,
+        key_id: None,
+        lore: Some("Part of the original key to Thornhold's deepest vault. Whoever carried it was consumed by what they guarded.".into()),
+        ..Default::default()
     });
 
     items
@@ -700,6 +970,7 @@ fn build_recipes() -> Vec<CraftingRecipe> {
             output: "makeshift_bandage".into(),
             hint: "Something torn could bind a wound with the right tool...".into(),
             discovered: false,
+            repairs: false,
         },
         CraftingRecipe {
             id: "lantern_torch".into(),
@@ -707,6 +978,7 @@ fn build_recipes() -> Vec<CraftingRecipe> {
             output: "lit_lantern".into(),
             hint: "A lantern needs a flame...".into(),
             discovered: false,
+            repairs: false,
         },
         CraftingRecipe {
             id: "bone_talisman".into(),
@@ -714,6 +986,15 @@ fn build_recipes() -> Vec<CraftingRecipe> {
             output: "bone_talisman".into(),
             hint: "Bone and silver have warding properties...".into(),
             discovered: false,
+            repairs: false,
+        },
+        CraftingRecipe {
+            id: "sharpen_sword".into(),
+            inputs: vec!["short_sword".into(), "whetstone".into()],
+            output: "short_sword".into(),
+            hint: "A dull blade wants a stone...".into(),
+            discovered: false,
+            repairs: true,
         },
     ]
 }
@@ -737,6 +1018,8 @@ fn build_npcs() -> HashMap<String, Npc> {
         examine_text: Some("His robes bear the insignia of the Thornhold Merchant Guild. A heavy ledger hangs from a spectral chain at his belt. His expression carries centuries of regret.".into()),
         relationship: 0,
         memory: vec![],
+        faction: Some("faithful_dead".into()),
+        ..Default::default()
     });
 
     npcs.insert("gristle_rat".into(), Npc {
@@ -755,6 +1038,9 @@ fn build_npcs() -> HashMap<String, Npc> {
         examine_text: None,
         relationship: 0,
         memory: vec![],
+        recruitable: true,
+        recruit_condition: Some(Condition::QuestCompleted("rats_request".into())),
+        ..Default::default()
     });
 
     npcs.insert("skeletal_guard".into(), Npc {
@@ -773,6 +1059,8 @@ fn build_npcs() -> HashMap<String, Npc> {
         examine_text: None,
         relationship: 0,
         memory: vec![],
+        faction: Some("wardens_host".into()),
+        ..Default::default()
     });
 
     npcs.insert("the_warden".into(), Npc {
@@ -791,6 +1079,8 @@ fn build_npcs() -> HashMap<String, Npc> {
         examine_text: None,
         relationship: 0,
         memory: vec![],
+        faction: Some("wardens_host".into()),
+        ..Default::default()
     });
 
     npcs.insert("the_forgotten_one".into(), Npc {
@@ -809,6 +1099,7 @@ fn build_npcs() -> HashMap<String, Npc> {
         examine_text: Some("Its form flickers between shapes — now a crowned king, now a beast of shadow, now something that has no name. The chains binding it glow faintly where they touch its shifting form.".into()),
         relationship: 0,
         memory: vec![],
+        ..Default::default()
     });
 
     // NEW NPCs - Phase 2 Content Expansion
@@ -829,6 +1120,29 @@ fn build_npcs() -> HashMap<String, Npc> {
         examine_text: Some("His vestments bear the holy symbol of the chapel. Though translucent, his presence carries weight and authority. You sense he has much knowledge to share.".into()),
         relationship: 0,
         memory: vec![],
+        faction: Some("faithful_dead".into()),
+        ..Default::default()
+    });
+
+    npcs.insert("armorer_shade".into(), Npc {
+        id: "armorer_shade".into(),
+        name: "The Armorer's Shade".into(),
+        description: "A broad-shouldered phantom in a scorched leather apron, bent over an anvil that is no longer there.".into(),
+        personality_seed: "Gruff and proud of his craft. Speaks in short sentences. Can't abide a neglected blade and will mend any gear brought to him.".into(),
+        dialogue_state: DialogueState::Greeting,
+        hostile: false,
+        health: 1,
+        max_health: 1,
+        attack: 0,
+        defense: 0,
+        items: vec![],
+        quest_giver: None,
+        examine_text: Some("His hammer arm still rises and falls in a steady rhythm. Type 'repair <item>' and he'll see to your gear.".into()),
+        relationship: 0,
+        memory: vec![],
+        smith: true,
+        faction: Some("faithful_dead".into()),
+        ..Default::default()
     });
 
     npcs.insert("the_oracle".into(), Npc {
@@ -847,6 +1161,7 @@ fn build_npcs() -> HashMap<String, Npc> {
         examine_text: Some("Her eyes hold the knowledge of ages past and futures yet to come. She wears robes of starlight and shadow. Ancient power radiates from her being.".into()),
         relationship: 0,
         memory: vec![],
+        ..Default::default()
     });
 
     npcs
@@ -860,11 +1175,12 @@ fn build_quests() -> HashMap<String, Quest> {
         name: "The Lost Key".into(),
         description: "The Skeletal Guard in the barracks holds a key. Defeat it to claim the Library Key.".into(),
         giver: "skeletal_guard".into(),
-        objective: QuestObjective::KillNpc("skeletal_guard".into()),
+        objective: Some(QuestObjective::KillNpc("skeletal_guard".into())),
         reward: vec![],
         completed: false,
         active: true,
         completed_turn: None,
+        ..Default::default()
     });
 
     quests.insert("rats_request".into(), Quest {
@@ -872,11 +1188,12 @@ fn build_quests() -> HashMap<String, Quest> {
         name: "The Rat's Request".into(),
         description: "Gristle the rat desperately wants cheese from the Wine Cellar. Find the Aged Cellar Cheese and bring it back.".into(),
         giver: "gristle_rat".into(),
-        objective: QuestObjective::FetchItem("cellar_cheese".into()),
+        objective: Some(QuestObjective::FetchItem("cellar_cheese".into())),
         reward: vec!["health_potion".into()],
         completed: false,
         active: false,
         completed_turn: None,
+        ..Default::default()
     });
 
     quests.insert("merchants_unfinished_business".into(), Quest {
@@ -884,11 +1201,12 @@ fn build_quests() -> HashMap<String, Quest> {
         name: "The Merchant's Unfinished Business".into(),
         description: "The ghost merchant begs you to take his journal to the Chapel altar. Use the journal at the Chapel to complete his final wish.".into(),
         giver: "merchant_ghost".into(),
-        objective: QuestObjective::FetchItem("merchant_journal".into()),
+        objective: Some(QuestObjective::FetchItem("merchant_journal".into())),
         reward: vec![],
         completed: false,
         active: false,
         completed_turn: None,
+        ..Default::default()
     });
 
     quests.insert("the_final_confrontation".into(), Quest {
@@ -896,11 +1214,12 @@ fn build_quests() -> HashMap<String, Quest> {
         name: "The Final Confrontation".into(),
         description: "Face The Forgotten One in the Final Sanctum. You may fight or negotiate — but only the worthy may negotiate.".into(),
         giver: "the_forgotten_one".into(),
-        objective: QuestObjective::ReachLocation("final_sanctum".into()),
+        objective: Some(QuestObjective::ReachLocation("final_sanctum".into())),
         reward: vec![],
         completed: false,
         active: false,
         completed_turn: None,
+        ..Default::default()
     });
 
     // NEW QUESTS - Phase 2 Content Expansion
@@ -910,11 +1229,12 @@ fn build_quests() -> HashMap<String, Quest> {
         name: "Venture Below".into(),
         description: "The old cleric's ghost mentioned a hidden crypt beneath the chapel. Find the Sacred Scroll and use it to unlock the passage downward.".into(),
         giver: "ghost_cleric".into(),
-        objective: QuestObjective::FetchItem("sacred_scroll".into()),
+        objective: Some(QuestObjective::FetchItem("sacred_scroll".into())),
         reward: vec!["blessed_water".into()],
         completed: false,
         active: false,
         completed_turn: None,
+        ..Default::default()
     });
 
     quests.insert("the_armory_challenge".into(), Quest {
@@ -922,11 +1242,12 @@ fn build_quests() -> HashMap<String, Quest> {
         name: "The Armory Challenge".into(),
         description: "Legend speaks of a legendary blade hidden in the Armory, but deadly traps protect it. Survive the traps and claim the Ethereal Blade.".into(),
         giver: "merchant_ghost".into(),
-        objective: QuestObjective::FetchItem("ethereal_blade".into()),
+        objective: Some(QuestObjective::FetchItem("ethereal_blade".into())),
         reward: vec!["master_key".into()],
         completed: false,
         active: false,
         completed_turn: None,
+        ..Default::default()
     });
 
     quests.insert("the_wardens_toll".into(), Quest {
@@ -934,11 +1255,12 @@ fn build_quests() -> HashMap<String, Quest> {
         name: "The Warden's Toll".into(),
         description: "The Warden in the Deep Chamber guards the path to the Final Sanctum. Defeat this ancient guardian to proceed.".into(),
         giver: "the_forgotten_one".into(),
-        objective: QuestObjective::KillNpc("the_warden".into()),
+        objective: Some(QuestObjective::KillNpc("the_warden".into())),
         reward: vec!["ancient_amulet".into()],
         completed: false,
         active: false,
         completed_turn: None,
+        ..Default::default()
     });
 
     quests.insert("the_keepers_ritual".into(), Quest {
@@ -946,11 +1268,12 @@ fn build_quests() -> HashMap<String, Quest> {
         name: "The Keeper's Ritual".into(),
         description: "The Forgotten One demands three sacred artifacts before granting audience: the Ancient Amulet, the Ethereal Blade, and the Blessed Water. Gather them all.".into(),
         giver: "the_forgotten_one".into(),
-        objective: QuestObjective::FetchItem("ancient_amulet".into()),
+        objective: Some(QuestObjective::FetchItem("ancient_amulet".into())),
         reward: vec!["dungeon_heart_shard".into()],
         completed: false,
         active: false,
         completed_turn: None,
+        ..Default::default()
     });
 
     quests.insert("seek_the_vault".into(), Quest {
@@ -958,86 +1281,407 @@ fn build_quests() -> HashMap<String, Quest> {
         name: "Seek the Hidden Vault".into(),
         description: "Rumors persist of a secret vault hidden within Thornhold, accessible only through knowledge of ancient words. Use the command 'plugh' in the Great Hall to reveal it.".into(),
         giver: "merchant_ghost".into(),
-        objective: QuestObjective::ReachLocation("hidden_vault".into()),
+        objective: Some(QuestObjective::ReachLocation("hidden_vault".into())),
         reward: vec!["treasure_map".into()],
         completed: false,
         active: false,
         completed_turn: None,
+        ..Default::default()
     });
 
     quests
 }
 
+fn build_loot_tables() -> HashMap<String, LootTable> {
+    let entry = |item_id: &str, weight: u32, rarity: Rarity| LootEntry {
+        item_id: item_id.into(),
+        weight,
+        min_quantity: 1,
+        max_quantity: 1,
+        rarity,
+        guaranteed: false,
+        randomize: false,
+    };
+
+    HashMap::from([
+        ("dungeon_rooms".into(), LootTable {
+            id: "dungeon_rooms".into(),
+            entries: vec![
+                entry("dungeon_health_potion", 4, Rarity::Common),
+                entry("bone_fragment", 2, Rarity::Common),
+                entry("tinkers_kit", 1, Rarity::Common),
+                LootEntry { randomize: true, ..entry("rusty_dagger", 1, Rarity::Uncommon) },
+                LootEntry { randomize: true, ..entry("leather_armor", 1, Rarity::Uncommon) },
+            ],
+            rolls: 1,
+            drop_chance: 100,
+        }),
+        ("dungeon_lurker".into(), LootTable {
+            id: "dungeon_lurker".into(),
+            entries: vec![
+                LootEntry { max_quantity: 2, ..entry("bone_fragment", 3, Rarity::Common) },
+                entry("dungeon_health_potion", 1, Rarity::Uncommon),
+            ],
+            rolls: 1,
+            drop_chance: 60,
+        }),
+    ])
+}
+
+fn build_scripts() -> HashMap<String, String> {
+    HashMap::from([(
+        "chapel_prayer".into(),
+        r#"
+if flag("prayed_at_altar") {
+    say("You bow your head again. The altar is quiet; your prayer was already heard.")
+    return
+}
+set_flag("prayed_at_altar")
+say("You kneel before the altar. Warmth gathers in the old stone and settles in your chest.")
+if has_item("sacred_scroll") {
+    say("The scroll in your pack grows warm, as if it wants to be read here.")
+} else {
+    say("A whisper brushes your ear: the words of passage lie among the books to the north.")
+}
+"#
+        .into(),
+    )])
+}
+
+fn build_custom_verbs() -> Vec<CustomVerb> {
+    vec![CustomVerb {
+        verb: "pray".into(),
+        aliases: vec!["kneel".into()],
+        script: "chapel_prayer".into(),
+        location: Some("chapel".into()),
+    }]
+}
+
+fn build_factions() -> HashMap<String, Faction> {
+    let mut factions = HashMap::new();
+
+    // The castle's restless but peaceable spirits
+    factions.insert("faithful_dead".into(), Faction {
+        id: "faithful_dead".into(),
+        name: "The Faithful Dead".into(),
+        description: "Spirits of Thornhold's servants, bound to the castle but not to the Warden.".into(),
+        allies: vec![],
+        enemies: vec!["wardens_host".into()],
+        property: vec!["whetstone".into()],
+        reputation: 0,
+        turned_hostile: vec![],
+    });
+
+    // The Warden and the dead that still serve him
+    factions.insert("wardens_host".into(), Faction {
+        id: "wardens_host".into(),
+        name: "The Warden's Host".into(),
+        description: "The undead garrison, sworn to keep the living out of the depths.".into(),
+        allies: vec![],
+        enemies: vec!["faithful_dead".into()],
+        property: vec![],
+        reputation: -60,
+        turned_hostile: vec![],
+    });
+
+    factions
+}
+
+fn build_encounters() -> Vec<EncounterTable> {
+    // The crypt's dead do not stay down for long
+    vec![EncounterTable {
+        id: "crypt_dead".into(),
+        locations: vec!["wine_cellar".into(), "crypt_passage".into()],
+        regions: vec![],
+        chance: 15,
+        cooldown: 10,
+        respawn_after: Some(40),
+        max_active: 2,
+        enemies: vec![
+            EnemyTemplate {
+                id: "bones".into(),
+                name: "Restless Bones".into(),
+                description: "A jumble of yellowed bones that drags itself together into the shape of a man.".into(),
+                personality_seed: "Mindless. Rattles and lunges. Falls apart and rises again.".into(),
+                health: 8,
+                attack: 3,
+                defense: 0,
+                items: vec![],
+                examine_text: None,
+                loot_table: None,
+                weight: 3,
+            },
+            EnemyTemplate {
+                id: "crypt_rat".into(),
+                name: "Crypt Rat".into(),
+                description: "A rat grown fat and bold on the crypt's leavings. Unlike Gristle, it doesn't talk.".into(),
+                personality_seed: "Feral and hungry. Squeals and bites.".into(),
+                health: 4,
+                attack: 2,
+                defense: 0,
+                items: vec![],
+                examine_text: None,
+                loot_table: None,
+                weight: 1,
+            },
+        ],
+        scaling: DifficultyScaling {
+            health_per_level: 1,
+            ..Default::default()
+        },
+        last_encounter: None,
+        active: vec![],
+        empty_since: HashMap::new(),
+    }]
+}
+
+fn build_item_sets() -> HashMap<String, ItemSet> {
+    let mut sets = HashMap::new();
+
+    // The old garrison's standard kit, scattered around the barracks and armory
+    sets.insert("garrison".into(), ItemSet {
+        id: "garrison".into(),
+        name: "Garrison Kit".into(),
+        bonuses: vec![
+            SetBonus { pieces: 2, modifier: StatModifier { attack: 0, defense: 1, health: 0 } },
+            SetBonus { pieces: 3, modifier: StatModifier { attack: 2, defense: 1, health: 0 } },
+        ],
+    });
+
+    sets
+}
+
+fn build_affixes() -> Vec<Affix> {
+    let affix = |id: &str, name: &str, kind: AffixKind, modifier: StatModifier| Affix {
+        id: id.into(),
+        name: name.into(),
+        kind,
+        modifier,
+        effect: None,
+        min_rarity: Rarity::Common,
+        item_types: vec![],
+        weight: 2,
+    };
+    let stats = |attack, defense, health| StatModifier { attack, defense, health };
+
+    vec![
+        Affix { item_types: vec![ItemType::Weapon], ..affix("keen", "Keen", AffixKind::Prefix, stats(2, 0, 0)) },
+        Affix { item_types: vec![ItemType::Armor], ..affix("sturdy", "Sturdy", AffixKind::Prefix, stats(0, 2, 0)) },
+        Affix {
+            item_types: vec![ItemType::Weapon],
+            effect: Some(SpecialEffect::Poison { damage_per_turn: 2, turns: 3 }),
+            min_rarity: Rarity::Rare,
+            weight: 1,
+            ..affix("venomous", "Venomous", AffixKind::Prefix, stats(1, 0, 0))
+        },
+        Affix {
+            item_types: vec![ItemType::Weapon],
+            effect: Some(SpecialEffect::Lifesteal { percent: 25 }),
+            min_rarity: Rarity::Epic,
+            weight: 1,
+            ..affix("vampiric", "Vampiric", AffixKind::Prefix, stats(0, 0, 0))
+        },
+        affix("warding", "of Warding", AffixKind::Suffix, stats(0, 2, 0)),
+        affix("vigor", "of Vigor", AffixKind::Suffix, stats(0, 0, 10)),
+        Affix { item_types: vec![ItemType::Weapon], ..affix("might", "of Might", AffixKind::Suffix, stats(2, 0, 0)) },
+    ]
+}
+
+fn build_regions() -> Vec<ProceduralRegion> {
+    let room = |name: &str, description: &str, mood: Mood| RoomTemplate {
+        name: name.into(),
+        description: description.into(),
+        mood,
+        weight: 1,
+    };
+
+    vec![ProceduralRegion {
+        id: "dungeon".into(),
+        entry_location: "armory".into(),
+        entry_direction: Direction::Down,
+        min_depth: 4,
+        max_depth: 6,
+        room_templates: vec![
+            room("Narrow Tunnel", "A cramped tunnel hewn from rough stone. Water seeps through cracks in the walls.", Mood::Dark),
+            room("Musty Chamber", "A large chamber with a low ceiling. Mushrooms grow in clusters along the walls.", Mood::Mysterious),
+            room("Collapsed Hall", "Once a grand hall, now half-buried in rubble. Dust motes dance in shafts of dim light.", Mood::Tense),
+            room("Flooded Passage", "Ankle-deep water fills this passage. Something ripples beneath the surface.", Mood::Dark),
+            room("Bone Gallery", "Walls lined with ancient bones arranged in deliberate patterns. A dark shrine stands at one end.", Mood::Dangerous),
+            room("Crystal Cavern", "Crystalline formations jut from every surface, casting prismatic reflections.", Mood::Mysterious),
+            room("Ancient Forge", "A dwarven forge, cold and silent. Tools still lie where their owners left them.", Mood::Tense),
+        ],
+        enemy_table: vec![EnemyTemplate {
+            id: "guard".into(),
+            name: "Dungeon Lurker".into(),
+            description: "A twisted creature adapted to the darkness. Its pale eyes gleam with hunger.".into(),
+            personality_seed: "Hostile. Attacks on sight. Protects its territory.".into(),
+            health: 5,
+            attack: 2,
+            defense: 0,
+            items: vec![],
+            examine_text: None,
+            loot_table: Some("dungeon_lurker".into()),
+            weight: 1,
+        }],
+        enemy_chance: 35,
+        loot_table: Some("dungeon_rooms".into()),
+        loot_chance: 30,
+        scaling: DifficultyScaling {
+            health_per_depth: 5,
+            attack_per_depth: 1,
+            defense_per_depth: 0,
+            health_per_level: 2,
+            attack_per_level: 1,
+            defense_per_level: 1,
+        },
+        final_room: Some(room(
+            "The Dungeon Heart",
+            "The deepest point of the dungeon. An oppressive aura permeates the air. A massive creature guards a chest overflowing with treasures.",
+            Mood::Dangerous,
+        )),
+        boss: Some(EnemyTemplate {
+            id: "boss".into(),
+            name: "The Dungeon Keeper".into(),
+            description: "A massive, armored beast with eyes like molten gold. It guards its hoard with primal fury.".into(),
+            personality_seed: "Territorial and primal. Roars before attacking. Ancient and powerful.".into(),
+            health: 10,
+            attack: 4,
+            defense: 0,
+            items: vec!["dungeon_key_shard".into()],
+            examine_text: Some("Scars criss-cross its thick hide. A crown of twisted metal sits upon its head — perhaps it was once something more.".into()),
+            loot_table: None,
+            weight: 1,
+        }),
+        final_items: vec!["dungeon_treasure".into()],
+    }]
+}
+
 fn build_events() -> Vec<GameEvent> {
     vec![
         // Crypt passage damage (repeating)
         GameEvent {
+            id: None,
             trigger: EventTrigger::OnEnter,
             action: EventAction::Damage(5),
             one_shot: false,
             fired: false,
             location_id: "crypt_passage".into(),
+            condition: None,
         },
         // Deep Chamber: spawn The Warden on first visit
         GameEvent {
+            id: None,
             trigger: EventTrigger::OnEnter,
             action: EventAction::SpawnNpc("the_warden".into()),
             one_shot: true,
             fired: false,
             location_id: "deep_chamber".into(),
+            condition: None,
+        },
+        // Deep Chamber: the runes pulse while the player lingers below
+        GameEvent {
+            id: None,
+            trigger: EventTrigger::OnEnter,
+            action: EventAction::ScheduleRepeating("deep_chamber_pulse".into(), 4),
+            one_shot: true,
+            fired: false,
+            location_id: "deep_chamber".into(),
+            condition: None,
+        },
+        GameEvent {
+            id: Some("deep_chamber_pulse".into()),
+            trigger: EventTrigger::OnTimer,
+            action: EventAction::Message("The runes flare in time with the fungi's pulse. Somewhere below, something stirs.".into()),
+            one_shot: false,
+            fired: false,
+            location_id: "deep_chamber".into(),
+            condition: Some(Condition::AtLocation("deep_chamber".into())),
         },
         // Ancient amulet pickup message
         GameEvent {
+            id: None,
             trigger: EventTrigger::OnTake("ancient_amulet".into()),
             action: EventAction::Message("The amulet pulses with warmth as you touch it. You feel a connection to something ancient.".into()),
             one_shot: true,
             fired: false,
             location_id: "deep_chamber".into(),
+            condition: None,
         },
         // Sacred scroll at Chapel unlocks passage to Crypt
         GameEvent {
+            id: None,
             trigger: EventTrigger::OnUse("sacred_scroll".into()),
             action: EventAction::Unlock(Direction::South),
             one_shot: true,
             fired: false,
             location_id: "chapel".into(),
+            condition: None,
         },
         GameEvent {
+            id: None,
             trigger: EventTrigger::OnUse("sacred_scroll".into()),
             action: EventAction::Message("The scroll dissolves into light. Ancient words echo through the chapel. The floor trembles — a hidden passage opens downward.".into()),
             one_shot: true,
             fired: false,
             location_id: "chapel".into(),
+            condition: None,
+        },
+        // The chapel tends to the badly wounded, once
+        GameEvent {
+            id: None,
+            trigger: EventTrigger::OnEnter,
+            action: EventAction::Message("Warmth spills from the altar and settles over your wounds. The chapel remembers how to heal.".into()),
+            one_shot: true,
+            fired: false,
+            location_id: "chapel".into(),
+            condition: Some(Condition::HealthBelow(30)),
+        },
+        GameEvent {
+            id: None,
+            trigger: EventTrigger::OnEnter,
+            action: EventAction::Heal(15),
+            one_shot: true,
+            fired: false,
+            location_id: "chapel".into(),
+            condition: Some(Condition::HealthBelow(30)),
         },
         // Merchant journal at Chapel
         GameEvent {
+            id: None,
             trigger: EventTrigger::OnUse("merchant_journal".into()),
             action: EventAction::SetQuestFlag("merchant_quest_complete".into()),
             one_shot: true,
             fired: false,
             location_id: "chapel".into(),
+            condition: None,
         },
         GameEvent {
+            id: None,
             trigger: EventTrigger::OnUse("merchant_journal".into()),
             action: EventAction::Message("You place the journal on the altar. It glows briefly, then fades. Somewhere, a spirit finds peace. You feel blessed.".into()),
             one_shot: true,
             fired: false,
             location_id: "chapel".into(),
+            condition: None,
         },
         // Armory trap on first visit
         GameEvent {
+            id: None,
             trigger: EventTrigger::OnEnter,
             action: EventAction::Damage(10),
             one_shot: true,
             fired: false,
             location_id: "armory".into(),
+            condition: None,
         },
         GameEvent {
+            id: None,
             trigger: EventTrigger::OnEnter,
             action: EventAction::Message("A blade swings from the shadows! You barely dodge, but it catches your side.".into()),
             one_shot: true,
             fired: false,
             location_id: "armory".into(),
+            condition: None,
         },
     ]
 }
@@ -1071,8 +1715,9 @@ mod tests {
     #[test]
     fn exits_are_bidirectional() {
         let state = build_thornhold();
-        // hidden_vault is a secret room reachable only via the "plugh" command,
-        // so its exit to great_hall is intentionally one-way at world build time.
+        // hidden_vault is a secret room reachable only via the "plugh" command
+        // or a search of the great hall, so its exit to great_hall is
+        // intentionally one-way at world build time.
         let secret_exits: std::collections::HashSet<(&str, &str)> =
             [("hidden_vault", "great_hall")].into_iter().collect();
         for (loc_id, loc) in &state.locations {
@@ -1143,7 +1788,10 @@ mod tests {
     fn quest_objectives_are_valid() {
         let state = build_thornhold();
         for (quest_id, quest) in &state.quests {
-            match &quest.objective {
+            let Some(objective) = &quest.objective else {
+                continue;
+            };
+            match objective {
                 QuestObjective::FetchItem(item_id) => {
                     assert!(
                         state.items.contains_key(item_id),
@@ -1165,6 +1813,8 @@ mod tests {
                         quest_id, loc_id
                     );
                 }
+                // Other objective kinds are covered by module validation
+                _ => {}
             }
         }
     }
@@ -1202,6 +1852,13 @@ mod tests {
                 );
             }
         }
+
+        for door in state.doors.values().filter(|d| d.state == DoorState::Locked) {
+            let key_id = door.key_id.as_ref().expect("locked doors need a key");
+            let placed = state.locations.values().any(|l| l.items.contains(key_id))
+                || state.npcs.values().any(|n| n.items.contains(key_id));
+            assert!(placed, "Door {} requires key {} but no one has it", door.id, key_id);
+        }
     }
 
     #[test]
@@ -0,0 +1,129 @@
+use serde::{Deserialize, Serialize};
+
+pub const MINUTES_PER_DAY: u32 = 24 * 60;
+
+fn default_start() -> u32 {
+    8 * 60
+}
+
+fn default_action_minutes() -> u32 {
+    5
+}
+
+fn default_move_minutes() -> u32 {
+    15
+}
+
+fn default_rest_minutes() -> u32 {
+    60
+}
+
+/// In-game time, counted in minutes since midnight of the first day. Each
+/// action, move and turn of rest advances it by its configured cost.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorldClock {
+    #[serde(default = "default_start")]
+    pub minutes: u32,
+    #[serde(default = "default_action_minutes")]
+    pub minutes_per_action: u32,
+    #[serde(default = "default_move_minutes")]
+    pub minutes_per_move: u32,
+    #[serde(default = "default_rest_minutes")]
+    pub minutes_per_rest: u32,
+}
+
+impl Default for WorldClock {
+    fn default() -> Self {
+        Self {
+            minutes: default_start(),
+            minutes_per_action: default_action_minutes(),
+            minutes_per_move: default_move_minutes(),
+            minutes_per_rest: default_rest_minutes(),
+        }
+    }
+}
+
+impl WorldClock {
+    /// Day number, starting at 1.
+    pub fn day(&self) -> u32 {
+        self.minutes / MINUTES_PER_DAY + 1
+    }
+
+    pub fn hour(&self) -> u32 {
+        self.minutes % MINUTES_PER_DAY / 60
+    }
+
+    pub fn minute(&self) -> u32 {
+        self.minutes % 60
+    }
+
+    pub fn phase(&self) -> DayPhase {
+        DayPhase::from_hour(self.hour())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DayPhase {
+    /// 05:00 to 07:59.
+    Dawn,
+    /// 08:00 to 17:59.
+    Day,
+    /// 18:00 to 20:59.
+    Dusk,
+    /// 21:00 to 04:59.
+    Night,
+}
+
+impl DayPhase {
+    pub fn from_hour(hour: u32) -> Self {
+        match hour {
+            5..=7 => DayPhase::Dawn,
+            8..=17 => DayPhase::Day,
+            18..=20 => DayPhase::Dusk,
+            _ => DayPhase::Night,
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            DayPhase::Dawn => "Dawn",
+            DayPhase::Day => "Day",
+            DayPhase::Dusk => "Dusk",
+            DayPhase::Night => "Night",
+        }
+    }
+
+    /// Percent applied to enemy spawn chances; daytime is the baseline.
+    pub fn spawn_rate(&self) -> u32 {
+        match self {
+            DayPhase::Dawn | DayPhase::Day => 100,
+            DayPhase::Dusk => 125,
+            DayPhase::Night => 150,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clock_reads_day_hour_and_phase() {
+        let clock: WorldClock = serde_json::from_str(r#"{ "minutesPerMove": 30 }"#).unwrap();
+        assert_eq!(clock.minutes_per_move, 30);
+        assert_eq!(clock.minutes_per_action, 5);
+        assert_eq!((clock.day(), clock.hour()), (1, 8));
+        assert_eq!(clock.phase(), DayPhase::Day);
+
+        let late = WorldClock {
+            minutes: MINUTES_PER_DAY + 21 * 60 + 15,
+            ..WorldClock::default()
+        };
+        assert_eq!((late.day(), late.hour(), late.minute()), (2, 21, 15));
+        assert_eq!(late.phase(), DayPhase::Night);
+        assert_eq!(DayPhase::from_hour(4), DayPhase::Night);
+        assert_eq!(DayPhase::from_hour(5), DayPhase::Dawn);
+    }
+}
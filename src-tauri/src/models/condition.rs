@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+
+use super::clock::DayPhase;
+use super::npc::DialogueState;
+
+/// A test against the world state, used to gate events. Leaf conditions
+/// name flags, items, NPCs and locations by id; `all`, `any` and `not`
+/// combine them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Condition {
+    /// A quest flag is set.
+    Flag(String),
+    HasItem(String),
+    /// Player health is at or above the value.
+    HealthAtLeast(i32),
+    /// Player health is below the value.
+    HealthBelow(i32),
+    /// The turn count lies within an inclusive range.
+    TurnBetween(u32, u32),
+    /// The world clock is in the given phase of the day.
+    TimeOfDay(DayPhase),
+    NpcState(String, DialogueState),
+    /// An NPC's relationship with the player is at or above the value.
+    RelationshipAtLeast(String, i32),
+    /// The player's reputation with a faction is at or above the value.
+    ReputationAtLeast(String, i32),
+    Visited(String),
+    QuestCompleted(String),
+    /// The player is standing in the location.
+    AtLocation(String),
+    All(Vec<Condition>),
+    Any(Vec<Condition>),
+    Not(Box<Condition>),
+}
+
+impl Condition {
+    /// Nesting depth; a leaf is 1.
+    pub fn depth(&self) -> usize {
+        match self {
+            Condition::All(terms) | Condition::Any(terms) => {
+                1 + terms.iter().map(Condition::depth).max().unwrap_or(0)
+            }
+            Condition::Not(inner) => 1 + inner.depth(),
+            _ => 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn condition_serde_roundtrip() {
+        let condition = Condition::All(vec![
+            Condition::Flag("seal_broken".into()),
+            Condition::Not(Box::new(Condition::HasItem("torch".into()))),
+            Condition::NpcState("the_warden".into(), DialogueState::Dead),
+        ]);
+        let json = serde_json::to_string(&condition).unwrap();
+        assert!(json.contains("hasItem"));
+        assert!(json.contains("npcState"));
+        let deserialized: Condition = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, condition);
+        assert_eq!(condition.depth(), 3);
+    }
+}
@@ -9,6 +9,10 @@ pub struct CraftingRecipe {
     pub hint: String,
     #[serde(default)]
     pub discovered: bool,
+    /// Repair recipes restore `output` (one of the inputs) to full durability
+    /// and consume only the other input.
+    #[serde(default)]
+    pub repairs: bool,
 }
 
 #[cfg(test)]
@@ -23,6 +27,7 @@ mod tests {
             output: "c".into(),
             hint: "Combine a and b".into(),
             discovered: false,
+            repairs: false,
         };
         let json = serde_json::to_string(&recipe).unwrap();
         let parsed: CraftingRecipe = serde_json::from_str(&json).unwrap();
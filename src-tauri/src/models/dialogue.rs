@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::condition::Condition;
+use super::event::EventAction;
+
+/// An authored conversation graph for one NPC. Talking to the NPC opens the
+/// `start` node; each choice leads to another node or ends the conversation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DialogueTree {
+    pub start: String,
+    pub nodes: HashMap<String, DialogueNode>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DialogueNode {
+    /// What the NPC says.
+    pub text: String,
+    /// Applied each time the node is reached.
+    #[serde(default)]
+    pub effects: Vec<EventAction>,
+    /// Numbered replies. A node without any ends the conversation.
+    #[serde(default)]
+    pub choices: Vec<DialogueChoice>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DialogueChoice {
+    pub text: String,
+    /// The choice is only offered while the condition holds.
+    #[serde(default)]
+    pub condition: Option<Condition>,
+    #[serde(default)]
+    pub skill_check: Option<SkillCheck>,
+    /// Applied when the choice is picked and any skill check passes.
+    #[serde(default)]
+    pub effects: Vec<EventAction>,
+    /// Node reached next; ends the conversation when absent.
+    #[serde(default)]
+    pub next: Option<String>,
+    /// Node reached when the skill check fails; ends the conversation when
+    /// absent.
+    #[serde(default)]
+    pub fail_next: Option<String>,
+}
+
+/// A d20 roll plus the player's skill, which passes at or above `difficulty`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillCheck {
+    pub skill: Skill,
+    pub difficulty: i32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Skill {
+    /// Total attack, including gear and statuses.
+    Might,
+    /// Total defense, including gear and statuses.
+    Resolve,
+    /// A tenth of the NPC's relationship with the player plus the player's
+    /// reputation with its faction.
+    Charm,
+}
+
+impl Skill {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Skill::Might => "Might",
+            Skill::Resolve => "Resolve",
+            Skill::Charm => "Charm",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dialogue_tree_serde_roundtrip() {
+        let json = r#"{
+            "start": "hello",
+            "nodes": {
+                "hello": {
+                    "text": "Who goes there?",
+                    "choices": [
+                        {"text": "A friend.", "next": "friend"},
+                        {"text": "Stand aside!", "skillCheck": {"skill": "might", "difficulty": 12}}
+                    ]
+                },
+                "friend": {"text": "Then pass.", "effects": [{"setQuestFlag": "gate_open"}]}
+            }
+        }"#;
+        let tree: DialogueTree = serde_json::from_str(json).unwrap();
+        let hello = &tree.nodes["hello"];
+        assert_eq!(hello.choices.len(), 2);
+        assert_eq!(
+            hello.choices[1].skill_check,
+            Some(SkillCheck {
+                skill: Skill::Might,
+                difficulty: 12
+            })
+        );
+        assert!(hello.choices[1].next.is_none());
+        assert_eq!(tree.nodes["friend"].effects.len(), 1);
+    }
+}
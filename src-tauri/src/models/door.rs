@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize};
+
+use super::location::LocationId;
+
+fn default_lock_difficulty() -> u32 {
+    30
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DoorState {
+    Open,
+    #[default]
+    Closed,
+    Locked,
+}
+
+impl DoorState {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            DoorState::Open => "open",
+            DoorState::Closed => "closed",
+            DoorState::Locked => "locked",
+        }
+    }
+}
+
+/// A door between two rooms. Each side's `Location.doors` maps the exit's
+/// direction to the door's id, so both sides share one state.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Door {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub state: DoorState,
+    /// Key that unlocks it, used up in the lock.
+    #[serde(default)]
+    pub key_id: Option<String>,
+    /// 0 to 100, taken off the chances of picking the lock or bashing the
+    /// door in.
+    #[serde(default = "default_lock_difficulty")]
+    pub lock_difficulty: u32,
+    /// Sealed doors can't be picked or bashed; only a key or an event opens
+    /// them.
+    #[serde(default)]
+    pub sealed: bool,
+    /// The only room the door can be opened or unlocked from, as with a bar
+    /// on one side. Once open it can be passed, and closed, from either
+    /// room. `None` works from both.
+    #[serde(default)]
+    pub opens_from: Option<LocationId>,
+    /// Bashed-in doors hang open for good.
+    #[serde(default)]
+    pub broken: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn door_serde_defaults() {
+        let door: Door =
+            serde_json::from_str(r#"{ "id": "oak_door", "name": "oak door" }"#).unwrap();
+        assert_eq!(door.state, DoorState::Closed);
+        assert_eq!(door.lock_difficulty, 30);
+        assert!(door.key_id.is_none() && door.opens_from.is_none());
+        assert!(!door.sealed && !door.broken);
+    }
+}
@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::region::{DifficultyScaling, EnemyTemplate};
+
+fn default_max_active() -> usize {
+    2
+}
+
+/// Enemies that turn up at random in a set of rooms, and that move back
+/// into covered rooms once they have stood empty for a while.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncounterTable {
+    pub id: String,
+    #[serde(default)]
+    pub locations: Vec<String>,
+    /// Procedural regions whose generated rooms the table also covers.
+    #[serde(default)]
+    pub regions: Vec<String>,
+    /// Percent chance per move into, or turn of rest in, a covered room.
+    pub chance: u32,
+    /// Turns after an encounter before the table rolls again.
+    #[serde(default)]
+    pub cooldown: u32,
+    /// Turns a covered room must stand without enemies before one moves
+    /// back in. Rooms never repopulate when unset.
+    #[serde(default)]
+    pub respawn_after: Option<u32>,
+    /// Most enemies from this table alive at once.
+    #[serde(default = "default_max_active")]
+    pub max_active: usize,
+    pub enemies: Vec<EnemyTemplate>,
+    #[serde(default)]
+    pub scaling: DifficultyScaling,
+    /// Turn of the last encounter.
+    #[serde(default)]
+    pub last_encounter: Option<u32>,
+    /// Living enemies spawned from this table.
+    #[serde(default)]
+    pub active: Vec<String>,
+    /// Turn each covered room was first seen without enemies.
+    #[serde(default)]
+    pub empty_since: HashMap<String, u32>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encounter_table_serde_defaults() {
+        let json = r#"{
+            "id": "crypt_dead",
+            "locations": ["crypt_passage"],
+            "chance": 20,
+            "enemies": [{
+                "id": "bones",
+                "name": "Restless Bones",
+                "description": "Rattling.",
+                "personalitySeed": "Mindless.",
+                "health": 10,
+                "attack": 3,
+                "defense": 1
+            }]
+        }"#;
+        let table: EncounterTable = serde_json::from_str(json).unwrap();
+        assert_eq!(table.max_active, 2);
+        assert_eq!(table.respawn_after, None);
+        assert!(table.active.is_empty());
+        assert_eq!(table.enemies[0].weight, 1);
+    }
+}
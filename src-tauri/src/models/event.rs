@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 
+use super::condition::Condition;
 use super::location::Direction;
+use super::world::{EndingType, SoundCue};
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -10,6 +12,9 @@ pub enum EventTrigger {
     OnUse(String),
     OnKill(String),
     OnTurn(u32),
+    /// Never fires on its own; only when a timer started by a `Schedule`
+    /// action comes due.
+    OnTimer,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -24,16 +29,70 @@ pub enum EventAction {
     ApplyStatus(super::player::StatusEffect),
     RemoveStatus(String),
     ChangeDescription(String, String),
+    GiveLoot(String),
+    Heal(i32),
+    /// Moves the player to a location without firing its OnEnter events.
+    Teleport(String),
+    /// Marks the rooms beyond the current room's exits on the map.
+    RevealExits,
+    StartQuest(String),
+    /// Seals an exit of the event's room; only an `Unlock` action reopens it.
+    Lock(Direction),
+    /// Opens a new exit from the event's room to a location.
+    AddExit(Direction, String),
+    RemoveExit(Direction),
+    /// Takes an item from the player, or failing that from the event's room.
+    RemoveItem(String),
+    /// Moves an NPC to a location, wherever it currently stands.
+    MoveNpc(String, String),
+    SetNpcHostile(String, bool),
+    /// Adjusts an NPC's relationship with the player by the given amount.
+    ChangeRelationship(String, i32),
+    /// Adjusts reputation with a faction, rippling to its allies and enemies.
+    ChangeReputation(String, i32),
+    /// Completes a quest outright and hands over its rewards.
+    CompleteQuest(String),
+    /// Fails an unfinished quest, applying its consequences.
+    FailQuest(String),
+    PlaySound(SoundCue),
+    EndGame(EndingType),
+    /// Runs several actions in order.
+    Sequence(Vec<EventAction>),
+    /// Fires the event with this id once, after the given number of turns.
+    Schedule(String, u32),
+    /// Fires the event with this id every N turns until cancelled.
+    ScheduleRepeating(String, u32),
+    /// Drops any pending timer for the event with this id.
+    CancelTimer(String),
+    /// Runs the module script with this id.
+    RunScript(String),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GameEvent {
+    /// Name used by `Schedule` and `CancelTimer` actions to refer to the event.
+    #[serde(default)]
+    pub id: Option<String>,
     pub trigger: EventTrigger,
     pub action: EventAction,
     pub one_shot: bool,
     pub fired: bool,
     pub location_id: String,
+    /// When set, the event only fires while the condition holds.
+    #[serde(default)]
+    pub condition: Option<Condition>,
+}
+
+/// A pending countdown to a scheduled event.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventTimer {
+    pub event_id: String,
+    pub due_turn: u32,
+    /// Interval to reschedule at after firing; `None` fires once.
+    #[serde(default)]
+    pub repeat_every: Option<u32>,
 }
 
 #[cfg(test)]
@@ -43,11 +102,13 @@ mod tests {
     #[test]
     fn event_serde_roundtrip() {
         let event = GameEvent {
+            id: None,
             trigger: EventTrigger::OnEnter,
             action: EventAction::Damage(5),
             one_shot: false,
             fired: false,
             location_id: "crypt_passage".into(),
+            condition: None,
         };
         let json = serde_json::to_string(&event).unwrap();
         assert!(json.contains("oneShot"));
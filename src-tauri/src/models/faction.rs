@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+
+/// A group of NPCs that shares the player's reputation. What the player does
+/// to one member shifts standing with the whole faction, and in turn with
+/// its allies and enemies.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Faction {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Factions that share half of any reputation change.
+    #[serde(default)]
+    pub allies: Vec<String>,
+    /// Factions that take the opposite of half of any reputation change.
+    #[serde(default)]
+    pub enemies: Vec<String>,
+    /// Items the faction owns; taking one in front of a member is theft.
+    #[serde(default)]
+    pub property: Vec<String>,
+    /// Current standing with the player, from -100 to 100.
+    #[serde(default)]
+    pub reputation: i32,
+    /// Members the faction turned on the player; only these calm down
+    /// again once standing recovers.
+    #[serde(default)]
+    pub turned_hostile: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ReputationTier {
+    /// Members attack on sight.
+    Hostile,
+    /// Members offer no quests or services.
+    Unfriendly,
+    Neutral,
+    Friendly,
+    Honored,
+}
+
+impl ReputationTier {
+    pub fn from_reputation(reputation: i32) -> Self {
+        match reputation {
+            i32::MIN..=-50 => ReputationTier::Hostile,
+            -49..=-15 => ReputationTier::Unfriendly,
+            -14..=14 => ReputationTier::Neutral,
+            15..=49 => ReputationTier::Friendly,
+            _ => ReputationTier::Honored,
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            ReputationTier::Hostile => "Hostile",
+            ReputationTier::Unfriendly => "Unfriendly",
+            ReputationTier::Neutral => "Neutral",
+            ReputationTier::Friendly => "Friendly",
+            ReputationTier::Honored => "Honored",
+        }
+    }
+}
+
+impl Faction {
+    pub fn tier(&self) -> ReputationTier {
+        ReputationTier::from_reputation(self.reputation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn faction_serde_roundtrip() {
+        let json = r#"{
+            "id": "castle_guard",
+            "name": "The Castle Guard",
+            "enemies": ["crypt_cult"],
+            "reputation": -20
+        }"#;
+        let faction: Faction = serde_json::from_str(json).unwrap();
+        assert_eq!(faction.enemies, vec!["crypt_cult"]);
+        assert!(faction.allies.is_empty());
+        assert_eq!(faction.tier(), ReputationTier::Unfriendly);
+        assert_eq!(ReputationTier::from_reputation(50), ReputationTier::Honored);
+        assert_eq!(
+            ReputationTier::from_reputation(-50),
+            ReputationTier::Hostile
+        );
+    }
+}
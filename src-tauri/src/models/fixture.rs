@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::event::EventAction;
+use super::location::LocationId;
+
+/// The state a riddle takes once answered.
+pub const RIDDLE_SOLVED: &str = "solved";
+
+/// An object fixed in a room that the player works rather than carries: a
+/// lever, a combination lock or a riddle. Rooms list them by id in
+/// `Location.fixtures`; riddle NPCs name theirs in `Npc.riddle`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Fixture {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub kind: FixtureKind,
+    /// Where it stands now: a lever's position, the combination a lock is
+    /// set to, or `RIDDLE_SOLVED`. Empty until first worked; a lever then
+    /// rests in its first position.
+    #[serde(default)]
+    pub state: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FixtureKind {
+    /// Pulled forward or pushed back through its named positions, such as
+    /// "up" and "down".
+    Lever { positions: Vec<String> },
+    /// Set to a combination of this many digits.
+    Combination { length: usize },
+    /// Solved by any of its answers, each a synonym of the others.
+    Riddle {
+        question: String,
+        answers: Vec<String>,
+    },
+}
+
+impl Fixture {
+    /// The state puzzles see, with a lever never yet moved in its first
+    /// position.
+    pub fn current(&self) -> &str {
+        match &self.kind {
+            FixtureKind::Lever { positions } if self.state.is_empty() => {
+                positions.first().map_or("", String::as_str)
+            }
+            _ => &self.state,
+        }
+    }
+
+    /// Whether the player can ever bring it to the given state.
+    pub fn can_reach(&self, state: &str) -> bool {
+        match &self.kind {
+            FixtureKind::Lever { positions } => positions.iter().any(|p| p == state),
+            FixtureKind::Combination { length } => {
+                state.len() == *length && state.chars().all(|c| c.is_ascii_digit())
+            }
+            FixtureKind::Riddle { answers, .. } => state == RIDDLE_SOLVED && !answers.is_empty(),
+        }
+    }
+}
+
+/// Solved the moment every fixture in `solution` stands in its given state.
+/// Its actions then run once, as an event's would in `location_id`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Puzzle {
+    pub id: String,
+    pub location_id: LocationId,
+    /// Fixture id to the state it must be in.
+    pub solution: HashMap<String, String>,
+    #[serde(default)]
+    pub on_solve: Vec<EventAction>,
+    /// Shown when it is solved.
+    #[serde(default)]
+    pub solved_text: Option<String>,
+    #[serde(default)]
+    pub solved: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levers_rest_in_their_first_position() {
+        let json = r#"{
+            "id": "gate_lever",
+            "name": "rusted lever",
+            "kind": { "lever": { "positions": ["up", "down"] } }
+        }"#;
+        let lever: Fixture = serde_json::from_str(json).unwrap();
+        assert_eq!(lever.current(), "up");
+        assert!(lever.can_reach("down"));
+        assert!(!lever.can_reach("sideways"));
+
+        let lock = Fixture {
+            kind: FixtureKind::Combination { length: 3 },
+            ..lever
+        };
+        assert_eq!(lock.current(), "");
+        assert!(lock.can_reach("042"));
+        assert!(!lock.can_reach("42") && !lock.can_reach("4a2"));
+    }
+}
@@ -1,8 +1,10 @@
 use serde::{Deserialize, Serialize};
 
+use super::event::EventAction;
+
 pub type ItemId = String;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum ItemType {
     Weapon,
@@ -11,6 +13,7 @@ pub enum ItemType {
     Key,
     Scroll,
     Quest,
+    #[default]
     Miscellaneous,
 }
 
@@ -22,7 +25,161 @@ pub struct StatModifier {
     pub health: i32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum Rarity {
+    #[default]
+    Common,
+    Uncommon,
+    Rare,
+    Epic,
+    Legendary,
+}
+
+impl Rarity {
+    pub fn display_name(&self) -> &str {
+        match self {
+            Rarity::Common => "Common",
+            Rarity::Uncommon => "Uncommon",
+            Rarity::Rare => "Rare",
+            Rarity::Epic => "Epic",
+            Rarity::Legendary => "Legendary",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum SpecialEffect {
+    /// Poisons the target on hit for `turns` rounds.
+    Poison { damage_per_turn: i32, turns: i32 },
+    /// Heals the wielder for a percentage of damage dealt.
+    Lifesteal { percent: i32 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AffixKind {
+    Prefix,
+    Suffix,
+}
+
+/// Where an equippable item is worn. Weapons and armor fit their obvious
+/// slots unless the item says otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EquipSlot {
+    Weapon,
+    Armor,
+    Helmet,
+    Shield,
+    Ring,
+    Amulet,
+    Boots,
+}
+
+impl EquipSlot {
+    pub const ALL: [EquipSlot; 7] = [
+        EquipSlot::Weapon,
+        EquipSlot::Armor,
+        EquipSlot::Helmet,
+        EquipSlot::Shield,
+        EquipSlot::Ring,
+        EquipSlot::Amulet,
+        EquipSlot::Boots,
+    ];
+
+    pub fn display_name(&self) -> &str {
+        match self {
+            EquipSlot::Weapon => "Weapon",
+            EquipSlot::Armor => "Armor",
+            EquipSlot::Helmet => "Helmet",
+            EquipSlot::Shield => "Off-hand",
+            EquipSlot::Ring => "Ring",
+            EquipSlot::Amulet => "Amulet",
+            EquipSlot::Boots => "Boots",
+        }
+    }
+}
+
+/// Wear on a weapon or armor piece. Only ticks down when the module has
+/// durability enabled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Durability {
+    pub current: u32,
+    pub max: u32,
+}
+
+impl Durability {
+    pub fn is_broken(&self) -> bool {
+        self.current == 0
+    }
+
+    /// True once the item is at a quarter of its durability or less.
+    pub fn is_worn(&self) -> bool {
+        self.current * 4 <= self.max
+    }
+
+    pub fn condition(&self) -> &str {
+        if self.is_broken() {
+            "Broken"
+        } else if self.is_worn() {
+            "Badly worn"
+        } else if self.current * 4 <= self.max * 3 {
+            "Worn"
+        } else {
+            "Good"
+        }
+    }
+}
+
+/// Who an item effect lands on. Only damage, healing and statuses can be
+/// aimed at an enemy; every other action affects the world around the player.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EffectTarget {
+    #[default]
+    Player,
+    /// The combat opponent, or the first hostile NPC in the room.
+    Enemy,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemEffect {
+    pub action: EventAction,
+    #[serde(default)]
+    pub target: EffectTarget,
+}
+
+fn default_weight() -> u32 {
+    1
+}
+
+/// A named modifier the item generator can roll onto a base item, e.g. the
+/// "Keen" in "Keen Short Sword of Warding".
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Affix {
+    pub id: String,
+    pub name: String,
+    pub kind: AffixKind,
+    #[serde(default)]
+    pub modifier: StatModifier,
+    #[serde(default)]
+    pub effect: Option<SpecialEffect>,
+    /// Lowest rarity this affix can roll on.
+    #[serde(default)]
+    pub min_rarity: Rarity,
+    /// Item types the affix fits; empty means any.
+    #[serde(default)]
+    pub item_types: Vec<ItemType>,
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Item {
     pub id: ItemId,
@@ -35,6 +192,75 @@ pub struct Item {
     pub key_id: Option<String>,
     #[serde(default)]
     pub lore: Option<String>,
+    /// Makes the item a container; opening it rolls this loot table once.
+    #[serde(default)]
+    pub loot_table: Option<String>,
+    #[serde(default)]
+    pub rarity: Rarity,
+    /// Affixes rolled onto a generated item, their modifiers scaled by its
+    /// rarity. Their bonuses are already folded into `modifier`.
+    #[serde(default)]
+    pub affixes: Vec<Affix>,
+    #[serde(default)]
+    pub special_effects: Vec<SpecialEffect>,
+    #[serde(default)]
+    pub slot: Option<EquipSlot>,
+    /// Id of the `ItemSet` this piece belongs to.
+    #[serde(default)]
+    pub set_id: Option<String>,
+    #[serde(default)]
+    pub durability: Option<Durability>,
+    /// Makes the item a repair kit restoring this many durability points.
+    #[serde(default)]
+    pub repair_amount: Option<u32>,
+    /// Makes the item a lockpick adding this much to the chance of picking
+    /// a lock.
+    #[serde(default)]
+    pub lockpick: Option<u32>,
+    /// Actions run on use, replacing the built-in behaviour for the item type.
+    #[serde(default)]
+    pub effects: Vec<ItemEffect>,
+    /// Uses left before the item is spent; `None` means unlimited.
+    #[serde(default)]
+    pub charges: Option<u32>,
+    /// Turns before the item can be used again.
+    #[serde(default)]
+    pub cooldown: u32,
+    /// Gives off light while carried, which gives a sneaking player away.
+    #[serde(default)]
+    pub light: bool,
+}
+
+impl Item {
+    /// The slot this item occupies when equipped, or `None` if it can't be worn.
+    pub fn equip_slot(&self) -> Option<EquipSlot> {
+        self.slot.or(match self.item_type {
+            ItemType::Weapon => Some(EquipSlot::Weapon),
+            ItemType::Armor => Some(EquipSlot::Armor),
+            _ => None,
+        })
+    }
+
+    pub fn is_broken(&self) -> bool {
+        self.durability.is_some_and(|d| d.is_broken())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetBonus {
+    /// Number of pieces that must be worn for the bonus to apply.
+    pub pieces: usize,
+    pub modifier: StatModifier,
+}
+
+/// A themed group of items. Every bonus whose piece count is met stacks.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemSet {
+    pub id: String,
+    pub name: String,
+    pub bonuses: Vec<SetBonus>,
 }
 
 #[cfg(test)]
@@ -57,6 +283,7 @@ mod tests {
             consumable: false,
             key_id: None,
             lore: None,
+            ..Default::default()
         };
         let json = serde_json::to_string(&item).unwrap();
         assert!(json.contains("itemType"));
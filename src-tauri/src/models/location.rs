@@ -1,8 +1,15 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use super::clock::DayPhase;
+use super::secret::HiddenFeature;
+
 pub type LocationId = String;
 
+/// Stands in for a key in `locked_exits` when no key opens the way; only an
+/// `Unlock` event lifts it.
+pub const SEALED: &str = "sealed";
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum Direction {
@@ -56,9 +63,10 @@ impl std::fmt::Display for Direction {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum Mood {
+    #[default]
     Peaceful,
     Tense,
     Mysterious,
@@ -67,7 +75,7 @@ pub enum Mood {
     Dangerous,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Location {
     pub id: LocationId,
@@ -84,6 +92,19 @@ pub struct Location {
     pub examine_details: Option<String>,
     #[serde(default)]
     pub revisit_description: Option<String>,
+    /// Descriptions for particular times of day, used in place of the
+    /// usual one.
+    #[serde(default)]
+    pub phase_descriptions: HashMap<DayPhase, String>,
+    /// Exits, items and traps that only a search turns up.
+    #[serde(default)]
+    pub secrets: Vec<HiddenFeature>,
+    /// Id of the door standing in each exit.
+    #[serde(default)]
+    pub doors: HashMap<Direction, String>,
+    /// Ids of the fixtures fixed in this room.
+    #[serde(default)]
+    pub fixtures: Vec<String>,
 }
 
 #[cfg(test)]
@@ -121,6 +142,7 @@ mod tests {
             ambient_mood: Mood::Peaceful,
                 examine_details: None,
                 revisit_description: None,
+            ..Default::default()
         };
         let json = serde_json::to_string(&loc).unwrap();
         assert!(json.contains("ambientMood"));
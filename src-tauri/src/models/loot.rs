@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+
+use super::item::Rarity;
+
+fn default_one() -> u32 {
+    1
+}
+
+fn default_drop_chance() -> u32 {
+    100
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LootEntry {
+    pub item_id: String,
+    #[serde(default = "default_one")]
+    pub weight: u32,
+    #[serde(default = "default_one")]
+    pub min_quantity: u32,
+    #[serde(default = "default_one")]
+    pub max_quantity: u32,
+    #[serde(default)]
+    pub rarity: Rarity,
+    /// Guaranteed entries always drop and don't take part in weighted rolls.
+    #[serde(default)]
+    pub guaranteed: bool,
+    /// Rolls each unit through the item generator, using `rarity` as the floor.
+    #[serde(default)]
+    pub randomize: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LootTable {
+    pub id: String,
+    pub entries: Vec<LootEntry>,
+    /// Number of weighted picks made from the non-guaranteed entries.
+    #[serde(default = "default_one")]
+    pub rolls: u32,
+    /// Percent chance for each roll to yield anything, before difficulty scaling.
+    #[serde(default = "default_drop_chance")]
+    pub drop_chance: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loot_table_defaults_from_minimal_json() {
+        let json = r#"{ "id": "rats", "entries": [{ "itemId": "cheese" }] }"#;
+        let table: LootTable = serde_json::from_str(json).unwrap();
+        assert_eq!(table.rolls, 1);
+        assert_eq!(table.drop_chance, 100);
+        let entry = &table.entries[0];
+        assert_eq!(entry.weight, 1);
+        assert_eq!((entry.min_quantity, entry.max_quantity), (1, 1));
+        assert_eq!(entry.rarity, Rarity::Common);
+        assert!(!entry.guaranteed);
+        assert!(!entry.randomize);
+    }
+}
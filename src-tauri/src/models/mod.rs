@@ -1,24 +1,48 @@
 pub mod achievement;
+pub mod clock;
+pub mod condition;
 pub mod crafting;
+pub mod dialogue;
+pub mod door;
+pub mod encounter;
 pub mod event;
+pub mod faction;
+pub mod fixture;
 pub mod item;
 pub mod journal;
 pub mod location;
+pub mod loot;
 pub mod module;
 pub mod npc;
 pub mod player;
 pub mod quest;
+pub mod region;
+pub mod script;
+pub mod secret;
 pub mod settings;
+pub mod topic;
 pub mod world;
 
 pub use achievement::*;
+pub use clock::*;
+pub use condition::*;
 pub use crafting::*;
+pub use dialogue::*;
+pub use door::*;
+pub use encounter::*;
 pub use event::*;
+pub use faction::*;
+pub use fixture::*;
 pub use item::*;
 pub use journal::*;
 pub use location::*;
+pub use loot::*;
 pub use npc::*;
 pub use player::*;
 pub use quest::*;
+pub use region::*;
+pub use script::*;
+pub use secret::*;
 pub use settings::*;
+pub use topic::*;
 pub use world::*;
@@ -1,5 +1,9 @@
 use serde::{Deserialize, Serialize};
 
+use super::clock::DayPhase;
+use super::condition::Condition;
+use super::player::StatusEffect;
+
 pub type NpcId = String;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -9,9 +13,10 @@ pub struct NpcMemory {
     pub event: String,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum DialogueState {
+    #[default]
     Greeting,
     Familiar,
     QuestOffered,
@@ -21,7 +26,7 @@ pub enum DialogueState {
     Dead,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Npc {
     pub id: NpcId,
@@ -42,6 +47,69 @@ pub struct Npc {
     pub relationship: i32,
     #[serde(default)]
     pub memory: Vec<NpcMemory>,
+    #[serde(default)]
+    pub loot_table: Option<String>,
+    #[serde(default)]
+    pub status_effects: Vec<StatusEffect>,
+    /// Smiths repair worn equipment for the player.
+    #[serde(default)]
+    pub smith: bool,
+    /// Faction the NPC belongs to.
+    #[serde(default)]
+    pub faction: Option<String>,
+    /// How the NPC moves between locations on its own.
+    #[serde(default)]
+    pub movement: Option<NpcMovement>,
+    /// The player can recruit the NPC as a companion.
+    #[serde(default)]
+    pub recruitable: bool,
+    /// Further requirement for recruiting, e.g. a finished quest.
+    #[serde(default)]
+    pub recruit_condition: Option<Condition>,
+    /// Id of a riddle fixture the NPC poses to those who talk to them.
+    #[serde(default)]
+    pub riddle: Option<String>,
+}
+
+/// A recruited NPC travelling with the player.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Companion {
+    pub npc_id: NpcId,
+    /// Told to wait, so stays put instead of following.
+    #[serde(default)]
+    pub waiting: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NpcMovement {
+    /// Walks the route one room at a time, every `every` turns, looping back
+    /// to the start.
+    Patrol { route: Vec<String>, every: u32 },
+    /// Stands wherever the latest entry at or before the current turn says.
+    /// With a cycle the schedule repeats, e.g. every 24 turns.
+    Schedule {
+        entries: Vec<ScheduleEntry>,
+        #[serde(default)]
+        cycle: Option<u32>,
+    },
+    /// Each turn, with the given percent chance, steps through a random exit
+    /// into one of the allowed rooms.
+    Wander { rooms: Vec<String>, chance: u32 },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleEntry {
+    /// Turn (within the cycle) the NPC heads here.
+    #[serde(default)]
+    pub from: u32,
+    /// Time of day the NPC spends here instead; while it lasts, it wins
+    /// over the turn-based entries.
+    #[serde(default)]
+    pub phase: Option<DayPhase>,
+    pub location: String,
 }
 
 #[cfg(test)]
@@ -66,6 +134,7 @@ mod tests {
             examine_text: None,
             relationship: 0,
             memory: vec![],
+            ..Default::default()
         };
         let json = serde_json::to_string(&npc).unwrap();
         assert!(json.contains("personalitySeed"));
@@ -76,4 +145,24 @@ mod tests {
         assert_eq!(deserialized.id, "merchant_ghost");
         assert_eq!(deserialized.dialogue_state, DialogueState::Greeting);
     }
+
+    #[test]
+    fn npc_movement_serde_roundtrip() {
+        let json = r#"{"schedule": {"entries": [{"from": 0, "location": "chapel"}], "cycle": 24}}"#;
+        let movement: NpcMovement = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            movement,
+            NpcMovement::Schedule {
+                entries: vec![ScheduleEntry {
+                    from: 0,
+                    phase: None,
+                    location: "chapel".into()
+                }],
+                cycle: Some(24),
+            }
+        );
+        let json = r#"{"patrol": {"route": ["barracks", "armory"], "every": 2}}"#;
+        let movement: NpcMovement = serde_json::from_str(json).unwrap();
+        assert!(matches!(movement, NpcMovement::Patrol { every: 2, .. }));
+    }
 }
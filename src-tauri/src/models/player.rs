@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 
+use super::item::EquipSlot;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum StatusEffectType {
@@ -21,6 +23,10 @@ pub struct StatusEffect {
     pub defense_modifier: i32,
 }
 
+fn default_agility() -> i32 {
+    5
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Player {
@@ -33,6 +39,16 @@ pub struct Player {
     pub defense: i32,
     pub equipped_weapon: Option<String>,
     pub equipped_armor: Option<String>,
+    #[serde(default)]
+    pub equipped_helmet: Option<String>,
+    #[serde(default)]
+    pub equipped_shield: Option<String>,
+    #[serde(default)]
+    pub equipped_ring: Option<String>,
+    #[serde(default)]
+    pub equipped_amulet: Option<String>,
+    #[serde(default)]
+    pub equipped_boots: Option<String>,
     pub quest_flags: HashMap<String, bool>,
     pub visited_locations: HashSet<String>,
     pub turns_elapsed: u32,
@@ -40,6 +56,15 @@ pub struct Player {
     pub status_effects: Vec<StatusEffect>,
     #[serde(default)]
     pub discovered_secrets: Vec<String>,
+    /// Turn on which each item on cooldown becomes usable again.
+    #[serde(default)]
+    pub item_cooldowns: HashMap<String, u32>,
+    /// Base stealth, before armor and light are counted.
+    #[serde(default = "default_agility")]
+    pub agility: i32,
+    /// Set while hostiles nearby haven't noticed the player.
+    #[serde(default)]
+    pub hidden: bool,
 }
 
 impl Default for Player {
@@ -56,11 +81,67 @@ impl Default for Player {
             defense: 3,
             equipped_weapon: None,
             equipped_armor: None,
+            equipped_helmet: None,
+            equipped_shield: None,
+            equipped_ring: None,
+            equipped_amulet: None,
+            equipped_boots: None,
             quest_flags: HashMap::new(),
             visited_locations: visited,
             turns_elapsed: 0,
             status_effects: Vec::new(),
             discovered_secrets: Vec::new(),
+            item_cooldowns: HashMap::new(),
+            agility: default_agility(),
+            hidden: false,
+        }
+    }
+}
+
+impl Player {
+    pub fn equipped(&self, slot: EquipSlot) -> Option<&String> {
+        match slot {
+            EquipSlot::Weapon => self.equipped_weapon.as_ref(),
+            EquipSlot::Armor => self.equipped_armor.as_ref(),
+            EquipSlot::Helmet => self.equipped_helmet.as_ref(),
+            EquipSlot::Shield => self.equipped_shield.as_ref(),
+            EquipSlot::Ring => self.equipped_ring.as_ref(),
+            EquipSlot::Amulet => self.equipped_amulet.as_ref(),
+            EquipSlot::Boots => self.equipped_boots.as_ref(),
+        }
+    }
+
+    pub fn equipped_mut(&mut self, slot: EquipSlot) -> &mut Option<String> {
+        match slot {
+            EquipSlot::Weapon => &mut self.equipped_weapon,
+            EquipSlot::Armor => &mut self.equipped_armor,
+            EquipSlot::Helmet => &mut self.equipped_helmet,
+            EquipSlot::Shield => &mut self.equipped_shield,
+            EquipSlot::Ring => &mut self.equipped_ring,
+            EquipSlot::Amulet => &mut self.equipped_amulet,
+            EquipSlot::Boots => &mut self.equipped_boots,
+        }
+    }
+
+    /// Every filled slot, in display order.
+    pub fn equipped_items(&self) -> Vec<(EquipSlot, &String)> {
+        EquipSlot::ALL
+            .iter()
+            .filter_map(|slot| self.equipped(*slot).map(|id| (*slot, id)))
+            .collect()
+    }
+
+    pub fn is_equipped(&self, item_id: &str) -> bool {
+        self.equipped_items().iter().any(|(_, id)| *id == item_id)
+    }
+
+    /// Clears whichever slot holds `item_id`.
+    pub fn unequip_item(&mut self, item_id: &str) {
+        for slot in EquipSlot::ALL {
+            let equipped = self.equipped_mut(slot);
+            if equipped.as_deref() == Some(item_id) {
+                *equipped = None;
+            }
         }
     }
 }
@@ -94,4 +175,28 @@ mod tests {
         assert_eq!(deserialized.location, "courtyard");
         assert_eq!(deserialized.health, 100);
     }
+
+    #[test]
+    fn equipment_slots_roundtrip_and_unequip() {
+        let mut p = Player::default();
+        *p.equipped_mut(EquipSlot::Ring) = Some("silver_ring".into());
+        p.equipped_weapon = Some("short_sword".into());
+        assert_eq!(
+            p.equipped_items(),
+            vec![
+                (EquipSlot::Weapon, &"short_sword".to_string()),
+                (EquipSlot::Ring, &"silver_ring".to_string())
+            ]
+        );
+        p.unequip_item("silver_ring");
+        assert!(!p.is_equipped("silver_ring"));
+        assert!(p.is_equipped("short_sword"));
+
+        // Saves from before the extra slots still load
+        let legacy = r#"{"location":"courtyard","inventory":[],"maxInventory":10,"health":100,
+            "maxHealth":100,"attack":5,"defense":3,"equippedWeapon":null,"equippedArmor":null,
+            "questFlags":{},"visitedLocations":[],"turnsElapsed":0}"#;
+        let loaded: Player = serde_json::from_str(legacy).unwrap();
+        assert!(loaded.equipped_helmet.is_none());
+    }
 }
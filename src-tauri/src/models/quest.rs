@@ -1,4 +1,8 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::condition::Condition;
+use super::event::EventAction;
 
 pub type QuestId = String;
 
@@ -8,21 +12,178 @@ pub enum QuestObjective {
     FetchItem(String),
     KillNpc(String),
     ReachLocation(String),
+    TalkTo(String),
+    /// Craft the recipe with this id.
+    CraftItem(String),
+    /// Use an item while standing in a location.
+    UseItemAt(String, String),
+    /// Carry this many of an item at once.
+    CollectCount(String, u32),
+    DiscoverSecret(String),
+    /// Bring an NPC to a location; they follow the player while it's open.
+    Escort(String, String),
+    /// Stay alive for this many turns after the quest begins.
+    SurviveTurns(u32),
+    SetFlag(String),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Quest {
     pub id: QuestId,
     pub name: String,
     pub description: String,
     pub giver: String,
-    pub objective: QuestObjective,
+    /// The quest's one objective; quests with stages leave it out.
+    #[serde(default)]
+    pub objective: Option<QuestObjective>,
     pub reward: Vec<String>,
     pub completed: bool,
     pub active: bool,
     #[serde(default)]
     pub completed_turn: Option<u32>,
+    /// Stages replace the single `objective` when present.
+    #[serde(default)]
+    pub stages: Vec<QuestStage>,
+    /// Whether stages may be finished in any order rather than one by one.
+    #[serde(default)]
+    pub unordered_stages: bool,
+    #[serde(default)]
+    pub outcomes: Vec<QuestOutcome>,
+    #[serde(default)]
+    pub progress: QuestProgress,
+    /// Quests that must be complete before the giver offers this one.
+    #[serde(default)]
+    pub required_quests: Vec<QuestId>,
+    /// Flags that must be set before the giver offers this one.
+    #[serde(default)]
+    pub required_flags: Vec<String>,
+    #[serde(default)]
+    pub fail_conditions: Vec<QuestFailure>,
+    /// Consequences applied when the quest fails.
+    #[serde(default)]
+    pub on_fail: Vec<EventAction>,
+    /// Quests closed off for good when this one fails.
+    #[serde(default)]
+    pub locks_out: Vec<QuestId>,
+    #[serde(default)]
+    pub failed: bool,
+}
+
+/// Something that makes an unfinished quest fail.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum QuestFailure {
+    NpcDies(String),
+    /// The quest isn't done by the end of this turn.
+    Deadline(u32),
+    /// The quest isn't done within this many turns of starting.
+    TimeLimit(u32),
+    /// The item no longer exists anywhere in the world.
+    ItemDestroyed(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StageObjective {
+    pub objective: QuestObjective,
+    /// Optional objectives never hold up their stage.
+    #[serde(default)]
+    pub optional: bool,
+    /// Quest log text; derived from the objective when absent.
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuestStage {
+    pub id: String,
+    pub name: String,
+    /// Shown in the quest log while the stage is in progress.
+    #[serde(default)]
+    pub journal: String,
+    pub objectives: Vec<StageObjective>,
+}
+
+/// An alternate ending. On completion the first outcome whose condition
+/// holds is chosen; an outcome without a condition acts as the fallback.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuestOutcome {
+    pub id: String,
+    pub description: String,
+    #[serde(default)]
+    pub condition: Option<Condition>,
+    /// Given on turn-in on top of the quest's own reward.
+    #[serde(default)]
+    pub reward: Vec<String>,
+    /// Quest started once this outcome is turned in.
+    #[serde(default)]
+    pub follow_up: Option<QuestId>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuestProgress {
+    /// Checked-off objectives as `stage_id:index` keys.
+    #[serde(default)]
+    pub checked: Vec<String>,
+    #[serde(default)]
+    pub completed_stages: Vec<String>,
+    /// Id of the chosen outcome, once the quest is complete.
+    #[serde(default)]
+    pub outcome: Option<String>,
+    /// Turn the quest was first seen active.
+    #[serde(default)]
+    pub started_turn: Option<u32>,
+    /// Turn each stage opened, by stage id.
+    #[serde(default)]
+    pub stage_started: HashMap<String, u32>,
+    /// Times a talk, craft or use objective was done, by `stage_id:index`.
+    #[serde(default)]
+    pub counts: HashMap<String, u32>,
+    /// The collect count last reported to the player, by `stage_id:index`.
+    #[serde(default)]
+    pub reported_counts: HashMap<String, u32>,
+    /// Why the quest failed, for the quest log.
+    #[serde(default)]
+    pub failure: Option<String>,
+}
+
+impl Quest {
+    /// The quest's stages. A quest without stages is a single stage `main`
+    /// holding its one objective.
+    pub fn stage_list(&self) -> Vec<QuestStage> {
+        if !self.stages.is_empty() {
+            return self.stages.clone();
+        }
+        let objectives = self.objective.iter().map(|objective| StageObjective {
+            objective: objective.clone(),
+            optional: false,
+            label: None,
+        });
+        vec![QuestStage {
+            id: "main".into(),
+            name: self.name.clone(),
+            journal: String::new(),
+            objectives: objectives.collect(),
+        }]
+    }
+
+    pub fn chosen_outcome(&self) -> Option<&QuestOutcome> {
+        let id = self.progress.outcome.as_ref()?;
+        self.outcomes.iter().find(|o| o.id == *id)
+    }
+
+    /// Items handed over on turn-in: the base reward plus the outcome's.
+    pub fn rewards(&self) -> Vec<String> {
+        let mut rewards = self.reward.clone();
+        if let Some(outcome) = self.chosen_outcome() {
+            rewards.extend(outcome.reward.iter().cloned());
+        }
+        rewards
+    }
 }
 
 #[cfg(test)]
@@ -36,11 +197,12 @@ mod tests {
             name: "The Rat's Request".into(),
             description: "Find cheese for Gristle.".into(),
             giver: "gristle_rat".into(),
-            objective: QuestObjective::FetchItem("cellar_cheese".into()),
+            objective: Some(QuestObjective::FetchItem("cellar_cheese".into())),
             reward: vec!["health_potion".into()],
             completed: false,
             active: false,
             completed_turn: None,
+            ..Default::default()
         };
         let json = serde_json::to_string(&quest).unwrap();
         assert!(json.contains("fetchItem"));
@@ -48,4 +210,18 @@ mod tests {
         assert_eq!(deserialized.id, "rats_request");
         assert!(!deserialized.completed);
     }
+
+    #[test]
+    fn objective_serde_roundtrip() {
+        let objectives = vec![
+            QuestObjective::UseItemAt("torch".into(), "crypt".into()),
+            QuestObjective::CollectCount("bone_shard".into(), 5),
+            QuestObjective::SurviveTurns(10),
+        ];
+        let json = serde_json::to_string(&objectives).unwrap();
+        assert!(json.contains("useItemAt"));
+        assert!(json.contains("collectCount"));
+        let deserialized: Vec<QuestObjective> = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, objectives);
+    }
 }
@@ -0,0 +1,115 @@
+use serde::{Deserialize, Serialize};
+
+use super::location::{Direction, Mood};
+
+fn default_weight() -> u32 {
+    1
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoomTemplate {
+    pub name: String,
+    pub description: String,
+    pub mood: Mood,
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnemyTemplate {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub personality_seed: String,
+    pub health: i32,
+    pub attack: i32,
+    pub defense: i32,
+    #[serde(default)]
+    pub items: Vec<String>,
+    #[serde(default)]
+    pub examine_text: Option<String>,
+    #[serde(default)]
+    pub loot_table: Option<String>,
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+}
+
+/// Stat bonuses added to every enemy spawned in a region. `*_per_depth`
+/// scales with how deep the room is, `*_per_level` with the difficulty level
+/// (3 on Easy, 5 on Normal, 8 on Hard).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DifficultyScaling {
+    #[serde(default)]
+    pub health_per_depth: i32,
+    #[serde(default)]
+    pub attack_per_depth: i32,
+    #[serde(default)]
+    pub defense_per_depth: i32,
+    #[serde(default)]
+    pub health_per_level: i32,
+    #[serde(default)]
+    pub attack_per_level: i32,
+    #[serde(default)]
+    pub defense_per_level: i32,
+}
+
+/// A module-declared procedural area, generated once per new game from the
+/// world seed. Rooms are chained from `entry_location` through `entry_direction`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProceduralRegion {
+    pub id: String,
+    pub entry_location: String,
+    pub entry_direction: Direction,
+    pub min_depth: usize,
+    pub max_depth: usize,
+    pub room_templates: Vec<RoomTemplate>,
+    #[serde(default)]
+    pub enemy_table: Vec<EnemyTemplate>,
+    /// Percent chance for each non-final room to hold an enemy.
+    #[serde(default)]
+    pub enemy_chance: u32,
+    /// Loot table rolled for rooms that pass `loot_chance`.
+    #[serde(default)]
+    pub loot_table: Option<String>,
+    /// Percent chance for each non-final room to hold loot.
+    #[serde(default)]
+    pub loot_chance: u32,
+    #[serde(default)]
+    pub scaling: DifficultyScaling,
+    #[serde(default)]
+    pub final_room: Option<RoomTemplate>,
+    #[serde(default)]
+    pub boss: Option<EnemyTemplate>,
+    #[serde(default)]
+    pub final_items: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn region_defaults_from_minimal_json() {
+        let json = r#"{
+            "id": "caves",
+            "entryLocation": "camp",
+            "entryDirection": "down",
+            "minDepth": 2,
+            "maxDepth": 4,
+            "roomTemplates": [
+                { "name": "Cave", "description": "Damp rock.", "mood": "dark" }
+            ]
+        }"#;
+        let region: ProceduralRegion = serde_json::from_str(json).unwrap();
+        assert_eq!(region.room_templates[0].weight, 1);
+        assert!(region.enemy_table.is_empty());
+        assert!(region.loot_table.is_none());
+        assert_eq!(region.enemy_chance, 0);
+        assert!(region.boss.is_none());
+        assert_eq!(region.scaling.health_per_depth, 0);
+    }
+}
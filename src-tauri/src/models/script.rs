@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+
+/// A module-defined command that runs a script, e.g. "pray" or "pull lever".
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomVerb {
+    pub verb: String,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    /// Id of the script in `WorldState::scripts` to run. The rest of the
+    /// player's input is passed to it as `arg`.
+    pub script: String,
+    /// Restricts the verb to one location.
+    #[serde(default)]
+    pub location: Option<String>,
+}
+
+impl CustomVerb {
+    pub fn matches(&self, word: &str) -> bool {
+        self.verb == word || self.aliases.iter().any(|a| a == word)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn custom_verb_serde_roundtrip() {
+        let json = r#"{"verb":"pray","aliases":["kneel"],"script":"altar_prayer"}"#;
+        let verb: CustomVerb = serde_json::from_str(json).unwrap();
+        assert!(verb.matches("pray"));
+        assert!(verb.matches("kneel"));
+        assert!(!verb.matches("sing"));
+        assert!(verb.location.is_none());
+    }
+}
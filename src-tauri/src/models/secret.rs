@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+
+use super::event::EventAction;
+use super::location::{Direction, LocationId};
+
+fn default_find_chance() -> u32 {
+    50
+}
+
+fn default_disarm_chance() -> u32 {
+    60
+}
+
+/// Something in a room that only turns up when the player searches for it,
+/// or carries an item that gives it away. Found features are recorded in the
+/// room's `discovered_secrets`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HiddenFeature {
+    pub id: String,
+    pub kind: HiddenKind,
+    /// Percent chance per search of turning it up.
+    #[serde(default = "default_find_chance")]
+    pub find_chance: u32,
+    /// Items that reveal it the moment the player walks in carrying one.
+    #[serde(default)]
+    pub revealed_by: Vec<String>,
+    /// Shown when it is found.
+    pub found_text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HiddenKind {
+    /// An exit that can't be taken, or seen, until found.
+    Exit(Direction, LocationId),
+    /// An item that joins the room's items once found.
+    Item(String),
+    Trap(Trap),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Trap {
+    pub name: String,
+    /// Run when the player walks into the room without having found it.
+    pub on_trigger: Vec<EventAction>,
+    /// Run when the player disarms it.
+    #[serde(default)]
+    pub on_disarm: Vec<EventAction>,
+    /// Percent chance a disarm attempt works; a botched one springs it.
+    #[serde(default = "default_disarm_chance")]
+    pub disarm_chance: u32,
+    /// Sprung and disarmed traps are harmless.
+    #[serde(default)]
+    pub spent: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hidden_feature_serde_defaults() {
+        let json = r#"{
+            "id": "tripwire",
+            "kind": { "trap": { "name": "Tripwire", "onTrigger": [{ "damage": 5 }] } },
+            "foundText": "A wire is strung across the floor."
+        }"#;
+        let feature: HiddenFeature = serde_json::from_str(json).unwrap();
+        assert_eq!(feature.find_chance, 50);
+        assert!(feature.revealed_by.is_empty());
+        let HiddenKind::Trap(trap) = feature.kind else {
+            panic!("expected a trap");
+        };
+        assert_eq!(trap.disarm_chance, 60);
+        assert_eq!(trap.on_trigger, vec![EventAction::Damage(5)]);
+        assert!(!trap.spent);
+    }
+}
@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+
+use super::condition::Condition;
+
+/// What an NPC can be asked or told about, e.g. "ask gristle about cellar".
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TopicMap {
+    pub topics: Vec<Topic>,
+    /// Said for topics the NPC doesn't know.
+    #[serde(default)]
+    pub fallback: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Topic {
+    /// Lowercase name the player types.
+    pub name: String,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    /// Reply to "ask <npc> about <topic>".
+    #[serde(default)]
+    pub ask: Option<String>,
+    /// Reply to "tell <npc> about <topic>".
+    #[serde(default)]
+    pub tell: Option<String>,
+    /// The NPC only talks about the topic while the condition holds.
+    #[serde(default)]
+    pub condition: Option<Condition>,
+    /// Lore journal entry unlocked the first time the topic comes up.
+    #[serde(default)]
+    pub journal: Option<String>,
+    /// Quest flag set the first time the topic comes up.
+    #[serde(default)]
+    pub sets_flag: Option<String>,
+}
+
+impl Topic {
+    pub fn matches(&self, word: &str) -> bool {
+        self.name == word || self.aliases.iter().any(|a| a == word)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn topic_map_serde_roundtrip() {
+        let json = r#"{
+            "topics": [
+                {"name": "cellar", "aliases": ["wine cellar"], "ask": "Cheese down there.", "setsFlag": "heard_of_cellar"}
+            ],
+            "fallback": "Squeak?"
+        }"#;
+        let map: TopicMap = serde_json::from_str(json).unwrap();
+        let topic = &map.topics[0];
+        assert!(topic.matches("cellar"));
+        assert!(topic.matches("wine cellar"));
+        assert!(topic.tell.is_none());
+        assert_eq!(topic.sets_flag.as_deref(), Some("heard_of_cellar"));
+        assert_eq!(map.fallback.as_deref(), Some("Squeak?"));
+    }
+}
@@ -1,15 +1,25 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
+use super::clock::WorldClock;
 use super::crafting::CraftingRecipe;
-use super::event::GameEvent;
-use super::item::Item;
+use super::dialogue::DialogueTree;
+use super::door::Door;
+use super::encounter::EncounterTable;
+use super::event::{EventTimer, GameEvent};
+use super::faction::Faction;
+use super::fixture::{Fixture, Puzzle};
+use super::item::{Affix, Item, ItemSet};
 use super::journal::JournalEntry;
 use super::location::Location;
-use super::npc::Npc;
+use super::loot::LootTable;
+use super::npc::{Companion, Npc};
 use super::player::Player;
 use super::quest::Quest;
+use super::region::ProceduralRegion;
+use super::script::CustomVerb;
 use super::settings::Difficulty;
+use super::topic::TopicMap;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -49,6 +59,7 @@ pub enum ActionType {
     CombatDefend { damage: i32, attacker_name: String },
     CombatVictory { enemy_name: String },
     CombatFlee { success: bool },
+    Rested { turns: u32, interrupted: bool },
     PlayerDeath,
     NpcDialogue { npc_name: String, dialogue_text: String },
     QuestStarted { quest_name: String },
@@ -69,6 +80,9 @@ pub struct NarrativeContext {
     pub inventory_names: Vec<String>,
     pub room_item_names: Vec<String>,
     pub room_npc_names: Vec<String>,
+    /// Companions travelling with the player.
+    #[serde(default)]
+    pub companion_names: Vec<String>,
     pub action_type: ActionType,
     pub turns_elapsed: u32,
 }
@@ -184,6 +198,73 @@ pub struct WorldState {
     pub dialogue_history: Vec<DialogueHistoryEntry>,
     #[serde(default)]
     pub command_log: Vec<CommandLogEntry>,
+    #[serde(default)]
+    pub regions: Vec<ProceduralRegion>,
+    #[serde(default)]
+    pub loot_tables: HashMap<String, LootTable>,
+    #[serde(default)]
+    pub affixes: Vec<Affix>,
+    #[serde(default)]
+    pub item_sets: HashMap<String, ItemSet>,
+    /// Whether weapons and armor wear down in combat.
+    #[serde(default)]
+    pub durability_enabled: bool,
+    /// Counter for unique ids of generated items.
+    #[serde(default)]
+    pub next_item_serial: u32,
+    /// Seed for all procedural generation and world rolls. Advanced on every
+    /// use so that saves resume the same sequence.
+    #[serde(default)]
+    pub rng_seed: u64,
+    /// Pending timers for scheduled events.
+    #[serde(default)]
+    pub timers: Vec<EventTimer>,
+    /// Module scripts by id, run by `RunScript` actions and custom verbs.
+    #[serde(default)]
+    pub scripts: HashMap<String, String>,
+    #[serde(default)]
+    pub custom_verbs: Vec<CustomVerb>,
+    /// Authored dialogue trees by NPC id.
+    #[serde(default)]
+    pub dialogues: HashMap<String, DialogueTree>,
+    /// Current node of an authored conversation.
+    #[serde(default)]
+    pub dialogue_node: Option<String>,
+    /// Conversation topics by NPC id.
+    #[serde(default)]
+    pub topics: HashMap<String, TopicMap>,
+    /// Factions by id; NPCs join one through `Npc.faction`.
+    #[serde(default)]
+    pub factions: HashMap<String, Faction>,
+    /// Recruited NPCs, in the order they joined.
+    #[serde(default)]
+    pub companions: Vec<Companion>,
+    /// Time of day, advanced by every action.
+    #[serde(default)]
+    pub clock: WorldClock,
+    #[serde(default)]
+    pub encounters: Vec<EncounterTable>,
+    /// Counter for unique ids of spawned enemies.
+    #[serde(default)]
+    pub next_npc_serial: u32,
+    #[serde(default)]
+    pub doors: HashMap<String, Door>,
+    #[serde(default)]
+    pub fixtures: HashMap<String, Fixture>,
+    #[serde(default)]
+    pub puzzles: HashMap<String, Puzzle>,
+    /// Copies split off a shared item once they began to wear, and rolled
+    /// variants of a base item, by their own id, naming the item each came
+    /// from.
+    #[serde(default)]
+    pub item_instances: HashMap<String, String>,
+    /// Containers already opened and emptied, each by the id of its own
+    /// copy, so that others of the same kind stay closed.
+    #[serde(default)]
+    pub opened_containers: HashSet<String>,
+    /// Sound cues raised by event actions, collected into the next result.
+    #[serde(skip)]
+    pub pending_sound_cues: Vec<SoundCue>,
 }
 
 impl Default for WorldState {
@@ -206,6 +287,30 @@ impl Default for WorldState {
             recipes: Vec::new(),
             dialogue_history: Vec::new(),
             command_log: Vec::new(),
+            regions: Vec::new(),
+            loot_tables: HashMap::new(),
+            affixes: Vec::new(),
+            item_sets: HashMap::new(),
+            durability_enabled: false,
+            next_item_serial: 0,
+            rng_seed: 0,
+            timers: Vec::new(),
+            scripts: HashMap::new(),
+            custom_verbs: Vec::new(),
+            dialogues: HashMap::new(),
+            dialogue_node: None,
+            topics: HashMap::new(),
+            factions: HashMap::new(),
+            companions: Vec::new(),
+            clock: WorldClock::default(),
+            encounters: Vec::new(),
+            next_npc_serial: 0,
+            doors: HashMap::new(),
+            fixtures: HashMap::new(),
+            puzzles: HashMap::new(),
+            item_instances: HashMap::new(),
+            opened_containers: HashSet::new(),
+            pending_sound_cues: Vec::new(),
         }
     }
 }
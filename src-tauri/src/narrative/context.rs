@@ -37,6 +37,12 @@ pub fn build_narrative_messages(
         context.room_npc_names.join(", ")
     };
 
+    let companions_str = if context.companion_names.is_empty() {
+        String::new()
+    } else {
+        format!("- Travelling with: {}\n", context.companion_names.join(", "))
+    };
+
     let action_desc = describe_action_type(&context.action_type);
 
     let user_msg = format!(
@@ -44,6 +50,7 @@ pub fn build_narrative_messages(
          - Location: \"{}\" — {}\n\
          - Player: {}/{} HP, carrying: {}\n\
          - Room contains: {}, with {}\n\
+         {}\
          - Mood: {}\n\
          - Turns elapsed: {}\n\n\
          ACTION: {}\n\n\
@@ -55,6 +62,7 @@ pub fn build_narrative_messages(
         inventory_str,
         room_items_str,
         room_npcs_str,
+        companions_str,
         context.mood,
         context.turns_elapsed,
         action_desc
@@ -175,6 +183,13 @@ fn describe_action_type(action_type: &ActionType) -> String {
                 "Player failed to flee from combat.".to_string()
             }
         }
+        ActionType::Rested { turns, interrupted } => {
+            if *interrupted {
+                format!("Player's rest was interrupted after {} turns.", turns)
+            } else {
+                format!("Player rested for {} turns.", turns)
+            }
+        }
         ActionType::PlayerDeath => "Player has died.".to_string(),
         ActionType::NpcDialogue {
             npc_name,
@@ -34,19 +34,25 @@ pub fn determine_tone(state: &WorldState) -> String {
         return "triumphant with dry humor, hard-won relief".to_string();
     }
 
-    match location_mood {
+    let tone = match location_mood {
         Mood::Peaceful => {
             if health_pct > 75 {
-                "calm, reflective, atmospheric".to_string()
+                "calm, reflective, atmospheric"
             } else {
-                "uneasy calm, a moment to catch breath".to_string()
+                "uneasy calm, a moment to catch breath"
             }
         }
-        Mood::Tense => "tense, watchful, danger lurks nearby".to_string(),
-        Mood::Mysterious => "curious, atmospheric, secrets in the shadows".to_string(),
-        Mood::Dark => "ominous, foreboding, darkness presses close".to_string(),
-        Mood::Sacred => "reverent, mysterious, ancient power lingers".to_string(),
-        Mood::Dangerous => "perilous, every step could be the last".to_string(),
+        Mood::Tense => "tense, watchful, danger lurks nearby",
+        Mood::Mysterious => "curious, atmospheric, secrets in the shadows",
+        Mood::Dark => "ominous, foreboding, darkness presses close",
+        Mood::Sacred => "reverent, mysterious, ancient power lingers",
+        Mood::Dangerous => "perilous, every step could be the last",
+    };
+    match state.clock.phase() {
+        DayPhase::Day => tone.to_string(),
+        DayPhase::Dawn => format!("{}, in the pale hush of dawn", tone),
+        DayPhase::Dusk => format!("{}, long shadows of dusk", tone),
+        DayPhase::Night => format!("{}, under cover of night", tone),
     }
 }
 
@@ -72,6 +78,7 @@ mod tests {
                 ambient_mood: Mood::Peaceful,
                 examine_details: None,
                 revisit_description: None,
+                ..Default::default()
             },
         );
 
@@ -82,6 +89,12 @@ mod tests {
         state.player.health = 10;
         let tone = determine_tone(&state);
         assert!(tone.contains("desperate"));
+
+        state.player.health = 100;
+        state.clock.minutes = 22 * 60;
+        let tone = determine_tone(&state);
+        assert!(tone.starts_with("calm"));
+        assert!(tone.contains("night"));
     }
 
     #[test]
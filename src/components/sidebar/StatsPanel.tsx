@@ -10,12 +10,17 @@ export function StatsPanel({ player, items }: Props) {
   const hpColor =
     hpPct > 60 ? "var(--hp-high)" : hpPct > 25 ? "var(--hp-mid)" : "var(--hp-low)";
 
-  const weaponBonus = player.equippedWeapon
-    ? (items[player.equippedWeapon]?.modifier?.attack ?? 0)
-    : 0;
-  const armorBonus = player.equippedArmor
-    ? (items[player.equippedArmor]?.modifier?.defense ?? 0)
-    : 0;
+  const equipped = [
+    player.equippedWeapon,
+    player.equippedArmor,
+    player.equippedHelmet,
+    player.equippedShield,
+    player.equippedRing,
+    player.equippedAmulet,
+    player.equippedBoots,
+  ].map((id) => (id ? items[id]?.modifier : null));
+  const weaponBonus = equipped.reduce((sum, m) => sum + (m?.attack ?? 0), 0);
+  const armorBonus = equipped.reduce((sum, m) => sum + (m?.defense ?? 0), 0);
 
   return (
     <div>
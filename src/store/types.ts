@@ -50,6 +50,15 @@ export interface StatModifier {
   health: number;
 }
 
+export type Rarity = "common" | "uncommon" | "rare" | "epic" | "legendary";
+
+export type EquipSlot = "weapon" | "armor" | "helmet" | "shield" | "ring" | "amulet" | "boots";
+
+export interface Durability {
+  current: number;
+  max: number;
+}
+
 export interface Item {
   id: string;
   name: string;
@@ -60,6 +69,15 @@ export interface Item {
   consumable: boolean;
   keyId: string | null;
   lore: string | null;
+  rarity?: Rarity;
+  slot?: EquipSlot | null;
+  setId?: string | null;
+  durability?: Durability | null;
+  repairAmount?: number | null;
+  charges?: number | null;
+  cooldown?: number;
+  light?: boolean;
+  lockpick?: number | null;
 }
 
 export interface Location {
@@ -75,6 +93,75 @@ export interface Location {
   ambientMood: Mood;
   examineDetails: string | null;
   revisitDescription: string | null;
+  phaseDescriptions?: Partial<Record<DayPhase, string>>;
+  secrets?: HiddenFeature[];
+  doors?: Partial<Record<Direction, string>>;
+  fixtures?: string[];
+}
+
+export type FixtureKind =
+  | { lever: { positions: string[] } }
+  | { combination: { length: number } }
+  | { riddle: { question: string; answers: string[] } };
+
+export interface Fixture {
+  id: string;
+  name: string;
+  description?: string;
+  kind: FixtureKind;
+  state?: string;
+}
+
+export interface Puzzle {
+  id: string;
+  locationId: string;
+  solution: Record<string, string>;
+  onSolve?: unknown[];
+  solvedText?: string | null;
+  solved?: boolean;
+}
+
+export type DoorState = "open" | "closed" | "locked";
+
+export interface Door {
+  id: string;
+  name: string;
+  state?: DoorState;
+  keyId?: string | null;
+  lockDifficulty?: number;
+  sealed?: boolean;
+  opensFrom?: string | null;
+  broken?: boolean;
+}
+
+export interface Trap {
+  name: string;
+  onTrigger: unknown[];
+  onDisarm?: unknown[];
+  disarmChance?: number;
+  spent?: boolean;
+}
+
+export type HiddenKind =
+  | { exit: [Direction, string] }
+  | { item: string }
+  | { trap: Trap };
+
+export interface HiddenFeature {
+  id: string;
+  kind: HiddenKind;
+  findChance?: number;
+  revealedBy?: string[];
+  foundText: string;
+}
+
+export type DayPhase = "dawn" | "day" | "dusk" | "night";
+
+export interface WorldClock {
+  minutes: number;
+  minutesPerAction: number;
+  minutesPerMove: number;
+  minutesPerRest: number;
 }
 
 export type StatusEffectType = "poison" | "blessed" | "weakened" | "burning";
@@ -98,11 +185,18 @@ export interface Player {
   defense: number;
   equippedWeapon: string | null;
   equippedArmor: string | null;
+  equippedHelmet?: string | null;
+  equippedShield?: string | null;
+  equippedRing?: string | null;
+  equippedAmulet?: string | null;
+  equippedBoots?: string | null;
   questFlags: Record<string, boolean>;
   visitedLocations: string[];
   turnsElapsed: number;
   statusEffects: StatusEffect[];
   discoveredSecrets: string[];
+  agility?: number;
+  hidden?: boolean;
 }
 
 export interface NpcMemory {
@@ -126,12 +220,42 @@ export interface Npc {
   examineText: string | null;
   relationship: number;
   memory: NpcMemory[];
+  smith?: boolean;
+  faction?: string | null;
+  movement?: NpcMovement | null;
+  recruitable?: boolean;
+  recruitCondition?: unknown;
+  riddle?: string | null;
+}
+
+export interface Companion {
+  npcId: string;
+  waiting?: boolean;
+}
+
+export interface ScheduleEntry {
+  from?: number;
+  phase?: DayPhase | null;
+  location: string;
 }
 
+export type NpcMovement =
+  | { patrol: { route: string[]; every: number } }
+  | { schedule: { entries: ScheduleEntry[]; cycle?: number | null } }
+  | { wander: { rooms: string[]; chance: number } };
+
 export interface QuestObjective {
   fetchItem?: string;
   killNpc?: string;
   reachLocation?: string;
+  talkTo?: string;
+  craftItem?: string;
+  useItemAt?: [string, string];
+  collectCount?: [string, number];
+  discoverSecret?: string;
+  escort?: [string, string];
+  surviveTurns?: number;
+  setFlag?: string;
 }
 
 export interface Quest {
@@ -139,11 +263,60 @@ export interface Quest {
   name: string;
   description: string;
   giver: string;
-  objective: QuestObjective;
+  objective?: QuestObjective | null;
   reward: string[];
   completed: boolean;
   active: boolean;
   completedTurn: number | null;
+  stages?: QuestStage[];
+  unorderedStages?: boolean;
+  outcomes?: QuestOutcome[];
+  progress?: QuestProgress;
+  requiredQuests?: string[];
+  requiredFlags?: string[];
+  failConditions?: QuestFailure[];
+  onFail?: unknown[];
+  locksOut?: string[];
+  failed?: boolean;
+}
+
+export interface QuestFailure {
+  npcDies?: string;
+  deadline?: number;
+  timeLimit?: number;
+  itemDestroyed?: string;
+}
+
+export interface StageObjective {
+  objective: QuestObjective;
+  optional?: boolean;
+  label?: string | null;
+}
+
+export interface QuestStage {
+  id: string;
+  name: string;
+  journal?: string;
+  objectives: StageObjective[];
+}
+
+export interface QuestOutcome {
+  id: string;
+  description: string;
+  condition?: unknown;
+  reward?: string[];
+  followUp?: string | null;
+}
+
+export interface QuestProgress {
+  checked: string[];
+  completedStages: string[];
+  outcome: string | null;
+  startedTurn?: number | null;
+  stageStarted?: Record<string, number>;
+  counts?: Record<string, number>;
+  reportedCounts?: Record<string, number>;
+  failure?: string | null;
 }
 
 export type JournalCategory = "lore" | "bestiary" | "location" | "item";
@@ -170,11 +343,76 @@ export interface DialogueHistoryEntry {
 }
 
 export interface GameEvent {
+  id?: string | null;
   trigger: unknown;
   action: unknown;
   oneShot: boolean;
   fired: boolean;
   locationId: string;
+  condition?: unknown;
+}
+
+export interface EventTimer {
+  eventId: string;
+  dueTurn: number;
+  repeatEvery?: number | null;
+}
+
+export interface CustomVerb {
+  verb: string;
+  aliases?: string[];
+  script: string;
+  location?: string | null;
+}
+
+export type Skill = "might" | "resolve" | "charm";
+
+export interface DialogueChoice {
+  text: string;
+  condition?: unknown;
+  skillCheck?: { skill: Skill; difficulty: number } | null;
+  effects?: unknown[];
+  next?: string | null;
+  failNext?: string | null;
+}
+
+export interface DialogueNode {
+  text: string;
+  effects?: unknown[];
+  choices?: DialogueChoice[];
+}
+
+export interface DialogueTree {
+  start: string;
+  nodes: Record<string, DialogueNode>;
+}
+
+export interface Topic {
+  name: string;
+  aliases?: string[];
+  ask?: string | null;
+  tell?: string | null;
+  condition?: unknown;
+  journal?: string | null;
+  setsFlag?: string | null;
+}
+
+export interface TopicMap {
+  topics: Topic[];
+  fallback?: string | null;
+}
+
+export type ReputationTier = "hostile" | "unfriendly" | "neutral" | "friendly" | "honored";
+
+export interface Faction {
+  id: string;
+  name: string;
+  description?: string;
+  allies?: string[];
+  enemies?: string[];
+  property?: string[];
+  reputation?: number;
+  turnedHostile?: string[];
 }
 
 export interface CombatState {
@@ -201,6 +439,21 @@ export interface WorldState {
   recipes: CraftingRecipe[];
   dialogueHistory: DialogueHistoryEntry[];
   commandLog: CommandLogEntry[];
+  durabilityEnabled?: boolean;
+  timers?: EventTimer[];
+  scripts?: Record<string, string>;
+  customVerbs?: CustomVerb[];
+  dialogues?: Record<string, DialogueTree>;
+  dialogueNode?: string | null;
+  topics?: Record<string, TopicMap>;
+  factions?: Record<string, Faction>;
+  companions?: Companion[];
+  clock?: WorldClock;
+  doors?: Record<string, Door>;
+  fixtures?: Record<string, Fixture>;
+  puzzles?: Record<string, Puzzle>;
+  itemInstances?: Record<string, string>;
+  openedContainers?: string[];
 }
 
 export interface OutputLine {
@@ -265,6 +518,7 @@ export interface MapEdge {
   from: string;
   to: string;
   locked: boolean;
+  doorState: DoorState | null;
 }
 
 export interface MapData {
@@ -290,6 +544,7 @@ export interface NarrativeContext {
   inventoryNames: string[];
   roomItemNames: string[];
   roomNpcNames: string[];
+  companionNames?: string[];
   actionType: unknown;
   turnsElapsed: number;
 }